@@ -0,0 +1,550 @@
+// This file is @generated by prost-build.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckRequest {
+    /// The request attributes.
+    #[prost(message, optional, tag = "1")]
+    pub attributes: ::core::option::Option<AttributeContext>,
+}
+/// An attribute is a piece of metadata that describes an activity on a network.
+///
+/// Upstream, this message is defined in `attribute_context.proto`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AttributeContext {
+    /// The source of a network activity, such as starting a TCP connection.
+    #[prost(message, optional, tag = "1")]
+    pub source: ::core::option::Option<attribute_context::Peer>,
+    /// The destination of a network activity, such as accepting a TCP connection.
+    #[prost(message, optional, tag = "2")]
+    pub destination: ::core::option::Option<attribute_context::Peer>,
+    /// Represents a network request, such as an HTTP request.
+    #[prost(message, optional, tag = "4")]
+    pub request: ::core::option::Option<attribute_context::Request>,
+    /// This is analogous to http_request.headers, however these contents will not
+    /// be sent to the upstream server. Context_extensions provide an extension
+    /// mechanism for sending additional information to the auth server without
+    /// modifying the proto definition.
+    #[prost(map = "string, string", tag = "10")]
+    pub context_extensions: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// TLS session details of the underlying connection.
+    #[prost(message, optional, tag = "12")]
+    pub tls_session: ::core::option::Option<attribute_context::TlsSession>,
+}
+/// Nested message and enum types in `AttributeContext`.
+pub mod attribute_context {
+    /// This message defines attributes for a node that handles a network request.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Peer {
+        /// The address of the peer.
+        #[prost(message, optional, tag = "1")]
+        pub address: ::core::option::Option<super::Address>,
+        /// The authenticated identity of this peer. When the peer is authenticated
+        /// via mTLS, this is its TLS identity.
+        #[prost(string, tag = "4")]
+        pub principal: ::prost::alloc::string::String,
+    }
+    /// Represents a network request, such as an HTTP request.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Request {
+        /// Represents an HTTP request or an HTTP-like request.
+        #[prost(message, optional, tag = "2")]
+        pub http: ::core::option::Option<HttpRequest>,
+    }
+    /// This message defines attributes for an HTTP request.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HttpRequest {
+        /// The HTTP request method, such as `GET`, `POST`.
+        #[prost(string, tag = "2")]
+        pub method: ::prost::alloc::string::String,
+        /// The HTTP request headers. If multiple headers share the same key, they
+        /// are merged according to the HTTP spec. All header keys are lower-cased.
+        #[prost(map = "string, string", tag = "3")]
+        pub headers: ::std::collections::HashMap<
+            ::prost::alloc::string::String,
+            ::prost::alloc::string::String,
+        >,
+        /// The request target, as it appears in the first line of the HTTP request.
+        /// This includes the URL path and query-string.
+        #[prost(string, tag = "4")]
+        pub path: ::prost::alloc::string::String,
+        /// The HTTP request `Host` or `:authority` header value.
+        #[prost(string, tag = "5")]
+        pub host: ::prost::alloc::string::String,
+        /// The HTTP URL scheme, such as `http` and `https`.
+        #[prost(string, tag = "6")]
+        pub scheme: ::prost::alloc::string::String,
+        /// The network protocol used with the request, such as "HTTP/1.0",
+        /// "HTTP/1.1", or "HTTP/2".
+        #[prost(string, tag = "10")]
+        pub protocol: ::prost::alloc::string::String,
+    }
+    /// This message defines attributes associated with TLS.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TlsSession {
+        /// SNI used for TLS session.
+        #[prost(string, tag = "1")]
+        pub sni: ::prost::alloc::string::String,
+    }
+}
+/// Intended for gRPC and Network Authorization servers `only`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckResponse {
+    /// Status `OK` allows the request. Any other status indicates the request
+    /// should be denied.
+    #[prost(message, optional, tag = "1")]
+    pub status: ::core::option::Option<Status>,
+    /// An message that contains HTTP response attributes. This message is
+    /// used when the authorization service needs to send custom responses to the
+    /// downstream client or, to modify/add request headers being dispatched to the
+    /// upstream.
+    #[prost(oneof = "check_response::HttpResponse", tags = "2, 3")]
+    pub http_response: ::core::option::Option<check_response::HttpResponse>,
+}
+/// Nested message and enum types in `CheckResponse`.
+pub mod check_response {
+    /// An message that contains HTTP response attributes. This message is
+    /// used when the authorization service needs to send custom responses to the
+    /// downstream client or, to modify/add request headers being dispatched to the
+    /// upstream.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum HttpResponse {
+        /// Supplies http attributes for a denied response.
+        #[prost(message, tag = "2")]
+        DeniedResponse(super::DeniedHttpResponse),
+        /// Supplies http attributes for an ok response.
+        #[prost(message, tag = "3")]
+        OkResponse(super::OkHttpResponse),
+    }
+}
+/// HTTP attributes for a denied response.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeniedHttpResponse {
+    /// This field allows the authorization service to send an HTTP response
+    /// status code to the downstream client.
+    #[prost(message, optional, tag = "1")]
+    pub status: ::core::option::Option<HttpStatus>,
+    /// This field allows the authorization service to send HTTP response headers
+    /// to the downstream client.
+    #[prost(message, repeated, tag = "2")]
+    pub headers: ::prost::alloc::vec::Vec<HeaderValueOption>,
+    /// This field allows the authorization service to send a response body data
+    /// to the downstream client.
+    #[prost(string, tag = "3")]
+    pub body: ::prost::alloc::string::String,
+}
+/// HTTP attributes for an OK response.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OkHttpResponse {
+    /// HTTP entity headers in addition to the original request headers. This
+    /// allows the authorization service to append, to add or to override headers
+    /// from the original request before dispatching it to the upstream.
+    #[prost(message, repeated, tag = "2")]
+    pub headers: ::prost::alloc::vec::Vec<HeaderValueOption>,
+    /// Use this field to specify headers to be removed from the original request
+    /// before dispatching it to the upstream.
+    #[prost(string, repeated, tag = "5")]
+    pub headers_to_remove: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Upstream, this message is defined in `envoy.config.core.v3`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Address {
+    #[prost(oneof = "address::Address", tags = "1")]
+    pub address: ::core::option::Option<address::Address>,
+}
+/// Nested message and enum types in `Address`.
+pub mod address {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Address {
+        #[prost(message, tag = "1")]
+        SocketAddress(super::SocketAddress),
+    }
+}
+/// Upstream, this message is defined in `envoy.config.core.v3`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SocketAddress {
+    /// The address for this socket.
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    #[prost(oneof = "socket_address::PortSpecifier", tags = "3")]
+    pub port_specifier: ::core::option::Option<socket_address::PortSpecifier>,
+}
+/// Nested message and enum types in `SocketAddress`.
+pub mod socket_address {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum PortSpecifier {
+        #[prost(uint32, tag = "3")]
+        PortValue(u32),
+    }
+}
+/// Header name/value pair.
+///
+/// Upstream, this message is defined in `envoy.config.core.v3`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeaderValue {
+    /// Header name.
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Header value.
+    #[prost(string, tag = "2")]
+    pub value: ::prost::alloc::string::String,
+}
+/// Header name/value pair plus option to control append behavior.
+///
+/// Upstream, this message is defined in `envoy.config.core.v3`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeaderValueOption {
+    /// Header name/value pair that this option applies to.
+    #[prost(message, optional, tag = "1")]
+    pub header: ::core::option::Option<HeaderValue>,
+}
+/// HTTP status.
+///
+/// Upstream, this message is defined in `envoy.type.v3`, where the code is a
+/// `StatusCode` enumeration.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpStatus {
+    /// Supplies HTTP response code.
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+}
+/// The `Status` type defines a logical error model.
+///
+/// Upstream, this message is defined in `google.rpc`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Status {
+    /// The status code, which should be an enum value of `google.rpc.Code`.
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+    /// A developer-facing error message.
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod authorization_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// A generic interface for performing authorization check on incoming
+    /// requests to a networked service.
+    ///
+    /// This is a subset of the Envoy external authorization API. Fields that the
+    /// proxy does not use, as well as validation and versioning annotations, have
+    /// been omitted. Messages that are defined in other packages upstream are
+    /// declared in this package. The remaining fields retain their upstream names
+    /// and field numbers so that the messages are wire-compatible with the upstream
+    /// API.
+    #[derive(Debug, Clone)]
+    pub struct AuthorizationClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> AuthorizationClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> AuthorizationClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            AuthorizationClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Performs authorization check based on the attributes associated with the
+    /// incoming request, and returns status `OK` or not `OK`.
+        pub async fn check(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CheckRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/envoy.service.auth.v3.Authorization/Check",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "envoy.service.auth.v3.Authorization",
+                        "Check",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod authorization_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with AuthorizationServer.
+    #[async_trait]
+    pub trait Authorization: Send + Sync + 'static {
+        /// Performs authorization check based on the attributes associated with the
+    /// incoming request, and returns status `OK` or not `OK`.
+        async fn check(
+            &self,
+            request: tonic::Request<super::CheckRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CheckResponse>,
+            tonic::Status,
+        >;
+    }
+    /// A generic interface for performing authorization check on incoming
+    /// requests to a networked service.
+    ///
+    /// This is a subset of the Envoy external authorization API. Fields that the
+    /// proxy does not use, as well as validation and versioning annotations, have
+    /// been omitted. Messages that are defined in other packages upstream are
+    /// declared in this package. The remaining fields retain their upstream names
+    /// and field numbers so that the messages are wire-compatible with the upstream
+    /// API.
+    #[derive(Debug)]
+    pub struct AuthorizationServer<T: Authorization> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Authorization> AuthorizationServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AuthorizationServer<T>
+    where
+        T: Authorization,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/envoy.service.auth.v3.Authorization/Check" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckSvc<T: Authorization>(pub Arc<T>);
+                    impl<
+                        T: Authorization,
+                    > tonic::server::UnaryService<super::CheckRequest>
+                    for CheckSvc<T> {
+                        type Response = super::CheckResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CheckRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).check(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CheckSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: Authorization> Clone for AuthorizationServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    impl<T: Authorization> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(Arc::clone(&self.0))
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Authorization> tonic::server::NamedService for AuthorizationServer<T> {
+        const NAME: &'static str = "envoy.service.auth.v3.Authorization";
+    }
+}
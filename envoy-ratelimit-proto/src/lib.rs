@@ -0,0 +1,13 @@
+//! gRPC bindings for the Envoy rate limit service.
+//!
+//! Vendored from <https://github.com/envoyproxy/envoy/blob/main/api/envoy/service/ratelimit/v3/rls.proto>
+//! and trimmed to the fields used by the proxy. Server bindings are included so
+//! that tests may run a stand-in rate limit service.
+
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![allow(clippy::derive_partial_eq_without_eq)]
+#![forbid(unsafe_code)]
+
+pub mod ratelimit {
+    include!("gen/envoy.service.ratelimit.v3.rs");
+}
@@ -9,6 +9,9 @@
 //! * `GET /tasks` -- returns a dump of spawned Tokio tasks (when enabled by the
 //!   tracing configuration).
 //! * `POST /shutdown` -- shuts down the proxy.
+//! * `GET /policy-suggestions.json` -- returns the authorizations suggested by
+//!   inbound policy learning (when enabled).
+//! * `DELETE /policy-suggestions.json` -- discards learned clients.
 
 use futures::future::{self, TryFutureExt};
 use http::StatusCode;
@@ -21,6 +24,7 @@ use linkerd_app_core::{
     proxy::http::ClientHandle,
     trace, Error, Result,
 };
+use linkerd_app_inbound::policy::PolicyLearner;
 use std::{
     future::Future,
     pin::Pin,
@@ -41,6 +45,7 @@ pub struct Admin<M> {
     ready: Readiness,
     shutdown_tx: mpsc::UnboundedSender<()>,
     enable_shutdown: bool,
+    policy_learner: Option<PolicyLearner>,
     #[cfg(feature = "pprof")]
     pprof: Option<crate::pprof::Pprof>,
 }
@@ -61,12 +66,18 @@ impl<M> Admin<M> {
             shutdown_tx,
             enable_shutdown,
             tracing,
+            policy_learner: None,
 
             #[cfg(feature = "pprof")]
             pprof: None,
         }
     }
 
+    pub fn with_policy_learner(mut self, learner: Option<PolicyLearner>) -> Self {
+        self.policy_learner = learner;
+        self
+    }
+
     #[cfg(feature = "pprof")]
     pub fn with_profiling(mut self, enabled: bool) -> Self {
         self.pprof = enabled.then_some(crate::pprof::Pprof);
@@ -142,6 +153,25 @@ impl<M> Admin<M> {
         json::json_rsp(&env)
     }
 
+    fn policy_suggestions_rsp<B>(learner: &PolicyLearner, req: Request<B>) -> Response<Body> {
+        match *req.method() {
+            http::Method::GET => {
+                if let Err(not_acceptable) = json::accepts_json(&req) {
+                    return not_acceptable;
+                }
+                json::json_rsp(&learner.suggestions())
+            }
+            http::Method::DELETE => {
+                learner.reset();
+                Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty())
+                    .expect("builder with known status code must not fail")
+            }
+            _ => Self::method_not_allowed(),
+        }
+    }
+
     fn shutdown(&self) -> Response<Body> {
         if !self.enable_shutdown {
             return Response::builder()
@@ -271,6 +301,17 @@ where
 
             "/env.json" => Box::pin(future::ok(Self::env_rsp(req))),
 
+            "/policy-suggestions.json" => {
+                let learner = match self.policy_learner {
+                    Some(ref learner) => learner,
+                    None => return Box::pin(future::ok(Self::not_found())),
+                };
+                if !Self::client_is_localhost(&req) {
+                    return Box::pin(future::ok(Self::forbidden_not_localhost()));
+                }
+                Box::pin(future::ok(Self::policy_suggestions_rsp(learner, req)))
+            }
+
             "/shutdown" => {
                 if req.method() == http::Method::POST {
                     if Self::client_is_localhost(&req) {
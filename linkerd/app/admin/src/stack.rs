@@ -85,6 +85,7 @@ impl Config {
         trace: trace::Handle,
         drain: drain::Watch,
         shutdown: mpsc::UnboundedSender<()>,
+        policy_learner: Option<inbound::policy::PolicyLearner>,
     ) -> Result<Task>
     where
         R: FmtMetrics + Clone + Send + Sync + Unpin + 'static,
@@ -101,7 +102,8 @@ impl Config {
         let (ready, latch) = crate::server::Readiness::new();
 
         #[cfg_attr(not(feature = "pprof"), allow(unused_mut))]
-        let admin = crate::server::Admin::new(report, ready, shutdown, self.enable_shutdown, trace);
+        let admin = crate::server::Admin::new(report, ready, shutdown, self.enable_shutdown, trace)
+            .with_policy_learner(policy_learner);
 
         #[cfg(feature = "pprof")]
         let admin = admin.with_profiling(self.enable_profiling);
@@ -117,6 +119,10 @@ impl Config {
             .push_map_target(|(permit, http)| Permitted { permit, http })
             .push(inbound::policy::NewHttpPolicy::layer(
                 metrics.http_authz.clone(),
+                None,
+                None,
+                None,
+                None,
             ))
             .push(Rescue::layer())
             .push_on_service(http::BoxResponse::layer())
@@ -2,7 +2,7 @@ pub use crate::exp_backoff::ExponentialBackoff;
 use crate::{
     proxy::http::{self, h1, h2},
     svc::{queue, CloneParam, ExtractParam, Param},
-    transport::{DualListenAddr, Keepalive, ListenAddr, UserTimeout},
+    transport::{DualListenAddr, Keepalive, ListenAddr, ListenShards, UserTimeout},
 };
 use std::time::Duration;
 
@@ -11,6 +11,7 @@ pub struct ServerConfig {
     pub addr: DualListenAddr,
     pub keepalive: Keepalive,
     pub user_timeout: UserTimeout,
+    pub shards: ListenShards,
     pub http2: h2::ServerParams,
 }
 
@@ -92,3 +93,9 @@ impl Param<UserTimeout> for ServerConfig {
         self.user_timeout
     }
 }
+
+impl Param<ListenShards> for ServerConfig {
+    fn param(&self) -> ListenShards {
+        self.shards
+    }
+}
@@ -57,6 +57,12 @@ impl svc::Param<svc::queue::Timeout> for ControlAddr {
     }
 }
 
+impl svc::Param<Option<http::balance::CodelConfig>> for ControlAddr {
+    fn param(&self) -> Option<http::balance::CodelConfig> {
+        None
+    }
+}
+
 impl svc::Param<http::balance::EwmaConfig> for ControlAddr {
     fn param(&self) -> http::balance::EwmaConfig {
         EWMA_CONFIG
@@ -70,7 +76,7 @@ impl fmt::Display for ControlAddr {
 }
 
 pub type RspBody =
-    linkerd_http_metrics::requests::ResponseBody<http::balance::Body<hyper::Body>, classify::Eos>;
+    linkerd_http_metrics::requests::ResponseBody<http::balance::Body<http::BoxBody>, classify::Eos>;
 
 #[derive(Clone, Debug, Default)]
 pub struct Metrics {
@@ -90,17 +96,44 @@ impl Metrics {
     }
 }
 
+type NewControlClient = svc::ArcNewService<
+    (),
+    svc::BoxCloneSyncService<http::Request<tonic::body::BoxBody>, http::Response<RspBody>>,
+>;
+
 impl Config {
+    /// Builds a gRPC client.
     pub fn build(
         self,
         dns: dns::Resolver,
         legacy_metrics: metrics::ControlHttp,
         metrics: Metrics,
         identity: identity::NewClient,
-    ) -> svc::ArcNewService<
-        (),
-        svc::BoxCloneSyncService<http::Request<tonic::body::BoxBody>, http::Response<RspBody>>,
-    > {
+    ) -> NewControlClient {
+        let params = http::client::Params::H2(self.connect.http2.clone());
+        self.build_client(params, dns, legacy_metrics, metrics, identity)
+    }
+
+    /// Builds an HTTP/1 client, for services that do not support HTTP/2.
+    pub fn build_http1(
+        self,
+        dns: dns::Resolver,
+        legacy_metrics: metrics::ControlHttp,
+        metrics: Metrics,
+        identity: identity::NewClient,
+    ) -> NewControlClient {
+        let params = http::client::Params::Http1(self.connect.http1.clone());
+        self.build_client(params, dns, legacy_metrics, metrics, identity)
+    }
+
+    fn build_client(
+        self,
+        params: http::client::Params,
+        dns: dns::Resolver,
+        legacy_metrics: metrics::ControlHttp,
+        metrics: Metrics,
+        identity: identity::NewClient,
+    ) -> NewControlClient {
         let addr = self.addr;
         tracing::trace!(%addr, "Building");
 
@@ -131,7 +164,7 @@ impl Config {
         .push(tls::Client::layer(identity))
         .push_connect_timeout(self.connect.timeout)
         .push_map_target(|(_version, target)| target)
-        .push(self::client::layer(self.connect.http2))
+        .push(http::client::layer_via(svc::CloneParam::from(params)))
         .push_on_service(svc::MapErr::layer_boxed())
         .into_new_service();
 
@@ -236,6 +269,12 @@ mod add_origin {
                 uri.authority = Some(self.authority.clone());
                 http::Uri::from_parts(uri).expect("URI must be valid")
             };
+            // HTTP/1 requests carry the authority in the Host header.
+            if parts.version != http::Version::HTTP_2 {
+                if let Ok(host) = http::HeaderValue::from_str(self.authority.as_str()) {
+                    parts.headers.insert(http::header::HOST, host);
+                }
+            }
             self.inner.call(http::Request::from_parts(parts, body))
         }
     }
@@ -326,10 +365,9 @@ mod balance {
     }
 }
 
-/// Creates a client suitable for gRPC.
+/// Describes a control-plane endpoint.
 mod client {
     use crate::{
-        proxy::http,
         svc, tls,
         transport::{Remote, ServerAddr},
     };
@@ -366,15 +404,4 @@ mod client {
             self.server_id.clone()
         }
     }
-
-    // === impl Layer ===
-
-    pub fn layer<C, B>(
-        h2: http::h2::ClientParams,
-    ) -> impl svc::Layer<C, Service = http::h2::Connect<C, B>> + Clone
-    where
-        http::h2::Connect<C, B>: tower::Service<Target>,
-    {
-        svc::layer::mk(move |mk_conn| http::h2::Connect::new(mk_conn, h2.clone()))
-    }
 }
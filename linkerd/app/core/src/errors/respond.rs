@@ -38,6 +38,7 @@ pub struct SyntheticHttpResponse {
     close_connection: bool,
     message: Cow<'static, str>,
     location: Option<HeaderValue>,
+    headers: http::HeaderMap,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -103,6 +104,7 @@ impl SyntheticHttpResponse {
             grpc_status: tonic::Code::Internal,
             message: msg.into(),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -113,6 +115,7 @@ impl SyntheticHttpResponse {
             grpc_status: tonic::Code::Unavailable,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -123,6 +126,7 @@ impl SyntheticHttpResponse {
             grpc_status: tonic::Code::DeadlineExceeded,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -133,6 +137,7 @@ impl SyntheticHttpResponse {
             grpc_status: tonic::Code::DeadlineExceeded,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -143,6 +148,18 @@ impl SyntheticHttpResponse {
             grpc_status: tonic::Code::Unavailable,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
+        }
+    }
+
+    pub fn unavailable_nonfatal(msg: impl ToString) -> Self {
+        Self {
+            close_connection: false,
+            http_status: http::StatusCode::SERVICE_UNAVAILABLE,
+            grpc_status: tonic::Code::Unavailable,
+            message: Cow::Owned(msg.to_string()),
+            location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -153,6 +170,7 @@ impl SyntheticHttpResponse {
             close_connection: false,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -163,6 +181,7 @@ impl SyntheticHttpResponse {
             close_connection: false,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -173,6 +192,7 @@ impl SyntheticHttpResponse {
             close_connection: false,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -183,6 +203,7 @@ impl SyntheticHttpResponse {
             close_connection: true,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -193,6 +214,7 @@ impl SyntheticHttpResponse {
             close_connection: false,
             message: Cow::Owned(msg.to_string()),
             location: None,
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -206,6 +228,7 @@ impl SyntheticHttpResponse {
                 HeaderValue::try_from(location.to_string())
                     .expect("location must be a valid header value"),
             ),
+            headers: http::HeaderMap::new(),
         }
     }
 
@@ -213,6 +236,7 @@ impl SyntheticHttpResponse {
         Self {
             http_status,
             location: None,
+            headers: http::HeaderMap::new(),
             grpc_status: tonic::Code::FailedPrecondition,
             close_connection: false,
             message: message.into(),
@@ -224,11 +248,25 @@ impl SyntheticHttpResponse {
             grpc_status,
             http_status: http::StatusCode::OK,
             location: None,
+            headers: http::HeaderMap::new(),
             close_connection: false,
             message: message.into(),
         }
     }
 
+    /// Overrides the HTTP and gRPC statuses of the response.
+    pub fn with_status(mut self, http_status: http::StatusCode, grpc_status: tonic::Code) -> Self {
+        self.http_status = http_status;
+        self.grpc_status = grpc_status;
+        self
+    }
+
+    /// Adds headers to the response.
+    pub fn with_headers(mut self, headers: http::HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
     #[inline]
     fn message(&self) -> HeaderValue {
         match self.message {
@@ -260,6 +298,10 @@ impl SyntheticHttpResponse {
             rsp = rsp.header(L5D_PROXY_CONNECTION, "close");
         }
 
+        for (name, value) in &self.headers {
+            rsp = rsp.header(name, value);
+        }
+
         rsp.body(B::default())
             .expect("error response must be valid")
     }
@@ -304,6 +346,10 @@ impl SyntheticHttpResponse {
             rsp = rsp.header(LOCATION, loc);
         }
 
+        for (name, value) in &self.headers {
+            rsp = rsp.header(name, value);
+        }
+
         rsp.body(B::default())
             .expect("error response must be valid")
     }
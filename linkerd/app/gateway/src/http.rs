@@ -124,6 +124,7 @@ impl Gateway {
             .push(svc::ArcNewService::layer())
             // Authorize requests to the gateway.
             .push(self.inbound.authorize_http())
+            .push_on_service(http::BoxResponse::layer())
             .arc_new_clone_http();
 
         self.inbound
@@ -127,9 +127,15 @@ async fn upgraded_request_remains_relative_form() {
                             kind: "authorizationpolicy".into(),
                             name: "testsaz".into(),
                         }),
+                        action: policy::authz::Action::Allow,
+                        audit: false,
                     }]))]),
                 },
                 local_rate_limit: Arc::new(Default::default()),
+                concurrency_limit: None,
+                connection_limit: None,
+                dscp: None,
+                proxy_protocol: false,
             };
             let (policy, tx) = inbound::policy::AllowPolicy::for_test(self.param(), policy);
             tokio::spawn(async move {
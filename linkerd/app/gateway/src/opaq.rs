@@ -77,6 +77,14 @@ impl svc::Param<watch::Receiver<outbound::opaq::Routes>> for Target {
     }
 }
 
+/// Gateway targets are shared by all clients of a gateway address, so they do
+/// not identify a single original destination.
+impl svc::Param<Option<OrigDstAddr>> for Target {
+    fn param(&self) -> Option<OrigDstAddr> {
+        None
+    }
+}
+
 impl PartialEq for Target {
     fn eq(&self, other: &Self) -> bool {
         self.addr == other.addr
@@ -1,10 +1,13 @@
 use crate::{
-    policy::{AllowPolicy, GetPolicy},
+    policy::{self, AllowPolicy, ConnectionKey, ConnectionLimitKey, GetPolicy},
     Inbound,
 };
 use linkerd_app_core::{
     io, svc,
-    transport::addrs::{ClientAddr, OrigDstAddr, Remote},
+    transport::{
+        addrs::{ClientAddr, OrigDstAddr, Remote},
+        SetDscp,
+    },
     Error,
 };
 use std::fmt::Debug;
@@ -32,7 +35,7 @@ impl<N> Inbound<N> {
     where
         T: svc::Param<Remote<ClientAddr>> + svc::Param<OrigDstAddr>,
         T: Clone + Send + 'static,
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice + SetDscp,
         I: Debug + Send + Sync + Unpin + 'static,
         N: svc::NewService<Accept, Service = NSvc> + Clone + Send + Sync + Unpin + 'static,
         NSvc: svc::Service<I, Response = ()>,
@@ -46,6 +49,13 @@ impl<N> Inbound<N> {
     {
         self.map_stack(|cfg, rt, accept| {
             accept
+                .push(policy::NewMarkDscp::layer())
+                // Enforce per-client connection limits that are keyed by IP
+                // address before the connection is processed further.
+                .push(policy::NewConnectionLimit::layer(
+                    ConnectionLimitKey::ClientIp,
+                    rt.metrics.tcp_authz.clone(),
+                ))
                 .push_switch(
                     // Switch to the `direct` stack when a connection's original destination is the
                     // proxy's inbound port. Otherwise, check that connections are allowed on the
@@ -105,6 +115,12 @@ impl svc::Param<AllowPolicy> for Accept {
     }
 }
 
+impl svc::Param<ConnectionKey> for Accept {
+    fn param(&self) -> ConnectionKey {
+        ConnectionKey::Ip(self.client_addr.ip())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,8 +130,11 @@ mod tests {
     };
     use futures::future;
     use linkerd_app_core::svc::{NewService, ServiceExt};
-    use linkerd_proxy_server_policy::{Authentication, Authorization, Meta, ServerPolicy};
-    use std::sync::Arc;
+    use linkerd_proxy_server_policy::{
+        authz, Authentication, Authorization, ConnectionLimit, ConnectionLimitExceeded, Meta,
+        ServerPolicy,
+    };
+    use std::{num::NonZeroU32, sync::Arc};
 
     #[tokio::test(flavor = "current_thread")]
     async fn default_allow() {
@@ -131,6 +150,8 @@ mod tests {
                             kind: "serverauthorization".into(),
                             name: "testsaz".into(),
                         }),
+                        action: authz::Action::Allow,
+                        audit: false,
                     },
                 ])),
                 meta: Arc::new(Meta::Resource {
@@ -139,6 +160,10 @@ mod tests {
                     name: "testsrv".into(),
                 }),
                 local_rate_limit: Default::default(),
+                concurrency_limit: None,
+                connection_limit: None,
+                dscp: None,
+                proxy_protocol: false,
             },
             None,
         );
@@ -167,6 +192,39 @@ mod tests {
             .expect("should succeed");
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn client_ip_connection_limit() {
+        let policies = Store::for_test(
+            ServerPolicy {
+                connection_limit: Some(Arc::new(ConnectionLimit::new(
+                    ConnectionLimitKey::ClientIp,
+                    NonZeroU32::new(1),
+                    None,
+                ))),
+                ..ServerPolicy::invalid(std::time::Duration::from_secs(10))
+            },
+            None,
+        );
+        let svc = inbound()
+            .with_stack(new_ok())
+            .push_accept(999, policies, new_panic("direct stack must not be built"))
+            .into_inner();
+
+        let (io, _) = io::duplex(1);
+        svc.new_service(Target(1000))
+            .oneshot(io)
+            .await
+            .expect("should succeed");
+
+        let (io, _) = io::duplex(1);
+        let error = svc
+            .new_service(Target(1000))
+            .oneshot(io)
+            .await
+            .expect_err("should be limited");
+        assert!(error.is::<ConnectionLimitExceeded>(), "{error}");
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn direct() {
         let policies = Store::for_test(DefaultPolicy::Deny, None);
@@ -1,5 +1,6 @@
 use crate::{
-    policy::{self, AllowPolicy, Protocol, ServerPermit},
+    policy::{self, AllowPolicy, ConnectionKey, ConnectionLimitKey, Protocol, ServerPermit},
+    proxy_protocol::{NewProxyProtocol, ProxyClient},
     Inbound,
 };
 use linkerd_app_core::{
@@ -62,13 +63,13 @@ type TlsIo<I> = tls::server::Io<identity::ServerIo<tls::server::DetectIo<I>>, I>
 // === impl Inbound ===
 
 impl Inbound<svc::ArcNewTcp<Http, io::BoxedIo>> {
-    /// Builds a stack that terminates mesh TLS and detects whether the traffic is HTTP (as hinted
-    /// by policy).
+    /// Builds a stack that reads PROXY protocol headers (on ports configured to expect them),
+    /// terminates mesh TLS, and detects whether the traffic is HTTP (as hinted by policy).
     pub(crate) fn push_detect<T, I, F, FSvc>(self, forward: F) -> Inbound<svc::ArcNewTcp<T, I>>
     where
         T: svc::Param<OrigDstAddr> + svc::Param<Remote<ClientAddr>> + svc::Param<AllowPolicy>,
         T: Clone + Send + 'static,
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
         I: Debug + Send + Sync + Unpin + 'static,
         F: svc::NewService<Forward, Service = FSvc> + Clone + Send + Sync + Unpin + 'static,
         FSvc: svc::Service<io::BoxedIo, Response = ()> + Send + 'static,
@@ -77,6 +78,7 @@ impl Inbound<svc::ArcNewTcp<Http, io::BoxedIo>> {
     {
         self.push_detect_http(forward.clone())
             .push_detect_tls(forward)
+            .push_proxy_protocol()
     }
 
     /// Builds a stack that handles HTTP detection once TLS detection has been performed. If the
@@ -84,7 +86,7 @@ impl Inbound<svc::ArcNewTcp<Http, io::BoxedIo>> {
     /// passed to the provided 'forward' stack.
     fn push_detect_http<I, F, FSvc>(self, forward: F) -> Inbound<svc::ArcNewTcp<Tls, I>>
     where
-        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Splice,
         I: Debug + Send + Sync + Unpin + 'static,
         F: svc::NewService<Forward, Service = FSvc> + Clone + Send + Sync + Unpin + 'static,
         FSvc: svc::Service<io::BoxedIo, Response = ()> + Send + 'static,
@@ -98,7 +100,11 @@ impl Inbound<svc::ArcNewTcp<Http, io::BoxedIo>> {
                     rt.metrics.proxy.transport.clone(),
                 ))
                 .push_map_target(Forward::from)
-                .push(policy::NewTcpPolicy::layer(rt.metrics.tcp_authz.clone()))
+                .push(policy::NewTcpPolicy::layer(
+                    rt.metrics.tcp_authz.clone(),
+                    rt.authz_audit.clone(),
+                    rt.policy_learner.clone(),
+                ))
                 .arc_new_tcp();
 
             let detect_timeout = cfg.proxy.detect_protocol_timeout;
@@ -195,7 +201,7 @@ impl<I> Inbound<svc::ArcNewTcp<Tls, TlsIo<I>>> {
     where
         T: svc::Param<OrigDstAddr> + svc::Param<Remote<ClientAddr>> + svc::Param<AllowPolicy>,
         T: Clone + Send + 'static,
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
         I: Debug + Send + Sync + Unpin + 'static,
         F: svc::NewService<Forward, Service = FSvc> + Clone + Send + Sync + Unpin + 'static,
         FSvc: svc::Service<io::BoxedIo, Response = ()> + Send + 'static,
@@ -209,11 +215,25 @@ impl<I> Inbound<svc::ArcNewTcp<Tls, TlsIo<I>>> {
                     rt.metrics.proxy.transport.clone(),
                 ))
                 .push_map_target(Forward::from)
-                .push(policy::NewTcpPolicy::layer(rt.metrics.tcp_authz.clone()))
+                .push(policy::NewTcpPolicy::layer(
+                    rt.metrics.tcp_authz.clone(),
+                    rt.authz_audit.clone(),
+                    rt.policy_learner.clone(),
+                ))
+                // Enforce per-client connection limits that are keyed by
+                // client identity once TLS has been terminated.
+                .push(policy::NewConnectionLimit::layer(
+                    ConnectionLimitKey::ClientIdentity,
+                    rt.metrics.tcp_authz.clone(),
+                ))
                 .arc_new_tcp();
 
             let detect_timeout = cfg.proxy.detect_protocol_timeout;
             detect
+                .push(policy::NewConnectionLimit::layer(
+                    ConnectionLimitKey::ClientIdentity,
+                    rt.metrics.tcp_authz.clone(),
+                ))
                 .push_switch(
                     // Ensure that the connection is authorized before proceeding with protocol
                     // detection.
@@ -277,6 +297,41 @@ impl<I> Inbound<svc::ArcNewTcp<Tls, TlsIo<I>>> {
     }
 }
 
+impl<T, I> Inbound<svc::ArcNewTcp<ProxyClient<T>, io::PrefixedIo<I>>> {
+    /// Builds a stack that reads a PROXY protocol header from connections that are configured to
+    /// expect one, so that the connection is attributed to the original client rather than to
+    /// the load balancer that proxied it.
+    fn push_proxy_protocol(self) -> Inbound<svc::ArcNewTcp<T, I>>
+    where
+        T: svc::Param<OrigDstAddr> + svc::Param<Remote<ClientAddr>> + svc::Param<AllowPolicy>,
+        T: Clone + Send + 'static,
+        I: io::AsyncRead + Send + Unpin + 'static,
+    {
+        self.map_stack(|cfg, rt, detect| {
+            detect
+                .clone()
+                // Per-client connection limits keyed by IP address are enforced on the socket's
+                // peer when the connection is accepted. When the client's address is read from a
+                // PROXY protocol header, the limit is enforced again on the original client.
+                .push(policy::NewConnectionLimit::layer(
+                    ConnectionLimitKey::ClientIp,
+                    rt.metrics.tcp_authz.clone(),
+                ))
+                .push_switch(
+                    |client: ProxyClient<T>| -> Result<_, Infallible> {
+                        if client.is_proxied() {
+                            return Ok(svc::Either::A(client));
+                        }
+                        Ok(svc::Either::B(client))
+                    },
+                    detect.into_inner(),
+                )
+                .push(NewProxyProtocol::layer(cfg.proxy_protocol.clone()))
+                .arc_new_tcp()
+        })
+    }
+}
+
 // === impl Forward ===
 
 impl From<(ServerPermit, Tls)> for Forward {
@@ -296,6 +351,12 @@ impl svc::Param<Remote<ServerAddr>> for Forward {
     }
 }
 
+impl svc::Param<Remote<ClientAddr>> for Forward {
+    fn param(&self) -> Remote<ClientAddr> {
+        self.client_addr
+    }
+}
+
 impl svc::Param<transport::labels::Key> for Forward {
     fn param(&self) -> transport::labels::Key {
         transport::labels::Key::inbound_server(
@@ -314,6 +375,19 @@ impl svc::Param<AllowPolicy> for Tls {
     }
 }
 
+impl svc::Param<ConnectionKey> for Tls {
+    fn param(&self) -> ConnectionKey {
+        // Clients without a mesh identity are attributed to their IP address.
+        match self.status {
+            tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+                client_id: Some(tls::ClientId(ref id)),
+                ..
+            }) => ConnectionKey::Identity(id.clone()),
+            _ => ConnectionKey::Ip(self.client_addr.ip()),
+        }
+    }
+}
+
 impl svc::Param<OrigDstAddr> for Tls {
     fn param(&self) -> OrigDstAddr {
         self.orig_dst_addr
@@ -1,13 +1,17 @@
 use super::*;
+use crate::proxy_protocol::ProxyProtocolError;
 use crate::test_util;
 use futures::future;
 use linkerd_app_core::{
     io::AsyncWriteExt,
     svc::{NewService, ServiceExt},
-    trace,
+    trace, IpMatch, IpNet,
 };
-use linkerd_proxy_server_policy::{Authentication, Authorization, Meta, ServerPolicy};
-use std::sync::Arc;
+use linkerd_proxy_server_policy::{
+    authz, Authentication, Authorization, ConnectionLimit, ConnectionLimitExceeded, Meta,
+    ServerPolicy,
+};
+use std::{num::NonZeroU32, sync::Arc};
 
 const HTTP1: &[u8] = b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n";
 const HTTP2: &[u8] = b"PRI * HTTP/2.0\r\n";
@@ -22,6 +26,8 @@ fn authzs() -> Arc<[Authorization]> {
             kind: "authorizationpolicy".into(),
             name: "testsaz".into(),
         }),
+        action: authz::Action::Allow,
+        audit: false,
     }])
 }
 
@@ -36,6 +42,10 @@ fn allow(protocol: Protocol) -> AllowPolicy {
                 name: "testsrv".into(),
             }),
             local_rate_limit: Arc::new(Default::default()),
+            concurrency_limit: None,
+            connection_limit: None,
+            dscp: None,
+            proxy_protocol: false,
         },
     );
     allow
@@ -195,6 +205,186 @@ async fn hinted_http2() {
         .expect("should succeed");
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_protocol_client_addr() {
+    let _trace = trace::test::trace_init();
+
+    let (ior, mut iow) = io::duplex(100);
+    iow.write_all(b"PROXY TCP4 198.51.100.7 192.0.2.2 40000 1000\r\n")
+        .await
+        .unwrap();
+    iow.write_all(HTTP1).await.unwrap();
+
+    inbound_proxy_protocol()
+        .with_stack(new_proxy_client(|client_addr| {
+            assert_eq!(
+                client_addr,
+                Remote(ClientAddr(([198, 51, 100, 7], 40000).into()))
+            );
+        }))
+        .push_proxy_protocol()
+        .into_inner()
+        .new_service(Target(allow(Protocol::Opaque(authzs()))))
+        .oneshot(ior)
+        .await
+        .expect("should succeed");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_protocol_disabled_port() {
+    let _trace = trace::test::trace_init();
+
+    let (ior, mut iow) = io::duplex(100);
+    iow.write_all(HTTP1).await.unwrap();
+
+    inbound()
+        .with_stack(new_proxy_client(|client| assert_eq!(client, client_addr())))
+        .push_proxy_protocol()
+        .into_inner()
+        .new_service(Target(allow(Protocol::Opaque(authzs()))))
+        .oneshot(ior)
+        .await
+        .expect("should succeed");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_protocol_server_policy() {
+    let _trace = trace::test::trace_init();
+
+    // The server's policy enables the PROXY protocol on a port that is not
+    // configured to expect it.
+    let mut inbound = inbound_proxy_protocol();
+    inbound.config.proxy_protocol.ports = Default::default();
+    let (policy, _tx) = AllowPolicy::for_test(
+        orig_dst_addr(),
+        ServerPolicy {
+            proxy_protocol: true,
+            ..ServerPolicy::invalid(std::time::Duration::from_secs(10))
+        },
+    );
+
+    let (ior, mut iow) = io::duplex(100);
+    iow.write_all(b"PROXY TCP4 198.51.100.7 192.0.2.2 40000 1000\r\n")
+        .await
+        .unwrap();
+
+    inbound
+        .with_stack(new_proxy_client(|client_addr| {
+            assert_eq!(
+                client_addr,
+                Remote(ClientAddr(([198, 51, 100, 7], 40000).into()))
+            );
+        }))
+        .push_proxy_protocol()
+        .into_inner()
+        .new_service(Target(policy))
+        .oneshot(ior)
+        .await
+        .expect("should succeed");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_protocol_untrusted_client() {
+    let _trace = trace::test::trace_init();
+
+    let mut inbound = inbound_proxy_protocol();
+    inbound.config.proxy_protocol.trusted_networks =
+        IpMatch::new(Some(IpNet::from(std::net::IpAddr::from([198, 51, 100, 1]))));
+
+    let (ior, mut iow) = io::duplex(100);
+    iow.write_all(b"PROXY TCP4 198.51.100.7 192.0.2.2 40000 1000\r\n")
+        .await
+        .unwrap();
+
+    let error = inbound
+        .with_stack(new_proxy_client(|_| panic!("inner stack must not be used")))
+        .push_proxy_protocol()
+        .into_inner()
+        .new_service(Target(allow(Protocol::Opaque(authzs()))))
+        .oneshot(ior)
+        .await
+        .expect_err("should be rejected");
+    assert!(
+        matches!(
+            error.downcast_ref::<ProxyProtocolError>(),
+            Some(ProxyProtocolError::Untrusted(_))
+        ),
+        "{error}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_protocol_missing_header() {
+    let _trace = trace::test::trace_init();
+
+    let (ior, mut iow) = io::duplex(100);
+    iow.write_all(HTTP1).await.unwrap();
+
+    let error = inbound_proxy_protocol()
+        .with_stack(new_proxy_client(|_| panic!("inner stack must not be used")))
+        .push_proxy_protocol()
+        .into_inner()
+        .new_service(Target(allow(Protocol::Opaque(authzs()))))
+        .oneshot(ior)
+        .await
+        .expect_err("should fail");
+    assert!(
+        matches!(
+            error.downcast_ref::<ProxyProtocolError>(),
+            Some(ProxyProtocolError::Missing)
+        ),
+        "{error}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_protocol_client_ip_connection_limit() {
+    let _trace = trace::test::trace_init();
+
+    let (policy, _tx) = AllowPolicy::for_test(
+        orig_dst_addr(),
+        ServerPolicy {
+            connection_limit: Some(Arc::new(ConnectionLimit::new(
+                ConnectionLimitKey::ClientIp,
+                NonZeroU32::new(1),
+                None,
+            ))),
+            ..ServerPolicy::invalid(std::time::Duration::from_secs(10))
+        },
+    );
+    let svc = inbound_proxy_protocol()
+        .with_stack(new_proxy_client(|_| {}))
+        .push_proxy_protocol()
+        .into_inner();
+
+    // Connections from the same load balancer are limited according to the
+    // client address in their PROXY protocol headers.
+    for (header, limited) in [
+        (
+            &b"PROXY TCP4 198.51.100.7 192.0.2.2 40000 1000\r\n"[..],
+            false,
+        ),
+        (
+            &b"PROXY TCP4 198.51.100.8 192.0.2.2 40000 1000\r\n"[..],
+            false,
+        ),
+        (
+            &b"PROXY TCP4 198.51.100.7 192.0.2.2 40001 1000\r\n"[..],
+            true,
+        ),
+    ] {
+        let (ior, mut iow) = io::duplex(100);
+        iow.write_all(header).await.unwrap();
+        let res = svc.new_service(Target(policy.clone())).oneshot(ior).await;
+        if limited {
+            let error = res.expect_err("should be limited");
+            assert!(error.is::<ConnectionLimitExceeded>(), "{error}");
+        } else {
+            res.expect("should succeed");
+        }
+    }
+}
+
 fn client_id() -> tls::ClientId {
     "testsa.testns.serviceaccount.identity.linkerd.cluster.local"
         .parse()
@@ -213,6 +403,14 @@ fn inbound() -> Inbound<()> {
     Inbound::new(test_util::default_config(), test_util::runtime().0)
 }
 
+fn inbound_proxy_protocol() -> Inbound<()> {
+    let mut config = test_util::default_config();
+    config.proxy_protocol.ports =
+        std::iter::once(orig_dst_addr().port()..=orig_dst_addr().port()).collect();
+    config.proxy_protocol.trusted_networks = IpMatch::new(Some(IpNet::from(client_addr().ip())));
+    Inbound::new(config, test_util::runtime().0)
+}
+
 fn new_panic<T, I: 'static>(msg: &'static str) -> svc::ArcNewTcp<T, I> {
     svc::ArcNewService::new(move |_| -> svc::BoxTcp<I> { panic!("{}", msg) })
 }
@@ -221,6 +419,16 @@ fn new_ok<T>() -> svc::ArcNewTcp<T, io::BoxedIo> {
     svc::ArcNewService::new(|_| svc::BoxService::new(svc::mk(|_| future::ok::<(), Error>(()))))
 }
 
+/// Builds a stack that passes the client address of each connection to `f`.
+fn new_proxy_client<T, I: Send + 'static>(
+    f: impl Fn(Remote<ClientAddr>) + Send + Sync + 'static,
+) -> svc::ArcNewTcp<ProxyClient<T>, I> {
+    svc::ArcNewService::new(move |target: ProxyClient<T>| {
+        f(svc::Param::param(&target));
+        svc::BoxService::new(svc::mk(|_| future::ok::<(), Error>(())))
+    })
+}
+
 #[derive(Clone, Debug)]
 struct Target(AllowPolicy);
 
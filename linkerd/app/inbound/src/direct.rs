@@ -39,6 +39,7 @@ pub(crate) struct LocalTcp {
 #[derive(Debug, Clone)]
 pub(crate) struct AuthorizedLocalTcp {
     addr: Remote<ServerAddr>,
+    client_addr: Remote<ClientAddr>,
     client_id: tls::ClientId,
     permit: policy::ServerPermit,
 }
@@ -98,7 +99,7 @@ impl<N> Inbound<N> {
     where
         T: Param<Remote<ClientAddr>> + Param<OrigDstAddr>,
         T: Clone + Send + 'static,
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
         I: Debug + Send + Sync + Unpin + 'static,
         N: svc::NewService<AuthorizedLocalTcp, Service = NSvc>,
         N: Clone + Send + Sync + Unpin + 'static,
@@ -123,12 +124,17 @@ impl<N> Inbound<N> {
                 .push_map_target(|(permit, tcp): (policy::ServerPermit, LocalTcp)| {
                     AuthorizedLocalTcp {
                         addr: tcp.server_addr,
+                        client_addr: tcp.client_addr,
                         client_id: tcp.client_id,
                         permit,
                     }
                 })
                 .check_new_service::<(policy::ServerPermit, LocalTcp), _>()
-                .push(policy::NewTcpPolicy::layer(rt.metrics.tcp_authz.clone()))
+                .push(policy::NewTcpPolicy::layer(
+                    rt.metrics.tcp_authz.clone(),
+                    rt.authz_audit.clone(),
+                    rt.policy_learner.clone(),
+                ))
                 .instrument(|_: &_| debug_span!("opaq"))
                 .check_new_service::<LocalTcp, _>()
                 // When the transport header is present, it may be used for either local TCP
@@ -295,6 +301,12 @@ impl Param<Remote<ServerAddr>> for AuthorizedLocalTcp {
     }
 }
 
+impl Param<Remote<ClientAddr>> for AuthorizedLocalTcp {
+    fn param(&self) -> Remote<ClientAddr> {
+        self.client_addr
+    }
+}
+
 impl Param<transport::labels::Key> for AuthorizedLocalTcp {
     fn param(&self) -> transport::labels::Key {
         transport::labels::Key::inbound_server(
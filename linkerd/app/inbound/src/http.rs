@@ -220,6 +220,8 @@ pub mod fuzz {
                                     kind: "server".into(),
                                     name: "testsaz".into(),
                                 }),
+                                action: linkerd_proxy_server_policy::authz::Action::Allow,
+                                audit: false,
                             },
                         ])),
                     ])),
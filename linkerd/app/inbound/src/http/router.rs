@@ -14,6 +14,7 @@ use tracing::{debug, debug_span};
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Http {
     addr: Remote<ServerAddr>,
+    source: Option<transport::TransparentSource>,
     params: http::client::Params,
     permit: policy::HttpRoutePermit,
 }
@@ -22,6 +23,7 @@ pub struct Http {
 #[derive(Clone, Debug)]
 struct LogicalPerRequest {
     server: Remote<ServerAddr>,
+    source: Option<transport::TransparentSource>,
     tls: tls::ConditionalServerTls,
     permit: policy::HttpRoutePermit,
     labels: tap::Labels,
@@ -33,6 +35,9 @@ struct Logical {
     /// The request's logical destination. Used for profile discovery.
     logical: Option<NameAddr>,
     addr: Remote<ServerAddr>,
+    /// Set when connections to the application originate from the client's
+    /// address, so that clients are not shared across client addresses.
+    source: Option<transport::TransparentSource>,
     http: http::Version,
     tls: tls::ConditionalServerTls,
     permit: policy::HttpRoutePermit,
@@ -83,6 +88,7 @@ impl<C> Inbound<C> {
     {
         self.map_stack(|config, rt, connect| {
             let allow_profile = config.allow_discovery.clone();
+            let transparent_source = config.transparent_source;
             let h1_params = config.proxy.connect.http1;
             let h2_params = config.proxy.connect.http2.clone();
 
@@ -103,6 +109,7 @@ impl<C> Inbound<C> {
                 .push_map_target(move |t: Logical| {
                     Http {
                         addr: t.addr,
+                        source: t.source,
                         permit: t.permit,
                         params: match t.http {
                             http::Version::Http1 => http::client::Params::Http1(h1_params),
@@ -235,12 +242,19 @@ impl<C> Inbound<C> {
                 .lift_new()
                 .check_new_new::<(policy::HttpRoutePermit, T), Logical>()
                 .push(svc::ArcNewService::layer())
-                .push(svc::NewOneshotRoute::layer_via(|(permit, t): &(policy::HttpRoutePermit, T)| {
-                    LogicalPerRequest::from((permit.clone(), t.clone()))
+                .push(svc::NewOneshotRoute::layer_via(move |(permit, t): &(policy::HttpRoutePermit, T)| {
+                    LogicalPerRequest::new(permit.clone(), t, transparent_source)
                 }))
                 .check_new_service::<(policy::HttpRoutePermit, T), http::Request<http::BoxBody>>()
                 .push(svc::ArcNewService::layer())
-                .push(policy::NewHttpPolicy::layer(rt.metrics.http_authz.clone()))
+                .push(policy::NewHttpPolicy::layer(
+                    rt.metrics.http_authz.clone(),
+                    rt.global_rate_limit.clone(),
+                    rt.ext_authz.clone(),
+                    rt.authz_audit.clone(),
+                    rt.policy_learner.clone(),
+                ))
+                .push_on_service(http::BoxResponse::layer())
                 // Used by tap.
                 .push_http_insert_target::<tls::ConditionalServerTls>()
                 .push_http_insert_target::<Remote<ClientAddr>>()
@@ -251,12 +265,13 @@ impl<C> Inbound<C> {
 
 // === impl LogicalPerRequest ===
 
-impl<T> From<(policy::HttpRoutePermit, T)> for LogicalPerRequest
-where
-    T: Param<Remote<ServerAddr>>,
-    T: Param<tls::ConditionalServerTls>,
-{
-    fn from((permit, t): (policy::HttpRoutePermit, T)) -> Self {
+impl LogicalPerRequest {
+    fn new<T>(permit: policy::HttpRoutePermit, t: &T, transparent_source: bool) -> Self
+    where
+        T: Param<Remote<ServerAddr>>,
+        T: Param<Remote<ClientAddr>>,
+        T: Param<tls::ConditionalServerTls>,
+    {
         let labels = [
             ("srv", &permit.labels.route.server.0),
             ("route", &permit.labels.route.route),
@@ -272,8 +287,14 @@ where
         })
         .collect::<std::collections::BTreeMap<_, _>>();
 
+        let source = transparent_source.then(|| {
+            let Remote(ClientAddr(client)) = t.param();
+            transport::TransparentSource(client.ip())
+        });
+
         Self {
             server: t.param(),
+            source,
             tls: t.param(),
             permit,
             labels: labels.into(),
@@ -310,6 +331,7 @@ impl<B> svc::router::SelectRoute<http::Request<B>> for LogicalPerRequest {
         Ok(Logical {
             logical,
             addr: self.server,
+            source: self.source,
             tls: self.tls.clone(),
             permit: self.permit.clone(),
             // Use the request's HTTP version (i.e. as modified by orig-proto downgrading).
@@ -442,6 +464,12 @@ impl Param<Remote<ServerAddr>> for Http {
     }
 }
 
+impl Param<Option<transport::TransparentSource>> for Http {
+    fn param(&self) -> Option<transport::TransparentSource> {
+        self.source
+    }
+}
+
 impl Param<http::client::Params> for Http {
     fn param(&self) -> http::client::Params {
         self.params.clone()
@@ -203,8 +203,61 @@ impl errors::HttpRescue<Error> for ServerRescue {
             ));
         }
 
-        if errors::is_caused_by::<linkerd_proxy_server_policy::RateLimitError>(&*error) {
-            return Ok(errors::SyntheticHttpResponse::rate_limited(error));
+        if let Some(limited) =
+            errors::cause_ref::<linkerd_proxy_server_policy::RateLimited>(&*error)
+        {
+            let linkerd_proxy_server_policy::Rejection {
+                http_status,
+                grpc_code,
+            } = limited.rejection;
+            let headers = limited.headers();
+            return Ok(errors::SyntheticHttpResponse::rate_limited(error)
+                .with_status(http_status, grpc_code)
+                .with_headers(headers));
+        }
+
+        if let Some(policy::GlobalRateLimited { retry_after }) =
+            errors::cause_ref::<policy::GlobalRateLimited>(&*error)
+        {
+            let mut headers = http::HeaderMap::new();
+            if let Some(retry_after) = retry_after {
+                // Round up so that clients do not retry before the limit resets.
+                let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                headers.insert(http::header::RETRY_AFTER, secs.into());
+            }
+            return Ok(errors::SyntheticHttpResponse::rate_limited(error).with_headers(headers));
+        }
+        if errors::is_caused_by::<linkerd_proxy_server_policy::ConcurrencyLimitExceeded>(&*error) {
+            // The connection is left open, since other requests on it may be
+            // admitted once capacity is available.
+            return Ok(
+                errors::SyntheticHttpResponse::rate_limited(error).with_status(
+                    http::StatusCode::SERVICE_UNAVAILABLE,
+                    tonic::Code::ResourceExhausted,
+                ),
+            );
+        }
+        if errors::is_caused_by::<policy::GlobalRateLimitUnavailable>(&*error) {
+            return Ok(errors::SyntheticHttpResponse::unavailable(error));
+        }
+
+        if let Some(policy::ExtAuthzDenied { status, headers }) =
+            errors::cause_ref::<policy::ExtAuthzDenied>(&*error)
+        {
+            let code = if *status == http::StatusCode::UNAUTHORIZED {
+                tonic::Code::Unauthenticated
+            } else {
+                tonic::Code::PermissionDenied
+            };
+            let (status, headers) = (*status, headers.clone());
+            return Ok(errors::SyntheticHttpResponse::permission_denied(error)
+                .with_status(status, code)
+                .with_headers(headers));
+        }
+        if errors::is_caused_by::<policy::ExtAuthzUnavailable>(&*error) {
+            // As with Envoy, requests are denied when the authorization service
+            // fails and the proxy is configured to fail closed.
+            return Ok(errors::SyntheticHttpResponse::permission_denied(error));
         }
 
         if errors::is_caused_by::<crate::GatewayDomainInvalid>(&*error) {
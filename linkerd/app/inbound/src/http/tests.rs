@@ -736,6 +736,8 @@ impl svc::Param<policy::AllowPolicy> for Target {
                 kind: "serverauthorization".into(),
                 name: "testsaz".into(),
             }),
+            action: linkerd_proxy_server_policy::authz::Action::Allow,
+            audit: false,
         }]);
         let (policy, _) = policy::AllowPolicy::for_test(
             self.param(),
@@ -749,6 +751,10 @@ impl svc::Param<policy::AllowPolicy> for Target {
                     name: "testsrv".into(),
                 }),
                 local_rate_limit: Default::default(),
+                concurrency_limit: None,
+                connection_limit: None,
+                dscp: None,
+                proxy_protocol: false,
             },
         );
         policy
@@ -13,6 +13,7 @@ pub mod direct;
 mod http;
 mod metrics;
 pub mod policy;
+pub mod proxy_protocol;
 mod server;
 
 #[cfg(any(test, feature = "test-util", fuzzing))]
@@ -31,7 +32,7 @@ use linkerd_app_core::{
     transport::{self, Remote, ServerAddr},
     Error, NameAddr, NameMatch, ProxyRuntime,
 };
-use std::{fmt::Debug, time::Duration};
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 use thiserror::Error;
 use tracing::debug_span;
 
@@ -52,6 +53,20 @@ pub struct Config {
 
     /// Configures how HTTP requests are buffered *for each inbound port*.
     pub http_request_queue: QueueConfig,
+
+    /// Configures the ports on which connections are prefaced by a PROXY
+    /// protocol header.
+    pub proxy_protocol: proxy_protocol::Config,
+
+    /// Maps application ports to Unix domain sockets to which inbound
+    /// connections for that port are forwarded instead of the loopback TCP
+    /// address.
+    pub unix_sockets: HashMap<u16, transport::UnixAddr>,
+
+    /// When set, connections to the application originate from each client's
+    /// IP address (bound with `IP_TRANSPARENT`) rather than from the proxy's,
+    /// so that the application observes its clients' addresses.
+    pub transparent_source: bool,
 }
 
 #[derive(Clone)]
@@ -68,6 +83,10 @@ struct Runtime {
     tap: tap::Registry,
     span_sink: Option<SpanSink>,
     drain: drain::Watch,
+    global_rate_limit: Option<policy::GlobalRateLimitClient>,
+    ext_authz: Option<policy::ExtAuthzClient>,
+    authz_audit: Option<policy::AuditSink>,
+    policy_learner: Option<policy::PolicyLearner>,
 }
 
 /// Indicates the name to be used to route gateway connections.
@@ -115,14 +134,24 @@ impl<S> Inbound<S> {
     pub fn authorize_http<N>(
         &self,
     ) -> impl svc::layer::Layer<N, Service = policy::NewHttpPolicy<N>> + Clone {
-        policy::NewHttpPolicy::layer(self.runtime.metrics.http_authz.clone())
+        policy::NewHttpPolicy::layer(
+            self.runtime.metrics.http_authz.clone(),
+            self.runtime.global_rate_limit.clone(),
+            self.runtime.ext_authz.clone(),
+            self.runtime.authz_audit.clone(),
+            self.runtime.policy_learner.clone(),
+        )
     }
 
     /// A helper for gateways to instrument policy checks.
     pub fn authorize_tcp<N>(
         &self,
     ) -> impl svc::layer::Layer<N, Service = policy::NewTcpPolicy<N>> + Clone {
-        policy::NewTcpPolicy::layer(self.runtime.metrics.tcp_authz.clone())
+        policy::NewTcpPolicy::layer(
+            self.runtime.metrics.tcp_authz.clone(),
+            self.runtime.authz_audit.clone(),
+            self.runtime.policy_learner.clone(),
+        )
     }
 
     pub fn into_stack(self) -> svc::Stack<S> {
@@ -155,6 +184,10 @@ impl Inbound<()> {
             tap: runtime.tap,
             span_sink: runtime.span_sink,
             drain: runtime.drain,
+            global_rate_limit: None,
+            ext_authz: None,
+            authz_audit: None,
+            policy_learner: None,
         };
         Self {
             config,
@@ -174,6 +207,33 @@ impl Inbound<()> {
         self.runtime.metrics.clone()
     }
 
+    /// Configures HTTP routes to consult an external rate limit service when
+    /// their policies configure a global rate limit.
+    pub fn with_global_rate_limit(mut self, client: policy::GlobalRateLimitClient) -> Self {
+        self.runtime.global_rate_limit = Some(client);
+        self
+    }
+
+    /// Configures HTTP routes to consult an external authorization service
+    /// when their policies configure external authorization.
+    pub fn with_ext_authz(mut self, client: policy::ExtAuthzClient) -> Self {
+        self.runtime.ext_authz = Some(client);
+        self
+    }
+
+    /// Records authorization decisions to an audit event stream.
+    pub fn with_authz_audit(mut self, sink: policy::AuditSink) -> Self {
+        self.runtime.authz_audit = Some(sink);
+        self
+    }
+
+    /// Records the clients permitted by each server and route so that
+    /// authorizations can be suggested for them.
+    pub fn with_policy_learner(mut self, learner: policy::PolicyLearner) -> Self {
+        self.runtime.policy_learner = Some(learner);
+        self
+    }
+
     pub fn with_stack<S>(self, stack: S) -> Inbound<S> {
         self.map_stack(move |_, _, _| svc::stack(stack))
     }
@@ -193,7 +253,9 @@ impl Inbound<()> {
             > + Clone,
     >
     where
-        T: svc::Param<Remote<ServerAddr>> + 'static,
+        T: svc::Param<Remote<ServerAddr>>,
+        T: svc::Param<Option<transport::TransparentSource>>,
+        T: 'static,
     {
         self.map_stack(|config, _, _| {
             // Establishes connections to remote peers (for both TCP
@@ -209,18 +271,53 @@ impl Inbound<()> {
             #[error("inbound connection must not target port {0}")]
             struct Loop(u16);
 
-            svc::stack(transport::ConnectTcp::new(*keepalive, *user_timeout))
-                // Limits the time we wait for a connection to be established.
-                .push_connect_timeout(*timeout)
-                // Prevent connections that would target the inbound proxy port from looping.
-                .push_filter(move |t: T| {
-                    let addr = t.param();
-                    let port = addr.port();
-                    if port == proxy_port {
-                        return Err(Loop(port));
-                    }
-                    Ok(addr)
+            #[derive(Clone, Debug)]
+            struct AppAddr {
+                addr: Remote<ServerAddr>,
+                unix: Option<transport::UnixAddr>,
+                source: Option<transport::TransparentSource>,
+            }
+
+            impl svc::Param<Remote<ServerAddr>> for AppAddr {
+                fn param(&self) -> Remote<ServerAddr> {
+                    self.addr
+                }
+            }
+
+            impl svc::Param<Option<transport::UnixAddr>> for AppAddr {
+                fn param(&self) -> Option<transport::UnixAddr> {
+                    self.unix.clone()
+                }
+            }
+
+            impl svc::Param<Option<transport::TransparentSource>> for AppAddr {
+                fn param(&self) -> Option<transport::TransparentSource> {
+                    self.source
+                }
+            }
+
+            let unix_sockets = Arc::new(config.unix_sockets.clone());
+            svc::stack(transport::ConnectUnix::new(
+                transport::ConnectTransparent::new(*keepalive, *user_timeout),
+            ))
+            // Limits the time we wait for a connection to be established.
+            .push_connect_timeout(*timeout)
+            // Prevent connections that would target the inbound proxy port from looping.
+            .push_filter(move |t: T| {
+                let addr: Remote<ServerAddr> = t.param();
+                let port = addr.port();
+                if port == proxy_port {
+                    return Err(Loop(port));
+                }
+                // Ports that the application serves on a Unix domain socket
+                // are forwarded to the socket rather than the TCP address.
+                let unix = unix_sockets.get(&port).cloned();
+                Ok(AppAddr {
+                    addr,
+                    unix,
+                    source: t.param(),
                 })
+            })
         })
     }
 }
@@ -248,10 +345,10 @@ impl<S> Inbound<S> {
             + Send
             + Sync
             + 'static,
-        I: io::AsyncRead + io::AsyncWrite,
+        I: io::AsyncRead + io::AsyncWrite + io::Splice,
         I: Debug + Send + Unpin + 'static,
         S: svc::MakeConnection<T> + Clone + Send + Sync + Unpin + 'static,
-        S::Connection: Send + Unpin,
+        S::Connection: io::Splice + Send + Unpin,
         S::Metadata: Send + Unpin,
         S::Future: Send,
     {
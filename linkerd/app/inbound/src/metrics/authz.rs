@@ -1,4 +1,4 @@
-use crate::policy::{AllowPolicy, HttpRoutePermit, ServerPermit};
+use crate::policy::{AllowPolicy, ConnectionLimitExceeded, HttpRoutePermit, ServerPermit};
 use linkerd_app_core::{
     metrics::{
         metrics, Counter, FmtLabels, FmtMetrics, RouteAuthzLabels, RouteLabels, ServerAuthzLabels,
@@ -7,8 +7,9 @@ use linkerd_app_core::{
     tls,
     transport::OrigDstAddr,
 };
+use linkerd_proxy_server_policy::{authz::jwt::JwtError, Meta};
 use parking_lot::Mutex;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 metrics! {
     inbound_http_authz_allow_total: Counter {
@@ -20,6 +21,15 @@ metrics! {
     inbound_http_route_not_found_total: Counter {
         "The total number of inbound HTTP requests that could not be associated with a route"
     },
+    inbound_http_authz_audit_total: Counter {
+        "The total number of inbound HTTP requests that would have been denied if not for an audit-mode authorization"
+    },
+    inbound_http_authz_jwt_failure_total: Counter {
+        "The total number of inbound HTTP requests that were denied because their JWT could not be validated"
+    },
+    inbound_http_concurrency_limit_rejected_total: Counter {
+        "The total number of inbound HTTP requests that were rejected by a server or route concurrency limit"
+    },
 
     inbound_tcp_authz_allow_total: Counter {
         "The total number of inbound TCP connections that were authorized"
@@ -27,8 +37,14 @@ metrics! {
     inbound_tcp_authz_deny_total: Counter {
         "The total number of inbound TCP connections that were denied"
     },
+    inbound_tcp_authz_audit_total: Counter {
+        "The total number of inbound TCP connections that would have been denied if not for an audit-mode authorization"
+    },
     inbound_tcp_authz_terminate_total: Counter {
         "The total number of inbound TCP connections that were terminated due to an authorization change"
+    },
+    inbound_tcp_connection_limit_rejected_total: Counter {
+        "The total number of inbound TCP connections that were rejected by a per-client connection limit"
     }
 }
 
@@ -43,13 +59,18 @@ struct HttpInner {
     allow: Mutex<HashMap<RouteAuthzKey, Counter>>,
     deny: Mutex<HashMap<RouteKey, Counter>>,
     route_not_found: Mutex<HashMap<ServerKey, Counter>>,
+    audit: Mutex<HashMap<RouteAuthzKey, Counter>>,
+    jwt_failure: Mutex<HashMap<JwtFailureKey, Counter>>,
+    concurrency_limit_rejected: Mutex<HashMap<ConcurrencyLimitKey, Counter>>,
 }
 
 #[derive(Debug, Default)]
 struct TcpInner {
     allow: Mutex<HashMap<ServerAuthzKey, Counter>>,
     deny: Mutex<HashMap<ServerKey, Counter>>,
+    audit: Mutex<HashMap<ServerAuthzKey, Counter>>,
     terminate: Mutex<HashMap<ServerKey, Counter>>,
+    connection_limit_rejected: Mutex<HashMap<ConnectionLimitedKey, Counter>>,
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -63,6 +84,34 @@ type ServerKey = Key<ServerLabel>;
 type ServerAuthzKey = Key<ServerAuthzLabels>;
 type RouteKey = Key<RouteLabels>;
 type RouteAuthzKey = Key<RouteAuthzLabels>;
+type JwtFailureKey = Key<(RouteLabels, JwtFailureReason)>;
+type ConcurrencyLimitKey = Key<(RouteLabels, ConcurrencyLimitScope)>;
+
+/// Connection limits may be enforced before TLS is terminated, so these
+/// metrics are not labeled with the connection's TLS status.
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct ConnectionLimitedKey {
+    target: TargetAddr,
+    server: ServerLabel,
+    reason: ConnectionLimitReason,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct JwtFailureReason(JwtError);
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+enum ConnectionLimitReason {
+    Rate,
+    Concurrency,
+}
+
+/// Identifies whether a request was rejected by its server's or its route's
+/// concurrency limit.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ConcurrencyLimitScope {
+    Server,
+    Route,
+}
 
 // === impl HttpAuthzMetrics ===
 
@@ -98,6 +147,56 @@ impl HttpAuthzMetrics {
             .or_default()
             .incr();
     }
+
+    /// Records a request that would have been denied if not for the
+    /// audit-mode authorization described by `labels`.
+    pub fn audit(
+        &self,
+        labels: RouteAuthzLabels,
+        dst: OrigDstAddr,
+        tls: tls::ConditionalServerTls,
+    ) {
+        self.0
+            .audit
+            .lock()
+            .entry(RouteAuthzKey::new(labels, dst, tls))
+            .or_default()
+            .incr();
+    }
+
+    pub fn jwt_failure(
+        &self,
+        labels: RouteLabels,
+        error: JwtError,
+        dst: OrigDstAddr,
+        tls: tls::ConditionalServerTls,
+    ) {
+        self.0
+            .jwt_failure
+            .lock()
+            .entry(JwtFailureKey::new(
+                (labels, JwtFailureReason(error)),
+                dst,
+                tls,
+            ))
+            .or_default()
+            .incr();
+    }
+
+    pub fn concurrency_limit_rejected(
+        &self,
+        labels: RouteLabels,
+        scope: ConcurrencyLimitScope,
+        dst: OrigDstAddr,
+        tls: tls::ConditionalServerTls,
+    ) {
+        self.0
+            .concurrency_limit_rejected
+            .lock()
+            .entry(ConcurrencyLimitKey::new((labels, scope), dst, tls))
+            .or_default()
+            .incr();
+    }
 }
 
 impl FmtMetrics for HttpAuthzMetrics {
@@ -140,6 +239,45 @@ impl FmtMetrics for HttpAuthzMetrics {
         }
         drop(route_not_found);
 
+        let audit = self.0.audit.lock();
+        if !audit.is_empty() {
+            inbound_http_authz_audit_total.fmt_help(f)?;
+            inbound_http_authz_audit_total.fmt_scopes(
+                f,
+                audit
+                    .iter()
+                    .map(|(k, c)| ((k.target, (&k.labels, TlsAccept(&k.tls))), c)),
+                |c| c,
+            )?;
+        }
+        drop(audit);
+
+        let jwt_failure = self.0.jwt_failure.lock();
+        if !jwt_failure.is_empty() {
+            inbound_http_authz_jwt_failure_total.fmt_help(f)?;
+            inbound_http_authz_jwt_failure_total.fmt_scopes(
+                f,
+                jwt_failure
+                    .iter()
+                    .map(|(k, c)| ((k.target, (&k.labels, TlsAccept(&k.tls))), c)),
+                |c| c,
+            )?;
+        }
+        drop(jwt_failure);
+
+        let concurrency_limit_rejected = self.0.concurrency_limit_rejected.lock();
+        if !concurrency_limit_rejected.is_empty() {
+            inbound_http_concurrency_limit_rejected_total.fmt_help(f)?;
+            inbound_http_concurrency_limit_rejected_total.fmt_scopes(
+                f,
+                concurrency_limit_rejected
+                    .iter()
+                    .map(|(k, c)| ((k.target, (&k.labels, TlsAccept(&k.tls))), c)),
+                |c| c,
+            )?;
+        }
+        drop(concurrency_limit_rejected);
+
         Ok(())
     }
 }
@@ -165,6 +303,21 @@ impl TcpAuthzMetrics {
             .incr();
     }
 
+    /// Records a connection that would have been denied if not for the
+    /// audit-mode authorization described by `authz`.
+    pub fn audit(&self, permit: &ServerPermit, authz: Arc<Meta>, tls: tls::ConditionalServerTls) {
+        let labels = ServerAuthzLabels {
+            authz,
+            server: permit.labels.server.clone(),
+        };
+        self.0
+            .audit
+            .lock()
+            .entry(ServerAuthzKey::new(labels, permit.dst, tls))
+            .or_default()
+            .incr();
+    }
+
     pub fn terminate(&self, policy: &AllowPolicy, tls: tls::ConditionalServerTls) {
         self.0
             .terminate
@@ -173,6 +326,23 @@ impl TcpAuthzMetrics {
             .or_default()
             .incr();
     }
+
+    pub fn connection_limited(&self, policy: &AllowPolicy, error: &ConnectionLimitExceeded) {
+        let reason = match error {
+            ConnectionLimitExceeded::Rate(_) => ConnectionLimitReason::Rate,
+            ConnectionLimitExceeded::Concurrency(_) => ConnectionLimitReason::Concurrency,
+        };
+        self.0
+            .connection_limit_rejected
+            .lock()
+            .entry(ConnectionLimitedKey {
+                target: TargetAddr(policy.dst_addr().into()),
+                server: policy.server_label(),
+                reason,
+            })
+            .or_default()
+            .incr();
+    }
 }
 
 impl FmtMetrics for TcpAuthzMetrics {
@@ -191,6 +361,13 @@ impl FmtMetrics for TcpAuthzMetrics {
         }
         drop(deny);
 
+        let audit = self.0.audit.lock();
+        if !audit.is_empty() {
+            inbound_tcp_authz_audit_total.fmt_help(f)?;
+            inbound_tcp_authz_audit_total.fmt_scopes(f, &*audit, |c| c)?;
+        }
+        drop(audit);
+
         let terminate = self.0.terminate.lock();
         if !terminate.is_empty() {
             inbound_tcp_authz_terminate_total.fmt_help(f)?;
@@ -198,6 +375,17 @@ impl FmtMetrics for TcpAuthzMetrics {
         }
         drop(terminate);
 
+        let connection_limit_rejected = self.0.connection_limit_rejected.lock();
+        if !connection_limit_rejected.is_empty() {
+            inbound_tcp_connection_limit_rejected_total.fmt_help(f)?;
+            inbound_tcp_connection_limit_rejected_total.fmt_scopes(
+                f,
+                &*connection_limit_rejected,
+                |c| c,
+            )?;
+        }
+        drop(connection_limit_rejected);
+
         Ok(())
     }
 }
@@ -220,6 +408,36 @@ impl<L: FmtLabels> FmtLabels for Key<L> {
     }
 }
 
+impl FmtLabels for JwtFailureReason {
+    fn fmt_labels(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "reason=\"{}\"", self.0.reason())
+    }
+}
+
+impl FmtLabels for ConcurrencyLimitScope {
+    fn fmt_labels(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Server => write!(f, "limit=\"server\""),
+            Self::Route => write!(f, "limit=\"route\""),
+        }
+    }
+}
+
+impl FmtLabels for ConnectionLimitedKey {
+    fn fmt_labels(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.target, (&self.server, self.reason)).fmt_labels(f)
+    }
+}
+
+impl FmtLabels for ConnectionLimitReason {
+    fn fmt_labels(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rate => write!(f, "reason=\"rate\""),
+            Self::Concurrency => write!(f, "reason=\"concurrency\""),
+        }
+    }
+}
+
 impl ServerKey {
     fn from_policy(policy: &AllowPolicy, tls: tls::ConditionalServerTls) -> Self {
         Self::new(policy.server_label(), policy.dst_addr(), tls)
@@ -3,7 +3,10 @@ mod tcp;
 
 pub(crate) use self::{http::HttpErrorMetrics, tcp::TcpErrorMetrics};
 use crate::{
-    policy::{HttpRouteNotFound, HttpRouteUnauthorized, ServerUnauthorized},
+    policy::{
+        ConnectionLimitExceeded, HttpRouteNotFound, HttpRouteUnauthorized, ServerUnauthorized,
+    },
+    proxy_protocol::ProxyProtocolError,
     GatewayDomainInvalid, GatewayIdentityRequired, GatewayLoop,
 };
 use linkerd_app_core::{
@@ -22,6 +25,7 @@ enum ErrorKind {
     GatewayIdentityRequired,
     GatewayLoop,
     Io,
+    ProxyProtocol,
     TlsDetectTimeout,
     Unexpected,
 }
@@ -34,12 +38,15 @@ impl ErrorKind {
         if err.is::<ServerUnauthorized>()
             || err.is::<HttpRouteUnauthorized>()
             || err.is::<HttpRouteNotFound>()
+            || err.is::<ConnectionLimitExceeded>()
         {
             return None;
         }
 
         if err.is::<FailFastError>() {
             Some(ErrorKind::FailFast)
+        } else if err.is::<ProxyProtocolError>() {
+            Some(ErrorKind::ProxyProtocol)
         } else if err.is::<std::io::Error>() {
             Some(ErrorKind::Io)
         } else if err.is::<tls::server::ServerTlsTimeoutError>() {
@@ -73,6 +80,7 @@ impl FmtLabels for ErrorKind {
                 ErrorKind::GatewayLoop => "gateway loop",
                 ErrorKind::GatewayDomainInvalid => "gateway domain invalid",
                 ErrorKind::Io => "i/o",
+                ErrorKind::ProxyProtocol => "proxy protocol",
                 ErrorKind::Unexpected => "unexpected",
             }
         )
@@ -1,17 +1,29 @@
 mod api;
+pub mod audit;
 mod config;
+mod connection_limit;
 pub mod defaults;
+mod dscp;
+pub mod ext_authz;
+pub mod global_rate_limit;
 mod http;
+pub mod learn;
 mod store;
 mod tcp;
 
 pub(crate) use self::store::Store;
 pub use self::{
+    audit::AuditSink,
     config::Config,
+    connection_limit::NewConnectionLimit,
+    dscp::NewMarkDscp,
+    ext_authz::{ExtAuthzClient, ExtAuthzDenied, ExtAuthzUnavailable},
+    global_rate_limit::{GlobalRateLimitClient, GlobalRateLimitUnavailable, GlobalRateLimited},
     http::{
         HttpInvalidPolicy, HttpRouteInvalidRedirect, HttpRouteNotFound, HttpRouteRedirect,
-        HttpRouteUnauthorized, NewHttpPolicy,
+        HttpRouteUnauthorized, NewHttpPolicy, PermittedBody,
     },
+    learn::PolicyLearner,
     tcp::NewTcpPolicy,
 };
 
@@ -23,11 +35,14 @@ use linkerd_app_core::{
     transport::{ClientAddr, OrigDstAddr, Remote},
 };
 use linkerd_idle_cache::Cached;
+use linkerd_proxy_server_policy::authz::Action;
 pub use linkerd_proxy_server_policy::{
     authz::Suffix,
+    connection_limit::{ConnectionKey, ConnectionLimitKey},
     grpc::Route as GrpcRoute,
     http::{filter::Redirection, Route as HttpRoute},
-    route, Authentication, Authorization, Meta, Protocol, RoutePolicy, ServerPolicy,
+    route, Authentication, Authorization, ConnectionLimitExceeded, Meta, Overrides, Protocol,
+    RoutePolicy, ServerPolicy,
 };
 use std::sync::Arc;
 use thiserror::Error;
@@ -37,6 +52,23 @@ use tokio::sync::watch;
 #[error("unauthorized connection on {}/{}", server.kind(), server.name())]
 pub struct ServerUnauthorized {
     server: Arc<Meta>,
+
+    /// The deny authorization that matched the connection, if any.
+    authz: Option<Arc<Meta>>,
+}
+
+/// The outcome of evaluating a list of authorizations.
+#[derive(Debug)]
+enum Decision<'a> {
+    /// The client is permitted by `authz`. If `audit` is set, the client would
+    /// have been denied if not for that audit-mode authorization.
+    Allow {
+        authz: &'a Authorization,
+        audit: Option<&'a Authorization>,
+    },
+
+    /// The client is denied, explicitly by `authz` if set.
+    Deny { authz: Option<&'a Authorization> },
 }
 
 pub trait GetPolicy {
@@ -91,6 +123,10 @@ impl From<DefaultPolicy> for ServerPolicy {
             DefaultPolicy::Deny => ServerPolicy {
                 protocol: Protocol::Opaque(Arc::new([])),
                 local_rate_limit: Default::default(),
+                concurrency_limit: None,
+                connection_limit: None,
+                dscp: None,
+                proxy_protocol: false,
                 meta: Meta::new_default("deny"),
             },
         }
@@ -166,19 +202,28 @@ fn is_tls_authorized(tls: &tls::ConditionalServerTls, authz: &Authorization) ->
         Authentication::TlsAuthenticated {
             ref identities,
             ref suffixes,
+            ref patterns,
         } => match tls {
             tls::ConditionalServerTls::Some(tls::ServerTls::Established {
                 client_id: Some(tls::server::ClientId(ref id)),
                 ..
-            }) => match id {
-                id::Id::Uri(_) => identities.contains(&*id.to_str()),
-                id::Id::Dns(_) => {
-                    identities.contains(&*id.to_str())
-                        || suffixes.iter().any(|s| s.contains(&id.to_str()))
-                }
-            },
+            }) => {
+                let id_str = id.to_str();
+                let matched = match id {
+                    id::Id::Uri(_) => identities.contains(&*id_str),
+                    id::Id::Dns(_) => {
+                        identities.contains(&*id_str)
+                            || suffixes.iter().any(|s| s.contains(&id_str))
+                    }
+                };
+                matched || patterns.iter().any(|p| p.matches(&id_str))
+            }
             _ => false,
         },
+
+        // JWTs are only validated per-request, so they never authorize a
+        // connection.
+        Authentication::Jwt(_) => false,
     }
 }
 
@@ -187,11 +232,50 @@ fn is_authorized(
     client_addr: Remote<ClientAddr>,
     tls: &tls::ConditionalServerTls,
 ) -> bool {
-    if !authz.networks.iter().any(|n| n.contains(&client_addr.ip())) {
-        return false;
+    is_network_authorized(authz, client_addr) && is_tls_authorized(tls, authz)
+}
+
+fn is_network_authorized(authz: &Authorization, client_addr: Remote<ClientAddr>) -> bool {
+    authz.networks.iter().any(|n| n.contains(&client_addr.ip()))
+}
+
+/// Evaluates a list of authorizations against a client, as determined by
+/// `is_match`.
+///
+/// Deny authorizations take precedence over allow authorizations, and
+/// audit-mode authorizations are only considered when the client would
+/// otherwise be denied.
+fn decide<'a>(
+    authzs: &'a [Authorization],
+    mut is_match: impl FnMut(&'a Authorization) -> bool,
+) -> Decision<'a> {
+    let mut audit = None;
+    for authz in authzs.iter().filter(|a| a.action == Action::Deny) {
+        if is_match(authz) {
+            if !authz.audit {
+                return Decision::Deny { authz: Some(authz) };
+            }
+            audit.get_or_insert(authz);
+        }
+    }
+
+    let mut audit_allow = None;
+    for authz in authzs.iter().filter(|a| a.action == Action::Allow) {
+        if is_match(authz) {
+            if !authz.audit {
+                return Decision::Allow { authz, audit };
+            }
+            audit_allow.get_or_insert(authz);
+        }
     }
 
-    is_tls_authorized(tls, authz)
+    match audit_allow {
+        Some(authz) => Decision::Allow {
+            authz,
+            audit: Some(authz),
+        },
+        None => Decision::Deny { authz: None },
+    }
 }
 
 // === impl Permit ===
@@ -211,11 +295,12 @@ impl ServerPermit {
 
 #[cfg(test)]
 mod tests {
-    use super::is_tls_authorized;
     use super::Meta;
     use super::Suffix;
+    use super::{decide, is_tls_authorized, Decision};
     use super::{Authentication, Authorization};
     use linkerd_app_core::tls;
+    use linkerd_proxy_server_policy::authz;
     use std::collections::BTreeSet;
     use std::str::FromStr;
     use std::sync::Arc;
@@ -229,7 +314,10 @@ mod tests {
             authentication: Authentication::TlsAuthenticated {
                 identities,
                 suffixes,
+                patterns: vec![],
             },
+            action: authz::Action::Allow,
+            audit: false,
         }
     }
 
@@ -296,6 +384,40 @@ mod tests {
         assert!(!is_tls_authorized(&tls, &authz))
     }
 
+    #[test]
+    fn is_authorized_for_matching_patterns() {
+        let authz = |pattern: &str| {
+            let mut authz = authorization(BTreeSet::new(), vec![]);
+            if let Authentication::TlsAuthenticated {
+                ref mut patterns, ..
+            } = authz.authentication
+            {
+                patterns.push(pattern.parse().expect("pattern must be valid"));
+            }
+            authz
+        };
+
+        let spiffe = authz("spiffe://some-root/ns/*/sa/payments-*");
+        assert!(is_tls_authorized(
+            &server_tls("spiffe://some-root/ns/prod/sa/payments-api"),
+            &spiffe
+        ));
+        assert!(!is_tls_authorized(
+            &server_tls("spiffe://some-root/ns/prod/sa/billing"),
+            &spiffe
+        ));
+
+        let dns = authz("*.prod.serviceaccount.identity.linkerd.cluster.local");
+        assert!(is_tls_authorized(
+            &server_tls("web.prod.serviceaccount.identity.linkerd.cluster.local"),
+            &dns
+        ));
+        assert!(!is_tls_authorized(
+            &server_tls("web.dev.serviceaccount.identity.linkerd.cluster.local"),
+            &dns
+        ));
+    }
+
     #[test]
     fn is_authorized_for_one_matching_spiffe_id() {
         let tls = server_tls("spiffe://some-root/some-workload-1");
@@ -323,4 +445,69 @@ mod tests {
         );
         assert!(is_tls_authorized(&tls, &authz))
     }
+
+    #[test]
+    fn decide_deny_and_audit() {
+        fn authz(name: &str, action: authz::Action, audit: bool) -> Authorization {
+            Authorization {
+                networks: vec![],
+                meta: Arc::new(Meta::Default { name: name.into() }),
+                authentication: Authentication::Unauthenticated,
+                action,
+                audit,
+            }
+        }
+        fn name(authz: &Authorization) -> &str {
+            authz.meta.name()
+        }
+
+        let allow = authz("allow", authz::Action::Allow, false);
+        let deny = authz("deny", authz::Action::Deny, false);
+        let audit_allow = authz("audit-allow", authz::Action::Allow, true);
+        let audit_deny = authz("audit-deny", authz::Action::Deny, true);
+
+        // Explicit denies take precedence regardless of order.
+        let authzs = [allow.clone(), deny.clone()];
+        match decide(&authzs, |_| true) {
+            Decision::Deny { authz: Some(a) } => assert_eq!(name(a), "deny"),
+            d => panic!("unexpected decision: {d:?}"),
+        }
+
+        // An audit-mode deny permits the client but is reported.
+        let authzs = [audit_deny, allow.clone()];
+        match decide(&authzs, |_| true) {
+            Decision::Allow {
+                authz,
+                audit: Some(audit),
+            } => {
+                assert_eq!(name(authz), "allow");
+                assert_eq!(name(audit), "audit-deny");
+            }
+            d => panic!("unexpected decision: {d:?}"),
+        }
+
+        // An audit-mode allow is only reported when nothing else permits the
+        // client.
+        let authzs = [audit_allow, allow];
+        match decide(&authzs, |_| true) {
+            Decision::Allow { authz, audit: None } => assert_eq!(name(authz), "allow"),
+            d => panic!("unexpected decision: {d:?}"),
+        }
+        match decide(&authzs, |a| a.audit) {
+            Decision::Allow {
+                authz,
+                audit: Some(audit),
+            } => {
+                assert_eq!(name(authz), "audit-allow");
+                assert_eq!(name(audit), "audit-allow");
+            }
+            d => panic!("unexpected decision: {d:?}"),
+        }
+
+        // Nothing matches.
+        assert!(matches!(
+            decide(&authzs, |_| false),
+            Decision::Deny { authz: None }
+        ));
+    }
 }
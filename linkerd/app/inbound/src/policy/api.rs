@@ -8,7 +8,7 @@ use linkerd_app_core::{
     svc::Service,
     Error, Recover, Result,
 };
-use linkerd_proxy_server_policy::ServerPolicy;
+use linkerd_proxy_server_policy::{Overrides, ServerPolicy};
 use linkerd_tonic_stream::{LimitReceiveFuture, ReceiveLimits};
 use linkerd_tonic_watch::StreamWatch;
 use std::sync::Arc;
@@ -19,6 +19,7 @@ pub(super) struct Api<S> {
     workload: Arc<str>,
     limits: ReceiveLimits,
     default_detect_timeout: time::Duration,
+    overrides: Arc<Overrides>,
     client: Client<S>,
 }
 
@@ -41,12 +42,14 @@ where
         workload: Arc<str>,
         limits: ReceiveLimits,
         default_detect_timeout: time::Duration,
+        overrides: Arc<Overrides>,
         client: S,
     ) -> Self {
         Self {
             workload,
             limits,
             default_detect_timeout,
+            overrides,
             client: Client::new(client),
         }
     }
@@ -84,6 +87,7 @@ where
 
         let detect_timeout = self.default_detect_timeout;
         let limits = self.limits;
+        let overrides = self.overrides.clone();
         let mut client = self.client.clone();
         Box::pin(async move {
             let rsp = LimitReceiveFuture::new(limits, client.watch_port(tonic::Request::new(req)))
@@ -93,12 +97,15 @@ where
                     // If the server returned an invalid server policy, we
                     // default to using an invalid policy that causes all
                     // requests to report an internal error.
-                    let policy = ServerPolicy::try_from(up).unwrap_or_else(|error| {
-                        tracing::warn!(%error, "Server misconfigured");
-                        INVALID_POLICY
-                            .get_or_init(|| ServerPolicy::invalid(detect_timeout))
-                            .clone()
-                    });
+                    let policy = match ServerPolicy::try_from(up) {
+                        Ok(policy) => overrides.apply(policy),
+                        Err(error) => {
+                            tracing::warn!(%error, "Server misconfigured");
+                            INVALID_POLICY
+                                .get_or_init(|| ServerPolicy::invalid(detect_timeout))
+                                .clone()
+                        }
+                    };
                     tracing::debug!(?policy);
                     policy
                 })
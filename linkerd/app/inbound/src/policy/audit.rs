@@ -0,0 +1,330 @@
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use linkerd_app_core::{
+    metrics::prom,
+    tls,
+    transport::{ClientAddr, OrigDstAddr, Remote},
+};
+use linkerd_proxy_server_policy::Meta;
+use serde_json::json;
+use std::{io, num::NonZeroU32, path::PathBuf, sync::Arc, time::SystemTime};
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    sync::mpsc,
+};
+
+/// Configures the authorization audit event stream.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub sink: Sink,
+
+    /// Determines whether allowed connections and requests are recorded in
+    /// addition to denied ones.
+    pub include_allowed: bool,
+
+    /// Bounds the number of events recorded each second so that a scan cannot
+    /// flood the sink. Events in excess of this limit are dropped.
+    pub max_events_per_second: NonZeroU32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sink {
+    Stdout,
+    File(PathBuf),
+}
+
+/// Writes one JSON event per line for each authorization decision.
+///
+/// Events are serialized on the calling task and written by a background task
+/// so that authorization never waits on the sink.
+#[derive(Clone)]
+pub struct AuditSink(Arc<Inner>);
+
+struct Inner {
+    tx: mpsc::Sender<String>,
+    limiter: DefaultDirectRateLimiter,
+    include_allowed: bool,
+    metrics: AuditMetrics,
+}
+
+#[derive(Clone, Debug)]
+pub struct AuditMetrics {
+    recorded: prom::Counter,
+    rate_limited: prom::Counter,
+    overflow: prom::Counter,
+    write_errors: prom::Counter,
+}
+
+/// Describes a single authorization decision.
+#[derive(Debug)]
+pub(crate) struct Event<'a> {
+    pub decision: Decision,
+    pub protocol: &'static str,
+    pub client: Remote<ClientAddr>,
+    pub tls: &'a tls::ConditionalServerTls,
+    pub dst: OrigDstAddr,
+    pub server: &'a Meta,
+    pub route: Option<&'a Meta>,
+    pub authz: Option<&'a Meta>,
+    pub reason: Option<&'static str>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Decision {
+    Allow,
+    Deny,
+    /// The client was allowed, but would have been denied if not for an
+    /// audit-mode authorization.
+    Audit,
+}
+
+/// The number of serialized events that may be buffered before events are
+/// dropped.
+const CAPACITY: usize = 1_000;
+
+// === impl AuditSink ===
+
+impl AuditSink {
+    /// Opens the configured sink and spawns a task to write events to it.
+    ///
+    /// Must be called on a Tokio runtime.
+    pub fn spawn(config: Config, metrics: AuditMetrics) -> io::Result<Self> {
+        match config.sink {
+            Sink::Stdout => Ok(Self::spawn_writer(&config, metrics, tokio::io::stdout())),
+            Sink::File(ref path) => {
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?;
+                Ok(Self::spawn_writer(
+                    &config,
+                    metrics,
+                    tokio::fs::File::from_std(file),
+                ))
+            }
+        }
+    }
+
+    fn spawn_writer<W>(config: &Config, metrics: AuditMetrics, mut writer: W) -> Self
+    where
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel::<String>(CAPACITY);
+        let write_errors = metrics.write_errors.clone();
+        tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                let res = async {
+                    writer.write_all(line.as_bytes()).await?;
+                    writer.flush().await
+                };
+                if let Err(error) = res.await {
+                    write_errors.inc();
+                    tracing::warn!(%error, "Failed to write authorization audit event");
+                }
+            }
+        });
+
+        Self(Arc::new(Inner {
+            tx,
+            limiter: RateLimiter::direct(Quota::per_second(config.max_events_per_second)),
+            include_allowed: config.include_allowed,
+            metrics,
+        }))
+    }
+
+    pub(crate) fn record(&self, event: Event<'_>) {
+        if event.decision == Decision::Allow && !self.0.include_allowed {
+            return;
+        }
+        if self.0.limiter.check().is_err() {
+            self.0.metrics.rate_limited.inc();
+            return;
+        }
+
+        let mut line = event.to_json().to_string();
+        line.push('\n');
+        match self.0.tx.try_send(line) {
+            Ok(()) => self.0.metrics.recorded.inc(),
+            Err(_) => self.0.metrics.overflow.inc(),
+        }
+    }
+}
+
+impl std::fmt::Debug for AuditSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditSink")
+            .field("include_allowed", &self.0.include_allowed)
+            .finish_non_exhaustive()
+    }
+}
+
+// === impl Event ===
+
+impl Event<'_> {
+    fn to_json(&self) -> serde_json::Value {
+        fn meta(m: &Meta) -> serde_json::Value {
+            json!({
+                "group": m.group(),
+                "kind": m.kind(),
+                "name": m.name(),
+            })
+        }
+
+        let identity = match self.tls {
+            tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+                client_id: Some(id),
+                ..
+            }) => Some(id.to_string()),
+            _ => None,
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        json!({
+            "timestamp": timestamp,
+            "decision": match self.decision {
+                Decision::Allow => "allow",
+                Decision::Deny => "deny",
+                Decision::Audit => "audit",
+            },
+            "protocol": self.protocol,
+            "client": {
+                "address": self.client.to_string(),
+                "identity": identity,
+            },
+            "destination": self.dst.to_string(),
+            "server": meta(self.server),
+            "route": self.route.map(meta),
+            "authorization": self.authz.map(meta),
+            "reason": self.reason,
+        })
+    }
+}
+
+// === impl AuditMetrics ===
+
+impl AuditMetrics {
+    pub fn register(registry: &mut prom::Registry) -> Self {
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelSet)]
+        struct EventLabels {
+            result: EventResult,
+        }
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelValue)]
+        #[allow(non_camel_case_types)]
+        enum EventResult {
+            recorded,
+            rate_limited,
+            overflow,
+        }
+        let events = prom::Family::<_, prom::Counter>::default();
+        registry.register(
+            "events",
+            "The total number of authorization audit events",
+            events.clone(),
+        );
+        let counter = |result: EventResult| events.get_or_create(&EventLabels { result }).clone();
+
+        let write_errors = prom::Counter::default();
+        registry.register(
+            "write_errors",
+            "The total number of authorization audit events that could not be written",
+            write_errors.clone(),
+        );
+
+        Self {
+            recorded: counter(EventResult::recorded),
+            rate_limited: counter(EventResult::rate_limited),
+            overflow: counter(EventResult::overflow),
+            write_errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    fn sink(include_allowed: bool, max: u32) -> (AuditSink, tokio::io::DuplexStream) {
+        let (tx, rx) = tokio::io::duplex(64 * 1024);
+        let config = Config {
+            sink: Sink::Stdout,
+            include_allowed,
+            max_events_per_second: NonZeroU32::new(max).unwrap(),
+        };
+        let metrics = AuditMetrics::register(&mut prom::Registry::default());
+        (AuditSink::spawn_writer(&config, metrics, tx), rx)
+    }
+
+    fn event<'a>(
+        decision: Decision,
+        tls: &'a tls::ConditionalServerTls,
+        server: &'a Meta,
+        authz: Option<&'a Meta>,
+    ) -> Event<'a> {
+        Event {
+            decision,
+            protocol: "tcp",
+            client: Remote(ClientAddr(([192, 0, 2, 3], 50000).into())),
+            tls,
+            dst: OrigDstAddr(([192, 0, 2, 2], 8080).into()),
+            server,
+            route: None,
+            authz,
+            reason: Some("unauthorized"),
+        }
+    }
+
+    #[tokio::test]
+    async fn records_denials() {
+        let (sink, rx) = sink(false, 100);
+        let server = Meta::new_default("srv");
+        let authz = Meta::new_default("authz");
+        let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+            client_id: Some(
+                "foo.ns1.serviceaccount.identity.linkerd.cluster.local"
+                    .parse()
+                    .unwrap(),
+            ),
+            negotiated_protocol: None,
+        });
+
+        // Allowed connections are not recorded by default.
+        sink.record(event(Decision::Allow, &tls, &server, Some(&*authz)));
+        sink.record(event(Decision::Deny, &tls, &server, None));
+
+        let mut lines = BufReader::new(rx).lines();
+        let line = lines
+            .next_line()
+            .await
+            .unwrap()
+            .expect("must emit an event");
+        let ev: serde_json::Value = serde_json::from_str(&line).expect("must be JSON");
+        assert_eq!(ev["decision"], "deny");
+        assert_eq!(ev["protocol"], "tcp");
+        assert_eq!(ev["client"]["address"], "192.0.2.3:50000");
+        assert_eq!(
+            ev["client"]["identity"],
+            "foo.ns1.serviceaccount.identity.linkerd.cluster.local"
+        );
+        assert_eq!(ev["destination"], "192.0.2.2:8080");
+        assert_eq!(ev["server"]["name"], "srv");
+        assert!(ev["authorization"].is_null());
+        assert_eq!(ev["reason"], "unauthorized");
+        assert!(ev["timestamp"].as_f64().unwrap() > 0.0);
+        assert_eq!(sink.0.metrics.recorded.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limited() {
+        let (sink, _rx) = sink(true, 2);
+        let server = Meta::new_default("srv");
+        let tls = tls::ConditionalServerTls::None(tls::NoServerTls::NoClientHello);
+        for _ in 0..5 {
+            sink.record(event(Decision::Allow, &tls, &server, None));
+        }
+        assert_eq!(sink.0.metrics.recorded.get(), 2);
+        assert_eq!(sink.0.metrics.rate_limited.get(), 3);
+    }
+}
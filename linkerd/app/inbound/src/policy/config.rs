@@ -1,4 +1,4 @@
-use super::{api::Api, DefaultPolicy, GetPolicy, Protocol, ServerPolicy, Store};
+use super::{api::Api, DefaultPolicy, GetPolicy, Overrides, Protocol, ServerPolicy, Store};
 use linkerd_app_core::{exp_backoff::ExponentialBackoff, proxy::http, Error};
 use linkerd_tonic_stream::ReceiveLimits;
 use rangemap::RangeInclusiveSet;
@@ -20,12 +20,16 @@ pub enum Config {
         cache_max_idle_age: Duration,
         ports: HashSet<u16>,
         opaque_ports: RangeInclusiveSet<u16>,
+        /// Locally-configured settings applied to each discovered policy.
+        overrides: Arc<Overrides>,
     },
     Fixed {
         default: DefaultPolicy,
         cache_max_idle_age: Duration,
         ports: HashMap<u16, ServerPolicy>,
         opaque_ports: RangeInclusiveSet<u16>,
+        /// Locally-configured settings applied to each fixed policy.
+        overrides: Arc<Overrides>,
     },
 }
 
@@ -52,14 +56,24 @@ impl Config {
                 ports,
                 cache_max_idle_age,
                 opaque_ports,
-            } => Store::spawn_fixed(default, cache_max_idle_age, ports, opaque_ports),
+                overrides,
+            } => {
+                let default = apply_overrides(&overrides, default);
+                let ports = ports
+                    .into_iter()
+                    .map(|(port, policy)| (port, overrides.apply(policy)))
+                    .collect();
+                Store::spawn_fixed(default, cache_max_idle_age, ports, opaque_ports)
+            }
 
             Self::Discover {
                 default,
                 ports,
                 cache_max_idle_age,
                 opaque_ports,
+                overrides,
             } => {
+                let default = apply_overrides(&overrides, default);
                 let watch = {
                     let detect_timeout = match default {
                         DefaultPolicy::Allow(ServerPolicy {
@@ -68,10 +82,19 @@ impl Config {
                         }) => timeout,
                         _ => Duration::from_secs(10),
                     };
-                    Api::new(workload, limits, detect_timeout, client).into_watch(backoff)
+                    Api::new(workload, limits, detect_timeout, overrides, client)
+                        .into_watch(backoff)
                 };
                 Store::spawn_discover(default, cache_max_idle_age, watch, ports, opaque_ports)
             }
         }
     }
 }
+
+/// Applies local overrides to the default policy.
+fn apply_overrides(overrides: &Overrides, default: DefaultPolicy) -> DefaultPolicy {
+    match default {
+        DefaultPolicy::Allow(policy) => DefaultPolicy::Allow(overrides.apply(policy)),
+        DefaultPolicy::Deny => DefaultPolicy::Deny,
+    }
+}
@@ -0,0 +1,130 @@
+use super::AllowPolicy;
+use crate::metrics::authz::TcpAuthzMetrics;
+use futures::future;
+use linkerd_app_core::{svc, Error, Result};
+use linkerd_proxy_server_policy::{
+    connection_limit::{ConnectionKey, ConnectionLimitKey, ConnectionPermit},
+    ConnectionLimitExceeded,
+};
+use std::{future::Future, pin::Pin, sync::Arc, task};
+
+/// A middleware that enforces a server's per-client connection limits.
+///
+/// A server's limits are keyed either by client IP address or by client
+/// identity. Since a client's identity is only known once TLS has been
+/// terminated, this middleware is installed at both points in the stack and
+/// each instance only enforces limits keyed by its `stage`.
+#[derive(Clone, Debug)]
+pub struct NewConnectionLimit<N> {
+    inner: N,
+    stage: ConnectionLimitKey,
+    metrics: TcpAuthzMetrics,
+}
+
+#[derive(Clone, Debug)]
+pub enum ConnectionLimit<S> {
+    Admitted {
+        inner: S,
+        permit: Option<Arc<ConnectionPermit>>,
+    },
+    Limited(ConnectionLimitExceeded),
+}
+
+// === impl NewConnectionLimit ===
+
+impl<N> NewConnectionLimit<N> {
+    pub(crate) fn layer(
+        stage: ConnectionLimitKey,
+        metrics: TcpAuthzMetrics,
+    ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            inner,
+            stage,
+            metrics: metrics.clone(),
+        })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewConnectionLimit<N>
+where
+    T: svc::Param<AllowPolicy> + svc::Param<ConnectionKey>,
+    N: svc::NewService<T>,
+{
+    type Service = ConnectionLimit<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let policy: AllowPolicy = target.param();
+        let limit = policy
+            .borrow()
+            .connection_limit
+            .clone()
+            .filter(|l| l.key() == self.stage);
+        let Some(limit) = limit else {
+            return ConnectionLimit::Admitted {
+                inner: self.inner.new_service(target),
+                permit: None,
+            };
+        };
+
+        // Services are built for each connection, so the permit is acquired
+        // here and held until the connection is closed.
+        let client: ConnectionKey = target.param();
+        match limit.acquire(client.clone()) {
+            Ok(permit) => ConnectionLimit::Admitted {
+                inner: self.inner.new_service(target),
+                permit: Some(Arc::new(permit)),
+            },
+            Err(error) => {
+                let meta = policy.meta();
+                tracing::debug!(
+                    server.group = %meta.group(),
+                    server.kind = %meta.kind(),
+                    server.name = %meta.name(),
+                    ?client,
+                    %error,
+                    "Connection limited"
+                );
+                self.metrics.connection_limited(&policy, &error);
+                ConnectionLimit::Limited(error)
+            }
+        }
+    }
+}
+
+// === impl ConnectionLimit ===
+
+impl<I, S> svc::Service<I> for ConnectionLimit<S>
+where
+    S: svc::Service<I, Response = ()>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = Error;
+    type Future = future::Either<
+        Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
+        future::Ready<Result<()>>,
+    >;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> task::Poll<Result<()>> {
+        match self {
+            Self::Admitted { inner, .. } => inner.poll_ready(cx).map_err(Into::into),
+            Self::Limited(error) => task::Poll::Ready(Err(error.clone().into())),
+        }
+    }
+
+    fn call(&mut self, io: I) -> Self::Future {
+        let (inner, permit) = match self {
+            Self::Admitted { inner, permit } => (inner, permit.take()),
+            Self::Limited(error) => {
+                return future::Either::Right(future::err(error.clone().into()))
+            }
+        };
+        let call = inner.call(io);
+        future::Either::Left(Box::pin(async move {
+            let _permit = permit;
+            call.await.map_err(Into::into)
+        }))
+    }
+}
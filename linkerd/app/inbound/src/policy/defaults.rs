@@ -1,6 +1,7 @@
 use linkerd_app_core::{IpNet, Ipv4Net, Ipv6Net};
 use linkerd_proxy_server_policy::{
-    authz::Suffix, http, Authentication, Authorization, Meta, Protocol, ServerPolicy,
+    authz::{self, Suffix},
+    http, Authentication, Authorization, Meta, Protocol, ServerPolicy,
 };
 use std::{sync::Arc, time::Duration};
 
@@ -62,6 +63,7 @@ fn authenticated() -> Authentication {
     Authentication::TlsAuthenticated {
         identities: Default::default(),
         suffixes: vec![Suffix::from(vec![])],
+        patterns: vec![],
     }
 }
 
@@ -75,6 +77,8 @@ fn mk(
         meta: Meta::new_default(name),
         networks: nets.into_iter().map(Into::into).collect(),
         authentication,
+        action: authz::Action::Allow,
+        audit: false,
     }]);
 
     // The default policy supports protocol detection and uses the default
@@ -89,5 +93,9 @@ fn mk(
         meta: Meta::new_default(name),
         protocol,
         local_rate_limit: Default::default(),
+        concurrency_limit: None,
+        connection_limit: None,
+        dscp: None,
+        proxy_protocol: false,
     }
 }
@@ -0,0 +1,71 @@
+use super::AllowPolicy;
+use linkerd_app_core::{svc, transport::SetDscp};
+use std::task;
+
+/// A middleware that marks the packets sent on a server's connections with
+/// the server's DSCP value, if it has one.
+///
+/// The mark is set when a connection is accepted, so policy updates only apply
+/// to new connections. Failures are logged rather than failing the connection.
+#[derive(Clone, Debug)]
+pub struct NewMarkDscp<N> {
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct MarkDscp<S> {
+    inner: S,
+    dscp: Option<u8>,
+}
+
+// === impl NewMarkDscp ===
+
+impl<N> NewMarkDscp<N> {
+    pub(crate) fn layer() -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewMarkDscp<N>
+where
+    T: svc::Param<AllowPolicy>,
+    N: svc::NewService<T>,
+{
+    type Service = MarkDscp<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let policy: AllowPolicy = target.param();
+        let dscp = policy.borrow().dscp;
+        MarkDscp {
+            inner: self.inner.new_service(target),
+            dscp,
+        }
+    }
+}
+
+// === impl MarkDscp ===
+
+impl<I, S> svc::Service<I> for MarkDscp<S>
+where
+    I: SetDscp,
+    S: svc::Service<I>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> task::Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, io: I) -> Self::Future {
+        if let Some(dscp) = self.dscp {
+            match io.set_dscp(dscp) {
+                Ok(()) => tracing::trace!(dscp, "Marked connection"),
+                Err(error) => tracing::warn!(dscp, %error, "Failed to set DSCP"),
+            }
+        }
+        self.inner.call(io)
+    }
+}
@@ -0,0 +1,958 @@
+use envoy_ext_authz_proto::auth::{
+    self as api, attribute_context, authorization_client::AuthorizationClient,
+    check_response::HttpResponse,
+};
+use linkerd_app_core::{
+    dns, identity,
+    metrics::prom,
+    proxy::http::{self, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    svc, tls, Conditional, Error, Result,
+};
+use linkerd_proxy_server_policy::ExtAuthz;
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time;
+
+pub use super::global_rate_limit::FailureMode;
+
+/// Configures how the inbound proxy consults an external authorization
+/// service.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub protocol: Protocol,
+
+    /// Bounds the time spent waiting for the authorization service to respond.
+    pub timeout: Duration,
+
+    /// Determines whether requests are allowed when the authorization service
+    /// cannot be reached.
+    pub failure_mode: FailureMode,
+
+    /// Configures caching of the service's decisions. When unset, the service
+    /// is consulted for every request.
+    pub cache: Option<CacheConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Protocol {
+    /// Requests are checked with the Envoy `ext_authz.v3` gRPC API.
+    Grpc,
+
+    /// Each request's method, path and headers are sent, without its body, to
+    /// the authorization service. A 2xx response allows the request; any other
+    /// response denies it.
+    Http {
+        /// Prepended to the request's path.
+        path_prefix: String,
+
+        /// The headers of an allowing response that are added to the request
+        /// before it is dispatched to the application.
+        allowed_upstream_headers: Vec<HeaderName>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    /// The maximum number of decisions held in the cache. When the cache is
+    /// full, the oldest decision is evicted.
+    pub capacity: usize,
+
+    /// The time for which a decision may be reused.
+    pub ttl: Duration,
+}
+
+/// A client for an external authorization service.
+#[derive(Clone)]
+pub struct ExtAuthzClient {
+    client: Client,
+    config: Config,
+    cache: Option<Arc<Cache>>,
+    metrics: ExtAuthzMetrics,
+
+    /// The proxy's own server name, reported as the SNI of TLS sessions.
+    server_name: Option<dns::Name>,
+}
+
+type Client = svc::BoxCloneSyncService<
+    http::Request<tonic::body::BoxBody>,
+    http::Response<tonic::body::BoxBody>,
+>;
+
+#[derive(Clone, Debug)]
+pub struct ExtAuthzMetrics {
+    allow: prom::Counter,
+    deny: prom::Counter,
+    error: prom::Counter,
+    timeout: prom::Counter,
+    cache_hits: prom::Counter,
+    latency: prom::Histogram,
+}
+
+/// The request metadata that is sent to the authorization service.
+#[derive(Clone, Debug)]
+pub(crate) struct Attributes {
+    key: Key,
+
+    /// The client's port is sent to the service but, since it differs for each
+    /// connection, it is not part of the cache key.
+    client_port: u16,
+
+    /// Decisions are only cached when the route limits the headers that are
+    /// sent to the service; otherwise, per-request headers would make each
+    /// request's key unique.
+    cacheable: bool,
+}
+
+/// Requests with equal keys share cached decisions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Key {
+    method: http::Method,
+    scheme: String,
+    host: String,
+    path: String,
+    version: http::Version,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    client_ip: IpAddr,
+    client_id: Option<identity::Id>,
+    tls: bool,
+    dst: SocketAddr,
+    context: BTreeMap<String, String>,
+}
+
+/// Headers that the authorization service applies to an allowed request.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Allowed {
+    set: Vec<(HeaderName, HeaderValue)>,
+    remove: Vec<HeaderName>,
+}
+
+#[derive(Clone, Debug)]
+enum Decision {
+    Allow(Allowed),
+    Deny {
+        status: StatusCode,
+        headers: HeaderMap,
+    },
+}
+
+#[derive(Debug)]
+struct Cache {
+    config: CacheConfig,
+    entries: Mutex<CacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    decisions: HashMap<Key, (time::Instant, Decision)>,
+
+    /// Keys in the order that they were inserted. Since all decisions share a
+    /// TTL, this is also the order in which they expire. A key that has been
+    /// reinserted may appear more than once; only its latest entry, whose
+    /// expiry matches the decision's, is current.
+    expiries: VecDeque<(time::Instant, Key)>,
+}
+
+/// Indicates that the authorization service denied a request.
+#[derive(Debug, thiserror::Error)]
+#[error("request denied by external authorization service with {status}")]
+pub struct ExtAuthzDenied {
+    pub status: StatusCode,
+
+    /// Headers that the service returned for the client.
+    pub headers: HeaderMap,
+}
+
+/// Indicates that the authorization service could not be consulted and that
+/// the proxy is configured to fail closed.
+#[derive(Debug, thiserror::Error)]
+#[error("external authorization service unavailable: {0}")]
+pub struct ExtAuthzUnavailable(#[source] Error);
+
+// === impl ExtAuthzClient ===
+
+impl ExtAuthzClient {
+    pub fn new<S, B>(client: S, config: Config, metrics: ExtAuthzMetrics) -> Self
+    where
+        S: svc::Service<http::Request<tonic::body::BoxBody>, Response = http::Response<B>>,
+        S: Clone + Send + Sync + 'static,
+        S::Error: Into<Error>,
+        S::Future: Send + 'static,
+        B: http::HttpBody<Data = tonic::codegen::Bytes> + Send + 'static,
+        B::Error: Into<Error>,
+    {
+        use svc::ServiceExt;
+
+        let client = svc::BoxCloneSyncService::new(
+            client.map_response(|rsp: http::Response<B>| rsp.map(tonic::body::boxed)),
+        );
+        let cache = config.cache.clone().map(|config| {
+            Arc::new(Cache {
+                config,
+                entries: Default::default(),
+            })
+        });
+        Self {
+            client,
+            config,
+            cache,
+            metrics,
+            server_name: None,
+        }
+    }
+
+    /// Sets the server name that is reported to the authorization service as
+    /// the SNI of clients' TLS sessions.
+    pub fn with_server_name(self, server_name: dns::Name) -> Self {
+        Self {
+            server_name: Some(server_name),
+            ..self
+        }
+    }
+
+    /// Checks whether the authorization service allows a request.
+    ///
+    /// Failures to reach the service are handled according to the configured
+    /// [`FailureMode`].
+    pub(crate) async fn check(self, attrs: Attributes) -> Result<Allowed> {
+        if let Some(decision) = self.cache.as_ref().and_then(|c| c.get(&attrs)) {
+            self.metrics.cache_hits.inc();
+            return decision.into_result();
+        }
+
+        let t0 = time::Instant::now();
+        let res = time::timeout(self.config.timeout, self.decide(&attrs)).await;
+        self.metrics.latency.observe(
+            time::Instant::now()
+                .saturating_duration_since(t0)
+                .as_secs_f64(),
+        );
+
+        let error = match res {
+            Ok(Ok(decision)) => {
+                match decision {
+                    Decision::Allow(_) => self.metrics.allow.inc(),
+                    Decision::Deny { .. } => self.metrics.deny.inc(),
+                };
+                if let Some(cache) = self.cache.as_ref() {
+                    cache.insert(attrs, decision.clone());
+                }
+                return decision.into_result();
+            }
+            Ok(Err(error)) => {
+                self.metrics.error.inc();
+                error
+            }
+            Err(_) => {
+                self.metrics.timeout.inc();
+                format!("no response within {:?}", self.config.timeout).into()
+            }
+        };
+
+        match self.config.failure_mode {
+            FailureMode::Open => {
+                tracing::debug!(%error, "Allowing request; authorization service failed");
+                Ok(Allowed::default())
+            }
+            FailureMode::Closed => Err(ExtAuthzUnavailable(error).into()),
+        }
+    }
+
+    async fn decide(&self, attrs: &Attributes) -> Result<Decision> {
+        match self.config.protocol {
+            Protocol::Grpc => self.decide_grpc(attrs).await,
+            Protocol::Http {
+                ref path_prefix,
+                ref allowed_upstream_headers,
+            } => {
+                self.decide_http(attrs, path_prefix, allowed_upstream_headers)
+                    .await
+            }
+        }
+    }
+
+    async fn decide_grpc(&self, attrs: &Attributes) -> Result<Decision> {
+        let mut client = AuthorizationClient::new(self.client.clone());
+        let rsp = client
+            .check(attrs.to_check_request(self.server_name.as_ref()))
+            .await?
+            .into_inner();
+
+        if rsp.status.map_or(0, |s| s.code) == tonic::Code::Ok as i32 {
+            let mut allowed = Allowed::default();
+            if let Some(HttpResponse::OkResponse(ok)) = rsp.http_response {
+                allowed.set = ok
+                    .headers
+                    .into_iter()
+                    .filter_map(|h| parse_header(h.header?))
+                    .collect();
+                allowed.remove = ok
+                    .headers_to_remove
+                    .into_iter()
+                    .filter_map(|h| h.parse().ok())
+                    .collect();
+            }
+            return Ok(Decision::Allow(allowed));
+        }
+
+        let mut status = StatusCode::FORBIDDEN;
+        let mut headers = HeaderMap::new();
+        if let Some(HttpResponse::DeniedResponse(denied)) = rsp.http_response {
+            if let Some(code) = denied
+                .status
+                .and_then(|s| u16::try_from(s.code).ok())
+                .and_then(|c| StatusCode::from_u16(c).ok())
+            {
+                status = code;
+            }
+            headers.extend(
+                denied
+                    .headers
+                    .into_iter()
+                    .filter_map(|h| parse_header(h.header?))
+                    .map(|(n, v)| (Some(n), v)),
+            );
+        }
+        Ok(Decision::Deny { status, headers })
+    }
+
+    async fn decide_http(
+        &self,
+        attrs: &Attributes,
+        path_prefix: &str,
+        allowed_upstream_headers: &[HeaderName],
+    ) -> Result<Decision> {
+        use svc::ServiceExt;
+
+        let attrs = &attrs.key;
+        let mut req = http::Request::builder()
+            .method(attrs.method.clone())
+            .uri(format!("{path_prefix}{}", attrs.path))
+            .body(tonic::body::empty_body())?;
+        let headers = req.headers_mut();
+        for (name, value) in &attrs.headers {
+            if !is_entity_header(name) {
+                headers.append(name, value.clone());
+            }
+        }
+        if let Some(id) = attrs.client_id.as_ref() {
+            headers.insert(
+                "l5d-client-id",
+                HeaderValue::try_from(id.to_string()).expect("identity must be a valid header"),
+            );
+        }
+
+        let rsp = self.client.clone().oneshot(req).await?;
+        if rsp.status().is_success() {
+            let set = allowed_upstream_headers
+                .iter()
+                .flat_map(|name| {
+                    rsp.headers()
+                        .get_all(name)
+                        .iter()
+                        .map(move |v| (name.clone(), v.clone()))
+                })
+                .collect();
+            return Ok(Decision::Allow(Allowed {
+                set,
+                remove: vec![],
+            }));
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in rsp.headers() {
+            if !is_entity_header(name) {
+                headers.append(name, value.clone());
+            }
+        }
+        Ok(Decision::Deny {
+            status: rsp.status(),
+            headers,
+        })
+    }
+}
+
+impl std::fmt::Debug for ExtAuthzClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtAuthzClient")
+            .field("config", &self.config)
+            .field("server_name", &self.server_name)
+            .finish_non_exhaustive()
+    }
+}
+
+fn parse_header(h: api::HeaderValue) -> Option<(HeaderName, HeaderValue)> {
+    let name = h.key.parse().ok()?;
+    let value = h.value.parse().ok()?;
+    Some((name, value))
+}
+
+/// Headers that describe a message body are not forwarded between the client
+/// and the authorization service, since bodies are not forwarded.
+fn is_entity_header(name: &HeaderName) -> bool {
+    name == http::header::CONTENT_LENGTH
+        || name == http::header::CONTENT_TYPE
+        || name == http::header::TRANSFER_ENCODING
+        || name == http::header::CONNECTION
+}
+
+// === impl Attributes ===
+
+impl Attributes {
+    pub(crate) fn new<B>(
+        config: &ExtAuthz,
+        req: &http::Request<B>,
+        client: SocketAddr,
+        client_id: Option<&identity::Id>,
+        tls: &tls::ConditionalServerTls,
+        dst: SocketAddr,
+    ) -> Self {
+        let uri = req.uri();
+        let host = uri
+            .authority()
+            .map(|a| a.to_string())
+            .or_else(|| {
+                req.headers()
+                    .get(http::header::HOST)
+                    .and_then(|h| h.to_str().ok())
+                    .map(Into::into)
+            })
+            .unwrap_or_default();
+        let key = Key {
+            method: req.method().clone(),
+            scheme: uri.scheme_str().unwrap_or("http").to_string(),
+            host,
+            path: uri
+                .path_and_query()
+                .map(|pq| pq.to_string())
+                .unwrap_or_else(|| "/".to_string()),
+            version: req.version(),
+            headers: config
+                .headers(req.headers())
+                .map(|(n, v)| (n.clone(), v.clone()))
+                .collect(),
+            client_ip: client.ip(),
+            client_id: client_id.cloned(),
+            tls: matches!(tls, Conditional::Some(tls::ServerTls::Established { .. })),
+            dst,
+            context: config.context.clone(),
+        };
+        Self {
+            key,
+            client_port: client.port(),
+            cacheable: !config.include_headers.is_empty(),
+        }
+    }
+
+    fn to_check_request(&self, server_name: Option<&dns::Name>) -> api::CheckRequest {
+        fn peer(addr: SocketAddr, principal: String) -> attribute_context::Peer {
+            attribute_context::Peer {
+                address: Some(api::Address {
+                    address: Some(api::address::Address::SocketAddress(api::SocketAddress {
+                        address: addr.ip().to_string(),
+                        port_specifier: Some(api::socket_address::PortSpecifier::PortValue(
+                            addr.port().into(),
+                        )),
+                    })),
+                }),
+                principal,
+            }
+        }
+
+        let key = &self.key;
+        let mut headers = HashMap::<String, String>::new();
+        for (name, value) in &key.headers {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            // Repeated headers are merged as a comma-separated list.
+            headers
+                .entry(name.as_str().to_string())
+                .and_modify(|v| {
+                    v.push(',');
+                    v.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+
+        let protocol = match key.version {
+            http::Version::HTTP_10 => "HTTP/1.0",
+            http::Version::HTTP_2 => "HTTP/2",
+            _ => "HTTP/1.1",
+        };
+
+        api::CheckRequest {
+            attributes: Some(api::AttributeContext {
+                source: Some(peer(
+                    SocketAddr::new(key.client_ip, self.client_port),
+                    key.client_id
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                )),
+                destination: Some(peer(key.dst, String::new())),
+                request: Some(attribute_context::Request {
+                    http: Some(attribute_context::HttpRequest {
+                        method: key.method.to_string(),
+                        headers,
+                        path: key.path.clone(),
+                        host: key.host.clone(),
+                        scheme: key.scheme.clone(),
+                        protocol: protocol.to_string(),
+                    }),
+                }),
+                context_extensions: key
+                    .context
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                tls_session: key.tls.then(|| attribute_context::TlsSession {
+                    sni: server_name.map(ToString::to_string).unwrap_or_default(),
+                }),
+            }),
+        }
+    }
+}
+
+// === impl Allowed ===
+
+impl Allowed {
+    /// Applies the service's header modifications to a request.
+    pub(crate) fn apply(self, headers: &mut HeaderMap) {
+        for name in self.remove {
+            headers.remove(name);
+        }
+        for (name, value) in self.set {
+            headers.insert(name, value);
+        }
+    }
+}
+
+// === impl Decision ===
+
+impl Decision {
+    fn into_result(self) -> Result<Allowed> {
+        match self {
+            Self::Allow(allowed) => Ok(allowed),
+            Self::Deny { status, headers } => Err(ExtAuthzDenied { status, headers }.into()),
+        }
+    }
+}
+
+// === impl Cache ===
+
+impl Cache {
+    fn get(&self, attrs: &Attributes) -> Option<Decision> {
+        if !attrs.cacheable {
+            return None;
+        }
+        let now = time::Instant::now();
+        let entries = self.entries.lock();
+        let (expiry, decision) = entries.decisions.get(&attrs.key)?;
+        (now < *expiry).then(|| decision.clone())
+    }
+
+    fn insert(&self, attrs: Attributes, decision: Decision) {
+        if !attrs.cacheable || self.config.capacity == 0 {
+            return;
+        }
+        let now = time::Instant::now();
+        let expiry = now + self.config.ttl;
+        let mut entries = self.entries.lock();
+        let CacheEntries {
+            decisions,
+            expiries,
+        } = &mut *entries;
+
+        // Drop expired decisions and, if the cache is still full, the oldest
+        // decision. Each key is queued once per insertion, so this is
+        // amortized constant time.
+        while let Some((queued, key)) = expiries.front() {
+            let full =
+                decisions.len() >= self.config.capacity && !decisions.contains_key(&attrs.key);
+            if *queued > now && !full {
+                break;
+            }
+            if decisions.get(key).map(|(e, _)| e) == Some(queued) {
+                decisions.remove(key);
+            }
+            expiries.pop_front();
+        }
+
+        expiries.push_back((expiry, attrs.key.clone()));
+        decisions.insert(attrs.key, (expiry, decision));
+    }
+}
+
+// === impl ExtAuthzMetrics ===
+
+impl ExtAuthzMetrics {
+    pub fn register(registry: &mut prom::Registry) -> Self {
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelSet)]
+        struct CheckLabels {
+            result: CheckResult,
+        }
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelValue)]
+        #[allow(non_camel_case_types)]
+        enum CheckResult {
+            allow,
+            deny,
+            error,
+            timeout,
+        }
+        let checks = prom::Family::<_, prom::Counter>::default();
+        registry.register(
+            "checks",
+            "The total number of requests checked against the authorization service",
+            checks.clone(),
+        );
+        let counter = |result: CheckResult| checks.get_or_create(&CheckLabels { result }).clone();
+
+        let cache_hits = prom::Counter::default();
+        registry.register(
+            "cache_hits",
+            "The total number of requests decided by a cached authorization",
+            cache_hits.clone(),
+        );
+
+        let latency = prom::Histogram::new([0.001, 0.005, 0.01, 0.05, 0.1, 0.5].iter().copied());
+        registry.register_with_unit(
+            "check_duration",
+            "The distribution of times spent waiting for the authorization service",
+            prom::Unit::Seconds,
+            latency.clone(),
+        );
+
+        Self {
+            allow: counter(CheckResult::allow),
+            deny: counter(CheckResult::deny),
+            error: counter(CheckResult::error),
+            timeout: counter(CheckResult::timeout),
+            cache_hits,
+            latency,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api::authorization_server::{Authorization, AuthorizationServer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A stand-in authorization service that only allows requests for the
+    /// `acme` tenant.
+    #[derive(Default)]
+    struct StandIn {
+        delay: Option<Duration>,
+        checks: AtomicUsize,
+    }
+
+    #[tonic::async_trait]
+    impl Authorization for StandIn {
+        async fn check(
+            &self,
+            req: tonic::Request<api::CheckRequest>,
+        ) -> Result<tonic::Response<api::CheckResponse>, tonic::Status> {
+            self.checks.fetch_add(1, Ordering::SeqCst);
+            if let Some(delay) = self.delay {
+                time::sleep(delay).await;
+            }
+
+            let attrs = req.into_inner().attributes.unwrap();
+            assert_eq!(attrs.context_extensions.get("env").unwrap(), "test");
+            assert_eq!(attrs.tls_session.unwrap().sni, "web.ns1.svc.cluster.local");
+            let source = attrs.source.unwrap();
+            match source.address.unwrap().address.unwrap() {
+                api::address::Address::SocketAddress(addr) => assert_eq!(
+                    addr.port_specifier,
+                    Some(api::socket_address::PortSpecifier::PortValue(50000))
+                ),
+                addr => panic!("unexpected address: {addr:?}"),
+            }
+            let http = attrs.request.unwrap().http.unwrap();
+            assert_eq!(http.method, "GET");
+            assert_eq!(http.path, "/search?q=foo");
+
+            let header = |key: &str, value: &str| api::HeaderValueOption {
+                header: Some(api::HeaderValue {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                }),
+            };
+            let rsp = if http.headers.get("x-tenant").map(String::as_str) == Some("acme") {
+                assert_eq!(
+                    source.principal,
+                    "foo.ns1.serviceaccount.identity.linkerd.cluster.local"
+                );
+                api::CheckResponse {
+                    status: Some(api::Status::default()),
+                    http_response: Some(HttpResponse::OkResponse(api::OkHttpResponse {
+                        headers: vec![header("x-entitled", "true")],
+                        headers_to_remove: vec!["authorization".to_string()],
+                    })),
+                }
+            } else {
+                api::CheckResponse {
+                    status: Some(api::Status {
+                        code: tonic::Code::PermissionDenied as i32,
+                        message: "unknown tenant".to_string(),
+                    }),
+                    http_response: Some(HttpResponse::DeniedResponse(api::DeniedHttpResponse {
+                        status: Some(api::HttpStatus { code: 401 }),
+                        headers: vec![header("www-authenticate", "Tenant")],
+                        body: String::new(),
+                    })),
+                }
+            };
+            Ok(tonic::Response::new(rsp))
+        }
+    }
+
+    fn config(protocol: Protocol, failure_mode: FailureMode) -> Config {
+        Config {
+            protocol,
+            timeout: Duration::from_secs(1),
+            failure_mode,
+            cache: None,
+        }
+    }
+
+    fn grpc_client(stand_in: Arc<StandIn>, config: Config) -> ExtAuthzClient {
+        ExtAuthzClient::new(
+            AuthorizationServer::from_arc(stand_in),
+            config,
+            ExtAuthzMetrics::register(&mut prom::Registry::default()),
+        )
+        .with_server_name("web.ns1.svc.cluster.local".parse().unwrap())
+    }
+
+    fn attributes(tenant: &str) -> Attributes {
+        attributes_with_headers(
+            tenant,
+            vec![
+                HeaderName::from_static("x-tenant"),
+                HeaderName::from_static("authorization"),
+            ],
+        )
+    }
+
+    fn attributes_with_headers(tenant: &str, include_headers: Vec<HeaderName>) -> Attributes {
+        let req = http::Request::builder()
+            .uri("http://example.com/search?q=foo")
+            .header("x-tenant", tenant)
+            .header("authorization", "Bearer token")
+            .body(())
+            .unwrap();
+        let ext_authz = ExtAuthz {
+            context: [("env".to_string(), "test".to_string())].into(),
+            include_headers,
+        };
+        let id: identity::Id = "foo.ns1.serviceaccount.identity.linkerd.cluster.local"
+            .parse()
+            .unwrap();
+        let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+            client_id: Some(tls::ClientId(id.clone())),
+            negotiated_protocol: None,
+        });
+        Attributes::new(
+            &ext_authz,
+            &req,
+            ([192, 0, 2, 3], 50000).into(),
+            Some(&id),
+            &tls,
+            ([192, 0, 2, 2], 8080).into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn grpc_allow_and_deny() {
+        let stand_in = Arc::new(StandIn::default());
+        let client = grpc_client(stand_in, config(Protocol::Grpc, FailureMode::Closed));
+
+        let allowed = client
+            .clone()
+            .check(attributes("acme"))
+            .await
+            .expect("request must be allowed");
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer token".parse().unwrap());
+        allowed.apply(&mut headers);
+        assert_eq!(headers.get("x-entitled").unwrap(), "true");
+        assert!(headers.get("authorization").is_none());
+
+        let err = client
+            .clone()
+            .check(attributes("initech"))
+            .await
+            .expect_err("request must be denied");
+        let denied = err.downcast_ref::<ExtAuthzDenied>().unwrap();
+        assert_eq!(denied.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(denied.headers.get("www-authenticate").unwrap(), "Tenant");
+
+        assert_eq!(client.metrics.allow.get(), 1);
+        assert_eq!(client.metrics.deny.get(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cache() {
+        let stand_in = Arc::new(StandIn::default());
+        let mut config = config(Protocol::Grpc, FailureMode::Closed);
+        config.cache = Some(CacheConfig {
+            capacity: 10,
+            ttl: Duration::from_secs(10),
+        });
+        let client = grpc_client(stand_in.clone(), config);
+
+        for _ in 0..3 {
+            client.clone().check(attributes("acme")).await.unwrap();
+            client
+                .clone()
+                .check(attributes("initech"))
+                .await
+                .unwrap_err();
+        }
+        assert_eq!(stand_in.checks.load(Ordering::SeqCst), 2);
+        assert_eq!(client.metrics.cache_hits.get(), 4);
+
+        time::sleep(Duration::from_secs(11)).await;
+        client.clone().check(attributes("acme")).await.unwrap();
+        assert_eq!(stand_in.checks.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cache_evicts_oldest() {
+        let stand_in = Arc::new(StandIn::default());
+        let mut config = config(Protocol::Grpc, FailureMode::Closed);
+        config.cache = Some(CacheConfig {
+            capacity: 1,
+            ttl: Duration::from_secs(10),
+        });
+        let client = grpc_client(stand_in.clone(), config);
+
+        client.clone().check(attributes("acme")).await.unwrap();
+        client
+            .clone()
+            .check(attributes("initech"))
+            .await
+            .unwrap_err();
+        client
+            .clone()
+            .check(attributes("initech"))
+            .await
+            .unwrap_err();
+        assert_eq!(stand_in.checks.load(Ordering::SeqCst), 2);
+
+        // The first decision was evicted to make room for the second.
+        client.clone().check(attributes("acme")).await.unwrap();
+        assert_eq!(stand_in.checks.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            client
+                .cache
+                .as_ref()
+                .unwrap()
+                .entries
+                .lock()
+                .decisions
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cache_requires_include_headers() {
+        let stand_in = Arc::new(StandIn::default());
+        let mut config = config(Protocol::Grpc, FailureMode::Closed);
+        config.cache = Some(CacheConfig {
+            capacity: 10,
+            ttl: Duration::from_secs(10),
+        });
+        let client = grpc_client(stand_in.clone(), config);
+
+        for _ in 0..3 {
+            client
+                .clone()
+                .check(attributes_with_headers("acme", vec![]))
+                .await
+                .unwrap();
+        }
+        assert_eq!(stand_in.checks.load(Ordering::SeqCst), 3);
+        assert_eq!(client.metrics.cache_hits.get(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failure_modes() {
+        let stand_in = Arc::new(StandIn {
+            delay: Some(Duration::from_secs(5)),
+            ..Default::default()
+        });
+
+        let open = grpc_client(stand_in.clone(), config(Protocol::Grpc, FailureMode::Open));
+        open.clone()
+            .check(attributes("initech"))
+            .await
+            .expect("fail-open must allow requests");
+        assert_eq!(open.metrics.timeout.get(), 1);
+
+        let closed = grpc_client(stand_in, config(Protocol::Grpc, FailureMode::Closed));
+        let err = closed
+            .clone()
+            .check(attributes("acme"))
+            .await
+            .expect_err("fail-closed must reject requests");
+        assert!(err.is::<ExtAuthzUnavailable>());
+    }
+
+    #[tokio::test]
+    async fn http() {
+        let stand_in = svc::mk(|req: http::Request<tonic::body::BoxBody>| {
+            assert_eq!(req.uri().path(), "/authz/search");
+            assert_eq!(
+                req.headers().get("l5d-client-id").unwrap(),
+                "foo.ns1.serviceaccount.identity.linkerd.cluster.local"
+            );
+            let rsp = if req.headers().get("x-tenant").unwrap() == "acme" {
+                http::Response::builder()
+                    .header("x-entitled", "true")
+                    .header("x-ignored", "true")
+            } else {
+                http::Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .header("x-denied-by", "authz")
+                    .header(http::header::CONTENT_LENGTH, "12")
+            };
+            futures::future::ok::<_, Error>(rsp.body(tonic::body::empty_body()).unwrap())
+        });
+        let client = ExtAuthzClient::new(
+            stand_in,
+            config(
+                Protocol::Http {
+                    path_prefix: "/authz".to_string(),
+                    allowed_upstream_headers: vec![HeaderName::from_static("x-entitled")],
+                },
+                FailureMode::Closed,
+            ),
+            ExtAuthzMetrics::register(&mut prom::Registry::default()),
+        );
+
+        let allowed = client.clone().check(attributes("acme")).await.unwrap();
+        let mut headers = HeaderMap::new();
+        allowed.apply(&mut headers);
+        assert_eq!(headers.get("x-entitled").unwrap(), "true");
+        assert!(headers.get("x-ignored").is_none());
+
+        let err = client
+            .clone()
+            .check(attributes("initech"))
+            .await
+            .unwrap_err();
+        let denied = err.downcast_ref::<ExtAuthzDenied>().unwrap();
+        assert_eq!(denied.status, StatusCode::FORBIDDEN);
+        assert_eq!(denied.headers.get("x-denied-by").unwrap(), "authz");
+        assert!(denied.headers.get(http::header::CONTENT_LENGTH).is_none());
+    }
+}
@@ -0,0 +1,371 @@
+use envoy_ratelimit_proto::ratelimit::{
+    self as api, rate_limit_response::Code, rate_limit_service_client::RateLimitServiceClient,
+};
+use linkerd_app_core::{metrics::prom, proxy::http, svc, Error, Result};
+use linkerd_proxy_server_policy::global_rate_limit::{DescriptorEntries, GlobalRateLimit};
+use std::time::Duration;
+use tokio::time;
+
+/// Configures how the inbound proxy consults an external rate limit service.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Bounds the time spent waiting for the rate limit service to respond.
+    pub timeout: Duration,
+
+    /// Determines whether requests are allowed when the rate limit service
+    /// cannot be reached.
+    pub failure_mode: FailureMode,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Requests are allowed when the rate limit service fails.
+    Open,
+
+    /// Requests are rejected when the rate limit service fails.
+    Closed,
+}
+
+/// A client for the Envoy `ratelimit.v3.RateLimitService` API.
+#[derive(Clone)]
+pub struct GlobalRateLimitClient {
+    client: RateLimitServiceClient<Client>,
+    config: Config,
+    metrics: GlobalRateLimitMetrics,
+}
+
+type Client = svc::BoxCloneSyncService<
+    http::Request<tonic::body::BoxBody>,
+    http::Response<tonic::body::BoxBody>,
+>;
+
+#[derive(Clone, Debug)]
+pub struct GlobalRateLimitMetrics {
+    ok: prom::Counter,
+    over_limit: prom::Counter,
+    error: prom::Counter,
+    timeout: prom::Counter,
+    latency: prom::Histogram,
+}
+
+/// Indicates that the rate limit service rejected a request.
+#[derive(Debug, thiserror::Error)]
+#[error("global rate limit exceeded")]
+pub struct GlobalRateLimited {
+    /// The time until the exhausted limit resets, if the service reported it.
+    pub retry_after: Option<Duration>,
+}
+
+/// Indicates that the rate limit service could not be consulted and that the
+/// proxy is configured to fail closed.
+#[derive(Debug, thiserror::Error)]
+#[error("global rate limit service unavailable: {0}")]
+pub struct GlobalRateLimitUnavailable(#[source] Error);
+
+// === impl GlobalRateLimitClient ===
+
+impl GlobalRateLimitClient {
+    pub fn new<S, B>(client: S, config: Config, metrics: GlobalRateLimitMetrics) -> Self
+    where
+        S: svc::Service<http::Request<tonic::body::BoxBody>, Response = http::Response<B>>,
+        S: Clone + Send + Sync + 'static,
+        S::Error: Into<Error>,
+        S::Future: Send + 'static,
+        B: http::HttpBody<Data = tonic::codegen::Bytes> + Send + 'static,
+        B::Error: Into<Error>,
+    {
+        use svc::ServiceExt;
+
+        let client = svc::BoxCloneSyncService::new(
+            client.map_response(|rsp: http::Response<B>| rsp.map(tonic::body::boxed)),
+        );
+        Self {
+            client: RateLimitServiceClient::new(client),
+            config,
+            metrics,
+        }
+    }
+
+    /// Checks the request's descriptors against the rate limit service.
+    ///
+    /// Failures to reach the service are handled according to the configured
+    /// [`FailureMode`].
+    pub(crate) async fn check(
+        mut self,
+        limit: &GlobalRateLimit,
+        descriptors: Vec<DescriptorEntries>,
+    ) -> Result<()> {
+        if descriptors.is_empty() {
+            return Ok(());
+        }
+
+        let req = api::RateLimitRequest {
+            domain: limit.domain.clone(),
+            descriptors: descriptors
+                .into_iter()
+                .map(|entries| api::RateLimitDescriptor {
+                    entries: entries
+                        .into_iter()
+                        .map(|(key, value)| api::rate_limit_descriptor::Entry { key, value })
+                        .collect(),
+                })
+                .collect(),
+            hits_addend: 0,
+        };
+
+        let t0 = time::Instant::now();
+        let res = time::timeout(self.config.timeout, self.client.should_rate_limit(req)).await;
+        self.metrics.latency.observe(
+            time::Instant::now()
+                .saturating_duration_since(t0)
+                .as_secs_f64(),
+        );
+
+        let error: Error = match res {
+            Ok(Ok(rsp)) => {
+                let rsp = rsp.into_inner();
+                if rsp.overall_code != Code::OverLimit as i32 {
+                    self.metrics.ok.inc();
+                    return Ok(());
+                }
+                self.metrics.over_limit.inc();
+                let retry_after = rsp
+                    .statuses
+                    .into_iter()
+                    .filter(|s| s.code == Code::OverLimit as i32)
+                    .filter_map(|s| Duration::try_from(s.duration_until_reset?).ok())
+                    .max();
+                return Err(GlobalRateLimited { retry_after }.into());
+            }
+            Ok(Err(status)) => {
+                self.metrics.error.inc();
+                status.into()
+            }
+            Err(_) => {
+                self.metrics.timeout.inc();
+                format!("no response within {:?}", self.config.timeout).into()
+            }
+        };
+
+        match self.config.failure_mode {
+            FailureMode::Open => {
+                tracing::debug!(%error, "Allowing request; rate limit service failed");
+                Ok(())
+            }
+            FailureMode::Closed => Err(GlobalRateLimitUnavailable(error).into()),
+        }
+    }
+}
+
+impl std::fmt::Debug for GlobalRateLimitClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlobalRateLimitClient")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+// === impl GlobalRateLimitMetrics ===
+
+impl GlobalRateLimitMetrics {
+    pub fn register(registry: &mut prom::Registry) -> Self {
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelSet)]
+        struct CheckLabels {
+            result: CheckResult,
+        }
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelValue)]
+        #[allow(non_camel_case_types)]
+        enum CheckResult {
+            ok,
+            over_limit,
+            error,
+            timeout,
+        }
+        let checks = prom::Family::<_, prom::Counter>::default();
+        registry.register(
+            "checks",
+            "The total number of requests checked against the rate limit service",
+            checks.clone(),
+        );
+        let counter = |result: CheckResult| checks.get_or_create(&CheckLabels { result }).clone();
+
+        let latency = prom::Histogram::new([0.001, 0.005, 0.01, 0.05, 0.1, 0.5].iter().copied());
+        registry.register_with_unit(
+            "check_duration",
+            "The distribution of times spent waiting for the rate limit service",
+            prom::Unit::Seconds,
+            latency.clone(),
+        );
+
+        Self {
+            ok: counter(CheckResult::ok),
+            over_limit: counter(CheckResult::over_limit),
+            error: counter(CheckResult::error),
+            timeout: counter(CheckResult::timeout),
+            latency,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api::{
+        rate_limit_response::DescriptorStatus,
+        rate_limit_service_server::{RateLimitService, RateLimitServiceServer},
+        RateLimitRequest, RateLimitResponse,
+    };
+    use linkerd_proxy_server_policy::global_rate_limit::{Descriptor, Entry};
+    use parking_lot::Mutex;
+    use std::{collections::HashMap, sync::Arc};
+
+    /// A stand-in rate limit service that limits each descriptor to a fixed
+    /// number of hits.
+    #[derive(Default)]
+    struct StandIn {
+        limit: u32,
+        delay: Option<Duration>,
+        hits: Mutex<HashMap<DescriptorEntries, u32>>,
+    }
+
+    #[tonic::async_trait]
+    impl RateLimitService for StandIn {
+        async fn should_rate_limit(
+            &self,
+            req: tonic::Request<RateLimitRequest>,
+        ) -> Result<tonic::Response<RateLimitResponse>, tonic::Status> {
+            if let Some(delay) = self.delay {
+                time::sleep(delay).await;
+            }
+            let req = req.into_inner();
+            assert_eq!(req.domain, "test");
+
+            let mut hits = self.hits.lock();
+            let statuses = req
+                .descriptors
+                .into_iter()
+                .map(|d| {
+                    let key = d.entries.into_iter().map(|e| (e.key, e.value)).collect();
+                    let n = hits.entry(key).or_default();
+                    *n += 1;
+                    let code = if *n > self.limit {
+                        Code::OverLimit
+                    } else {
+                        Code::Ok
+                    };
+                    DescriptorStatus {
+                        code: code as i32,
+                        limit_remaining: self.limit.saturating_sub(*n),
+                        duration_until_reset: Some(Duration::from_secs(3).try_into().unwrap()),
+                        ..Default::default()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let overall_code = statuses.iter().map(|s| s.code).max().unwrap_or_default();
+            Ok(tonic::Response::new(RateLimitResponse {
+                overall_code,
+                statuses,
+            }))
+        }
+    }
+
+    fn client(stand_in: Arc<StandIn>, failure_mode: FailureMode) -> GlobalRateLimitClient {
+        GlobalRateLimitClient::new(
+            RateLimitServiceServer::from_arc(stand_in),
+            Config {
+                timeout: Duration::from_secs(1),
+                failure_mode,
+            },
+            GlobalRateLimitMetrics::register(&mut prom::Registry::default()),
+        )
+    }
+
+    impl GlobalRateLimitClient {
+        /// Returns a client for a stand-in service that allows each descriptor
+        /// in the `test` domain a fixed number of hits.
+        pub(crate) fn for_test(limit: u32) -> Self {
+            client(
+                Arc::new(StandIn {
+                    limit,
+                    ..Default::default()
+                }),
+                FailureMode::Closed,
+            )
+        }
+    }
+
+    fn limit() -> GlobalRateLimit {
+        GlobalRateLimit {
+            domain: "test".to_string(),
+            descriptors: vec![Descriptor {
+                entries: vec![Entry::Path {
+                    key: "path".to_string(),
+                }],
+            }],
+        }
+    }
+
+    fn descriptors(path: &str) -> Vec<DescriptorEntries> {
+        vec![vec![("path".to_string(), path.to_string())]]
+    }
+
+    #[tokio::test]
+    async fn over_limit() {
+        let stand_in = Arc::new(StandIn {
+            limit: 1,
+            ..Default::default()
+        });
+        let client = client(stand_in, FailureMode::Closed);
+        let limit = limit();
+
+        client
+            .clone()
+            .check(&limit, descriptors("/foo"))
+            .await
+            .expect("first request must be allowed");
+        let err = client
+            .clone()
+            .check(&limit, descriptors("/foo"))
+            .await
+            .expect_err("second request must be limited");
+        let limited = err
+            .downcast_ref::<GlobalRateLimited>()
+            .expect("must be rate limited");
+        assert_eq!(limited.retry_after, Some(Duration::from_secs(3)));
+
+        // Other descriptors are limited independently.
+        client
+            .clone()
+            .check(&limit, descriptors("/bar"))
+            .await
+            .expect("other paths must be allowed");
+        assert_eq!(client.metrics.ok.get(), 2);
+        assert_eq!(client.metrics.over_limit.get(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failure_modes() {
+        let stand_in = Arc::new(StandIn {
+            limit: 1,
+            delay: Some(Duration::from_secs(5)),
+            ..Default::default()
+        });
+        let limit = limit();
+
+        let open = client(stand_in.clone(), FailureMode::Open);
+        open.clone()
+            .check(&limit, descriptors("/foo"))
+            .await
+            .expect("fail-open must allow requests");
+        assert_eq!(open.metrics.timeout.get(), 1);
+
+        let closed = client(stand_in, FailureMode::Closed);
+        let err = closed
+            .clone()
+            .check(&limit, descriptors("/foo"))
+            .await
+            .expect_err("fail-closed must reject requests");
+        assert!(err.is::<GlobalRateLimitUnavailable>());
+        assert_eq!(closed.metrics.timeout.get(), 1);
+    }
+}
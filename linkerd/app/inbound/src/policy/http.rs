@@ -1,17 +1,25 @@
-use super::{RoutePolicy, Routes};
+use super::{
+    audit, ext_authz, AuditSink, Authentication, Decision, ExtAuthzClient, GlobalRateLimitClient,
+    PolicyLearner, RoutePolicy, Routes,
+};
 use crate::{
-    metrics::authz::HttpAuthzMetrics,
+    metrics::authz::{ConcurrencyLimitScope, HttpAuthzMetrics},
     policy::{AllowPolicy, HttpRoutePermit},
 };
-use futures::{future, TryFutureExt};
+use futures::{future, FutureExt, TryFutureExt};
 use linkerd_app_core::{
+    identity,
     metrics::{RouteAuthzLabels, RouteLabels},
+    proxy::http::RetainBody,
     svc::{self, ServiceExt},
     tls,
     transport::{ClientAddr, OrigDstAddr, Remote},
     Conditional, Error, Result,
 };
-use linkerd_proxy_server_policy::{grpc, http, route::RouteMatch};
+use linkerd_proxy_server_policy::{
+    concurrency_limit::ConcurrencyPermit, grpc, http, route::RouteMatch, ConcurrencyLimit,
+    ExtAuthz, GlobalRateLimit, Meta,
+};
 use std::{sync::Arc, task};
 
 #[cfg(test)]
@@ -27,6 +35,10 @@ mod tests;
 #[derive(Clone, Debug)]
 pub struct NewHttpPolicy<N> {
     metrics: HttpAuthzMetrics,
+    global_rate_limit: Option<GlobalRateLimitClient>,
+    ext_authz: Option<ExtAuthzClient>,
+    audit: Option<AuditSink>,
+    learner: Option<PolicyLearner>,
     inner: N,
 }
 
@@ -36,9 +48,17 @@ pub struct HttpPolicyService<T, N> {
     connection: ConnectionMeta,
     policy: AllowPolicy,
     metrics: HttpAuthzMetrics,
+    global_rate_limit: Option<GlobalRateLimitClient>,
+    ext_authz: Option<ExtAuthzClient>,
+    audit: Option<AuditSink>,
+    learner: Option<PolicyLearner>,
     inner: N,
 }
 
+/// A response body that holds the request's concurrency permits until the
+/// response has been fully processed.
+pub type PermittedBody<B> = RetainBody<Vec<ConcurrencyPermit>, B>;
+
 #[derive(Clone, Debug)]
 struct ConnectionMeta {
     dst: OrigDstAddr,
@@ -86,9 +106,19 @@ pub struct HttpInvalidPolicy(&'static str);
 // === impl NewHttpPolicy ===
 
 impl<N> NewHttpPolicy<N> {
-    pub fn layer(metrics: HttpAuthzMetrics) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+    pub fn layer(
+        metrics: HttpAuthzMetrics,
+        global_rate_limit: Option<GlobalRateLimitClient>,
+        ext_authz: Option<ExtAuthzClient>,
+        audit: Option<AuditSink>,
+        learner: Option<PolicyLearner>,
+    ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
         svc::layer::mk(move |inner| Self {
             metrics: metrics.clone(),
+            global_rate_limit: global_rate_limit.clone(),
+            ext_authz: ext_authz.clone(),
+            audit: audit.clone(),
+            learner: learner.clone(),
             inner,
         })
     }
@@ -113,6 +143,10 @@ where
             policy,
             connection: ConnectionMeta { client, dst, tls },
             metrics: self.metrics.clone(),
+            global_rate_limit: self.global_rate_limit.clone(),
+            ext_authz: self.ext_authz.clone(),
+            audit: self.audit.clone(),
+            learner: self.learner.clone(),
             inner: self.inner.clone(),
         }
     }
@@ -135,17 +169,28 @@ macro_rules! try_fut {
     };
 }
 
-impl<B, T, N, S> svc::Service<::http::Request<B>> for HttpPolicyService<T, N>
+type UnlimitedFuture<S, B, RspB> = future::MapOk<
+    future::ErrInto<svc::stack::Oneshot<S, ::http::Request<B>>, Error>,
+    fn(::http::Response<RspB>) -> ::http::Response<PermittedBody<RspB>>,
+>;
+
+impl<B, RspB, T, N, S> svc::Service<::http::Request<B>> for HttpPolicyService<T, N>
 where
+    B: Send + 'static,
+    RspB: Send + 'static,
     T: Clone,
     N: svc::NewService<(HttpRoutePermit, T), Service = S>,
-    S: svc::Service<::http::Request<B>>,
+    S: svc::Service<::http::Request<B>, Response = ::http::Response<RspB>> + Send + 'static,
     S::Error: Into<Error>,
+    S::Future: Send,
 {
-    type Response = S::Response;
+    type Response = ::http::Response<PermittedBody<RspB>>;
     type Error = Error;
     type Future = future::Either<
-        future::ErrInto<svc::stack::Oneshot<S, ::http::Request<B>>, Error>,
+        future::Either<
+            UnlimitedFuture<S, B, RspB>,
+            future::BoxFuture<'static, Result<Self::Response>>,
+        >,
         future::Ready<Result<Self::Response>>,
     >;
 
@@ -157,28 +202,73 @@ where
     fn call(&mut self, mut req: ::http::Request<B>) -> Self::Future {
         // Find an appropriate route for the request and ensure that it's
         // authorized.
-        let permit = match self.policy.routes() {
+        let (permit, global_rate_limit, ext_authz, concurrency_limit) = match self.policy.routes() {
             None => err!(self.mk_route_not_found()),
             Some(Routes::Http(routes)) => {
-                let (permit, mtch, route) = try_fut!(self.authorize(&routes, &req));
+                let (permit, mtch, route) = try_fut!(self.authorize(&routes, &mut req));
+                try_fut!(self.check_route_rate_limit(route, &req));
                 try_fut!(apply_http_filters(mtch, route, &mut req));
-                permit
+                (
+                    permit,
+                    route.global_rate_limit.clone(),
+                    route.ext_authz.clone(),
+                    route.concurrency_limit.clone(),
+                )
             }
             Some(Routes::Grpc(routes)) => {
-                let (permit, _, route) = try_fut!(self.authorize(&routes, &req));
+                let (permit, _, route) = try_fut!(self.authorize(&routes, &mut req));
+                try_fut!(self.check_route_rate_limit(route, &req));
                 try_fut!(apply_grpc_filters(route, &mut req));
-                permit
+                (
+                    permit,
+                    route.global_rate_limit.clone(),
+                    route.ext_authz.clone(),
+                    route.concurrency_limit.clone(),
+                )
             }
         };
 
-        try_fut!(self.check_rate_limit());
+        try_fut!(self.check_rate_limit(&req));
+
+        let concurrency = self.acquire_concurrency(&permit, concurrency_limit);
+        let svc = self.inner.new_service((permit, self.target.clone()));
+        let ext_authz = self.ext_authz_check(ext_authz, &req);
+        let global_rate_limit = self.global_rate_limit(global_rate_limit);
+        if ext_authz.is_none() && global_rate_limit.is_none() && concurrency.is_none() {
+            return future::Either::Left(future::Either::Left(
+                svc.oneshot(req)
+                    .err_into::<Error>()
+                    .map_ok(without_permits as fn(_) -> _),
+            ));
+        }
 
-        future::Either::Left(
-            self.inner
-                .new_service((permit, self.target.clone()))
-                .oneshot(req)
-                .err_into::<Error>(),
-        )
+        // External services are consulted only after all local checks have
+        // passed. The authorization service is consulted before the rate limit
+        // service so that denied requests do not consume its limits, and so
+        // that rate limit descriptors may refer to injected headers.
+        //
+        // Concurrency permits are acquired last so that time spent consulting
+        // external services does not count against the limit. Permits are held
+        // until the response body has been fully processed.
+        let client_id = self.client_id().cloned();
+        future::Either::Left(future::Either::Right(
+            async move {
+                if let Some(check) = ext_authz {
+                    check.await?.apply(req.headers_mut());
+                }
+                if let Some((client, limit)) = global_rate_limit {
+                    let descriptors = limit.descriptors(client_id.as_ref(), &req);
+                    client.check(&limit, descriptors).await?;
+                }
+                let permits = match concurrency {
+                    Some(acquire) => acquire.await?,
+                    None => Vec::new(),
+                };
+                let rsp = svc.oneshot(req).await.map_err(Into::into)?;
+                Ok(rsp.map(|body| RetainBody::new(body, permits)))
+            }
+            .boxed(),
+        ))
     }
 }
 
@@ -186,10 +276,13 @@ impl<T, N> HttpPolicyService<T, N> {
     /// Finds a matching route for the given request and checks that a
     /// sufficient authorization is present, returning a permit describing the
     /// authorization.
+    ///
+    /// If the request is authorized by a JWT, the token's configured claims
+    /// are set as request headers.
     fn authorize<'m, M: super::route::Match + 'm, P, B>(
         &self,
         routes: &'m [super::route::Route<M, RoutePolicy<P>>],
-        req: &::http::Request<B>,
+        req: &mut ::http::Request<B>,
     ) -> Result<(HttpRoutePermit, RouteMatch<M::Summary>, &'m RoutePolicy<P>)> {
         let (r#match, route) =
             super::route::find(routes, req).ok_or_else(|| self.mk_route_not_found())?;
@@ -199,12 +292,37 @@ impl<T, N> HttpPolicyService<T, N> {
             server: self.policy.server_label(),
         };
 
-        let authz = match route
-            .authorizations
-            .iter()
-            .find(|a| super::is_authorized(a, self.connection.client, &self.connection.tls))
-        {
-            Some(authz) => {
+        // Claim headers may only be set by the authorization that permits the
+        // request, so any values set by the client are removed, even if the
+        // request is permitted by another authorization.
+        for authz in route.authorizations.iter() {
+            if let Authentication::Jwt(ref jwt) = authz.authentication {
+                jwt.remove_claim_headers(req.headers_mut());
+            }
+        }
+
+        let mut jwt_claims = Vec::new();
+        let mut jwt_error = None;
+        let decision = super::decide(&route.authorizations, |a| match a.authentication {
+            Authentication::Jwt(ref jwt) => {
+                if !super::is_network_authorized(a, self.connection.client) {
+                    return false;
+                }
+                match jwt.authenticate(req.headers()) {
+                    Ok(claims) => {
+                        jwt_claims.push((a, jwt, claims));
+                        true
+                    }
+                    Err(error) => {
+                        jwt_error.get_or_insert(error);
+                        false
+                    }
+                }
+            }
+            _ => super::is_authorized(a, self.connection.client, &self.connection.tls),
+        });
+        let (authz, audit) = match decision {
+            Decision::Allow { authz, audit } => {
                 if authz.meta.is_audit() {
                     tracing::info!(
                         server.group = %labels.server.0.group(),
@@ -221,33 +339,70 @@ impl<T, N> HttpPolicyService<T, N> {
                         "Request allowed",
                     );
                 }
-                authz
+                (authz, audit)
             }
-            None => {
-                tracing::info!(
-                    server.group = %labels.server.0.group(),
-                    server.kind = %labels.server.0.kind(),
-                    server.name = %labels.server.0.name(),
-                    route.group = %labels.route.group(),
-                    route.kind = %labels.route.kind(),
-                    route.name = %labels.route.name(),
-                    client.tls = ?self.connection.tls,
-                    client.ip = %self.connection.client.ip(),
-                    "Request denied",
-                );
-                if tracing::event_enabled!(tracing::Level::DEBUG) {
-                    if route.authorizations.is_empty() {
-                        tracing::debug!("No authorizations defined",);
+            Decision::Deny { authz } => {
+                if let Some(authz) = authz {
+                    tracing::info!(
+                        server.group = %labels.server.0.group(),
+                        server.kind = %labels.server.0.kind(),
+                        server.name = %labels.server.0.name(),
+                        route.group = %labels.route.group(),
+                        route.kind = %labels.route.kind(),
+                        route.name = %labels.route.name(),
+                        client.tls = ?self.connection.tls,
+                        client.ip = %self.connection.client.ip(),
+                        authz.group = %authz.meta.group(),
+                        authz.kind = %authz.meta.kind(),
+                        authz.name = %authz.meta.name(),
+                        "Request denied by authorization",
+                    );
+                } else {
+                    tracing::info!(
+                        server.group = %labels.server.0.group(),
+                        server.kind = %labels.server.0.kind(),
+                        server.name = %labels.server.0.name(),
+                        route.group = %labels.route.group(),
+                        route.kind = %labels.route.kind(),
+                        route.name = %labels.route.name(),
+                        client.tls = ?self.connection.tls,
+                        client.ip = %self.connection.client.ip(),
+                        "Request denied",
+                    );
+                    if tracing::event_enabled!(tracing::Level::DEBUG) {
+                        if route.authorizations.is_empty() {
+                            tracing::debug!("No authorizations defined",);
+                        }
+                        for authz in &*route.authorizations {
+                            tracing::debug!(
+                                authz.group = %authz.meta.group(),
+                                authz.kind = %authz.meta.kind(),
+                                authz.name = %authz.meta.name(),
+                                "Authorization did not apply",
+                            );
+                        }
                     }
-                    for authz in &*route.authorizations {
-                        tracing::debug!(
-                            authz.group = %authz.meta.group(),
-                            authz.kind = %authz.meta.kind(),
-                            authz.name = %authz.meta.name(),
-                            "Authorization did not apply",
+                    if let Some(error) = jwt_error {
+                        tracing::debug!(%error, "JWT authentication failed");
+                        self.metrics.jwt_failure(
+                            labels.clone(),
+                            error,
+                            self.connection.dst,
+                            self.connection.tls.clone(),
                         );
                     }
                 }
+                let reason = match (authz, jwt_error) {
+                    (Some(_), _) => "denied",
+                    (None, Some(error)) => error.reason(),
+                    (None, None) => "unauthorized",
+                };
+                self.record_audit(
+                    audit::Decision::Deny,
+                    &labels,
+                    authz.map(|a| &*a.meta),
+                    Some(reason),
+                );
                 self.metrics
                     .deny(labels, self.connection.dst, self.connection.tls.clone());
                 return Err(HttpRouteUnauthorized(()).into());
@@ -279,10 +434,88 @@ impl<T, N> HttpPolicyService<T, N> {
             }
         };
 
+        if let Some(audit) = audit {
+            tracing::info!(
+                server.group = %permit.labels.route.server.0.group(),
+                server.kind = %permit.labels.route.server.0.kind(),
+                server.name = %permit.labels.route.server.0.name(),
+                route.group = %permit.labels.route.route.group(),
+                route.kind = %permit.labels.route.route.kind(),
+                route.name = %permit.labels.route.route.name(),
+                client.tls = ?self.connection.tls,
+                client.ip = %self.connection.client.ip(),
+                authz.group = %audit.meta.group(),
+                authz.kind = %audit.meta.kind(),
+                authz.name = %audit.meta.name(),
+                "Request would have been denied",
+            );
+            self.metrics.audit(
+                RouteAuthzLabels {
+                    route: permit.labels.route.clone(),
+                    authz: audit.meta.clone(),
+                },
+                self.connection.dst,
+                self.connection.tls.clone(),
+            );
+        }
+        match audit {
+            Some(audit) => self.record_audit(
+                audit::Decision::Audit,
+                &permit.labels.route,
+                Some(&*audit.meta),
+                None,
+            ),
+            None => self.record_audit(
+                audit::Decision::Allow,
+                &permit.labels.route,
+                Some(&*authz.meta),
+                None,
+            ),
+        }
+
+        if let Some(learner) = &self.learner {
+            learner.observe(
+                &permit.labels.route.server.0,
+                Some(&permit.labels.route.route),
+                self.connection.client,
+                &self.connection.tls,
+            );
+        }
+
+        // Only the authorization that permits the request forwards its claims.
+        if let Some((_, jwt, claims)) = jwt_claims
+            .into_iter()
+            .find(|(a, _, _)| std::ptr::eq(*a, authz))
+        {
+            jwt.apply_claim_headers(&claims, req.headers_mut());
+        }
+
         self.metrics.allow(&permit, self.connection.tls.clone());
         Ok((permit, r#match, route))
     }
 
+    fn record_audit(
+        &self,
+        decision: audit::Decision,
+        labels: &RouteLabels,
+        authz: Option<&Meta>,
+        reason: Option<&'static str>,
+    ) {
+        if let Some(sink) = &self.audit {
+            sink.record(audit::Event {
+                decision,
+                protocol: "http",
+                client: self.connection.client,
+                tls: &self.connection.tls,
+                dst: self.connection.dst,
+                server: &labels.server.0,
+                route: Some(&*labels.route),
+                authz,
+                reason,
+            });
+        }
+    }
+
     fn mk_route_not_found(&self) -> Error {
         let labels = self.policy.server_label();
         self.metrics
@@ -290,20 +523,122 @@ impl<T, N> HttpPolicyService<T, N> {
         HttpRouteNotFound(()).into()
     }
 
-    fn check_rate_limit(&self) -> Result<()> {
-        let id = match self.connection.tls {
+    fn client_id(&self) -> Option<&identity::Id> {
+        match self.connection.tls {
             Conditional::Some(tls::ServerTls::Established {
                 client_id: Some(tls::ClientId(ref id)),
                 ..
             }) => Some(id),
             _ => None,
-        };
+        }
+    }
+
+    fn check_rate_limit<B>(&self, req: &::http::Request<B>) -> Result<()> {
         self.policy
             .borrow()
             .local_rate_limit
-            .check(id)
+            .check_request(self.client_id(), req.headers())
             .map_err(Into::into)
     }
+
+    /// Returns the rate limit service client, if the route configures a
+    /// global rate limit and a client is configured.
+    fn global_rate_limit(
+        &self,
+        limit: Option<Arc<GlobalRateLimit>>,
+    ) -> Option<(GlobalRateLimitClient, Arc<GlobalRateLimit>)> {
+        let limit = limit?;
+        let Some(client) = self.global_rate_limit.clone() else {
+            tracing::debug!("Ignoring global rate limit; no rate limit service configured");
+            return None;
+        };
+        Some((client, limit))
+    }
+
+    /// Returns a future that consults the authorization service, if the route
+    /// configures external authorization and a client is configured.
+    fn ext_authz_check<B>(
+        &self,
+        config: Option<Arc<ExtAuthz>>,
+        req: &::http::Request<B>,
+    ) -> Option<impl std::future::Future<Output = Result<ext_authz::Allowed>> + Send + 'static>
+    {
+        let config = config?;
+        let Some(client) = self.ext_authz.clone() else {
+            tracing::debug!("Ignoring external authorization; no authorization service configured");
+            return None;
+        };
+        let attrs = ext_authz::Attributes::new(
+            &config,
+            req,
+            self.connection.client.0.into(),
+            self.client_id(),
+            &self.connection.tls,
+            self.connection.dst.0,
+        );
+        Some(client.check(attrs))
+    }
+
+    /// Returns a future that acquires permits from the server's and route's
+    /// concurrency limits, if either is configured.
+    ///
+    /// Rejections are recorded in metrics, labeled by the limit that rejected
+    /// the request.
+    fn acquire_concurrency(
+        &self,
+        permit: &HttpRoutePermit,
+        route: Option<Arc<ConcurrencyLimit>>,
+    ) -> Option<impl std::future::Future<Output = Result<Vec<ConcurrencyPermit>>> + Send + 'static>
+    {
+        let server = self.policy.borrow().concurrency_limit.clone();
+        if server.is_none() && route.is_none() {
+            return None;
+        }
+
+        let metrics = self.metrics.clone();
+        let labels = permit.labels.route.clone();
+        let dst = self.connection.dst;
+        let tls = self.connection.tls.clone();
+        Some(async move {
+            // The route's permit is acquired first so that, while a request
+            // waits for the route's limit, it does not hold the server's
+            // capacity (which would block requests on other routes).
+            let limits = [
+                (ConcurrencyLimitScope::Route, route),
+                (ConcurrencyLimitScope::Server, server),
+            ];
+            let mut permits = Vec::with_capacity(limits.len());
+            for (scope, limit) in limits {
+                let Some(limit) = limit else { continue };
+                match limit.acquire().await {
+                    Ok(permit) => permits.push(permit),
+                    Err(error) => {
+                        tracing::debug!(%error, ?scope, "Request rejected by concurrency limit");
+                        metrics.concurrency_limit_rejected(labels, scope, dst, tls);
+                        return Err(error.into());
+                    }
+                }
+            }
+            Ok(permits)
+        })
+    }
+
+    /// Checks the route's rate limit, which applies in addition to the
+    /// server's rate limit.
+    fn check_route_rate_limit<P, B>(
+        &self,
+        route: &RoutePolicy<P>,
+        req: &::http::Request<B>,
+    ) -> Result<()> {
+        route
+            .local_rate_limit
+            .check_request(self.client_id(), req.headers())
+            .map_err(Into::into)
+    }
+}
+
+fn without_permits<B>(rsp: ::http::Response<B>) -> ::http::Response<PermittedBody<B>> {
+    rsp.map(|body| RetainBody::new(body, Vec::new()))
 }
 
 fn apply_http_filters<B>(
@@ -1,7 +1,7 @@
 use super::*;
 use crate::policy::{Authentication, Authorization, Meta, Protocol, ServerPolicy};
 use linkerd_app_core::{svc::Service, Infallible};
-use linkerd_proxy_server_policy::{LocalRateLimit, RateLimitError};
+use linkerd_proxy_server_policy::{authz, LocalRateLimit, RateLimitError, RateLimited};
 
 macro_rules! conn {
     ($client:expr, $dst:expr) => {{
@@ -31,6 +31,10 @@ macro_rules! new_svc {
                     name: "testsrv".into(),
                 }),
                 local_rate_limit: Arc::new($rl),
+                concurrency_limit: None,
+                connection_limit: None,
+                dscp: None,
+                proxy_protocol: false,
             },
         );
         let svc = HttpPolicyService {
@@ -38,6 +42,10 @@ macro_rules! new_svc {
             policy,
             connection: $conn,
             metrics: HttpAuthzMetrics::default(),
+            global_rate_limit: None,
+            ext_authz: None,
+            audit: None,
+            learner: None,
             inner: |(permit, _): (HttpRoutePermit, ())| {
                 let f = $rsp;
                 svc::mk(move |req: ::http::Request<hyper::Body>| {
@@ -98,8 +106,14 @@ async fn http_route() {
                             kind: "AuthorizationPolicy".into(),
                             name: "test".into(),
                         }),
+                        action: authz::Action::Allow,
+                        audit: false,
                     }]),
                     filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
                     meta: rmeta.clone(),
                 },
             },
@@ -111,6 +125,10 @@ async fn http_route() {
                 policy: Policy {
                     authorizations: Arc::new([]),
                     filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
                     meta: rmeta.clone(),
                 },
             }
@@ -182,8 +200,14 @@ async fn http_route() {
                                 kind: "AuthorizationPolicy".into(),
                                 name: "other".into(),
                             }),
+                            action: authz::Action::Allow,
+                            audit: false,
                         }]),
                         filters: vec![],
+                        local_rate_limit: Default::default(),
+                        global_rate_limit: None,
+                        ext_authz: None,
+                        concurrency_limit: None,
                         meta: rmeta.clone(),
                     },
                 },
@@ -201,14 +225,24 @@ async fn http_route() {
                                 kind: "AuthorizationPolicy".into(),
                                 name: "test".into(),
                             }),
+                            action: authz::Action::Allow,
+                            audit: false,
                         }]),
                         filters: vec![],
+                        local_rate_limit: Default::default(),
+                        global_rate_limit: None,
+                        ext_authz: None,
+                        concurrency_limit: None,
                         meta: rmeta.clone(),
                     },
                 },
             ],
         }])),
         local_rate_limit: Arc::new(Default::default()),
+        concurrency_limit: None,
+        connection_limit: None,
+        dscp: None,
+        proxy_protocol: false,
     })
     .expect("must send");
 
@@ -276,11 +310,17 @@ async fn http_filter_header() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    action: authz::Action::Allow,
+                    audit: false,
                 }]),
                 filters: vec![Filter::RequestHeaders(filter::ModifyHeader {
                     add: vec![("testkey".parse().unwrap(), "testval".parse().unwrap())],
                     ..filter::ModifyHeader::default()
                 })],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
                 meta: rmeta.clone(),
             },
         }],
@@ -341,6 +381,8 @@ async fn http_filter_inject_failure() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    action: authz::Action::Allow,
+                    audit: false,
                 }]),
                 filters: vec![Filter::InjectFailure(filter::InjectFailure {
                     distribution: filter::Distribution::from_ratio(1, 1).unwrap(),
@@ -349,6 +391,10 @@ async fn http_filter_inject_failure() {
                         message: "oopsie".into(),
                     },
                 })],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
                 meta: rmeta.clone(),
             },
         }],
@@ -388,6 +434,8 @@ async fn rate_limit_allow() {
         meta: rmeta.clone(),
         networks: vec![Ipv4Net::default().into(), Ipv6Net::default().into()],
         authentication: Authentication::Unauthenticated,
+        action: authz::Action::Allow,
+        audit: false,
     }]);
 
     let (mut svc, _tx) = new_svc!(
@@ -431,6 +479,8 @@ async fn rate_limit_deny() {
         meta: rmeta.clone(),
         networks: vec![Ipv4Net::default().into(), Ipv6Net::default().into()],
         authentication: Authentication::Unauthenticated,
+        action: authz::Action::Allow,
+        audit: false,
     }]);
 
     let (mut svc, _tx) = new_svc!(
@@ -459,7 +509,8 @@ async fn rate_limit_deny() {
         .await
         .expect_err("should deny");
     let err = rsp
-        .downcast_ref::<RateLimitError>()
+        .downcast_ref::<RateLimited>()
+        .map(|RateLimited { limit, .. }| limit)
         .expect("rate limit error");
     match err {
         RateLimitError::PerIdentity(rps) => assert_eq!(rps, &std::num::NonZeroU32::new(1).unwrap()),
@@ -467,6 +518,375 @@ async fn rate_limit_deny() {
     };
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn route_rate_limit() {
+    use linkerd_app_core::{Ipv4Net, Ipv6Net};
+    use linkerd_proxy_server_policy::http::{
+        r#match::{MatchPath, MatchRequest},
+        Policy, Route, Rule,
+    };
+
+    let rmeta = Meta::new_default("default");
+    let authorizations: Arc<[Authorization]> = Arc::new([Authorization {
+        meta: rmeta.clone(),
+        networks: vec![Ipv4Net::default().into(), Ipv6Net::default().into()],
+        authentication: Authentication::Unauthenticated,
+        action: authz::Action::Allow,
+        audit: false,
+    }]);
+
+    // Only the search route is limited, with room for one request per tenant.
+    let (mut svc, _tx) = new_svc!(Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![
+            Rule {
+                matches: vec![MatchRequest {
+                    path: Some(MatchPath::Prefix("/search".to_string())),
+                    ..MatchRequest::default()
+                }],
+                policy: Policy {
+                    authorizations: authorizations.clone(),
+                    filters: vec![],
+                    local_rate_limit: Arc::new(LocalRateLimit::default().with_per_header(
+                        "x-tenant".parse().unwrap(),
+                        std::num::NonZeroU32::new(1).unwrap(),
+                    )),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
+                    meta: rmeta.clone(),
+                },
+            },
+            Rule {
+                matches: vec![],
+                policy: Policy {
+                    authorizations,
+                    filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
+                    meta: rmeta.clone(),
+                },
+            },
+        ],
+    }])));
+
+    let req = |path: &str, tenant: &str| {
+        ::http::Request::builder()
+            .uri(path)
+            .header("x-tenant", tenant)
+            .body(hyper::Body::default())
+            .unwrap()
+    };
+
+    svc.call(req("/search", "a")).await.expect("serves");
+    svc.call(req("/search", "b")).await.expect("serves");
+    let err = svc
+        .call(req("/search", "a"))
+        .await
+        .expect_err("should deny");
+    assert_eq!(
+        err.downcast_ref::<RateLimited>().map(|e| &e.limit),
+        Some(&RateLimitError::PerHeader(
+            "x-tenant".parse().unwrap(),
+            std::num::NonZeroU32::new(1).unwrap()
+        ))
+    );
+
+    // Other routes are not limited.
+    svc.call(req("/", "a")).await.expect("serves");
+    svc.call(req("/", "a")).await.expect("serves");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn route_global_rate_limit_overrides() {
+    use linkerd_app_core::{Ipv4Net, Ipv6Net};
+    use linkerd_proxy_server_policy::Overrides;
+
+    let authorizations = Arc::new([Authorization {
+        meta: Meta::new_default("default"),
+        networks: vec![Ipv4Net::default().into(), Ipv6Net::default().into()],
+        authentication: Authentication::Unauthenticated,
+        action: authz::Action::Allow,
+        audit: false,
+    }]);
+    let (mut svc, tx) = new_svc!(Protocol::Http1(Arc::new([http::default(authorizations)])));
+    svc.global_rate_limit = Some(GlobalRateLimitClient::for_test(1));
+
+    let req = |path: &str| {
+        ::http::Request::builder()
+            .uri(path)
+            .body(hyper::Body::default())
+            .unwrap()
+    };
+
+    // Without a global rate limit, the rate limit service is not consulted.
+    svc.call(req("/a")).await.expect("serves");
+    svc.call(req("/a")).await.expect("serves");
+
+    // Configure the default route to limit each path via the overrides.
+    let overrides = r#"{
+        "servers": [{
+            "kind": "Server",
+            "name": "testsrv",
+            "routes": [{
+                "name": "default",
+                "globalRateLimit": {
+                    "domain": "test",
+                    "descriptors": [{"entries": [{"path": {"key": "path"}}]}]
+                }
+            }]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+    let policy = overrides.apply(tx.borrow().clone());
+    tx.send(policy).expect("must send");
+
+    svc.call(req("/a")).await.expect("serves");
+    let err = svc.call(req("/a")).await.expect_err("should deny");
+    assert!(err.is::<crate::policy::GlobalRateLimited>());
+    svc.call(req("/b")).await.expect("serves");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn route_concurrency_limit() {
+    use linkerd_app_core::{Ipv4Net, Ipv6Net};
+    use linkerd_proxy_server_policy::{
+        http::{Policy, Route, Rule},
+        ConcurrencyLimit, ConcurrencyLimitExceeded,
+    };
+
+    let rmeta = Meta::new_default("default");
+    let limit = Arc::new(ConcurrencyLimit::new(1, None));
+    let (mut svc, _tx) = new_svc!(Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![Rule {
+            matches: vec![],
+            policy: Policy {
+                authorizations: Arc::new([Authorization {
+                    meta: rmeta.clone(),
+                    networks: vec![Ipv4Net::default().into(), Ipv6Net::default().into()],
+                    authentication: Authentication::Unauthenticated,
+                    action: authz::Action::Allow,
+                    audit: false,
+                }]),
+                filters: vec![],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: Some(limit.clone()),
+                meta: rmeta.clone(),
+            },
+        }],
+    }])));
+
+    // Permits are held until response bodies are released.
+    let rsp = svc.call(::http::Request::default()).await.expect("serves");
+    svc.call(::http::Request::default())
+        .await
+        .expect_err("should reject while the response is in flight");
+    drop(rsp);
+    svc.call(::http::Request::default()).await.expect("serves");
+
+    // Requests are rejected while the limit is exhausted.
+    let permit = limit.acquire().await.expect("must acquire");
+    let err = svc
+        .call(::http::Request::default())
+        .await
+        .expect_err("should reject");
+    assert_eq!(
+        err.downcast_ref::<ConcurrencyLimitExceeded>(),
+        Some(&ConcurrencyLimitExceeded::InFlight(1))
+    );
+
+    drop(permit);
+    svc.call(::http::Request::default()).await.expect("serves");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn jwt_route() {
+    use linkerd_app_core::{Ipv4Net, Ipv6Net};
+    use linkerd_proxy_server_policy::{
+        authz::jwt::{ClaimHeader, ClaimMatch, Jwks, Jwt},
+        http::{Policy, Route, Rule},
+    };
+
+    let secret = b"an-example-secret-of-sufficient-length";
+    let jwks = serde_json::json!({
+        "keys": [{
+            "kty": "oct",
+            "alg": "HS256",
+            "k": "YW4tZXhhbXBsZS1zZWNyZXQtb2Ytc3VmZmljaWVudC1sZW5ndGg",
+        }],
+    });
+    let jwt = Jwt {
+        jwks: Jwks::from_json(&jwks.to_string()).unwrap(),
+        issuer: "https://issuer.example.com".to_string(),
+        audiences: Default::default(),
+        claims: vec![ClaimMatch {
+            name: "groups".to_string(),
+            values: ["admin".to_string()].into(),
+        }],
+        claim_headers: vec![ClaimHeader {
+            claim: "sub".to_string(),
+            header: "x-jwt-sub".parse().unwrap(),
+        }],
+    };
+
+    let rmeta = Meta::new_default("default");
+    let (mut svc, _tx) = new_svc!(
+        Protocol::Http1(Arc::new([Route {
+            hosts: vec![],
+            rules: vec![Rule {
+                matches: vec![],
+                policy: Policy {
+                    authorizations: Arc::new([Authorization {
+                        meta: rmeta.clone(),
+                        networks: vec![Ipv4Net::default().into(), Ipv6Net::default().into()],
+                        authentication: Authentication::Jwt(Arc::new(jwt)),
+                        action: authz::Action::Allow,
+                        audit: false,
+                    }]),
+                    filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
+                    meta: rmeta.clone(),
+                },
+            }],
+        }])),
+        conn!(),
+        |_: HttpRoutePermit, req: ::http::Request<hyper::Body>| {
+            // Echo the forwarded claim so that it can be checked.
+            let mut rsp = ::http::Response::builder();
+            if let Some(sub) = req.headers().get("x-jwt-sub") {
+                rsp = rsp.header("x-jwt-sub", sub);
+            }
+            Ok::<_, Infallible>(rsp.body(hyper::Body::default()).unwrap())
+        },
+        Default::default()
+    );
+
+    let req = |groups: &[&str]| {
+        let claims = serde_json::json!({
+            "iss": "https://issuer.example.com",
+            "sub": "alice",
+            "groups": groups,
+            "exp": jsonwebtoken::get_current_timestamp() + 60,
+        });
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(secret),
+        )
+        .unwrap();
+        ::http::Request::builder()
+            .header("authorization", format!("Bearer {token}"))
+            .header("x-jwt-sub", "mallory")
+            .body(hyper::Body::default())
+            .unwrap()
+    };
+
+    let rsp = svc.call(req(&["admin"])).await.expect("serves");
+    assert_eq!(rsp.headers().get("x-jwt-sub").unwrap(), "alice");
+
+    assert!(svc
+        .call(req(&["dev"]))
+        .await
+        .expect_err("claims must match")
+        .is::<HttpRouteUnauthorized>());
+    assert!(svc
+        .call(
+            ::http::Request::builder()
+                .body(hyper::Body::default())
+                .unwrap()
+        )
+        .await
+        .expect_err("token is required")
+        .is::<HttpRouteUnauthorized>());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn jwt_claim_headers_cannot_be_forged() {
+    use linkerd_app_core::{Ipv4Net, Ipv6Net};
+    use linkerd_proxy_server_policy::{
+        authz::jwt::{ClaimHeader, Jwks, Jwt},
+        http::{Policy, Route, Rule},
+    };
+
+    let jwks = serde_json::json!({
+        "keys": [{
+            "kty": "oct",
+            "alg": "HS256",
+            "k": "YW4tZXhhbXBsZS1zZWNyZXQtb2Ytc3VmZmljaWVudC1sZW5ndGg",
+        }],
+    });
+    let jwt = Jwt {
+        jwks: Jwks::from_json(&jwks.to_string()).unwrap(),
+        issuer: "https://issuer.example.com".to_string(),
+        audiences: Default::default(),
+        claims: vec![],
+        claim_headers: vec![ClaimHeader {
+            claim: "sub".to_string(),
+            header: "x-jwt-sub".parse().unwrap(),
+        }],
+    };
+
+    // Requests without a token are permitted by another authorization.
+    let rmeta = Meta::new_default("default");
+    let networks = vec![Ipv4Net::default().into(), Ipv6Net::default().into()];
+    let (mut svc, _tx) = new_svc!(
+        Protocol::Http1(Arc::new([Route {
+            hosts: vec![],
+            rules: vec![Rule {
+                matches: vec![],
+                policy: Policy {
+                    authorizations: Arc::new([
+                        Authorization {
+                            meta: Meta::new_default("jwt"),
+                            networks: networks.clone(),
+                            authentication: Authentication::Jwt(Arc::new(jwt)),
+                            action: authz::Action::Allow,
+                            audit: false,
+                        },
+                        Authorization {
+                            meta: Meta::new_default("all"),
+                            networks,
+                            authentication: Authentication::Unauthenticated,
+                            action: authz::Action::Allow,
+                            audit: false,
+                        },
+                    ]),
+                    filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
+                    meta: rmeta.clone(),
+                },
+            }],
+        }])),
+        conn!(),
+        |_: HttpRoutePermit, req: ::http::Request<hyper::Body>| {
+            assert!(req.headers().get("x-jwt-sub").is_none());
+            Ok::<_, Infallible>(::http::Response::new(hyper::Body::default()))
+        },
+        Default::default()
+    );
+
+    svc.call(
+        ::http::Request::builder()
+            .header("x-jwt-sub", "mallory")
+            .body(hyper::Body::default())
+            .unwrap(),
+    )
+    .await
+    .expect("serves");
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn grpc_route() {
     use linkerd_proxy_server_policy::grpc::{
@@ -499,8 +919,14 @@ async fn grpc_route() {
                             kind: "AuthorizationPolicy".into(),
                             name: "test".into(),
                         }),
+                        action: authz::Action::Allow,
+                        audit: false,
                     }]),
                     filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
                     meta: rmeta.clone(),
                 },
             },
@@ -515,6 +941,10 @@ async fn grpc_route() {
                 policy: Policy {
                     authorizations: Arc::new([]),
                     filters: vec![],
+                    local_rate_limit: Default::default(),
+                    global_rate_limit: None,
+                    ext_authz: None,
+                    concurrency_limit: None,
                     meta: rmeta.clone(),
                 },
             }
@@ -597,11 +1027,17 @@ async fn grpc_filter_header() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    action: authz::Action::Allow,
+                    audit: false,
                 }]),
                 filters: vec![Filter::RequestHeaders(http::filter::ModifyHeader {
                     add: vec![("testkey".parse().unwrap(), "testval".parse().unwrap())],
                     ..http::filter::ModifyHeader::default()
                 })],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
                 meta: rmeta.clone(),
             },
         }],
@@ -670,6 +1106,8 @@ async fn grpc_filter_inject_failure() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    action: authz::Action::Allow,
+                    audit: false,
                 }]),
                 filters: vec![Filter::InjectFailure(filter::InjectFailure {
                     distribution: filter::Distribution::from_ratio(1, 1).unwrap(),
@@ -678,6 +1116,10 @@ async fn grpc_filter_inject_failure() {
                         message: "oopsie".into(),
                     },
                 })],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
                 meta: rmeta.clone(),
             },
         }],
@@ -0,0 +1,295 @@
+use linkerd_app_core::{
+    tls,
+    transport::{ClientAddr, Remote},
+    IpNet,
+};
+use linkerd_proxy_server_policy::Meta;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    net::IpAddr,
+    sync::Arc,
+};
+
+/// Configures policy learning.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Bounds the number of distinct observations that are retained. Once the
+    /// limit is reached, new observations are dropped.
+    pub capacity: usize,
+
+    /// Client addresses are aggregated into networks of these prefix lengths.
+    pub ipv4_prefix_len: u8,
+    pub ipv6_prefix_len: u8,
+}
+
+/// Records the distinct clients that are permitted by each server and route so
+/// that a set of authorizations can be proposed for them.
+///
+/// This is intended to be used while servers run under a permissive policy so
+/// that the policy can be safely tightened.
+#[derive(Clone)]
+pub struct PolicyLearner(Arc<Inner>);
+
+struct Inner {
+    config: Config,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    observed: HashSet<Observation>,
+    dropped: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Observation {
+    server: Arc<Meta>,
+    route: Option<Arc<Meta>>,
+    client: Client,
+    network: IpNet,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Client {
+    Unauthenticated,
+    TlsUnauthenticated,
+    Identity(String),
+}
+
+type MetaKey<'a> = (&'a str, &'a str, &'a str);
+
+// === impl PolicyLearner ===
+
+impl PolicyLearner {
+    pub fn new(config: Config) -> Self {
+        Self(Arc::new(Inner {
+            config,
+            state: Default::default(),
+        }))
+    }
+
+    /// Records a client that was permitted by the given server and, for HTTP
+    /// requests, route.
+    pub(crate) fn observe(
+        &self,
+        server: &Arc<Meta>,
+        route: Option<&Arc<Meta>>,
+        client: Remote<ClientAddr>,
+        tls: &tls::ConditionalServerTls,
+    ) {
+        let client_id = match tls {
+            tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+                client_id: Some(id),
+                ..
+            }) => Client::Identity(id.to_string()),
+            tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+                client_id: None,
+                ..
+            }) => Client::TlsUnauthenticated,
+            _ => Client::Unauthenticated,
+        };
+        let ip = client.ip();
+        let prefix_len = match ip {
+            IpAddr::V4(_) => self.0.config.ipv4_prefix_len,
+            IpAddr::V6(_) => self.0.config.ipv6_prefix_len,
+        };
+        let network = IpNet::new(ip, prefix_len)
+            .map(|net| net.trunc())
+            .unwrap_or_else(|_| ip.into());
+
+        let obs = Observation {
+            server: server.clone(),
+            route: route.cloned(),
+            client: client_id,
+            network,
+        };
+
+        let mut state = self.0.state.lock();
+        if state.observed.contains(&obs) {
+            return;
+        }
+        if state.observed.len() >= self.0.config.capacity {
+            state.dropped += 1;
+            return;
+        }
+        tracing::debug!(?obs, "Learned new client");
+        state.observed.insert(obs);
+    }
+
+    /// Discards all observations.
+    pub fn reset(&self) {
+        let mut state = self.0.state.lock();
+        state.observed.clear();
+        state.dropped = 0;
+    }
+
+    /// Describes the suggested authorizations for each observed server and
+    /// route.
+    ///
+    /// A single authorization is proposed for each distinct client, permitting
+    /// all of the networks from which it was observed.
+    pub fn suggestions(&self) -> serde_json::Value {
+        fn meta_key(m: &Meta) -> MetaKey<'_> {
+            (m.group(), m.kind(), m.name())
+        }
+        fn meta((group, kind, name): MetaKey<'_>) -> serde_json::Value {
+            json!({ "group": group, "kind": kind, "name": name })
+        }
+
+        let state = self.0.state.lock();
+
+        type Clients = BTreeMap<Client, BTreeSet<IpNet>>;
+        let mut servers = BTreeMap::<MetaKey<'_>, BTreeMap<Option<MetaKey<'_>>, Clients>>::new();
+        for obs in &state.observed {
+            servers
+                .entry(meta_key(&obs.server))
+                .or_default()
+                .entry(obs.route.as_deref().map(meta_key))
+                .or_default()
+                .entry(obs.client.clone())
+                .or_default()
+                .insert(obs.network);
+        }
+
+        let servers = servers
+            .into_iter()
+            .map(|(server, routes)| {
+                let routes = routes
+                    .into_iter()
+                    .map(|(route, clients)| {
+                        let authorizations = clients
+                            .into_iter()
+                            .map(|(client, networks)| {
+                                let authentication = match client {
+                                    Client::Unauthenticated => json!("unauthenticated"),
+                                    Client::TlsUnauthenticated => {
+                                        json!({ "meshTLS": "unauthenticated" })
+                                    }
+                                    Client::Identity(id) => {
+                                        json!({ "meshTLS": { "identities": [id] } })
+                                    }
+                                };
+                                json!({
+                                    "networks": networks
+                                        .iter()
+                                        .map(ToString::to_string)
+                                        .collect::<Vec<_>>(),
+                                    "authentication": authentication,
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        json!({
+                            "route": route.map(meta),
+                            "authorizations": authorizations,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "server": meta(server),
+                    "routes": routes,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "servers": servers,
+            "dropped": state.dropped,
+        })
+    }
+}
+
+impl std::fmt::Debug for PolicyLearner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolicyLearner")
+            .field("config", &self.0.config)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn learner(capacity: usize) -> PolicyLearner {
+        PolicyLearner::new(Config {
+            capacity,
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 64,
+        })
+    }
+
+    fn client(ip: [u8; 4]) -> Remote<ClientAddr> {
+        Remote(ClientAddr((ip, 40000).into()))
+    }
+
+    fn tls(id: &str) -> tls::ConditionalServerTls {
+        tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+            client_id: Some(id.parse().unwrap()),
+            negotiated_protocol: None,
+        })
+    }
+
+    #[test]
+    fn suggests_authorizations() {
+        let learner = learner(100);
+        let server = Meta::new_default("srv");
+        let route = Meta::new_default("route");
+        let plain = tls::ConditionalServerTls::None(tls::NoServerTls::NoClientHello);
+
+        learner.observe(&server, Some(&route), client([10, 0, 1, 5]), &tls("a.ns"));
+        learner.observe(&server, Some(&route), client([10, 0, 1, 6]), &tls("a.ns"));
+        learner.observe(&server, Some(&route), client([10, 0, 2, 6]), &tls("a.ns"));
+        learner.observe(&server, Some(&route), client([192, 0, 2, 1]), &plain);
+        learner.observe(&server, None, client([10, 0, 1, 5]), &tls("b.ns"));
+
+        let json = learner.suggestions();
+        assert_eq!(json["dropped"], 0);
+        let servers = json["servers"].as_array().unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0]["server"]["name"], "srv");
+
+        let routes = servers[0]["routes"].as_array().unwrap();
+        assert_eq!(routes.len(), 2);
+        assert!(routes[0]["route"].is_null());
+        assert_eq!(
+            routes[0]["authorizations"],
+            json!([{
+                "networks": ["10.0.1.0/24"],
+                "authentication": { "meshTLS": { "identities": ["b.ns"] } },
+            }])
+        );
+        assert_eq!(routes[1]["route"]["name"], "route");
+        assert_eq!(
+            routes[1]["authorizations"],
+            json!([
+                {
+                    "networks": ["192.0.2.0/24"],
+                    "authentication": "unauthenticated",
+                },
+                {
+                    "networks": ["10.0.1.0/24", "10.0.2.0/24"],
+                    "authentication": { "meshTLS": { "identities": ["a.ns"] } },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn bounded() {
+        let learner = learner(2);
+        let server = Meta::new_default("srv");
+        let plain = tls::ConditionalServerTls::None(tls::NoServerTls::NoClientHello);
+        for i in 0..4 {
+            learner.observe(&server, None, client([10, 0, i, 1]), &plain);
+        }
+        // Repeated observations are not counted as dropped.
+        learner.observe(&server, None, client([10, 0, 0, 2]), &plain);
+        assert_eq!(learner.0.state.lock().observed.len(), 2);
+        assert_eq!(learner.suggestions()["dropped"], 2);
+
+        learner.reset();
+        assert!(learner.0.state.lock().observed.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use super::{audit, AuditSink, Decision, PolicyLearner};
 use crate::{
     metrics::authz::TcpAuthzMetrics,
     policy::{AllowPolicy, ServerPermit, ServerUnauthorized},
@@ -8,8 +9,8 @@ use linkerd_app_core::{
     transport::{ClientAddr, OrigDstAddr, Remote},
     Error, Result,
 };
-use linkerd_proxy_server_policy::{Protocol, ServerPolicy};
-use std::{future::Future, pin::Pin, task};
+use linkerd_proxy_server_policy::{Meta, Protocol, ServerPolicy};
+use std::{future::Future, pin::Pin, sync::Arc, task};
 #[cfg(test)]
 mod tests;
 
@@ -22,6 +23,8 @@ mod tests;
 pub struct NewTcpPolicy<N> {
     inner: N,
     metrics: TcpAuthzMetrics,
+    audit: Option<AuditSink>,
+    learner: Option<PolicyLearner>,
 }
 
 #[derive(Clone, Debug)]
@@ -44,10 +47,14 @@ pub struct Authorized<S> {
 impl<N> NewTcpPolicy<N> {
     pub(crate) fn layer(
         metrics: TcpAuthzMetrics,
+        audit: Option<AuditSink>,
+        learner: Option<PolicyLearner>,
     ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
         svc::layer::mk(move |inner| Self {
             inner,
             metrics: metrics.clone(),
+            audit: audit.clone(),
+            learner: learner.clone(),
         })
     }
 }
@@ -68,11 +75,46 @@ where
         let authorized = {
             let p = policy.server.borrow();
             tracing::trace!(policy = ?p, "Authorizing connection");
-            check_authorized(&p, policy.dst, client, &tls)
+            authorize(&p, policy.dst, client, &tls)
         };
         match authorized {
-            Ok(permit) => {
+            Ok((permit, audit)) => {
                 tracing::debug!(?permit, ?tls, %client, "Connection authorized");
+                if let Some(authz) = &audit {
+                    let meta = policy.meta();
+                    tracing::info!(
+                        server.group = %meta.group(),
+                        server.kind = %meta.kind(),
+                        server.name = %meta.name(),
+                        ?tls, %client,
+                        authz.group = %authz.group(),
+                        authz.kind = %authz.kind(),
+                        authz.name = %authz.name(),
+                        "Connection would have been denied",
+                    );
+                    self.metrics.audit(&permit, authz.clone(), tls.clone());
+                }
+                if let Some(sink) = &self.audit {
+                    let (decision, authz) = match audit {
+                        Some(ref authz) => (audit::Decision::Audit, &**authz),
+                        None => (audit::Decision::Allow, &*permit.labels.authz),
+                    };
+                    sink.record(audit::Event {
+                        decision,
+                        protocol: "tcp",
+                        client,
+                        tls: &tls,
+                        dst: policy.dst,
+                        server: &permit.labels.server.0,
+                        route: None,
+                        authz: Some(authz),
+                        reason: None,
+                    });
+                }
+
+                if let Some(learner) = &self.learner {
+                    learner.observe(&permit.labels.server.0, None, client, &tls);
+                }
 
                 // This new services requires a ClientAddr, so it must necessarily be built for each
                 // connection. So we can just increment the counter here since the service can only
@@ -97,6 +139,23 @@ where
                     ?tls, %client,
                     "Connection denied"
                 );
+                if let Some(sink) = &self.audit {
+                    sink.record(audit::Event {
+                        decision: audit::Decision::Deny,
+                        protocol: "tcp",
+                        client,
+                        tls: &tls,
+                        dst: policy.dst,
+                        server: &meta,
+                        route: None,
+                        authz: deny.authz.as_deref(),
+                        reason: Some(if deny.authz.is_some() {
+                            "denied"
+                        } else {
+                            "unauthorized"
+                        }),
+                    });
+                }
                 self.metrics.deny(&policy, tls);
                 TcpPolicy::Unauthorized(deny)
             }
@@ -185,6 +244,18 @@ fn check_authorized(
     client_addr: Remote<ClientAddr>,
     tls: &tls::ConditionalServerTls,
 ) -> Result<ServerPermit, ServerUnauthorized> {
+    authorize(server, dst, client_addr, tls).map(|(permit, _)| permit)
+}
+
+/// Like [`check_authorized`], but if the connection is only permitted by an
+/// audit-mode authorization, that authorization's metadata is returned with
+/// the permit.
+fn authorize(
+    server: &ServerPolicy,
+    dst: OrigDstAddr,
+    client_addr: Remote<ClientAddr>,
+    tls: &tls::ConditionalServerTls,
+) -> Result<(ServerPermit, Option<Arc<Meta>>), ServerUnauthorized> {
     if let Protocol::Detect {
         tcp_authorizations: authzs,
         ..
@@ -192,8 +263,8 @@ fn check_authorized(
     | Protocol::Tls(authzs)
     | Protocol::Opaque(authzs) = &server.protocol
     {
-        for authz in &**authzs {
-            if super::is_authorized(authz, client_addr, tls) {
+        match super::decide(authzs, |a| super::is_authorized(a, client_addr, tls)) {
+            Decision::Allow { authz, audit } => {
                 if authz.meta.is_audit() {
                     tracing::info!(
                         server.group = %server.meta.group(),
@@ -207,12 +278,29 @@ fn check_authorized(
                         "Request allowed",
                     );
                 }
-                return Ok(ServerPermit::new(dst, server, authz));
+                return Ok((
+                    ServerPermit::new(dst, server, authz),
+                    audit.map(|a| a.meta.clone()),
+                ));
+            }
+            Decision::Deny { authz: Some(authz) } => {
+                tracing::debug!(
+                    authz.group = %authz.meta.group(),
+                    authz.kind = %authz.meta.kind(),
+                    authz.name = %authz.meta.name(),
+                    "Connection matched a deny authorization",
+                );
+                return Err(ServerUnauthorized {
+                    server: server.meta.clone(),
+                    authz: Some(authz.meta.clone()),
+                });
             }
+            Decision::Deny { authz: None } => {}
         }
     }
 
     Err(ServerUnauthorized {
         server: server.meta.clone(),
+        authz: None,
     })
 }
@@ -1,6 +1,7 @@
 use super::*;
 use crate::policy::*;
 use linkerd_app_core::proxy::http;
+use linkerd_proxy_server_policy::authz;
 use std::collections::BTreeSet;
 
 #[derive(Clone)]
@@ -18,6 +19,8 @@ async fn unauthenticated_allowed() {
                     kind: "serverauthorization".into(),
                     name: "unauth".into(),
                 }),
+                action: authz::Action::Allow,
+                audit: false,
             }]
             .into(),
         ),
@@ -27,6 +30,10 @@ async fn unauthenticated_allowed() {
             name: "test".into(),
         }),
         local_rate_limit: Arc::new(Default::default()),
+        concurrency_limit: None,
+        connection_limit: None,
+        dscp: None,
+        proxy_protocol: false,
     };
 
     let tls = tls::ConditionalServerTls::None(tls::NoServerTls::NoClientHello);
@@ -61,6 +68,7 @@ async fn authenticated_identity() {
                 authentication: Authentication::TlsAuthenticated {
                     suffixes: vec![],
                     identities: vec![client_id().to_string()].into_iter().collect(),
+                    patterns: vec![],
                 },
                 networks: vec!["192.0.2.0/24".parse().unwrap()],
                 meta: Arc::new(Meta::Resource {
@@ -68,6 +76,8 @@ async fn authenticated_identity() {
                     kind: "serverauthorization".into(),
                     name: "tls-auth".into(),
                 }),
+                action: authz::Action::Allow,
+                audit: false,
             }]
             .into(),
         ),
@@ -77,6 +87,10 @@ async fn authenticated_identity() {
             name: "test".into(),
         }),
         local_rate_limit: Arc::new(Default::default()),
+        concurrency_limit: None,
+        connection_limit: None,
+        dscp: None,
+        proxy_protocol: false,
     };
 
     let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
@@ -125,6 +139,7 @@ async fn authenticated_suffix() {
                 authentication: Authentication::TlsAuthenticated {
                     identities: BTreeSet::default(),
                     suffixes: vec![Suffix::from(vec!["cluster".into(), "local".into()])],
+                    patterns: vec![],
                 },
                 networks: vec!["192.0.2.0/24".parse().unwrap()],
                 meta: Arc::new(Meta::Resource {
@@ -132,6 +147,8 @@ async fn authenticated_suffix() {
                     kind: "serverauthorization".into(),
                     name: "tls-auth".into(),
                 }),
+                action: authz::Action::Allow,
+                audit: false,
             }]
             .into(),
         ),
@@ -141,6 +158,10 @@ async fn authenticated_suffix() {
             name: "test".into(),
         }),
         local_rate_limit: Arc::new(Default::default()),
+        concurrency_limit: None,
+        connection_limit: None,
+        dscp: None,
+        proxy_protocol: false,
     };
 
     let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
@@ -192,6 +213,8 @@ async fn tls_unauthenticated() {
                     kind: "serverauthorization".into(),
                     name: "tls-unauth".into(),
                 }),
+                action: authz::Action::Allow,
+                audit: false,
             }]
             .into(),
         ),
@@ -201,6 +224,10 @@ async fn tls_unauthenticated() {
             name: "test".into(),
         }),
         local_rate_limit: Arc::new(Default::default()),
+        concurrency_limit: None,
+        connection_limit: None,
+        dscp: None,
+        proxy_protocol: false,
     };
 
     let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
@@ -0,0 +1,223 @@
+//! Reads PROXY protocol headers so that connections proxied by a load balancer
+//! are attributed to their original clients.
+
+use crate::policy::{AllowPolicy, ConnectionKey};
+use bytes::BytesMut;
+use linkerd_app_core::{
+    io,
+    proxy_protocol::ProxyHeader,
+    svc::{self, ServiceExt},
+    transport::addrs::{ClientAddr, OrigDstAddr, Remote},
+    Error, IpMatch, Result,
+};
+use rangemap::RangeInclusiveSet;
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::time;
+use tracing::debug;
+
+/// Configures how PROXY protocol headers are read from inbound connections.
+///
+/// Connections are expected to be prefaced by a header when their port is
+/// configured here or when their server's policy enables the PROXY protocol.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Ports on which connections must be prefaced by a PROXY protocol header,
+    /// regardless of the server's policy.
+    pub ports: RangeInclusiveSet<u16>,
+
+    /// The networks from which PROXY protocol headers are accepted.
+    /// Connections to the configured ports from other clients are rejected.
+    pub trusted_networks: IpMatch,
+
+    /// The maximum amount of time to wait for a PROXY protocol header.
+    pub timeout: time::Duration,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProxyProtocolError {
+    #[error("PROXY protocol client {0} is not trusted")]
+    Untrusted(SocketAddr),
+
+    #[error("PROXY protocol header timed out after {0:?}")]
+    Timeout(time::Duration),
+
+    #[error("connection did not include a PROXY protocol header")]
+    Missing,
+
+    #[error("failed to read PROXY protocol header: {0}")]
+    Read(#[source] io::Error),
+}
+
+/// A target whose client address may have been read from a PROXY protocol
+/// header.
+#[derive(Clone, Debug)]
+pub(crate) struct ProxyClient<T> {
+    client_addr: Remote<ClientAddr>,
+    /// Set when the client address was read from a PROXY protocol header.
+    proxied: bool,
+    parent: T,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct NewProxyProtocol<N> {
+    inner: N,
+    config: Arc<Config>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ProxyProtocol<T, N> {
+    target: T,
+    inner: N,
+    /// Set when the target's connections are expected to include a PROXY
+    /// protocol header.
+    config: Option<Arc<Config>>,
+}
+
+// === impl NewProxyProtocol ===
+
+impl<N> NewProxyProtocol<N> {
+    pub(crate) fn layer(config: Config) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        let config = Arc::new(config);
+        svc::layer::mk(move |inner| Self {
+            inner,
+            config: config.clone(),
+        })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewProxyProtocol<N>
+where
+    T: svc::Param<OrigDstAddr> + svc::Param<AllowPolicy>,
+    N: Clone,
+{
+    type Service = ProxyProtocol<T, N>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let OrigDstAddr(addr) = target.param();
+        let policy: AllowPolicy = target.param();
+        let enabled = self.config.ports.contains(&addr.port()) || policy.borrow().proxy_protocol;
+        let config = enabled.then(|| self.config.clone());
+        ProxyProtocol {
+            target,
+            inner: self.inner.clone(),
+            config,
+        }
+    }
+}
+
+// === impl ProxyProtocol ===
+
+impl<T, I, N, S> svc::Service<I> for ProxyProtocol<T, N>
+where
+    T: svc::Param<Remote<ClientAddr>> + Clone + Send + 'static,
+    I: io::AsyncRead + Send + Unpin + 'static,
+    N: svc::NewService<ProxyClient<T>, Service = S> + Clone + Send + 'static,
+    S: svc::Service<io::PrefixedIo<I>> + Send,
+    S::Error: Into<Error>,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut io: I) -> Self::Future {
+        let target = self.target.clone();
+        let inner = self.inner.clone();
+        let config = self.config.clone();
+        Box::pin(async move {
+            let client_addr: Remote<ClientAddr> = target.param();
+            let Some(config) = config else {
+                let svc = inner.new_service(ProxyClient {
+                    client_addr,
+                    proxied: false,
+                    parent: target,
+                });
+                return svc
+                    .oneshot(io::PrefixedIo::from(io))
+                    .await
+                    .map_err(Into::into);
+            };
+
+            // Only trusted clients (i.e. load balancers) may assert another
+            // client's address.
+            if !config.trusted_networks.matches(client_addr.ip()) {
+                debug!(client.addr = %client_addr, "Untrusted PROXY protocol client");
+                return Err(ProxyProtocolError::Untrusted(client_addr.into()).into());
+            }
+
+            let mut buf = BytesMut::with_capacity(512);
+            let header = time::timeout(config.timeout, ProxyHeader::read(&mut io, &mut buf))
+                .await
+                .map_err(|_| ProxyProtocolError::Timeout(config.timeout))?
+                .map_err(ProxyProtocolError::Read)?
+                .ok_or(ProxyProtocolError::Missing)?;
+
+            // The header does not include addresses when the load balancer
+            // connects on its own behalf (e.g. for health checks).
+            let client_addr = header
+                .addrs
+                .map(|addrs| Remote(ClientAddr(addrs.source)))
+                .unwrap_or(client_addr);
+            debug!(
+                client.addr = %client_addr,
+                version = ?header.version,
+                tlvs = header.tlvs.len(),
+                "Read PROXY protocol header"
+            );
+
+            let svc = inner.new_service(ProxyClient {
+                client_addr,
+                proxied: header.addrs.is_some(),
+                parent: target,
+            });
+            svc.oneshot(io::PrefixedIo::new(buf.freeze(), io))
+                .await
+                .map_err(Into::into)
+        })
+    }
+}
+
+// === impl ProxyClient ===
+
+impl<T> ProxyClient<T> {
+    /// Returns true if the client address was read from a PROXY protocol
+    /// header rather than from the connection's socket.
+    pub(crate) fn is_proxied(&self) -> bool {
+        self.proxied
+    }
+}
+
+impl<T: svc::Param<OrigDstAddr>> svc::Param<OrigDstAddr> for ProxyClient<T> {
+    fn param(&self) -> OrigDstAddr {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Remote<ClientAddr>> for ProxyClient<T> {
+    fn param(&self) -> Remote<ClientAddr> {
+        self.client_addr
+    }
+}
+
+impl<T: svc::Param<AllowPolicy>> svc::Param<AllowPolicy> for ProxyClient<T> {
+    fn param(&self) -> AllowPolicy {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<ConnectionKey> for ProxyClient<T> {
+    fn param(&self) -> ConnectionKey {
+        ConnectionKey::Ip(self.client_addr.ip())
+    }
+}
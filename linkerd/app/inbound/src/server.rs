@@ -14,6 +14,7 @@ use tracing::debug_span;
 #[derive(Copy, Clone, Debug)]
 struct TcpEndpoint {
     addr: Remote<ServerAddr>,
+    source: Option<transport::TransparentSource>,
 }
 
 // === impl Inbound ===
@@ -51,17 +52,20 @@ impl Inbound<()> {
         A: svc::Param<OrigDstAddr>,
         A: svc::Param<AddrPair>,
         A: Clone + Send + Sync + 'static,
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
+        I: transport::SetDscp,
         I: Debug + Unpin + Send + Sync + 'static,
         P: profiles::GetProfile<Error = Error>,
     {
+        let transparent_source = self.config.transparent_source;
+
         // Handles connections to ports that can't be determined to be HTTP.
         let forward = self
             .clone()
             .into_tcp_connect(addr.port())
             .push_tcp_forward()
             .into_stack()
-            .push_map_target(TcpEndpoint::from_param)
+            .push_map_target(move |t| TcpEndpoint::from_param(t, transparent_source))
             .instrument(|_: &_| debug_span!("tcp"))
             .into_inner();
 
@@ -81,7 +85,9 @@ impl Inbound<()> {
             self.clone()
                 .into_tcp_connect(addr.port())
                 .push_tcp_forward()
-                .map_stack(|_, _, s| s.push_map_target(TcpEndpoint::from_param))
+                .map_stack(|_, _, s| {
+                    s.push_map_target(move |t| TcpEndpoint::from_param(t, transparent_source))
+                })
                 .push_direct(policies.clone(), gateway, http)
                 .into_stack()
                 .instrument(|_: &_| debug_span!("direct"))
@@ -106,8 +112,18 @@ impl Inbound<()> {
 // === impl TcpEndpoint ===
 
 impl TcpEndpoint {
-    pub fn from_param<T: svc::Param<Remote<ServerAddr>>>(t: T) -> Self {
-        Self { addr: t.param() }
+    pub fn from_param<T>(t: T, transparent_source: bool) -> Self
+    where
+        T: svc::Param<Remote<ServerAddr>> + svc::Param<Remote<ClientAddr>>,
+    {
+        let source = transparent_source.then(|| {
+            let Remote(ClientAddr(client)) = t.param();
+            transport::TransparentSource(client.ip())
+        });
+        Self {
+            addr: t.param(),
+            source,
+        }
     }
 }
 
@@ -117,6 +133,12 @@ impl svc::Param<Remote<ServerAddr>> for TcpEndpoint {
     }
 }
 
+impl svc::Param<Option<transport::TransparentSource>> for TcpEndpoint {
+    fn param(&self) -> Option<transport::TransparentSource> {
+        self.source
+    }
+}
+
 impl svc::Param<transport::labels::Key> for TcpEndpoint {
     fn param(&self) -> transport::labels::Key {
         transport::labels::Key::InboundClient
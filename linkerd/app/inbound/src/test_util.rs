@@ -1,4 +1,4 @@
-use crate::{policy, Config};
+use crate::{policy, proxy_protocol, Config};
 pub use futures::prelude::*;
 use linkerd_app_core::{
     config,
@@ -10,11 +10,13 @@ use linkerd_app_core::{
         http::{h1, h2},
         tap,
     },
-    transport::{DualListenAddr, Keepalive, UserTimeout},
+    transport::{DualListenAddr, Keepalive, ListenShards, UserTimeout},
     ProxyRuntime,
 };
 pub use linkerd_app_test as support;
-use linkerd_proxy_server_policy::{Authentication, Authorization, Meta, Protocol, ServerPolicy};
+use linkerd_proxy_server_policy::{
+    authz, Authentication, Authorization, Meta, Protocol, ServerPolicy,
+};
 use std::{sync::Arc, time::Duration};
 
 pub fn default_config() -> Config {
@@ -30,6 +32,8 @@ pub fn default_config() -> Config {
             kind: "serverauthorization".into(),
             name: "testsaz".into(),
         }),
+        action: authz::Action::Allow,
+        audit: false,
     }]);
     let policy = policy::Config::Fixed {
         cache_max_idle_age: Duration::from_secs(20),
@@ -47,10 +51,15 @@ pub fn default_config() -> Config {
                 name: "testsrv".into(),
             }),
             local_rate_limit: Arc::new(Default::default()),
+            concurrency_limit: None,
+            connection_limit: None,
+            dscp: None,
+            proxy_protocol: false,
         }
         .into(),
         ports: Default::default(),
         opaque_ports: Default::default(),
+        overrides: Default::default(),
     };
 
     Config {
@@ -61,6 +70,7 @@ pub fn default_config() -> Config {
                 addr: DualListenAddr(([0, 0, 0, 0], 0).into(), None),
                 keepalive: Keepalive(None),
                 user_timeout: UserTimeout(None),
+                shards: ListenShards::default(),
                 http2: h2::ServerParams::default(),
             },
             connect: config::ConnectConfig {
@@ -89,6 +99,13 @@ pub fn default_config() -> Config {
         },
         discovery_idle_timeout: Duration::from_secs(20),
         profile_skip_timeout: Duration::from_secs(1),
+        proxy_protocol: proxy_protocol::Config {
+            ports: Default::default(),
+            trusted_networks: Default::default(),
+            timeout: Duration::from_secs(1),
+        },
+        unix_sockets: Default::default(),
+        transparent_source: false,
     }
 }
 
@@ -2,7 +2,7 @@ use crate::{
     policy::{self, ClientPolicy},
     Outbound,
 };
-use linkerd_app_core::{errors, profiles, svc, transport::OrigDstAddr, Error};
+use linkerd_app_core::{errors, profiles, svc, transport::OrigDstAddr, Addr, Error};
 use once_cell::sync::Lazy;
 use std::{
     fmt::Debug,
@@ -72,6 +72,31 @@ impl<N> Outbound<N> {
            + Send
            + Sync
            + 'static {
+        self.resolver_with(profiles, policies, |OrigDstAddr(orig_dst)| {
+            (orig_dst.into(), orig_dst)
+        })
+    }
+
+    /// Like [`Outbound::resolver`], but discovers the address obtained from
+    /// each `K`-typed target, falling back to the target's original
+    /// destination address when no policy is discovered.
+    pub(crate) fn resolver_with<K>(
+        &self,
+        profiles: impl profiles::GetProfile<Error = Error>,
+        policies: impl policy::GetPolicy,
+        addrs: fn(K) -> (Addr, SocketAddr),
+    ) -> impl svc::Service<
+        K,
+        Error = Error,
+        Response = (Option<profiles::Receiver>, policy::Receiver),
+        Future = impl Send,
+    > + Clone
+           + Send
+           + Sync
+           + 'static
+    where
+        K: 'static,
+    {
         let detect_timeout = self.config.proxy.detect_protocol_timeout;
         let queue = {
             let queue = self.config.tcp_connection_queue;
@@ -80,15 +105,16 @@ impl<N> Outbound<N> {
                 failfast_timeout: queue.failfast_timeout,
             }
         };
-        svc::mk(move |OrigDstAddr(orig_dst)| {
-            tracing::debug!(addr = %orig_dst, "Discover");
+        svc::mk(move |target: K| {
+            let (addr, orig_dst) = addrs(target);
+            tracing::debug!(%addr, "Discover");
 
             let profile = profiles
                 .clone()
-                .get_profile(profiles::LookupAddr(orig_dst.into()))
+                .get_profile(profiles::LookupAddr(addr.clone()))
                 .instrument(tracing::debug_span!("profiles").or_current());
             let policy = policies
-                .get_policy(orig_dst.into())
+                .get_policy(addr)
                 .instrument(tracing::debug_span!("policy").or_current());
 
             Box::pin(async move {
@@ -198,6 +224,9 @@ pub fn synthesize_forward_policy(
             meta: meta.clone(),
             queue,
             dispatcher: policy::BackendDispatcher::Forward(addr, metadata),
+            proxy_protocol: None,
+            upstream_proxy: None,
+            marking: None,
         },
     )
 }
@@ -221,6 +250,9 @@ pub fn synthesize_balance_policy(
                     path: addr.to_string(),
                 },
             ),
+            proxy_protocol: None,
+            upstream_proxy: None,
+            marking: None,
         },
     )
 }
@@ -235,17 +267,20 @@ fn policy_for_backend(
 
     let opaque = policy::opaq::Opaque {
         routes: Some(policy::opaq::Route {
-            policy: policy::opaq::Policy {
-                meta: meta.clone(),
-                filters: NO_OPAQ_FILTERS.clone(),
-                params: Default::default(),
-                distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
-                    policy::RouteBackend {
-                        filters: NO_OPAQ_FILTERS.clone(),
-                        backend: backend.clone(),
-                    },
-                ])),
-            },
+            rules: vec![policy::opaq::Rule {
+                matches: vec![],
+                policy: policy::opaq::Policy {
+                    meta: meta.clone(),
+                    filters: NO_OPAQ_FILTERS.clone(),
+                    params: Default::default(),
+                    distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
+                        policy::RouteBackend {
+                            filters: NO_OPAQ_FILTERS.clone(),
+                            backend: backend.clone(),
+                        },
+                    ])),
+                },
+            }],
         }),
     };
 
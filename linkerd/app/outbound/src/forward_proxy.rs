@@ -0,0 +1,222 @@
+//! Serves clients that are explicitly configured to use the proxy (e.g. via
+//! `HTTP_PROXY`), for environments in which outbound traffic cannot be
+//! transparently redirected to the proxy.
+
+use crate::{policy, Outbound};
+use linkerd_app_core::{
+    dns,
+    forward_proxy::{self, Addr},
+    io, profiles,
+    proxy::{
+        api_resolve::{ConcreteAddr, Metadata},
+        core::Resolve,
+    },
+    svc::{self, ServiceExt},
+    transport::addrs::*,
+    Error, NameAddr,
+};
+use std::{
+    fmt::Debug,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::time;
+use tracing::debug;
+
+#[cfg(test)]
+mod tests;
+
+/// The target of a forwarded connection.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Forward {
+    /// The address requested by the client, by which the target is
+    /// discovered and routed.
+    addr: Addr,
+
+    /// The address to which the connection is forwarded if no policy is
+    /// discovered for `addr`. Names are resolved via DNS.
+    orig_dst: OrigDstAddr,
+}
+
+#[derive(Clone, Debug)]
+struct NewForwardProxy<N> {
+    inner: N,
+    dns: dns::Resolver,
+    timeout: time::Duration,
+}
+
+#[derive(Clone, Debug)]
+struct ForwardProxy<N> {
+    inner: N,
+    dns: dns::Resolver,
+    timeout: time::Duration,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("forward proxy handshake not received after {0:?}")]
+struct HandshakeTimeout(time::Duration);
+
+#[derive(Debug, thiserror::Error)]
+#[error("failed to resolve {addr}: {source}")]
+struct ResolveError {
+    addr: NameAddr,
+    #[source]
+    source: Error,
+}
+
+// === impl Outbound ===
+
+impl Outbound<()> {
+    /// Builds a stack for connections to the forward-proxy listener.
+    ///
+    /// Each connection's target is read from its HTTP `CONNECT`, absolute-form
+    /// HTTP, or SOCKS5 handshake and is routed by the same discovery and policy
+    /// stack as connections to the transparent outbound listener. Names are
+    /// discovered by name, so that they are routed by their service's policy;
+    /// they are also resolved via DNS, so that names for which no policy is
+    /// discovered are forwarded to the resolved address.
+    pub fn mk_forward_proxy<T, I, R>(
+        &self,
+        profiles: impl profiles::GetProfile<Error = Error>,
+        policies: impl policy::GetPolicy,
+        resolve: R,
+        dns: dns::Resolver,
+    ) -> svc::ArcNewTcp<T, I>
+    where
+        // Target describing an accepted connection.
+        T: 'static,
+        // Server-side socket.
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Splice,
+        I: Debug + Unpin + Send + Sync + 'static,
+        // Endpoint resolver.
+        R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
+        R::Resolution: Unpin,
+    {
+        let profiles = profiles::WithAllowlist::new(profiles, self.config.allow_discovery.clone());
+        let discover = self.resolver_with(profiles, policies, |Forward { addr, orig_dst }| {
+            let OrigDstAddr(orig_dst) = orig_dst;
+            (addr, orig_dst)
+        });
+        let sidecar = self.mk_sidecar_with::<Forward, Forward, io::PrefixedIo<I>, R, _>(
+            discover,
+            |fwd: &Forward| fwd.addr.clone(),
+            resolve,
+        );
+        let timeout = self.config.proxy.detect_protocol_timeout;
+        svc::stack(sidecar)
+            .push(NewForwardProxy::layer(dns, timeout))
+            .arc_new_tcp()
+            .into_inner()
+    }
+}
+
+// === impl Forward ===
+
+impl svc::Param<OrigDstAddr> for Forward {
+    fn param(&self) -> OrigDstAddr {
+        self.orig_dst
+    }
+}
+
+// === impl NewForwardProxy ===
+
+impl<N> NewForwardProxy<N> {
+    fn layer(
+        dns: dns::Resolver,
+        timeout: time::Duration,
+    ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            inner,
+            dns: dns.clone(),
+            timeout,
+        })
+    }
+}
+
+impl<T, N: Clone> svc::NewService<T> for NewForwardProxy<N> {
+    type Service = ForwardProxy<N>;
+
+    fn new_service(&self, _: T) -> Self::Service {
+        ForwardProxy {
+            inner: self.inner.clone(),
+            dns: self.dns.clone(),
+            timeout: self.timeout,
+        }
+    }
+}
+
+// === impl ForwardProxy ===
+
+impl<I, N, S> svc::Service<I> for ForwardProxy<N>
+where
+    I: io::AsyncRead + io::AsyncWrite + Unpin + Send + 'static,
+    N: svc::NewService<Forward, Service = S> + Clone + Send + 'static,
+    S: svc::Service<io::PrefixedIo<I>, Response = (), Error = Error> + Send,
+    S::Future: Send,
+{
+    type Response = ();
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut io: I) -> Self::Future {
+        let inner = self.inner.clone();
+        let dns = self.dns.clone();
+        let timeout = self.timeout;
+        Box::pin(async move {
+            let req = time::timeout(timeout, forward_proxy::accept(&mut io))
+                .await
+                .map_err(|_| HandshakeTimeout(timeout))??;
+
+            // Discover the target before informing the client that its
+            // connection is forwarded, so that failures may be reported to it.
+            let ready = async {
+                let addr = match req.target {
+                    Addr::Socket(addr) => addr,
+                    Addr::Name(ref name) => resolve(&dns, name).await?,
+                };
+                let target = Forward {
+                    addr: req.target.clone(),
+                    orig_dst: OrigDstAddr(addr),
+                };
+                debug!(request = %req.target, server.addr = %addr, protocol = ?req.protocol, "Forwarding");
+                let proxy =
+                    svc::ServiceExt::<io::PrefixedIo<I>>::ready_oneshot(inner.new_service(target))
+                        .await?;
+                Ok::<_, Error>(proxy)
+            };
+            let proxy = match ready.await {
+                Ok(proxy) => proxy,
+                Err(error) => {
+                    // The client may already be gone, in which case there's
+                    // no one to inform.
+                    let _ = req.rejected(&mut io).await;
+                    return Err(error);
+                }
+            };
+            req.accepted(&mut io).await?;
+
+            proxy.oneshot(io::PrefixedIo::new(req.prefix, io)).await
+        })
+    }
+}
+
+/// Resolves a name to the first of its addresses.
+async fn resolve(dns: &dns::Resolver, addr: &NameAddr) -> Result<SocketAddr, ResolveError> {
+    let (addrs, _) = dns
+        .resolve_addrs(addr.name().as_ref(), addr.port())
+        .await
+        .map_err(|e| ResolveError {
+            addr: addr.clone(),
+            source: e.into(),
+        })?;
+    addrs.into_iter().next().ok_or_else(|| ResolveError {
+        addr: addr.clone(),
+        source: "no addresses found".into(),
+    })
+}
@@ -0,0 +1,156 @@
+use super::*;
+use linkerd_app_core::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+
+const TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+/// Builds a forward proxy whose inner stack reports each connection's target
+/// and the first five bytes that the target receives.
+fn forward_proxy() -> (
+    svc::BoxTcp<io::DuplexStream>,
+    mpsc::UnboundedReceiver<(Forward, [u8; 5])>,
+) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let inner = move |target: Forward| {
+        let tx = tx.clone();
+        svc::mk(move |mut io: io::PrefixedIo<io::DuplexStream>| {
+            let tx = tx.clone();
+            let target = target.clone();
+            async move {
+                let mut buf = [0u8; 5];
+                io.read_exact(&mut buf).await?;
+                tx.send((target, buf)).expect("test must be running");
+                Ok::<_, Error>(())
+            }
+        })
+    };
+    (new_forward_proxy(inner), rx)
+}
+
+fn new_forward_proxy<N, S>(inner: N) -> svc::BoxTcp<io::DuplexStream>
+where
+    N: svc::NewService<Forward, Service = S> + Clone + Send + 'static,
+    S: svc::Service<io::PrefixedIo<io::DuplexStream>, Response = (), Error = Error>,
+    S: Send + 'static,
+    S::Future: Send,
+{
+    let new = NewForwardProxy {
+        inner,
+        dns: dns::Resolver::new(Default::default(), Default::default()),
+        timeout: TIMEOUT,
+    };
+    svc::BoxService::new(svc::NewService::new_service(&new, ()))
+}
+
+fn socket_target(addr: SocketAddr) -> Forward {
+    Forward {
+        addr: Addr::Socket(addr),
+        orig_dst: OrigDstAddr(addr),
+    }
+}
+
+/// A target stack that fails to become ready, e.g. because discovery failed.
+#[derive(Clone, Debug)]
+struct Unavailable;
+
+impl<I> svc::Service<I> for Unavailable {
+    type Response = ();
+    type Error = Error;
+    type Future = futures::future::Ready<Result<(), Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Err("discovery failed".into()))
+    }
+
+    fn call(&mut self, _: I) -> Self::Future {
+        unreachable!("service must not be called when not ready")
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_connect() {
+    let _trace = linkerd_tracing::test::trace_init();
+    let (proxy, mut rx) = forward_proxy();
+
+    let (mut client, server) = io::duplex(1024);
+    let task = tokio::spawn(proxy.oneshot(server));
+
+    client
+        .write_all(b"CONNECT 192.0.2.2:443 HTTP/1.1\r\nHost: 192.0.2.2:443\r\n\r\nhel")
+        .await
+        .unwrap();
+    let mut rsp = [0u8; 39];
+    client.read_exact(&mut rsp).await.unwrap();
+    assert_eq!(&rsp[..], b"HTTP/1.1 200 Connection established\r\n\r\n");
+    client.write_all(b"lo").await.unwrap();
+
+    let (target, data) = rx.recv().await.expect("must forward the connection");
+    assert_eq!(target, socket_target(([192, 0, 2, 2], 443).into()));
+    assert_eq!(&data, b"hello");
+    task.await.unwrap().expect("forwarding must succeed");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn socks5() {
+    let _trace = linkerd_tracing::test::trace_init();
+    let (proxy, mut rx) = forward_proxy();
+
+    let (mut client, server) = io::duplex(1024);
+    let task = tokio::spawn(proxy.oneshot(server));
+
+    client.write_all(&[0x05, 0x01, 0x00]).await.unwrap();
+    let mut rsp = [0u8; 2];
+    client.read_exact(&mut rsp).await.unwrap();
+    assert_eq!(rsp, [0x05, 0x00]);
+    client
+        .write_all(&[0x05, 0x01, 0x00, 0x01, 192, 0, 2, 3, 0x1f, 0x90])
+        .await
+        .unwrap();
+    let mut rsp = [0u8; 10];
+    client.read_exact(&mut rsp).await.unwrap();
+    assert_eq!(rsp[..2], [0x05, 0x00]);
+    client.write_all(b"hello").await.unwrap();
+
+    let (target, data) = rx.recv().await.expect("must forward the connection");
+    assert_eq!(target, socket_target(([192, 0, 2, 3], 8080).into()));
+    assert_eq!(&data, b"hello");
+    task.await.unwrap().expect("forwarding must succeed");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn handshake_timeout() {
+    let _trace = linkerd_tracing::test::trace_init();
+    time::pause();
+    let (proxy, _rx) = forward_proxy();
+
+    let (_client, server) = io::duplex(1024);
+    let err = proxy
+        .oneshot(server)
+        .await
+        .expect_err("handshake must time out");
+    assert!(err.is::<HandshakeTimeout>(), "{err}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_undiscovered() {
+    let _trace = linkerd_tracing::test::trace_init();
+    let proxy = new_forward_proxy(|_: Forward| Unavailable);
+
+    let (mut client, server) = io::duplex(1024);
+    let task = tokio::spawn(proxy.oneshot(server));
+
+    client
+        .write_all(b"CONNECT 192.0.2.2:443 HTTP/1.1\r\nHost: 192.0.2.2:443\r\n\r\n")
+        .await
+        .unwrap();
+    let mut rsp = Vec::new();
+    client.read_to_end(&mut rsp).await.unwrap();
+    assert!(
+        rsp.starts_with(b"HTTP/1.1 502 Bad Gateway\r\n"),
+        "{:?}",
+        String::from_utf8_lossy(&rsp)
+    );
+    task.await
+        .unwrap()
+        .expect_err("forwarding must fail without discovery");
+}
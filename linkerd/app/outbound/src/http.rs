@@ -32,6 +32,7 @@ pub struct Http<T>(T);
 #[derive(Clone, Debug, Default)]
 pub struct HttpMetrics {
     balancer: concrete::BalancerMetrics,
+    adaptive_concurrency: concrete::AdaptiveConcurrencyMetrics,
     http_route: policy::HttpRouteMetrics,
     grpc_route: policy::GrpcRouteMetrics,
 }
@@ -135,12 +136,16 @@ impl HttpMetrics {
         let http_route = policy::HttpRouteMetrics::register(http.sub_registry_with_prefix("route"));
         let balancer =
             concrete::BalancerMetrics::register(http.sub_registry_with_prefix("balancer"));
+        let adaptive_concurrency = concrete::AdaptiveConcurrencyMetrics::register(
+            http.sub_registry_with_prefix("adaptive_concurrency"),
+        );
 
         let grpc = registry.sub_registry_with_prefix("grpc");
         let grpc_route = policy::GrpcRouteMetrics::register(grpc.sub_registry_with_prefix("route"));
 
         Self {
             balancer,
+            adaptive_concurrency,
             http_route,
             grpc_route,
         }
@@ -4,6 +4,7 @@
 use super::{balance::EwmaConfig, client, handle_proxy_error_headers};
 use crate::{
     http, stack_labels,
+    tcp::ForwardName,
     zone::{tcp_zone_labels, TcpZoneLabels},
     BackendRef, Outbound, ParentRef,
 };
@@ -20,13 +21,17 @@ use linkerd_app_core::{
     transport::{self, addrs::*},
     Error, Infallible, NameAddr, Result,
 };
-use linkerd_proxy_client_policy::FailureAccrual;
+use linkerd_proxy_client_policy::{FailureAccrual, Marking, ProxyProtocolVersion, UpstreamProxy};
 use std::{fmt::Debug, net::SocketAddr, sync::Arc};
 use tracing::info_span;
 
+pub mod adaptive_concurrency;
 mod balance;
 
-pub use self::balance::BalancerMetrics;
+pub use self::{
+    adaptive_concurrency::{AdaptiveConcurrencyLimited, AdaptiveConcurrencyMetrics},
+    balance::BalancerMetrics,
+};
 
 /// Parameter configuring dispatcher behavior.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -94,6 +99,7 @@ impl<N> Outbound<N> {
 
             // TODO(ver) Configure this from discovery.
             let queue = config.http_request_queue;
+            let codel = config.http_balancer_codel;
 
             let forward = inner
                 .clone()
@@ -112,6 +118,9 @@ impl<N> Outbound<N> {
 
             let ConnectConfig { http1, http2, .. } = config.proxy.connect.clone();
 
+            let adaptive_concurrency = config.http_adaptive_concurrency;
+            let adaptive_concurrency_metrics = rt.metrics.prom.http.adaptive_concurrency.clone();
+
             inner
                 .push(balance::Balance::layer(config, rt, resolve))
                 .check_new_clone()
@@ -125,6 +134,7 @@ impl<N> Outbound<N> {
                                     ewma,
                                     parent,
                                     queue,
+                                    codel,
                                 }))
                             }
                             Dispatch::Forward(addr, metadata) => svc::Either::A(svc::Either::B({
@@ -146,6 +156,14 @@ impl<N> Outbound<N> {
                     },
                     svc::stack(fail).check_new_clone().into_inner(),
                 )
+                // Shed requests before they are dispatched to the backend's
+                // queue when the backend's adaptive concurrency limit is
+                // reached.
+                .push(adaptive_concurrency::NewAdaptiveConcurrency::layer(
+                    adaptive_concurrency,
+                    adaptive_concurrency_metrics,
+                ))
+                .push_on_service(http::BoxResponse::layer())
                 .arc_new_clone_http()
         })
     }
@@ -194,6 +212,42 @@ impl<T> svc::Param<Option<http::AuthorityOverride>> for Endpoint<T> {
     }
 }
 
+impl<T> svc::Param<Option<ProxyProtocolVersion>> for Endpoint<T>
+where
+    T: svc::Param<Option<ProxyProtocolVersion>>,
+{
+    fn param(&self) -> Option<ProxyProtocolVersion> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<UpstreamProxy>> for Endpoint<T>
+where
+    T: svc::Param<Option<UpstreamProxy>>,
+{
+    fn param(&self) -> Option<UpstreamProxy> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<ForwardName>> for Endpoint<T>
+where
+    T: svc::Param<Option<ForwardName>>,
+{
+    fn param(&self) -> Option<ForwardName> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<Marking>> for Endpoint<T>
+where
+    T: svc::Param<Option<Marking>>,
+{
+    fn param(&self) -> Option<Marking> {
+        self.parent.param()
+    }
+}
+
 impl<T> svc::Param<handle_proxy_error_headers::CloseServerConnection> for Endpoint<T> {
     fn param(&self) -> handle_proxy_error_headers::CloseServerConnection {
         handle_proxy_error_headers::CloseServerConnection(
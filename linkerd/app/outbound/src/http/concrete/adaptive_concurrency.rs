@@ -0,0 +1,637 @@
+//! Adaptive concurrency limiting for concrete backends.
+//!
+//! A backend's capacity is rarely known ahead of time, so rather than relying
+//! on a static limit, the limiter observes the latency of each request and
+//! adjusts the number of requests that may be in flight: the limit shrinks
+//! when latency rises (or requests fail) and grows while the backend is
+//! healthy. Requests in excess of the limit are failed immediately rather
+//! than being queued.
+//!
+//! A request's latency is measured until its response body completes, so
+//! that slow streaming responses are accounted for. All services for a
+//! backend share a single limiter, so that the limit persists as stacks are
+//! rebuilt.
+
+use crate::{metrics::ConcreteLabels, BackendRef, ParentRef};
+use futures::{future, prelude::*};
+use http_body::Body;
+use linkerd_app_core::{metrics::prom, svc, Error};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Configures adaptive concurrency limits for concrete backends.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub algorithm: Algorithm,
+    pub initial_limit: usize,
+    pub min_limit: usize,
+    pub max_limit: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Compares each request's latency to a long-term average, reducing the
+    /// limit in proportion to the increase in latency.
+    Gradient,
+
+    /// Increases the limit additively while requests succeed within the
+    /// latency threshold and decreases it multiplicatively otherwise.
+    Aimd { latency_threshold: Duration },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AdaptiveConcurrencyMetrics {
+    limit: prom::Family<ConcreteLabels, prom::Gauge>,
+    rejected: prom::Family<ConcreteLabels, prom::Counter>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct NewAdaptiveConcurrency<N> {
+    config: Option<Config>,
+    limiters: Limiters,
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct AdaptiveConcurrency<S> {
+    limiter: Option<Arc<Limiter>>,
+    inner: S,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("adaptive concurrency limit reached: {limit} requests in flight")]
+pub struct AdaptiveConcurrencyLimited {
+    limit: usize,
+}
+
+#[pin_project::pin_project]
+#[derive(Debug)]
+pub(super) struct ResponseFuture<F> {
+    #[pin]
+    inner: F,
+    permit: Option<Permit>,
+}
+
+/// Holds a request's permit until its response body completes.
+///
+/// If the body is dropped before it completes, the permit is released without
+/// updating the limit.
+#[pin_project::pin_project]
+#[derive(Debug)]
+pub(super) struct ResponseBody<B> {
+    #[pin]
+    inner: B,
+    permit: Option<Permit>,
+    overloaded: bool,
+}
+
+/// Tracks the limiter for each backend so that it is shared by all of the
+/// backend's services.
+#[derive(Clone, Debug, Default)]
+struct Limiters {
+    by_backend: Arc<Mutex<HashMap<ConcreteLabels, Weak<Limiter>>>>,
+    metrics: AdaptiveConcurrencyMetrics,
+}
+
+#[derive(Debug)]
+struct Limiter {
+    config: Config,
+    state: Mutex<State>,
+    limit_gauge: prom::Gauge,
+    rejected: prom::Counter,
+
+    /// Unregisters the limiter and its metrics when it is dropped.
+    registration: Option<(ConcreteLabels, Limiters)>,
+}
+
+#[derive(Debug)]
+struct State {
+    limit: f64,
+    in_flight: usize,
+    /// A long-term moving average of request latencies, in seconds. Only used
+    /// by the gradient algorithm.
+    long_rtt: Option<f64>,
+}
+
+/// Tracks a single in-flight request.
+#[derive(Debug)]
+struct Permit {
+    limiter: Arc<Limiter>,
+    start: Instant,
+    in_flight: usize,
+}
+
+/// The number of samples over which the gradient algorithm's long-term
+/// latency is averaged.
+const LONG_WINDOW: f64 = 600.0;
+
+/// Permits latency to rise by this factor before the gradient algorithm
+/// reduces the limit.
+const RTT_TOLERANCE: f64 = 1.5;
+
+/// Weights each new gradient-derived limit against the current limit.
+const SMOOTHING: f64 = 0.2;
+
+/// Scales the limit down when requests fail or exceed the AIMD latency
+/// threshold.
+const BACKOFF_RATIO: f64 = 0.9;
+
+// === impl Config ===
+
+impl Config {
+    fn clamp(&self, limit: f64) -> f64 {
+        limit.clamp(self.min_limit.max(1) as f64, self.max_limit as f64)
+    }
+}
+
+// === impl AdaptiveConcurrencyMetrics ===
+
+impl AdaptiveConcurrencyMetrics {
+    pub fn register(registry: &mut prom::Registry) -> Self {
+        let limit = prom::Family::default();
+        registry.register(
+            "limit",
+            "The number of requests that may be in flight to a backend",
+            limit.clone(),
+        );
+
+        let rejected = prom::Family::default();
+        registry.register(
+            "rejected",
+            "The total number of requests rejected because a backend's concurrency limit was reached",
+            rejected.clone(),
+        );
+
+        Self { limit, rejected }
+    }
+}
+
+// === impl NewAdaptiveConcurrency ===
+
+impl<N> NewAdaptiveConcurrency<N> {
+    pub fn layer(
+        config: Option<Config>,
+        metrics: AdaptiveConcurrencyMetrics,
+    ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        let limiters = Limiters {
+            by_backend: Default::default(),
+            metrics,
+        };
+        svc::layer::mk(move |inner| Self {
+            config,
+            limiters: limiters.clone(),
+            inner,
+        })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewAdaptiveConcurrency<N>
+where
+    T: svc::Param<ParentRef> + svc::Param<BackendRef>,
+    N: svc::NewService<T>,
+{
+    type Service = AdaptiveConcurrency<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let limiter = self.config.map(|config| {
+            self.limiters
+                .get_or_create(config, ConcreteLabels(target.param(), target.param()))
+        });
+        let inner = self.inner.new_service(target);
+        AdaptiveConcurrency { limiter, inner }
+    }
+}
+
+// === impl AdaptiveConcurrency ===
+
+impl<S, B, RspB> svc::Service<http::Request<B>> for AdaptiveConcurrency<S>
+where
+    S: svc::Service<http::Request<B>, Response = http::Response<RspB>>,
+    S::Error: Into<Error>,
+    RspB: Body,
+{
+    type Response = http::Response<ResponseBody<RspB>>;
+    type Error = Error;
+    type Future = future::Either<
+        future::Ready<Result<Self::Response, Error>>,
+        ResponseFuture<future::MapErr<S::Future, fn(S::Error) -> Error>>,
+    >;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let permit = match self.limiter.as_ref().map(Limiter::acquire) {
+            None => None,
+            Some(Ok(permit)) => Some(permit),
+            Some(Err(error)) => return future::Either::Left(future::err(error.into())),
+        };
+        future::Either::Right(ResponseFuture {
+            inner: self.inner.call(req).map_err(Into::into),
+            permit,
+        })
+    }
+}
+
+// === impl ResponseFuture ===
+
+impl<F, RspB> Future for ResponseFuture<F>
+where
+    F: TryFuture<Ok = http::Response<RspB>, Error = Error>,
+    RspB: Body,
+{
+    type Output = Result<http::Response<ResponseBody<RspB>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let rsp = match futures::ready!(this.inner.try_poll(cx)) {
+            Ok(rsp) => rsp,
+            Err(error) => {
+                // Failures are treated as a signal to reduce the limit.
+                if let Some(permit) = this.permit.take() {
+                    permit.complete(true);
+                }
+                return Poll::Ready(Err(error));
+            }
+        };
+
+        // Responses indicating that the backend is overloaded are treated as
+        // a signal to reduce the limit once the body completes.
+        let overloaded = matches!(
+            rsp.status(),
+            http::StatusCode::TOO_MANY_REQUESTS | http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        let mut permit = this.permit.take();
+        if rsp.body().is_end_stream() {
+            if let Some(permit) = permit.take() {
+                permit.complete(overloaded);
+            }
+        }
+        Poll::Ready(Ok(rsp.map(|inner| ResponseBody {
+            inner,
+            permit,
+            overloaded,
+        })))
+    }
+}
+
+// === impl ResponseBody ===
+
+impl<B> ResponseBody<B> {
+    fn complete(self: Pin<&mut Self>, failed: bool) {
+        let this = self.project();
+        if let Some(permit) = this.permit.take() {
+            permit.complete(*this.overloaded || failed);
+        }
+    }
+}
+
+impl<B: Body> Body for ResponseBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<B::Data, B::Error>>> {
+        let res = futures::ready!(self.as_mut().project().inner.poll_data(cx));
+        match res {
+            Some(Err(_)) => self.complete(true),
+            // Trailers are not awaited, since not all clients read them.
+            None => self.complete(false),
+            Some(Ok(_)) if self.inner.is_end_stream() => self.complete(false),
+            Some(Ok(_)) => {}
+        }
+        Poll::Ready(res)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap<http::HeaderValue>>, B::Error>> {
+        let res = futures::ready!(self.as_mut().project().inner.poll_trailers(cx));
+        self.complete(res.is_err());
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// === impl Limiters ===
+
+impl Limiters {
+    fn get_or_create(&self, config: Config, labels: ConcreteLabels) -> Arc<Limiter> {
+        let mut by_backend = self.by_backend.lock();
+        if let Some(limiter) = by_backend.get(&labels).and_then(Weak::upgrade) {
+            return limiter;
+        }
+
+        let mut limiter = Limiter::new(
+            config,
+            self.metrics.limit.get_or_create(&labels).clone(),
+            self.metrics.rejected.get_or_create(&labels).clone(),
+        );
+        limiter.registration = Some((labels.clone(), self.clone()));
+        let limiter = Arc::new(limiter);
+        by_backend.insert(labels, Arc::downgrade(&limiter));
+        limiter
+    }
+
+    fn remove(&self, labels: &ConcreteLabels) {
+        let mut by_backend = self.by_backend.lock();
+        // A new limiter may have been registered for the backend while this
+        // one was being dropped.
+        if by_backend
+            .get(labels)
+            .is_some_and(|l| l.strong_count() == 0)
+        {
+            by_backend.remove(labels);
+            self.metrics.limit.remove(labels);
+            self.metrics.rejected.remove(labels);
+        }
+    }
+}
+
+// === impl Limiter ===
+
+impl Limiter {
+    fn new(config: Config, limit_gauge: prom::Gauge, rejected: prom::Counter) -> Self {
+        let limit = config.clamp(config.initial_limit as f64);
+        limit_gauge.set(limit as i64);
+        Self {
+            config,
+            state: Mutex::new(State {
+                limit,
+                in_flight: 0,
+                long_rtt: None,
+            }),
+            limit_gauge,
+            rejected,
+            registration: None,
+        }
+    }
+
+    fn acquire(self: &Arc<Self>) -> Result<Permit, AdaptiveConcurrencyLimited> {
+        let mut state = self.state.lock();
+        let limit = state.limit as usize;
+        if state.in_flight >= limit {
+            drop(state);
+            self.rejected.inc();
+            tracing::debug!(limit, "Adaptive concurrency limit reached");
+            return Err(AdaptiveConcurrencyLimited { limit });
+        }
+        state.in_flight += 1;
+        Ok(Permit {
+            limiter: self.clone(),
+            start: Instant::now(),
+            in_flight: state.in_flight,
+        })
+    }
+
+    /// Updates the limit given the latency of a completed request and the
+    /// number of requests that were in flight when it was dispatched.
+    fn update(&self, rtt: Duration, in_flight: usize, overloaded: bool) {
+        let mut state = self.state.lock();
+        let limit = state.limit;
+        let new_limit = match self.config.algorithm {
+            Algorithm::Aimd { latency_threshold } => {
+                if overloaded || rtt > latency_threshold {
+                    limit * BACKOFF_RATIO
+                } else if (in_flight as f64) * 2.0 >= limit {
+                    limit + 1.0
+                } else {
+                    // The limit is not being exercised, so there's no
+                    // evidence that it can safely grow.
+                    limit
+                }
+            }
+
+            Algorithm::Gradient => {
+                if overloaded {
+                    limit * BACKOFF_RATIO
+                } else {
+                    let rtt = rtt.as_secs_f64().max(f64::EPSILON);
+                    let long_rtt = match state.long_rtt {
+                        None => rtt,
+                        Some(long) => {
+                            let long = long + (rtt - long) / LONG_WINDOW;
+                            // Recover quickly when latency drops well below
+                            // the long-term average, e.g. after an incident.
+                            if long / rtt > 2.0 {
+                                long * 0.95
+                            } else {
+                                long
+                            }
+                        }
+                    };
+                    state.long_rtt = Some(long_rtt);
+
+                    if (in_flight as f64) * 2.0 < limit {
+                        limit
+                    } else {
+                        let gradient = (RTT_TOLERANCE * long_rtt / rtt).clamp(0.5, 1.0);
+                        // Leave room for some queueing so that the limit can
+                        // grow while latency is stable.
+                        let target = limit * gradient + limit.sqrt();
+                        limit * (1.0 - SMOOTHING) + target * SMOOTHING
+                    }
+                }
+            }
+        };
+
+        state.limit = self.config.clamp(new_limit);
+        self.limit_gauge.set(state.limit as i64);
+        tracing::trace!(
+            ?rtt,
+            in_flight,
+            overloaded,
+            limit = state.limit,
+            "Updated limit"
+        );
+    }
+}
+
+impl Drop for Limiter {
+    fn drop(&mut self) {
+        if let Some((labels, limiters)) = self.registration.take() {
+            limiters.remove(&labels);
+        }
+    }
+}
+
+// === impl Permit ===
+
+impl Permit {
+    fn complete(self, overloaded: bool) {
+        self.limiter
+            .update(self.start.elapsed(), self.in_flight, overloaded);
+        // The in-flight count is released when the permit is dropped.
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.limiter.state.lock().in_flight -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(algorithm: Algorithm) -> Config {
+        Config {
+            algorithm,
+            initial_limit: 10,
+            min_limit: 2,
+            max_limit: 20,
+        }
+    }
+
+    fn limiter(algorithm: Algorithm) -> Arc<Limiter> {
+        Arc::new(Limiter::new(
+            config(algorithm),
+            Default::default(),
+            Default::default(),
+        ))
+    }
+
+    fn limit(limiter: &Limiter) -> usize {
+        limiter.state.lock().limit as usize
+    }
+
+    #[test]
+    fn sheds_excess_requests() {
+        let limiter = limiter(Algorithm::Gradient);
+        let permits = (0..10)
+            .map(|_| limiter.acquire().expect("must acquire"))
+            .collect::<Vec<_>>();
+        assert!(limiter.acquire().is_err());
+        assert_eq!(limiter.rejected.get(), 1);
+
+        drop(permits);
+        assert_eq!(limiter.state.lock().in_flight, 0);
+        limiter.acquire().expect("must acquire");
+    }
+
+    #[tokio::test]
+    async fn completes_with_response_body() {
+        let limiter = limiter(Algorithm::Gradient);
+        let (mut tx, inner) = hyper::Body::channel();
+        let mut body = ResponseBody {
+            inner,
+            permit: Some(limiter.acquire().expect("must acquire")),
+            overloaded: true,
+        };
+
+        tx.send_data("hello".into()).await.expect("must send");
+        body.data()
+            .await
+            .expect("must have data")
+            .expect("must succeed");
+        assert_eq!(limiter.state.lock().in_flight, 1);
+        assert_eq!(
+            limit(&limiter),
+            10,
+            "limit must not change until the body completes"
+        );
+
+        drop(tx);
+        assert!(body.data().await.is_none());
+        assert_eq!(limiter.state.lock().in_flight, 0);
+        assert_eq!(limit(&limiter), 9);
+    }
+
+    #[test]
+    fn shares_limiter_per_backend() {
+        let limiters = Limiters::default();
+        let labels = ConcreteLabels(
+            ParentRef(linkerd_proxy_client_policy::Meta::new_default("parent")),
+            BackendRef(linkerd_proxy_client_policy::Meta::new_default("backend")),
+        );
+        let config = config(Algorithm::Gradient);
+
+        let a = limiters.get_or_create(config, labels.clone());
+        let b = limiters.get_or_create(config, labels.clone());
+        assert!(Arc::ptr_eq(&a, &b));
+
+        drop((a, b));
+        assert!(limiters.by_backend.lock().is_empty());
+        assert!(
+            !limiters.metrics.limit.remove(&labels),
+            "the limit gauge must be removed with the limiter"
+        );
+        assert!(
+            !limiters.metrics.rejected.remove(&labels),
+            "the rejected counter must be removed with the limiter"
+        );
+    }
+
+    #[test]
+    fn aimd() {
+        let limiter = limiter(Algorithm::Aimd {
+            latency_threshold: Duration::from_millis(100),
+        });
+        let fast = Duration::from_millis(10);
+        let slow = Duration::from_millis(200);
+
+        // The limit only grows when it is being exercised.
+        limiter.update(fast, 1, false);
+        assert_eq!(limit(&limiter), 10);
+        limiter.update(fast, 5, false);
+        assert_eq!(limit(&limiter), 11);
+
+        limiter.update(slow, 5, false);
+        assert_eq!(limit(&limiter), 9);
+        limiter.update(fast, 5, true);
+        assert_eq!(limit(&limiter), 8);
+
+        for _ in 0..100 {
+            limiter.update(fast, 20, false);
+        }
+        assert_eq!(limit(&limiter), 20);
+        for _ in 0..100 {
+            limiter.update(slow, 20, false);
+        }
+        assert_eq!(limit(&limiter), 2);
+        assert_eq!(limiter.limit_gauge.get(), 2);
+    }
+
+    #[test]
+    fn gradient() {
+        let limiter = limiter(Algorithm::Gradient);
+        let healthy = Duration::from_millis(10);
+
+        // While latency is stable, the limit grows.
+        for _ in 0..10 {
+            limiter.update(healthy, 10, false);
+        }
+        let grown = limit(&limiter);
+        assert!(grown > 10, "limit must grow: {grown}");
+
+        // As latency rises, the limit shrinks.
+        for _ in 0..20 {
+            limiter.update(healthy * 10, 20, false);
+        }
+        let shrunk = limit(&limiter);
+        assert!(shrunk < grown, "limit must shrink: {shrunk}");
+        assert_eq!(limiter.limit_gauge.get(), shrunk as i64);
+    }
+}
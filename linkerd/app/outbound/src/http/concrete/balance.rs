@@ -25,6 +25,7 @@ pub struct Balance<T> {
     pub addr: NameAddr,
     pub ewma: balance::EwmaConfig,
     pub queue: QueueConfig,
+    pub codel: Option<balance::CodelConfig>,
     pub parent: T,
 }
 
@@ -59,6 +60,12 @@ impl<T> svc::Param<svc::queue::Timeout> for Balance<T> {
     }
 }
 
+impl<T> svc::Param<Option<balance::CodelConfig>> for Balance<T> {
+    fn param(&self) -> Option<balance::CodelConfig> {
+        self.codel
+    }
+}
+
 impl<T: svc::Param<ParentRef>> svc::Param<ParentRef> for Balance<T> {
     fn param(&self) -> ParentRef {
         self.parent.param()
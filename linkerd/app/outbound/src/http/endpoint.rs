@@ -4,13 +4,19 @@ use super::{
     handle_proxy_error_headers::{self, NewHandleProxyErrorHeaders},
     NewRequireIdentity,
 };
-use crate::{tcp::tagged_transport, zone::TcpZoneLabels, Outbound};
+use crate::{
+    policy,
+    tcp::{self, tagged_transport},
+    zone::TcpZoneLabels,
+    Outbound,
+};
 use linkerd_app_core::{
     classify, config, errors, http_tracing, metrics,
     proxy::{api_resolve::ProtocolHint, http, tap},
+    proxy_protocol,
     svc::{self, ExtractParam},
     tls,
-    transport::{self, Remote, ServerAddr},
+    transport::{self, ClientAddr, OrigDstAddr, Remote, ServerAddr},
     transport_header::SessionProtocol,
     Error, Result, CANONICAL_DST_HEADER,
 };
@@ -231,6 +237,56 @@ impl<T: svc::Param<Option<http::AuthorityOverride>>> svc::Param<Option<http::Aut
     }
 }
 
+impl<T: svc::Param<Option<proxy_protocol::Version>>> svc::Param<Option<proxy_protocol::Version>>
+    for Connect<T>
+{
+    #[inline]
+    fn param(&self) -> Option<proxy_protocol::Version> {
+        self.inner.param()
+    }
+}
+
+/// HTTP connections are pooled and may be shared by many clients, so they are
+/// not attributed to a single client.
+impl<T> svc::Param<Option<Remote<ClientAddr>>> for Connect<T> {
+    #[inline]
+    fn param(&self) -> Option<Remote<ClientAddr>> {
+        None
+    }
+}
+
+/// Likewise, an HTTP connection may be shared by clients that connected to
+/// different original destinations.
+impl<T> svc::Param<Option<OrigDstAddr>> for Connect<T> {
+    #[inline]
+    fn param(&self) -> Option<OrigDstAddr> {
+        None
+    }
+}
+
+impl<T: svc::Param<Option<policy::UpstreamProxy>>> svc::Param<Option<policy::UpstreamProxy>>
+    for Connect<T>
+{
+    #[inline]
+    fn param(&self) -> Option<policy::UpstreamProxy> {
+        self.inner.param()
+    }
+}
+
+impl<T: svc::Param<Option<tcp::ForwardName>>> svc::Param<Option<tcp::ForwardName>> for Connect<T> {
+    #[inline]
+    fn param(&self) -> Option<tcp::ForwardName> {
+        self.inner.param()
+    }
+}
+
+impl<T: svc::Param<Option<policy::Marking>>> svc::Param<Option<policy::Marking>> for Connect<T> {
+    #[inline]
+    fn param(&self) -> Option<policy::Marking> {
+        self.inner.param()
+    }
+}
+
 impl<T: svc::Param<transport::labels::Key>> svc::Param<transport::labels::Key> for Connect<T> {
     #[inline]
     fn param(&self) -> transport::labels::Key {
@@ -290,6 +290,30 @@ impl svc::Param<Option<http::AuthorityOverride>> for Endpoint {
     }
 }
 
+impl svc::Param<Option<proxy_protocol::Version>> for Endpoint {
+    fn param(&self) -> Option<proxy_protocol::Version> {
+        None
+    }
+}
+
+impl svc::Param<Option<policy::UpstreamProxy>> for Endpoint {
+    fn param(&self) -> Option<policy::UpstreamProxy> {
+        None
+    }
+}
+
+impl svc::Param<Option<tcp::ForwardName>> for Endpoint {
+    fn param(&self) -> Option<tcp::ForwardName> {
+        None
+    }
+}
+
+impl svc::Param<Option<policy::Marking>> for Endpoint {
+    fn param(&self) -> Option<policy::Marking> {
+        None
+    }
+}
+
 impl svc::Param<transport::labels::Key> for Endpoint {
     fn param(&self) -> transport::labels::Key {
         transport::labels::Key::OutboundClient(self.param())
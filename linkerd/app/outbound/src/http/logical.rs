@@ -1,7 +1,7 @@
 //! A stack that routes HTTP requests to concrete backends.
 
 use super::concrete;
-use crate::{BackendRef, EndpointRef, Outbound, OutboundMetrics, ParentRef};
+use crate::{tcp, BackendRef, EndpointRef, Outbound, OutboundMetrics, ParentRef};
 use linkerd_app_core::{
     proxy::{api_resolve::Metadata, http},
     svc,
@@ -43,6 +43,10 @@ pub struct Concrete<T> {
     parent_ref: ParentRef,
     backend_ref: BackendRef,
     failure_accrual: policy::FailureAccrual,
+    proxy_protocol: Option<policy::ProxyProtocolVersion>,
+    upstream_proxy: Option<policy::UpstreamProxy>,
+    forward_name: Option<tcp::ForwardName>,
+    marking: Option<policy::Marking>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -155,6 +159,10 @@ where
                                     authority: None,
                                     parent,
                                     failure_accrual: Default::default(),
+                                    proxy_protocol: None,
+                                    upstream_proxy: None,
+                                    forward_name: None,
+                                    marking: None,
                                 })
                             }
                             Self::Profile(profile) => svc::Either::B(svc::Either::A(profile)),
@@ -255,6 +263,30 @@ impl<T> svc::Param<policy::FailureAccrual> for Concrete<T> {
     }
 }
 
+impl<T> svc::Param<Option<policy::ProxyProtocolVersion>> for Concrete<T> {
+    fn param(&self) -> Option<policy::ProxyProtocolVersion> {
+        self.proxy_protocol
+    }
+}
+
+impl<T> svc::Param<Option<policy::UpstreamProxy>> for Concrete<T> {
+    fn param(&self) -> Option<policy::UpstreamProxy> {
+        self.upstream_proxy.clone()
+    }
+}
+
+impl<T> svc::Param<Option<tcp::ForwardName>> for Concrete<T> {
+    fn param(&self) -> Option<tcp::ForwardName> {
+        self.forward_name.clone()
+    }
+}
+
+impl<T> svc::Param<Option<policy::Marking>> for Concrete<T> {
+    fn param(&self) -> Option<policy::Marking> {
+        self.marking
+    }
+}
+
 // === impl CanonicalDstHeader ===
 
 impl From<CanonicalDstHeader> for http::HeaderPair {
@@ -211,6 +211,7 @@ impl<T> svc::Param<extensions::Params> for Http<T> {
                 retryable_grpc_statuses: None,
             }),
             allow_l5d_request_headers: self.params.params.allow_l5d_request_headers,
+            priority: self.params.params.priority,
         }
     }
 }
@@ -267,6 +268,7 @@ impl<T> svc::Param<extensions::Params> for Grpc<T> {
                 retryable_grpc_statuses: Some(r.codes),
             }),
             allow_l5d_request_headers: self.params.params.allow_l5d_request_headers,
+            priority: self.params.params.priority,
         }
     }
 }
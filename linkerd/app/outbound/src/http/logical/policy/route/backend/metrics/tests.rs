@@ -321,6 +321,10 @@ fn mock_http_route_backend_metrics(
                     ),
                     authority: None,
                     failure_accrual: Default::default(),
+                    proxy_protocol: None,
+                    upstream_proxy: None,
+                    forward_name: None,
+                    marking: None,
                     parent: (),
                     parent_ref: parent_ref.clone(),
                     backend_ref: backend_ref.clone(),
@@ -377,6 +381,10 @@ fn mock_grpc_route_backend_metrics(
                     ),
                     authority: None,
                     failure_accrual: Default::default(),
+                    proxy_protocol: None,
+                    upstream_proxy: None,
+                    forward_name: None,
+                    marking: None,
                     parent: (),
                     parent_ref: parent_ref.clone(),
                     backend_ref: backend_ref.clone(),
@@ -1,5 +1,9 @@
 use super::retry::RetryPolicy;
-use linkerd_app_core::{config::ExponentialBackoff, proxy::http, svc};
+use linkerd_app_core::{
+    config::ExponentialBackoff,
+    proxy::http::{self, balance::Priority},
+    svc,
+};
 use linkerd_proxy_client_policy as policy;
 use std::task::{Context, Poll};
 use tokio::time;
@@ -9,6 +13,7 @@ pub struct Params {
     pub retry: Option<RetryPolicy>,
     pub timeouts: policy::http::Timeouts,
     pub allow_l5d_request_headers: bool,
+    pub priority: Option<Priority>,
 }
 
 // A request extension that marks the number of times a request has been
@@ -72,7 +77,14 @@ where
         let mut timeouts = self.configure_timeouts(req.headers_mut());
         timeouts.response_headers = retry.as_ref().and_then(|r| r.timeout);
 
-        tracing::debug!(?retry, ?timeouts, "Initializing route extensions");
+        let priority = self.configure_priority(req.headers_mut());
+
+        tracing::debug!(
+            ?retry,
+            ?timeouts,
+            ?priority,
+            "Initializing route extensions"
+        );
         if let Some(retry) = retry {
             let _prior = req.extensions_mut().insert(retry);
             debug_assert!(_prior.is_none(), "RetryPolicy must only be configured once");
@@ -87,6 +99,11 @@ where
         let _prior = req.extensions_mut().insert(Attempt(1.try_into().unwrap()));
         debug_assert!(_prior.is_none(), "Attempts must only be configured once");
 
+        if let Some(priority) = priority {
+            let _prior = req.extensions_mut().insert(priority);
+            debug_assert!(_prior.is_none(), "Priority must only be configured once");
+        }
+
         self.inner.call(req)
     }
 }
@@ -169,6 +186,17 @@ impl<S> SetExtensions<S> {
 
         timeouts
     }
+
+    fn configure_priority(&self, req: &mut http::HeaderMap) -> Option<Priority> {
+        if !self.params.allow_l5d_request_headers {
+            return self.params.priority;
+        }
+
+        let user_priority = req
+            .remove("l5d-priority")
+            .and_then(|val| val.to_str().ok()?.parse::<Priority>().ok());
+        user_priority.or(self.params.priority)
+    }
 }
 
 fn parse_http_conditions(s: &str) -> Option<policy::http::StatusRanges> {
@@ -222,7 +222,10 @@ impl Error {
         if errors::is_caused_by::<errors::FailFastError>(&**error) {
             return Ok(Self::FailFast);
         }
-        if errors::is_caused_by::<errors::LoadShedError>(&**error) {
+        if errors::is_caused_by::<errors::LoadShedError>(&**error)
+            || errors::is_caused_by::<http::balance::QueueShedError>(&**error)
+            || errors::is_caused_by::<http::balance::QueueDelayError>(&**error)
+        {
             return Ok(Self::LoadShed);
         }
 
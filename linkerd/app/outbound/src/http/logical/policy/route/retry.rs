@@ -102,6 +102,10 @@ impl retry::Policy for RetryPolicy {
             dst.insert(timeouts);
         }
 
+        if let Some(priority) = src.get::<http::balance::Priority>().copied() {
+            dst.insert(priority);
+        }
+
         // The HTTP server sets a ClientHandle with the client's address and a means
         // to close the server-side connection.
         if let Some(client_handle) = src.get::<http::ClientHandle>().cloned() {
@@ -136,7 +140,10 @@ impl RetryPolicy {
     fn retryable_error(error: &Error) -> bool {
         // While LoadShed errors are not retryable, FailFast errors are, since
         // retrying may put us in another backend that is available.
-        if is_caused_by::<svc::LoadShedError>(&**error) {
+        if is_caused_by::<svc::LoadShedError>(&**error)
+            || is_caused_by::<http::balance::QueueShedError>(&**error)
+            || is_caused_by::<http::balance::QueueDelayError>(&**error)
+        {
             return false;
         }
         if is_caused_by::<svc::FailFastError>(&**error) {
@@ -2,7 +2,7 @@ use super::{
     super::{concrete, Concrete, LogicalAddr, NoRoute},
     route,
 };
-use crate::{BackendRef, EndpointRef, ParentRef, RouteRef};
+use crate::{tcp, BackendRef, EndpointRef, ParentRef, RouteRef};
 use linkerd_app_core::{
     classify, proxy::http, svc, transport::addrs::*, Addr, Error, NameAddr, Result,
 };
@@ -123,7 +123,8 @@ where
         let mk_concrete = {
             let parent = parent.clone();
             let parent_ref = parent_ref.clone();
-            move |backend_ref: BackendRef, target: concrete::Dispatch| {
+            let addr = addr.clone();
+            move |bke: &policy::Backend, backend_ref: BackendRef, target: concrete::Dispatch| {
                 // XXX With policies we don't have a top-level authority name at
                 // the moment. So, instead, we use the concrete addr used for
                 // discovery for now.
@@ -131,6 +132,14 @@ where
                     concrete::Dispatch::Balance(ref addr, ..) => Some(addr.as_http_authority()),
                     _ => None,
                 };
+                // Endpoints that are forwarded to, rather than discovered, are
+                // known by the logical name that was targeted.
+                let forward_name = match (&target, &addr) {
+                    (concrete::Dispatch::Forward(..), Addr::Name(name)) => {
+                        Some(tcp::ForwardName(name.clone()))
+                    }
+                    _ => None,
+                };
                 Concrete {
                     target,
                     authority,
@@ -138,6 +147,10 @@ where
                     backend_ref,
                     parent_ref: parent_ref.clone(),
                     failure_accrual,
+                    proxy_protocol: bke.proxy_protocol,
+                    upstream_proxy: bke.upstream_proxy.clone(),
+                    forward_name,
+                    marking: bke.marking,
                 }
             }
         };
@@ -147,6 +160,7 @@ where
                 policy::Load::PeakEwma(policy::PeakEwma { decay, default_rtt }),
                 policy::EndpointDiscovery::DestinationGet { ref path },
             ) => mk_concrete(
+                bke,
                 BackendRef(bke.meta.clone()),
                 concrete::Dispatch::Balance(
                     path.parse::<NameAddr>()
@@ -155,10 +169,12 @@ where
                 ),
             ),
             policy::BackendDispatcher::Forward(addr, ref md) => mk_concrete(
+                bke,
                 EndpointRef::new(md, addr.port().try_into().expect("port must not be 0")).into(),
                 concrete::Dispatch::Forward(Remote(ServerAddr(addr)), md.clone()),
             ),
             policy::BackendDispatcher::Fail { ref message } => mk_concrete(
+                bke,
                 BackendRef(policy::Meta::new_default("fail")),
                 concrete::Dispatch::Fail {
                     message: message.clone(),
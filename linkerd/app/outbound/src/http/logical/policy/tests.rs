@@ -36,6 +36,9 @@ async fn header_based_route() {
                 path: format!("{name}.ns.svc.cluster.local:8080"),
             },
         ),
+        proxy_protocol: None,
+        upstream_proxy: None,
+        marking: None,
     };
     let mk_policy = |name: &'static str, backend: policy::Backend| policy::RoutePolicy {
         meta: Arc::new(policy::Meta::Resource {
@@ -188,6 +191,9 @@ async fn http_filter_request_headers() {
             failfast_timeout: time::Duration::from_secs(1),
         },
         dispatcher: policy::BackendDispatcher::Forward(addr, Default::default()),
+        proxy_protocol: None,
+        upstream_proxy: None,
+        marking: None,
     };
 
     // Stack that produces mock services.
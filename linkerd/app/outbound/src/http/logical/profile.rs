@@ -118,6 +118,10 @@ where
                 authority: Some(addr.as_http_authority()),
                 parent: parent.clone(),
                 failure_accrual: Default::default(),
+                proxy_protocol: None,
+                upstream_proxy: None,
+                forward_name: None,
+                marking: None,
             };
             let backends = std::iter::once(concrete.clone()).collect();
             let distribution = Distribution::first_available(std::iter::once(concrete));
@@ -134,6 +138,10 @@ where
                     authority: Some(t.addr.as_http_authority()),
                     parent: parent.clone(),
                     failure_accrual: Default::default(),
+                    proxy_protocol: None,
+                    upstream_proxy: None,
+                    forward_name: None,
+                    marking: None,
                 })
                 .collect();
             let distribution = Distribution::random_available(targets.iter().cloned().map(
@@ -147,6 +155,10 @@ where
                         target: concrete::Dispatch::Balance(addr, DEFAULT_EWMA),
                         parent: parent.clone(),
                         failure_accrual: Default::default(),
+                        proxy_protocol: None,
+                        upstream_proxy: None,
+                        forward_name: None,
+                        marking: None,
                     };
                     (concrete, weight)
                 },
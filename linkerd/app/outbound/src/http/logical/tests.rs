@@ -214,6 +214,9 @@ fn default_backend(path: impl ToString) -> client_policy::Backend {
                 path: path.to_string(),
             },
         ),
+        proxy_protocol: None,
+        upstream_proxy: None,
+        marking: None,
     }
 }
 
@@ -131,6 +131,16 @@ impl errors::HttpRescue<Error> for ServerRescue {
         if errors::is_caused_by::<errors::LoadShedError>(&*error) {
             return Ok(errors::SyntheticHttpResponse::unavailable(error));
         }
+        if errors::is_caused_by::<http::balance::QueueShedError>(&*error)
+            || errors::is_caused_by::<http::balance::QueueDelayError>(&*error)
+        {
+            return Ok(errors::SyntheticHttpResponse::unavailable(error));
+        }
+        // A backend's adaptive concurrency limit was reached. The connection
+        // is left open, since other requests on it may target other backends.
+        if errors::is_caused_by::<super::concrete::AdaptiveConcurrencyLimited>(&*error) {
+            return Ok(errors::SyntheticHttpResponse::unavailable_nonfatal(error));
+        }
 
         // Handle policy-driven timeouts.
         if errors::is_caused_by::<http::stream_timeouts::ResponseTimeoutError>(&*error) {
@@ -79,7 +79,7 @@ impl Outbound<()> {
         T: svc::Param<OrigDstAddr>,
         T: Clone + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
         I: Debug + Unpin + Send + Sync + 'static,
         // Endpoint resolver.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -641,6 +641,12 @@ impl svc::Param<watch::Receiver<opaq::Routes>> for Opaq {
     }
 }
 
+impl svc::Param<Option<OrigDstAddr>> for Opaq {
+    fn param(&self) -> Option<OrigDstAddr> {
+        Some(self.orig_dst)
+    }
+}
+
 // === impl RequestTarget ===
 
 impl From<RequestTarget> for Addr {
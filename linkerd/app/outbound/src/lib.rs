@@ -8,7 +8,7 @@
 
 use linkerd_app_core::http_tracing::SpanSink;
 use linkerd_app_core::{
-    config::{ProxyConfig, QueueConfig},
+    config::{ProxyConfig, QueueConfig, ServerConfig},
     drain,
     exp_backoff::ExponentialBackoff,
     identity, io,
@@ -36,6 +36,7 @@ use std::{
 };
 
 mod discover;
+mod forward_proxy;
 pub mod http;
 mod ingress;
 mod metrics;
@@ -75,6 +76,14 @@ pub struct Config {
     /// each IP:port to which an application has opened an outbound TCP connection.
     pub http_request_queue: QueueConfig,
 
+    /// Configures HTTP balancer queues to drop requests while queueing delay
+    /// persistently exceeds a target.
+    pub http_balancer_codel: Option<http::balance::CodelConfig>,
+
+    /// Configures adaptive concurrency limits for each HTTP backend. When
+    /// unset, requests are only bounded by `http_request_queue`.
+    pub http_adaptive_concurrency: Option<http::concrete::adaptive_concurrency::Config>,
+
     // In "ingress mode", we assume we are always routing HTTP requests and do
     // not perform per-target-address discovery. Non-HTTP connections are
     // forwarded without discovery/routing/mTLS.
@@ -83,6 +92,14 @@ pub struct Config {
 
     // Whether the proxy may include informational headers on HTTP responses.
     pub emit_headers: bool,
+
+    /// Configures an additional listener for clients that explicitly use the
+    /// proxy (via HTTP `CONNECT`, absolute-form HTTP requests, or SOCKS5)
+    /// rather than having their traffic redirected to it.
+    pub forward_proxy: Option<ServerConfig>,
+
+    /// Locally-configured settings applied to each discovered client policy.
+    pub policy_overrides: Arc<policy::Overrides>,
 }
 
 #[derive(Clone, Debug)]
@@ -151,12 +168,18 @@ impl Outbound<()> {
         C::ResponseBody: Default + Send + 'static,
         C::Future: Send,
     {
-        policy::Api::new(workload, limits, Duration::from_secs(10), client)
-            .into_watch(backoff)
-            .map_result(|response| match response {
-                Err(e) => Err(e.into()),
-                Ok(rsp) => Ok(rsp.into_inner()),
-            })
+        policy::Api::new(
+            workload,
+            limits,
+            Duration::from_secs(10),
+            self.config.policy_overrides.clone(),
+            client,
+        )
+        .into_watch(backoff)
+        .map_result(|response| match response {
+            Err(e) => Err(e.into()),
+            Ok(rsp) => Ok(rsp.into_inner()),
+        })
     }
 
     #[cfg(any(test, feature = "test-util"))]
@@ -235,7 +258,7 @@ impl Outbound<()> {
         T: svc::Param<OrigDstAddr>,
         T: Clone + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
         I: Debug + Unpin + Send + Sync + 'static,
         // Endpoint resolution.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -6,7 +6,10 @@ use crate::http::IdentityRequired;
 use linkerd_app_core::{
     errors::{FailFastError, LoadShedError},
     metrics::FmtLabels,
-    proxy::http::ResponseTimeoutError,
+    proxy::http::{
+        balance::{QueueDelayError, QueueShedError},
+        ResponseTimeoutError,
+    },
 };
 use std::fmt;
 
@@ -33,7 +36,10 @@ impl ErrorKind {
             ErrorKind::FailFast
         } else if err.is::<ResponseTimeoutError>() {
             ErrorKind::ResponseTimeout
-        } else if err.is::<LoadShedError>() {
+        } else if err.is::<LoadShedError>()
+            || err.is::<QueueShedError>()
+            || err.is::<QueueDelayError>()
+        {
             ErrorKind::LoadShed
         } else if let Some(e) = err.source() {
             Self::mk(e)
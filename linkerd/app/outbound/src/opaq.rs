@@ -40,8 +40,9 @@ impl<C> Outbound<C> {
         // Opaque target
         T: Clone + Debug + PartialEq + Eq + Hash + Send + Sync + 'static,
         T: svc::Param<watch::Receiver<Routes>>,
+        T: svc::Param<Option<OrigDstAddr>>,
         // Server-side connection
-        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Splice,
         I: Debug + Send + Sync + Unpin + 'static,
         // Endpoint discovery
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -49,7 +50,7 @@ impl<C> Outbound<C> {
         // TCP endpoint stack.
         C: svc::MakeConnection<tcp::Connect, Metadata = Local<ClientAddr>, Error = io::Error>,
         C: Clone + Send + Sync + Unpin + 'static,
-        C::Connection: Send + Unpin,
+        C::Connection: io::Splice + Send + Unpin,
         C::Future: Send + Unpin,
     {
         self.push_tcp_endpoint()
@@ -76,6 +77,15 @@ where
     }
 }
 
+impl<T> svc::Param<Option<OrigDstAddr>> for Opaq<T>
+where
+    T: svc::Param<Option<OrigDstAddr>>,
+{
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.0.param()
+    }
+}
+
 // === impl OpaqMetrics ===
 
 impl OpaqMetrics {
@@ -186,6 +196,9 @@ fn routes_from_profile(addr: NameAddr, profile: &profiles::Profile) -> Routes {
                             path: target.addr.to_string(),
                         },
                     ),
+                    proxy_protocol: None,
+                    upstream_proxy: None,
+                    marking: None,
                 },
                 filters: std::sync::Arc::new([]),
             };
@@ -199,13 +212,16 @@ fn routes_from_profile(addr: NameAddr, profile: &profiles::Profile) -> Routes {
     static ROUTE_META: Lazy<Arc<policy::Meta>> =
         Lazy::new(|| policy::Meta::new_default("serviceprofile"));
     let route = policy::opaq::Route {
-        policy: policy::opaq::Policy {
-            // TODO(ver) use resource metadata from the profile response.
-            meta: ROUTE_META.clone(),
-            params: (),
-            filters: std::sync::Arc::new([]),
-            distribution,
-        },
+        rules: vec![policy::opaq::Rule {
+            matches: vec![],
+            policy: policy::opaq::Policy {
+                // TODO(ver) use resource metadata from the profile response.
+                meta: ROUTE_META.clone(),
+                params: (),
+                filters: std::sync::Arc::new([]),
+                distribution,
+            },
+        }],
     };
 
     Routes {
@@ -2,6 +2,7 @@ use super::Logical;
 use crate::{
     metrics::BalancerMetricsParams,
     stack_labels,
+    tcp::{ForwardName, FromClient, NewConnectFromClient},
     zone::{tcp_zone_labels, TcpZoneLabels},
     BackendRef, Outbound, ParentRef,
 };
@@ -25,6 +26,7 @@ use linkerd_app_core::{
     transport_header::SessionProtocol,
     Error, Infallible, NameAddr,
 };
+use linkerd_proxy_client_policy::{Marking, ProxyProtocolVersion, UpstreamProxy};
 use std::{fmt::Debug, net::SocketAddr, sync::Arc};
 use tracing::info_span;
 
@@ -143,13 +145,14 @@ impl<C> Outbound<C> {
         T: svc::Param<BackendRef>,
         T: svc::Param<ParentRef>,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + Debug + Send + Unpin + 'static,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Splice,
+        I: Debug + Send + Unpin + 'static,
         // Endpoint resolution.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
         R::Resolution: Unpin,
         // Endpoint connector.
-        C: svc::MakeConnection<Endpoint<T>> + Clone + Send + 'static,
-        C::Connection: Send + Unpin,
+        C: svc::MakeConnection<FromClient<Endpoint<T>>> + Clone + Send + 'static,
+        C::Connection: io::Splice + Send + Unpin,
         C::Metadata: Send + Unpin,
         C::Future: Send,
         C: Send + Sync + 'static,
@@ -161,9 +164,11 @@ impl<C> Outbound<C> {
         self.map_stack(|config, rt, inner| {
             let queue = config.tcp_connection_queue;
 
+            // Each connection is established on behalf of the client whose
+            // connection is forwarded.
             let connect = inner
                 .push(svc::stack::WithoutConnectionMetadata::layer())
-                .push_new_thunk();
+                .push(NewConnectFromClient::layer());
 
             let forward = connect
                 .clone()
@@ -243,7 +248,7 @@ impl<C> Outbound<C> {
                     },
                     svc::stack(fail).check_new_clone().into_inner(),
                 )
-                .push_on_service(tcp::Forward::layer())
+                .push_on_service(tcp::ForwardFromPeer::layer())
                 .push_on_service(drain::Retain::layer(rt.drain.clone()))
                 .push(svc::ArcNewService::layer())
         })
@@ -289,6 +294,13 @@ impl<T> svc::Param<svc::queue::Timeout> for Balance<T> {
     }
 }
 
+/// Connections are not dropped based on their queueing delay.
+impl<T> svc::Param<Option<balance::CodelConfig>> for Balance<T> {
+    fn param(&self) -> Option<balance::CodelConfig> {
+        None
+    }
+}
+
 impl<T: svc::Param<ParentRef>> svc::Param<ParentRef> for Balance<T> {
     fn param(&self) -> ParentRef {
         self.parent.param()
@@ -338,6 +350,51 @@ impl<T> svc::Param<Option<SessionProtocol>> for Endpoint<T> {
     }
 }
 
+impl<T> svc::Param<Option<ProxyProtocolVersion>> for Endpoint<T>
+where
+    T: svc::Param<Option<ProxyProtocolVersion>>,
+{
+    fn param(&self) -> Option<ProxyProtocolVersion> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<OrigDstAddr>> for Endpoint<T>
+where
+    T: svc::Param<Option<OrigDstAddr>>,
+{
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<UpstreamProxy>> for Endpoint<T>
+where
+    T: svc::Param<Option<UpstreamProxy>>,
+{
+    fn param(&self) -> Option<UpstreamProxy> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<ForwardName>> for Endpoint<T>
+where
+    T: svc::Param<Option<ForwardName>>,
+{
+    fn param(&self) -> Option<ForwardName> {
+        self.parent.param()
+    }
+}
+
+impl<T> svc::Param<Option<Marking>> for Endpoint<T>
+where
+    T: svc::Param<Option<Marking>>,
+{
+    fn param(&self) -> Option<Marking> {
+        self.parent.param()
+    }
+}
+
 impl<T> svc::Param<transport::labels::Key> for Endpoint<T>
 where
     T: svc::Param<Logical>,
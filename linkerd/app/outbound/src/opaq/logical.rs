@@ -1,6 +1,6 @@
 use super::concrete;
-use crate::{BackendRef, Outbound, ParentRef};
-use linkerd_app_core::{io, svc, Addr, Error};
+use crate::{tcp, BackendRef, Outbound, ParentRef};
+use linkerd_app_core::{io, svc, transport::addrs::OrigDstAddr, Addr, Error};
 use linkerd_proxy_client_policy as client_policy;
 use std::{fmt::Debug, hash::Hash, sync::Arc};
 use tokio::sync::watch;
@@ -30,6 +30,9 @@ pub struct Concrete<T> {
     parent: T,
     logical: Logical,
     backend_ref: BackendRef,
+    proxy_protocol: Option<client_policy::ProxyProtocolVersion>,
+    upstream_proxy: Option<client_policy::UpstreamProxy>,
+    marking: Option<client_policy::Marking>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -59,7 +62,7 @@ impl<N> Outbound<N> {
         T: svc::Param<watch::Receiver<Routes>>,
         T: Eq + Hash + Clone + Debug + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + Debug + Send + Unpin + 'static,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + Debug + Send + Unpin + 'static,
         // Concrete stack.
         N: svc::NewService<Concrete<T>, Service = NSvc> + Clone + Send + Sync + 'static,
         NSvc: svc::Service<I, Response = ()> + Clone + Send + Sync + 'static,
@@ -119,3 +122,43 @@ impl<T> svc::Param<BackendRef> for Concrete<T> {
         self.backend_ref.clone()
     }
 }
+
+impl<T> svc::Param<Option<client_policy::ProxyProtocolVersion>> for Concrete<T> {
+    fn param(&self) -> Option<client_policy::ProxyProtocolVersion> {
+        self.proxy_protocol
+    }
+}
+
+impl<T> svc::Param<Option<client_policy::UpstreamProxy>> for Concrete<T> {
+    fn param(&self) -> Option<client_policy::UpstreamProxy> {
+        self.upstream_proxy.clone()
+    }
+}
+
+/// Endpoints that are forwarded to, rather than discovered, are known by the
+/// logical name that was targeted.
+impl<T> svc::Param<Option<tcp::ForwardName>> for Concrete<T> {
+    fn param(&self) -> Option<tcp::ForwardName> {
+        match (&self.target, &self.logical.addr) {
+            (concrete::Dispatch::Forward(..), Addr::Name(name)) => {
+                Some(tcp::ForwardName(name.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T> svc::Param<Option<client_policy::Marking>> for Concrete<T> {
+    fn param(&self) -> Option<client_policy::Marking> {
+        self.marking
+    }
+}
+
+impl<T> svc::Param<Option<OrigDstAddr>> for Concrete<T>
+where
+    T: svc::Param<Option<OrigDstAddr>>,
+{
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.parent.param()
+    }
+}
@@ -2,6 +2,7 @@ use super::{super::Concrete, Logical};
 use crate::RouteRef;
 use linkerd_app_core::{io, svc, Error};
 use linkerd_distribute as distribute;
+use linkerd_opaq_route as opaq_route;
 use std::{fmt::Debug, hash::Hash};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -12,6 +13,7 @@ pub(crate) struct Backend<T> {
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) struct MatchedRoute<T> {
+    pub(super) r#match: opaq_route::RouteMatch,
     pub(super) params: Route<T>,
 }
 
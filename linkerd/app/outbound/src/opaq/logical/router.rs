@@ -27,7 +27,7 @@ where
 {
     pub fn layer<N, I, NSvc>() -> impl svc::Layer<N, Service = svc::ArcNewCloneTcp<Self, I>> + Clone
     where
-        I: io::AsyncRead + io::AsyncWrite + Debug + Send + Unpin + 'static,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + Debug + Send + Unpin + 'static,
         // Concrete stack.
         N: svc::NewService<Concrete<T>, Service = NSvc> + Clone + Send + Sync + 'static,
         NSvc: svc::Service<I, Response = ()> + Clone + Send + Sync + 'static,
@@ -63,11 +63,16 @@ where
             let parent = parent.clone();
             let logical = logical.clone();
 
-            move |backend_ref: BackendRef, target: concrete::Dispatch| Concrete {
-                target,
-                parent: parent.clone(),
-                backend_ref,
-                logical: logical.clone(),
+            move |bke: &policy::Backend, backend_ref: BackendRef, target: concrete::Dispatch| {
+                Concrete {
+                    target,
+                    parent: parent.clone(),
+                    backend_ref,
+                    logical: logical.clone(),
+                    proxy_protocol: bke.proxy_protocol,
+                    upstream_proxy: bke.upstream_proxy.clone(),
+                    marking: bke.marking,
+                }
             }
         };
 
@@ -76,6 +81,7 @@ where
                 policy::Load::PeakEwma(policy::PeakEwma { decay, default_rtt }),
                 policy::EndpointDiscovery::DestinationGet { ref path },
             ) => mk_concrete(
+                bke,
                 BackendRef(bke.meta.clone()),
                 concrete::Dispatch::Balance(
                     path.parse::<NameAddr>()
@@ -84,10 +90,12 @@ where
                 ),
             ),
             policy::BackendDispatcher::Forward(addr, ref md) => mk_concrete(
+                bke,
                 EndpointRef::new(md, addr.port().try_into().expect("port must not be 0")).into(),
                 concrete::Dispatch::Forward(Remote(ServerAddr(addr)), md.clone()),
             ),
             policy::BackendDispatcher::Fail { ref message } => mk_concrete(
+                bke,
                 BackendRef(policy::Meta::new_default("fail")),
                 concrete::Dispatch::Fail {
                     message: message.clone(),
@@ -139,7 +147,14 @@ where
             };
 
         let routes = routes.as_ref().map(|route| opaq_route::Route {
-            policy: mk_policy(route.policy.clone()),
+            rules: route
+                .rules
+                .iter()
+                .map(|rule| opaq_route::Rule {
+                    matches: rule.matches.clone(),
+                    policy: mk_policy(rule.policy.clone()),
+                })
+                .collect(),
         });
 
         let backends = backends.iter().map(mk_dispatch).collect();
@@ -156,19 +171,38 @@ where
 impl<T, I> svc::router::SelectRoute<I> for Router<T>
 where
     T: Clone + Eq + Hash + Debug,
+    I: io::PeerAddr,
 {
     type Key = route::MatchedRoute<T>;
     type Error = NoRoute;
 
-    fn select(&self, _: &I) -> Result<Self::Key, Self::Error> {
+    fn select(&self, io: &I) -> Result<Self::Key, Self::Error> {
         tracing::trace!("Selecting Opaq route");
-        let Some(ref route) = self.routes else {
-            return Err(NoRoute);
+        let client_addr = match io.peer_addr() {
+            Ok(addr) => addr.ip(),
+            Err(error) => {
+                tracing::debug!(%error, "Unable to determine client address");
+                return Err(NoRoute);
+            }
         };
-        let params = route.policy.clone();
+        // The outbound proxy never reads a transport header, so the session
+        // name is the logical name the routes were discovered for. On gateway
+        // connections this is the name carried in the inbound transport
+        // header, since the gateway discovers policy for that name; otherwise
+        // it is the name resolved for the original destination address.
+        let si = policy::opaq::SessionInfo {
+            dst_port: self.logical.addr.port(),
+            client_addr,
+            name: self.logical.addr.name_addr().map(|na| na.name().clone()),
+        };
+        let (r#match, params) = policy::opaq::find(self.routes.as_slice(), &si).ok_or(NoRoute)?;
         tracing::debug!(meta = ?params.route_ref, "Selected route");
+        tracing::trace!(?r#match);
 
-        Ok(route::MatchedRoute { params })
+        Ok(route::MatchedRoute {
+            r#match,
+            params: params.clone(),
+        })
     }
 }
 
@@ -1,6 +1,9 @@
 use super::{client_policy as policy, *};
-use crate::opaq::{self, policy::Receiver as PolicyReceiver};
 use crate::test_util::*;
+use crate::{
+    opaq::{self, policy::Receiver as PolicyReceiver},
+    tcp,
+};
 use io::AsyncWriteExt;
 use linkerd_app_core::{
     errors::{self, FailFastError},
@@ -40,14 +43,16 @@ async fn forward() {
     // Build the TCP logical stack with a mocked connector.
     let (rt, _shutdown) = runtime();
     let stack = Outbound::new(default_config(), rt, &mut Default::default())
-        .with_stack(svc::mk(move |ep: concrete::Endpoint<Concrete<Target>>| {
-            let Remote(ServerAddr(ea)) = svc::Param::param(&ep);
-            assert_eq!(ea, ep_addr);
-            let mut io = support::io();
-            io.write(b"hola").read(b"mundo");
-            let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
-            future::ok::<_, support::io::Error>((io.build(), local))
-        }))
+        .with_stack(svc::mk(
+            move |ep: tcp::FromClient<concrete::Endpoint<Concrete<Target>>>| {
+                let Remote(ServerAddr(ea)) = svc::Param::param(&ep);
+                assert_eq!(ea, ep_addr);
+                let mut io = support::io();
+                io.write(b"hola").read(b"mundo");
+                let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
+                future::ok::<_, support::io::Error>((io.build(), local))
+            },
+        ))
         .push_opaq_concrete(resolve)
         .push_opaq_logical()
         .into_inner();
@@ -92,30 +97,33 @@ async fn balances() {
     // Build the TCP logical stack with a mocked endpoint stack that alters its response stream
     // based on the address.
     let (rt, _shutdown) = runtime();
-    let svc = Outbound::new(default_config(), rt, &mut Default::default())
-        .with_stack(svc::mk(
-            move |ep: concrete::Endpoint<Concrete<Target>>| match svc::Param::param(&ep) {
-                Remote(ServerAddr(addr)) if addr == ep0_addr => {
-                    tracing::debug!(%addr, "writing ep0");
-                    let mut io = support::io();
-                    io.write(b"who r u?").read(b"ep0");
-                    let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
-                    future::ok::<_, support::io::Error>((io.build(), local))
-                }
-                Remote(ServerAddr(addr)) if addr == ep1_addr => {
-                    tracing::debug!(%addr, "writing ep1");
-                    let mut io = support::io();
-                    io.write(b"who r u?").read(b"ep1");
-                    let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
-                    future::ok::<_, support::io::Error>((io.build(), local))
-                }
-                addr => unreachable!("unexpected endpoint: {}", addr),
-            },
-        ))
-        .push_opaq_concrete(resolve)
-        .push_opaq_logical()
-        .into_inner()
-        .new_service(target);
+    let svc =
+        Outbound::new(default_config(), rt, &mut Default::default())
+            .with_stack(svc::mk(
+                move |ep: tcp::FromClient<concrete::Endpoint<Concrete<Target>>>| {
+                    match svc::Param::param(&ep) {
+                        Remote(ServerAddr(addr)) if addr == ep0_addr => {
+                            tracing::debug!(%addr, "writing ep0");
+                            let mut io = support::io();
+                            io.write(b"who r u?").read(b"ep0");
+                            let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
+                            future::ok::<_, support::io::Error>((io.build(), local))
+                        }
+                        Remote(ServerAddr(addr)) if addr == ep1_addr => {
+                            tracing::debug!(%addr, "writing ep1");
+                            let mut io = support::io();
+                            io.write(b"who r u?").read(b"ep1");
+                            let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
+                            future::ok::<_, support::io::Error>((io.build(), local))
+                        }
+                        addr => unreachable!("unexpected endpoint: {}", addr),
+                    }
+                },
+            ))
+            .push_opaq_concrete(resolve)
+            .push_opaq_logical()
+            .into_inner()
+            .new_service(target);
 
     // We add a single endpoint to the balancer and it is used:
 
@@ -235,21 +243,27 @@ fn default_service_policy(addr: NameAddr) -> policy::ClientPolicy {
                 path: addr.to_string(),
             },
         ),
+        proxy_protocol: None,
+        upstream_proxy: None,
+        marking: None,
     };
 
     let opaque = policy::opaq::Opaque {
         routes: Some(policy::opaq::Route {
-            policy: policy::opaq::Policy {
-                distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
-                    policy::RouteBackend {
-                        backend: backend.clone(),
-                        filters: Arc::new([]),
-                    },
-                ])),
-                filters: Arc::new([]),
-                meta: meta.clone(),
-                params: (),
-            },
+            rules: vec![policy::opaq::Rule {
+                matches: vec![],
+                policy: policy::opaq::Policy {
+                    distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
+                        policy::RouteBackend {
+                            backend: backend.clone(),
+                            filters: Arc::new([]),
+                        },
+                    ])),
+                    filters: Arc::new([]),
+                    meta: meta.clone(),
+                    params: (),
+                },
+            }],
         }),
     };
 
@@ -8,7 +8,7 @@ use linkerd_app_core::{
     svc::Service,
     Addr, Error, Recover, Result,
 };
-use linkerd_proxy_client_policy::ClientPolicy;
+use linkerd_proxy_client_policy::{ClientPolicy, Overrides};
 use linkerd_tonic_stream::{LimitReceiveFuture, ReceiveLimits};
 use linkerd_tonic_watch::StreamWatch;
 use std::sync::Arc;
@@ -19,6 +19,7 @@ pub(crate) struct Api<S> {
     workload: Arc<str>,
     limits: ReceiveLimits,
     default_detect_timeout: time::Duration,
+    overrides: Arc<Overrides>,
     client: Client<S>,
 }
 
@@ -40,12 +41,14 @@ where
         workload: Arc<str>,
         limits: ReceiveLimits,
         default_detect_timeout: time::Duration,
+        overrides: Arc<Overrides>,
         client: S,
     ) -> Self {
         Self {
             workload,
             limits,
             default_detect_timeout,
+            overrides,
             client: Client::new(client),
         }
     }
@@ -89,6 +92,7 @@ where
 
         let detect_timeout = self.default_detect_timeout;
         let limits = self.limits;
+        let overrides = self.overrides.clone();
         let mut client = self.client.clone();
         Box::pin(async move {
             let rsp =
@@ -98,12 +102,15 @@ where
                     // If the server returned an invalid client policy, we
                     // default to using an invalid policy that causes all
                     // requests to report an internal error.
-                    let policy = ClientPolicy::try_from(up).unwrap_or_else(|error| {
-                        tracing::warn!(%error, "Client policy misconfigured");
-                        INVALID_POLICY
-                            .get_or_init(|| ClientPolicy::invalid(detect_timeout))
-                            .clone()
-                    });
+                    let policy = match ClientPolicy::try_from(up) {
+                        Ok(policy) => overrides.apply(policy),
+                        Err(error) => {
+                            tracing::warn!(%error, "Client policy misconfigured");
+                            INVALID_POLICY
+                                .get_or_init(|| ClientPolicy::invalid(detect_timeout))
+                                .clone()
+                        }
+                    };
                     tracing::debug!(?policy);
                     policy
                 })
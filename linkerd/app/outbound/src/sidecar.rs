@@ -13,7 +13,7 @@ use linkerd_app_core::{
     transport::addrs::*,
     Addr, Error,
 };
-use std::fmt::Debug;
+use std::{fmt::Debug, hash::Hash};
 use tokio::sync::watch;
 use tracing::info_span;
 
@@ -21,6 +21,8 @@ use tracing::info_span;
 #[derive(Clone, Debug)]
 struct Sidecar {
     orig_dst: OrigDstAddr,
+    /// The logical address by which the target was discovered.
+    addr: Addr,
     profile: Option<profiles::Receiver>,
     policy: policy::Receiver,
 }
@@ -28,6 +30,7 @@ struct Sidecar {
 #[derive(Clone, Debug)]
 struct HttpSidecar {
     orig_dst: OrigDstAddr,
+    addr: Addr,
     version: http::Version,
     routes: watch::Receiver<http::Routes>,
 }
@@ -35,12 +38,14 @@ struct HttpSidecar {
 #[derive(Clone, Debug)]
 struct TlsSidecar {
     orig_dst: OrigDstAddr,
+    addr: Addr,
     routes: watch::Receiver<tls::Routes>,
 }
 
 #[derive(Clone, Debug)]
 struct OpaqSidecar {
     orig_dst: OrigDstAddr,
+    addr: Addr,
     routes: watch::Receiver<opaq::Routes>,
 }
 
@@ -58,11 +63,50 @@ impl Outbound<()> {
         T: svc::Param<OrigDstAddr>,
         T: Clone + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
         I: Debug + Unpin + Send + Sync + 'static,
         // Endpoint resolver.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
         R::Resolution: Unpin,
+    {
+        self.mk_sidecar_with(
+            self.resolver(profiles, policies),
+            |target: &T| {
+                let OrigDstAddr(addr) = target.param();
+                Addr::Socket(addr)
+            },
+            resolve,
+        )
+    }
+
+    /// Like [`Outbound::mk_sidecar`], but discovers each target with
+    /// `discover` and routes it by the logical address obtained with
+    /// `logical`.
+    pub(crate) fn mk_sidecar_with<T, K, I, R, D>(
+        &self,
+        discover: D,
+        logical: fn(&T) -> Addr,
+        resolve: R,
+    ) -> svc::ArcNewTcp<T, I>
+    where
+        // Target describing an outbound connection.
+        T: svc::Param<K>,
+        T: svc::Param<OrigDstAddr>,
+        T: Clone + Send + Sync + 'static,
+        // Discovery key.
+        K: Clone + Debug + Eq + Hash + Send + Sync + 'static,
+        // Server-side socket.
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Splice,
+        I: Debug + Unpin + Send + Sync + 'static,
+        // Endpoint resolver.
+        R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
+        R::Resolution: Unpin,
+        // Discovery client.
+        D: svc::Service<K, Error = Error>,
+        D: Clone + Send + Sync + 'static,
+        D::Response: Clone + Send + Sync + 'static,
+        D::Future: Send + Unpin + 'static,
+        Discovery<T>: From<(D::Response, T)>,
     {
         let opaq = self.clone().with_stack(
             self.to_tcp_connect()
@@ -93,9 +137,13 @@ impl Outbound<()> {
             .push_protocol(http.into_inner(), tls.into_inner())
             // Use a dedicated target type to bind discovery results to the
             // outbound sidecar stack configuration.
-            .map_stack(move |_, _, stk| stk.push_map_target(Sidecar::from))
+            .map_stack(move |_, _, stk| {
+                stk.push_map_target(move |parent: Discovery<T>| {
+                    Sidecar::new(logical(&parent), parent)
+                })
+            })
             // Access cached discovery information.
-            .push_discover(self.resolver(profiles, policies))
+            .push_discover(discover)
             // Instrument server-side connections for telemetry.
             .push_tcp_instrument(|t: &T| {
                 let addr: OrigDstAddr = t.param();
@@ -107,16 +155,17 @@ impl Outbound<()> {
 
 // === impl Sidecar ===
 
-impl<T> From<Discovery<T>> for Sidecar
-where
-    T: svc::Param<OrigDstAddr>,
-{
-    fn from(parent: Discovery<T>) -> Self {
+impl Sidecar {
+    fn new<T>(addr: Addr, parent: Discovery<T>) -> Self
+    where
+        T: svc::Param<OrigDstAddr>,
+    {
         use svc::Param;
         Self {
             policy: parent.param(),
             profile: parent.param(),
             orig_dst: (*parent).param(),
+            addr,
         }
     }
 }
@@ -160,7 +209,7 @@ impl svc::Param<Protocol> for Sidecar {
 
 impl PartialEq for Sidecar {
     fn eq(&self, other: &Self) -> bool {
-        self.orig_dst == other.orig_dst
+        self.orig_dst == other.orig_dst && self.addr == other.addr
     }
 }
 
@@ -169,6 +218,7 @@ impl Eq for Sidecar {}
 impl std::hash::Hash for Sidecar {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.orig_dst.hash(state);
+        self.addr.hash(state);
     }
 }
 
@@ -177,6 +227,7 @@ impl std::hash::Hash for Sidecar {
 impl From<protocol::Http<Sidecar>> for HttpSidecar {
     fn from(parent: protocol::Http<Sidecar>) -> Self {
         let orig_dst = parent.orig_dst;
+        let addr = parent.addr.clone();
         let version = svc::Param::<http::Version>::param(&parent);
         let mut policy = parent.policy.clone();
 
@@ -192,6 +243,7 @@ impl From<protocol::Http<Sidecar>> for HttpSidecar {
                     });
                 return HttpSidecar {
                     orig_dst,
+                    addr: parent.addr.clone(),
                     version,
                     routes,
                 };
@@ -199,13 +251,17 @@ impl From<protocol::Http<Sidecar>> for HttpSidecar {
         }
 
         tracing::debug!("Using ClientPolicy routes");
-        let init = Self::mk_policy_routes(orig_dst, version, &policy.borrow_and_update())
+        let init = Self::mk_policy_routes(addr.clone(), version, &policy.borrow_and_update())
             .expect("initial policy must not be opaque");
-        let routes = http::spawn_routes(policy, init, move |policy: &policy::ClientPolicy| {
-            Self::mk_policy_routes(orig_dst, version, policy)
-        });
+        let routes = {
+            let addr = addr.clone();
+            http::spawn_routes(policy, init, move |policy: &policy::ClientPolicy| {
+                Self::mk_policy_routes(addr.clone(), version, policy)
+            })
+        };
         HttpSidecar {
             orig_dst,
+            addr,
             version,
             routes,
         }
@@ -214,7 +270,7 @@ impl From<protocol::Http<Sidecar>> for HttpSidecar {
 
 impl HttpSidecar {
     fn mk_policy_routes(
-        OrigDstAddr(orig_dst): OrigDstAddr,
+        addr: Addr,
         version: http::Version,
         policy: &policy::ClientPolicy,
     ) -> Option<http::Routes> {
@@ -248,7 +304,7 @@ impl HttpSidecar {
             }) => {
                 return Some(http::Routes::Policy(http::policy::Params::Grpc(
                     http::policy::GrpcParams {
-                        addr: orig_dst.into(),
+                        addr,
                         meta: parent_ref,
                         backends: policy.backends.clone(),
                         routes: routes.clone(),
@@ -266,7 +322,7 @@ impl HttpSidecar {
 
         Some(http::Routes::Policy(http::policy::Params::Http(
             http::policy::HttpParams {
-                addr: orig_dst.into(),
+                addr,
                 meta: parent_ref,
                 routes,
                 backends: policy.backends.clone(),
@@ -318,7 +374,7 @@ impl svc::Param<http::normalize_uri::DefaultAuthority> for HttpSidecar {
 
 impl std::cmp::PartialEq for HttpSidecar {
     fn eq(&self, other: &Self) -> bool {
-        self.orig_dst == other.orig_dst && self.version == other.version
+        self.orig_dst == other.orig_dst && self.addr == other.addr && self.version == other.version
     }
 }
 
@@ -327,6 +383,7 @@ impl std::cmp::Eq for HttpSidecar {}
 impl std::hash::Hash for HttpSidecar {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.orig_dst.hash(state);
+        self.addr.hash(state);
         self.version.hash(state);
     }
 }
@@ -336,22 +393,27 @@ impl std::hash::Hash for HttpSidecar {
 impl From<Sidecar> for TlsSidecar {
     fn from(parent: Sidecar) -> Self {
         let orig_dst = parent.orig_dst;
+        let addr = parent.addr.clone();
         let mut policy = parent.policy.clone();
 
-        let init = Self::mk_policy_routes(orig_dst, &policy.borrow_and_update())
+        let init = Self::mk_policy_routes(addr.clone(), &policy.borrow_and_update())
             .expect("initial policy must be tls");
-        let routes = tls::spawn_routes(policy, init, move |policy: &policy::ClientPolicy| {
-            Self::mk_policy_routes(orig_dst, policy)
-        });
-        TlsSidecar { orig_dst, routes }
+        let routes = {
+            let addr = addr.clone();
+            tls::spawn_routes(policy, init, move |policy: &policy::ClientPolicy| {
+                Self::mk_policy_routes(addr.clone(), policy)
+            })
+        };
+        TlsSidecar {
+            orig_dst,
+            addr,
+            routes,
+        }
     }
 }
 
 impl TlsSidecar {
-    fn mk_policy_routes(
-        OrigDstAddr(orig_dst): OrigDstAddr,
-        policy: &policy::ClientPolicy,
-    ) -> Option<tls::Routes> {
+    fn mk_policy_routes(addr: Addr, policy: &policy::ClientPolicy) -> Option<tls::Routes> {
         let parent_ref = ParentRef(policy.parent.clone());
         let routes = match policy.protocol {
             policy::Protocol::Tls(policy::tls::Tls { ref routes }) => routes.clone(),
@@ -362,7 +424,7 @@ impl TlsSidecar {
         };
 
         Some(tls::Routes {
-            addr: orig_dst.into(),
+            addr,
             meta: parent_ref,
             routes,
             backends: policy.backends.clone(),
@@ -376,9 +438,15 @@ impl svc::Param<watch::Receiver<tls::Routes>> for TlsSidecar {
     }
 }
 
+impl svc::Param<Option<OrigDstAddr>> for TlsSidecar {
+    fn param(&self) -> Option<OrigDstAddr> {
+        Some(self.orig_dst)
+    }
+}
+
 impl std::cmp::PartialEq for TlsSidecar {
     fn eq(&self, other: &Self) -> bool {
-        self.orig_dst == other.orig_dst
+        self.orig_dst == other.orig_dst && self.addr == other.addr
     }
 }
 
@@ -387,6 +455,7 @@ impl std::cmp::Eq for TlsSidecar {}
 impl std::hash::Hash for TlsSidecar {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.orig_dst.hash(state);
+        self.addr.hash(state);
     }
 }
 
@@ -394,13 +463,11 @@ impl std::hash::Hash for TlsSidecar {
 
 impl From<Sidecar> for OpaqSidecar {
     fn from(parent: Sidecar) -> Self {
-        let routes = opaq::routes_from_discovery(
-            Addr::Socket(parent.orig_dst.into()),
-            parent.profile,
-            parent.policy,
-        );
+        let routes =
+            opaq::routes_from_discovery(parent.addr.clone(), parent.profile, parent.policy);
         OpaqSidecar {
             orig_dst: parent.orig_dst,
+            addr: parent.addr,
             routes,
         }
     }
@@ -412,9 +479,15 @@ impl svc::Param<watch::Receiver<opaq::Routes>> for OpaqSidecar {
     }
 }
 
+impl svc::Param<Option<OrigDstAddr>> for OpaqSidecar {
+    fn param(&self) -> Option<OrigDstAddr> {
+        Some(self.orig_dst)
+    }
+}
+
 impl std::cmp::PartialEq for OpaqSidecar {
     fn eq(&self, other: &Self) -> bool {
-        self.orig_dst == other.orig_dst
+        self.orig_dst == other.orig_dst && self.addr == other.addr
     }
 }
 
@@ -423,5 +496,6 @@ impl std::cmp::Eq for OpaqSidecar {}
 impl std::hash::Hash for OpaqSidecar {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.orig_dst.hash(state);
+        self.addr.hash(state);
     }
 }
@@ -1,4 +1,7 @@
-pub use self::connect::Connect;
+pub use self::{
+    client::{FromClient, NewConnectFromClient},
+    connect::{Connect, ForwardName},
+};
 use crate::Outbound;
 use linkerd_app_core::{
     io, svc,
@@ -6,6 +9,7 @@ use linkerd_app_core::{
     Error, Infallible,
 };
 
+mod client;
 mod connect;
 mod endpoint;
 pub mod tagged_transport;
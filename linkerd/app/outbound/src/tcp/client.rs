@@ -0,0 +1,177 @@
+use super::{tagged_transport::PortOverride, ForwardName};
+use crate::{policy, zone::TcpZoneLabels};
+use linkerd_app_core::{
+    proxy::http,
+    proxy_protocol, svc, tls,
+    transport::{self, addrs::*},
+    transport_header::SessionProtocol,
+};
+use std::{
+    net::SocketAddr,
+    task::{Context, Poll},
+};
+
+/// A target for a connection that is established on behalf of a single
+/// client, so that the client's address may be conveyed to the endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FromClient<T> {
+    client_addr: Remote<ClientAddr>,
+    parent: T,
+}
+
+/// Builds services that establish a connection to the target for each client
+/// address with which they are called.
+#[derive(Clone, Debug)]
+pub struct NewConnectFromClient<C> {
+    inner: C,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConnectFromClient<T, C> {
+    target: T,
+    inner: C,
+}
+
+// === impl NewConnectFromClient ===
+
+impl<C> NewConnectFromClient<C> {
+    pub fn layer() -> impl svc::layer::Layer<C, Service = Self> + Clone {
+        svc::layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, C: Clone> svc::NewService<T> for NewConnectFromClient<C> {
+    type Service = ConnectFromClient<T, C>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        ConnectFromClient {
+            target,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+// === impl ConnectFromClient ===
+
+impl<T, C> svc::Service<SocketAddr> for ConnectFromClient<T, C>
+where
+    T: Clone,
+    C: svc::Service<FromClient<T>>,
+{
+    type Response = C::Response;
+    type Error = C::Error;
+    type Future = C::Future;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), C::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, client_addr: SocketAddr) -> C::Future {
+        self.inner.call(FromClient {
+            client_addr: Remote(ClientAddr(client_addr)),
+            parent: self.target.clone(),
+        })
+    }
+}
+
+// === impl FromClient ===
+
+impl<T> svc::Param<Option<Remote<ClientAddr>>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Option<Remote<ClientAddr>> {
+        Some(self.client_addr)
+    }
+}
+
+impl<T: svc::Param<Option<OrigDstAddr>>> svc::Param<Option<OrigDstAddr>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Remote<ServerAddr>>> svc::Param<Remote<ServerAddr>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Remote<ServerAddr> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<tls::ConditionalClientTls>> svc::Param<tls::ConditionalClientTls>
+    for FromClient<T>
+{
+    #[inline]
+    fn param(&self) -> tls::ConditionalClientTls {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<PortOverride>>> svc::Param<Option<PortOverride>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Option<PortOverride> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<http::AuthorityOverride>>> svc::Param<Option<http::AuthorityOverride>>
+    for FromClient<T>
+{
+    #[inline]
+    fn param(&self) -> Option<http::AuthorityOverride> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<SessionProtocol>>> svc::Param<Option<SessionProtocol>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Option<SessionProtocol> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<proxy_protocol::Version>>> svc::Param<Option<proxy_protocol::Version>>
+    for FromClient<T>
+{
+    #[inline]
+    fn param(&self) -> Option<proxy_protocol::Version> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<policy::UpstreamProxy>>> svc::Param<Option<policy::UpstreamProxy>>
+    for FromClient<T>
+{
+    #[inline]
+    fn param(&self) -> Option<policy::UpstreamProxy> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<ForwardName>>> svc::Param<Option<ForwardName>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Option<ForwardName> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<Option<policy::Marking>>> svc::Param<Option<policy::Marking>> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> Option<policy::Marking> {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<transport::labels::Key>> svc::Param<transport::labels::Key> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> transport::labels::Key {
+        self.parent.param()
+    }
+}
+
+impl<T: svc::Param<TcpZoneLabels>> svc::Param<TcpZoneLabels> for FromClient<T> {
+    #[inline]
+    fn param(&self) -> TcpZoneLabels {
+        self.parent.param()
+    }
+}
@@ -1,30 +1,82 @@
-use crate::Outbound;
-use futures::future;
+use crate::{policy, Outbound};
+use futures::{future, prelude::*};
 use linkerd_app_core::{
-    io, svc, tls,
-    transport::{addrs::*, ConnectTcp},
+    forward_proxy, io,
+    proxy_protocol::{self, Addrs, ProxyHeader},
+    svc, tls,
+    transport::{addrs::*, ConnectMarked},
+    NameAddr,
 };
-use std::task::{Context, Poll};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tracing::debug;
 
 #[derive(Clone, Debug)]
 pub struct Connect {
     pub addr: Remote<ServerAddr>,
     pub tls: tls::ConditionalClientTls,
+
+    /// The client on whose behalf the connection is established, if the
+    /// connection is not shared by multiple clients.
+    pub client_addr: Option<Remote<ClientAddr>>,
+
+    /// The address to which the client originally connected, if known.
+    pub orig_dst_addr: Option<OrigDstAddr>,
+
+    pub proxy_protocol: Option<proxy_protocol::Version>,
+    pub upstream_proxy: Option<policy::UpstreamProxy>,
+    pub forward_name: Option<ForwardName>,
+    pub marking: Option<policy::Marking>,
 }
 
+/// The logical name of a service whose traffic is forwarded to a single
+/// endpoint, rather than balanced over discovered endpoints.
+///
+/// Connections that are tunneled through an upstream proxy request this name
+/// rather than the endpoint's address, so that the upstream proxy may resolve
+/// and authorize the name itself.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ForwardName(pub NameAddr);
+
 /// Prevents outbound connections on the loopback interface, unless the
 /// `allow-loopback` feature is enabled.
 #[derive(Clone, Debug)]
 pub struct PreventLoopback<S>(S);
 
+/// Prefaces connections to endpoints that are not part of the mesh with a
+/// PROXY protocol header, if the target is configured to send one.
+///
+/// The header conveys the address of the client on whose behalf the
+/// connection is established and the address to which that client originally
+/// connected. Connections that are shared by multiple clients (i.e. pooled
+/// HTTP connections) instead convey the connection's local address, and the
+/// endpoint's address is used when the original destination is not known.
+#[derive(Clone, Debug)]
+pub struct SendProxyHeader<S>(S);
+
+/// Tunnels connections through an upstream HTTP proxy with a `CONNECT`
+/// request, if the target is configured with one.
+///
+/// The tunnel is established before any other bytes are written to the
+/// connection, so PROXY protocol headers, TLS handshakes, and opaque traffic
+/// are all sent to the endpoint through the tunnel. The tunnel targets the
+/// connection's [`ForwardName`], if it has one, and otherwise the endpoint's
+/// address.
+#[derive(Clone, Debug)]
+pub struct ConnectUpstreamProxy<S>(S);
+
 // === impl Outbound ===
 
 impl Outbound<()> {
-    pub fn to_tcp_connect(&self) -> Outbound<PreventLoopback<ConnectTcp>> {
-        let connect = PreventLoopback(ConnectTcp::new(
+    pub fn to_tcp_connect(
+        &self,
+    ) -> Outbound<PreventLoopback<SendProxyHeader<ConnectUpstreamProxy<ConnectMarked>>>> {
+        let connect = PreventLoopback(SendProxyHeader(ConnectUpstreamProxy(ConnectMarked::new(
             self.config.proxy.connect.keepalive,
             self.config.proxy.connect.user_timeout,
-        ));
+        ))));
         self.clone().with_stack(connect)
     }
 }
@@ -75,6 +127,119 @@ where
     }
 }
 
+// === impl SendProxyHeader ===
+
+impl<T, S, I> svc::Service<T> for SendProxyHeader<S>
+where
+    T: svc::Param<Remote<ServerAddr>>
+        + svc::Param<tls::ConditionalClientTls>
+        + svc::Param<Option<proxy_protocol::Version>>
+        + svc::Param<Option<Remote<ClientAddr>>>
+        + svc::Param<Option<OrigDstAddr>>,
+    S: svc::Service<T, Response = (I, Local<ClientAddr>), Error = io::Error>,
+    S::Future: Send + 'static,
+    I: io::AsyncWrite + Send + Unpin + 'static,
+{
+    type Response = S::Response;
+    type Error = io::Error;
+    type Future = future::Either<
+        S::Future,
+        Pin<Box<dyn Future<Output = io::Result<S::Response>> + Send + 'static>>,
+    >;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, ep: T) -> Self::Future {
+        let Some(version) = ep.param() else {
+            return future::Either::Left(self.0.call(ep));
+        };
+
+        // Meshed endpoints expect the connection to begin with a TLS handshake.
+        if let tls::ConditionalClientTls::Some(_) = ep.param() {
+            debug!("Not sending a PROXY protocol header to a meshed endpoint");
+            return future::Either::Left(self.0.call(ep));
+        }
+
+        let client: Option<Remote<ClientAddr>> = ep.param();
+        let destination = match ep.param() {
+            Some(OrigDstAddr(addr)) => addr,
+            None => {
+                let Remote(ServerAddr(addr)) = ep.param();
+                addr
+            }
+        };
+        let connect = self.0.call(ep);
+        future::Either::Right(Box::pin(async move {
+            let (mut io, local) = connect.await?;
+            let source = match client {
+                Some(Remote(ClientAddr(addr))) => addr,
+                None => {
+                    let Local(ClientAddr(addr)) = local;
+                    addr
+                }
+            };
+            let header = ProxyHeader {
+                version,
+                addrs: Some(Addrs {
+                    source,
+                    destination,
+                }),
+                tlvs: Vec::new(),
+            };
+            let sz = header.write(&mut io).await?;
+            debug!(?version, sz, "Wrote PROXY protocol header");
+            Ok((io, local))
+        }))
+    }
+}
+
+// === impl ConnectUpstreamProxy ===
+
+impl<S, I> svc::Service<Connect> for ConnectUpstreamProxy<S>
+where
+    S: svc::Service<Connect, Response = (I, Local<ClientAddr>), Error = io::Error>,
+    S::Future: Send + 'static,
+    I: io::AsyncRead + io::AsyncWrite + Send + Unpin + 'static,
+{
+    type Response = (io::PrefixedIo<I>, Local<ClientAddr>);
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut ep: Connect) -> Self::Future {
+        let Some(proxy) = ep.upstream_proxy.take() else {
+            return Box::pin(self.0.call(ep).map_ok(|(io, local)| (io.into(), local)));
+        };
+
+        let target = match ep.forward_name.take() {
+            Some(ForwardName(name)) => forward_proxy::Addr::Name(name),
+            None => forward_proxy::Addr::Socket(ep.addr.into()),
+        };
+        let connect = self.0.call(Connect {
+            addr: Remote(ServerAddr(proxy.addr)),
+            ..ep
+        });
+        Box::pin(async move {
+            let (mut io, local) = connect.await?;
+            let prefix = forward_proxy::connect(&mut io, &target, proxy.authorization.as_ref())
+                .await
+                .map_err(|error| match error {
+                    forward_proxy::Error::Io(e) => e,
+                    error => io::Error::new(io::ErrorKind::ConnectionRefused, error),
+                })?;
+            debug!(proxy.addr = %proxy.addr, "Tunneled through upstream proxy");
+            Ok((io::PrefixedIo::new(prefix, io), local))
+        })
+    }
+}
+
 // === impl Connect ===
 
 impl svc::Param<Remote<ServerAddr>> for Connect {
@@ -88,3 +253,206 @@ impl svc::Param<tls::ConditionalClientTls> for Connect {
         self.tls.clone()
     }
 }
+
+impl svc::Param<Option<proxy_protocol::Version>> for Connect {
+    fn param(&self) -> Option<proxy_protocol::Version> {
+        self.proxy_protocol
+    }
+}
+
+impl svc::Param<Option<Remote<ClientAddr>>> for Connect {
+    fn param(&self) -> Option<Remote<ClientAddr>> {
+        self.client_addr
+    }
+}
+
+impl svc::Param<Option<OrigDstAddr>> for Connect {
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.orig_dst_addr
+    }
+}
+
+impl svc::Param<Option<policy::UpstreamProxy>> for Connect {
+    fn param(&self) -> Option<policy::UpstreamProxy> {
+        self.upstream_proxy.clone()
+    }
+}
+
+impl svc::Param<Option<ForwardName>> for Connect {
+    fn param(&self) -> Option<ForwardName> {
+        self.forward_name.clone()
+    }
+}
+
+impl svc::Param<Option<policy::Marking>> for Connect {
+    fn param(&self) -> Option<policy::Marking> {
+        self.marking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::util::{service_fn, ServiceExt};
+
+    fn target(
+        proxy_protocol: Option<proxy_protocol::Version>,
+        tls: tls::ConditionalClientTls,
+    ) -> Connect {
+        Connect {
+            addr: Remote(ServerAddr(([192, 0, 2, 2], 8080).into())),
+            tls,
+            client_addr: None,
+            orig_dst_addr: None,
+            proxy_protocol,
+            upstream_proxy: None,
+            forward_name: None,
+            marking: None,
+        }
+    }
+
+    fn connect(
+        expected: &'static [u8],
+    ) -> impl FnMut(Connect) -> future::Ready<io::Result<(tokio_test::io::Mock, Local<ClientAddr>)>>
+    {
+        move |_| {
+            let mut io = tokio_test::io::Builder::new();
+            if !expected.is_empty() {
+                io.write(expected);
+            }
+            let local = Local(ClientAddr(([192, 0, 2, 1], 40000).into()));
+            future::ok((io.build(), local))
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn sends_header() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let svc = SendProxyHeader(service_fn(connect(
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 40000 8080\r\n",
+        )));
+        svc.oneshot(target(
+            Some(proxy_protocol::Version::V1),
+            tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery),
+        ))
+        .await
+        .expect("must connect");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn sends_client_header() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let mut ep = target(
+            Some(proxy_protocol::Version::V1),
+            tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery),
+        );
+        ep.client_addr = Some(Remote(ClientAddr(([192, 0, 2, 10], 50000).into())));
+        ep.orig_dst_addr = Some(OrigDstAddr(([198, 51, 100, 1], 80).into()));
+
+        let svc = SendProxyHeader(service_fn(connect(
+            b"PROXY TCP4 192.0.2.10 198.51.100.1 50000 80\r\n",
+        )));
+        svc.oneshot(ep).await.expect("must connect");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn no_header() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let svc = SendProxyHeader(service_fn(connect(b"")));
+        svc.oneshot(target(
+            None,
+            tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery),
+        ))
+        .await
+        .expect("must connect");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn tunnels_through_upstream_proxy() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let mut ep = target(
+            None,
+            tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery),
+        );
+        ep.upstream_proxy = Some(policy::UpstreamProxy {
+            addr: ([192, 0, 2, 3], 3128).into(),
+            authorization: Some(::http::HeaderValue::from_static("Basic Zm9vOmJhcg==")),
+        });
+
+        let svc = ConnectUpstreamProxy(service_fn(|ep: Connect| {
+            assert_eq!(ep.addr, Remote(ServerAddr(([192, 0, 2, 3], 3128).into())));
+            let io = tokio_test::io::Builder::new()
+                .write(b"CONNECT 192.0.2.2:8080 HTTP/1.1\r\nhost: 192.0.2.2:8080\r\n")
+                .write(b"proxy-authorization: Basic Zm9vOmJhcg==\r\n\r\n")
+                .read(b"HTTP/1.1 200 Connection established\r\n\r\nhello")
+                .build();
+            let local = Local(ClientAddr(([192, 0, 2, 1], 40000).into()));
+            future::ok::<_, io::Error>((io, local))
+        }));
+        let (mut io, _) = svc.oneshot(ep).await.expect("must connect");
+
+        let mut buf = [0u8; 5];
+        io::AsyncReadExt::read_exact(&mut io, &mut buf)
+            .await
+            .expect("must read");
+        assert_eq!(&buf, b"hello");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn tunnels_forward_name_through_upstream_proxy() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let mut ep = target(
+            None,
+            tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery),
+        );
+        ep.upstream_proxy = Some(policy::UpstreamProxy {
+            addr: ([192, 0, 2, 3], 3128).into(),
+            authorization: None,
+        });
+        ep.forward_name = Some(ForwardName(
+            "api.example.com:8080".parse().expect("must parse"),
+        ));
+
+        let svc = ConnectUpstreamProxy(service_fn(|_: Connect| {
+            let io = tokio_test::io::Builder::new()
+                .write(
+                    b"CONNECT api.example.com:8080 HTTP/1.1\r\nhost: api.example.com:8080\r\n\r\n",
+                )
+                .read(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .build();
+            let local = Local(ClientAddr(([192, 0, 2, 1], 40000).into()));
+            future::ok::<_, io::Error>((io, local))
+        }));
+        svc.oneshot(ep).await.expect("must connect");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn upstream_proxy_refuses() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let mut ep = target(
+            None,
+            tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery),
+        );
+        ep.upstream_proxy = Some(policy::UpstreamProxy {
+            addr: ([192, 0, 2, 3], 3128).into(),
+            authorization: None,
+        });
+
+        let svc = ConnectUpstreamProxy(service_fn(|_: Connect| {
+            let io = tokio_test::io::Builder::new()
+                .write(b"CONNECT 192.0.2.2:8080 HTTP/1.1\r\nhost: 192.0.2.2:8080\r\n\r\n")
+                .read(b"HTTP/1.1 403 Forbidden\r\ncontent-length: 0\r\n\r\n")
+                .build();
+            let local = Local(ClientAddr(([192, 0, 2, 1], 40000).into()));
+            future::ok::<_, io::Error>((io, local))
+        }));
+        let error = svc.oneshot(ep).await.expect_err("must fail");
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
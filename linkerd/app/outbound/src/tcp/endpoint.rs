@@ -1,6 +1,6 @@
 use super::{tagged_transport::TaggedTransport, *};
-use crate::{zone::TcpZoneLabels, ConnectMeta};
-use linkerd_app_core::{proxy::http, tls, transport_header::SessionProtocol};
+use crate::{policy, zone::TcpZoneLabels, ConnectMeta};
+use linkerd_app_core::{proxy::http, proxy_protocol, tls, transport_header::SessionProtocol};
 
 impl<C> Outbound<C> {
     pub fn push_tcp_endpoint<T>(
@@ -8,7 +8,7 @@ impl<C> Outbound<C> {
     ) -> Outbound<
         impl svc::MakeConnection<
                 T,
-                Connection = impl io::AsyncRead + io::AsyncWrite + Send + Unpin,
+                Connection = impl io::AsyncRead + io::AsyncWrite + io::Splice + Send + Unpin,
                 Metadata = ConnectMeta,
                 Error = Error,
                 Future = impl Send,
@@ -21,12 +21,18 @@ impl<C> Outbound<C> {
         T: svc::Param<Option<tagged_transport::PortOverride>>,
         T: svc::Param<Option<http::AuthorityOverride>>,
         T: svc::Param<Option<SessionProtocol>>,
+        T: svc::Param<Option<proxy_protocol::Version>>,
+        T: svc::Param<Option<Remote<ClientAddr>>>,
+        T: svc::Param<Option<OrigDstAddr>>,
+        T: svc::Param<Option<policy::UpstreamProxy>>,
+        T: svc::Param<Option<ForwardName>>,
+        T: svc::Param<Option<policy::Marking>>,
         T: svc::Param<transport::labels::Key>,
         T: svc::Param<TcpZoneLabels>,
         // Connector stack.
         C: svc::MakeConnection<Connect, Metadata = Local<ClientAddr>, Error = io::Error>,
         C: Clone + Send + 'static,
-        C::Connection: Send + Unpin,
+        C::Connection: io::Splice + Send + Unpin,
         C::Metadata: Send + Unpin,
         C::Future: Send + 'static,
     {
@@ -1,10 +1,14 @@
-use crate::{tcp::Connect, ConnectMeta};
+use crate::{
+    policy,
+    tcp::{Connect, ForwardName},
+    ConnectMeta,
+};
 use futures::prelude::*;
 use linkerd_app_core::{
     dns,
     proxy::http,
-    svc, tls,
-    transport::{Remote, ServerAddr},
+    proxy_protocol, svc, tls,
+    transport::{ClientAddr, OrigDstAddr, Remote, ServerAddr},
     transport_header::{SessionProtocol, TransportHeader, PROTOCOL},
     Conditional, Error, Result,
 };
@@ -48,7 +52,13 @@ where
         + svc::Param<Remote<ServerAddr>>
         + svc::Param<Option<PortOverride>>
         + svc::Param<Option<http::AuthorityOverride>>
-        + svc::Param<Option<SessionProtocol>>,
+        + svc::Param<Option<SessionProtocol>>
+        + svc::Param<Option<proxy_protocol::Version>>
+        + svc::Param<Option<Remote<ClientAddr>>>
+        + svc::Param<Option<OrigDstAddr>>
+        + svc::Param<Option<policy::UpstreamProxy>>
+        + svc::Param<Option<ForwardName>>
+        + svc::Param<Option<policy::Marking>>,
     S: svc::MakeConnection<Connect, Metadata = ConnectMeta> + Send + 'static,
     S::Connection: Send + Unpin,
     S::Future: Send + 'static,
@@ -70,6 +80,12 @@ where
             let target = Connect {
                 addr: ep.param(),
                 tls,
+                client_addr: ep.param(),
+                orig_dst_addr: ep.param(),
+                proxy_protocol: ep.param(),
+                upstream_proxy: ep.param(),
+                forward_name: ep.param(),
+                marking: ep.param(),
             };
             return Box::pin(self.inner.connect(target).err_into::<Error>());
         }
@@ -112,6 +128,12 @@ where
         let connect = self.inner.connect(Connect {
             addr: Remote(ServerAddr((addr.ip(), connect_port).into())),
             tls,
+            client_addr: ep.param(),
+            orig_dst_addr: ep.param(),
+            proxy_protocol: ep.param(),
+            upstream_proxy: ep.param(),
+            forward_name: ep.param(),
+            marking: ep.param(),
         });
         Box::pin(async move {
             let (mut io, meta) = connect.await.map_err(Into::into)?;
@@ -141,7 +163,7 @@ mod test {
     use super::*;
     use linkerd_app_core::{
         io::{self, AsyncWriteExt},
-        transport::{ClientAddr, Local},
+        transport::Local,
         transport_header,
     };
     use tower::util::{service_fn, ServiceExt};
@@ -198,6 +220,42 @@ mod test {
         }
     }
 
+    impl svc::Param<Option<proxy_protocol::Version>> for Endpoint {
+        fn param(&self) -> Option<proxy_protocol::Version> {
+            None
+        }
+    }
+
+    impl svc::Param<Option<Remote<ClientAddr>>> for Endpoint {
+        fn param(&self) -> Option<Remote<ClientAddr>> {
+            None
+        }
+    }
+
+    impl svc::Param<Option<OrigDstAddr>> for Endpoint {
+        fn param(&self) -> Option<OrigDstAddr> {
+            None
+        }
+    }
+
+    impl svc::Param<Option<policy::UpstreamProxy>> for Endpoint {
+        fn param(&self) -> Option<policy::UpstreamProxy> {
+            None
+        }
+    }
+
+    impl svc::Param<Option<ForwardName>> for Endpoint {
+        fn param(&self) -> Option<ForwardName> {
+            None
+        }
+    }
+
+    impl svc::Param<Option<policy::Marking>> for Endpoint {
+        fn param(&self) -> Option<policy::Marking> {
+            None
+        }
+    }
+
     fn expect_header(
         header: TransportHeader,
     ) -> impl Fn(Connect) -> futures::future::Ready<Result<(tokio_test::io::Mock, ConnectMeta), io::Error>>
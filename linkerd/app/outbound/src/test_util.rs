@@ -7,7 +7,7 @@ use linkerd_app_core::{
         http::{h1, h2},
         tap,
     },
-    transport::{DualListenAddr, Keepalive, UserTimeout},
+    transport::{DualListenAddr, Keepalive, ListenShards, UserTimeout},
     IpMatch, IpNet, ProxyRuntime,
 };
 pub use linkerd_app_test as support;
@@ -27,6 +27,7 @@ pub(crate) fn default_config() -> Config {
                 addr: DualListenAddr(([0, 0, 0, 0], 0).into(), None),
                 keepalive: Keepalive(None),
                 user_timeout: UserTimeout(None),
+                shards: ListenShards::default(),
                 http2: h2::ServerParams::default(),
             },
             connect: config::ConnectConfig {
@@ -52,6 +53,10 @@ pub(crate) fn default_config() -> Config {
         discovery_idle_timeout: Duration::from_secs(60),
         tcp_connection_queue: buffer,
         http_request_queue: buffer,
+        http_balancer_codel: None,
+        http_adaptive_concurrency: None,
+        forward_proxy: None,
+        policy_overrides: Default::default(),
     }
 }
 
@@ -7,7 +7,7 @@ use linkerd_app_core::{
         core::Resolve,
     },
     svc,
-    tls::{NewDetectRequiredSni, ServerName},
+    tls::{ClientHello, NewDetectRequiredSni, ServerName},
     transport::addrs::*,
     Error,
 };
@@ -15,6 +15,7 @@ use std::{fmt::Debug, hash::Hash};
 use tokio::sync::watch;
 
 mod concrete;
+mod filters;
 mod logical;
 
 pub use self::logical::{Concrete, Routes};
@@ -78,8 +79,9 @@ impl<C> Outbound<C> {
         // Tls target
         T: Clone + Debug + PartialEq + Eq + Hash + Send + Sync + 'static,
         T: svc::Param<watch::Receiver<Routes>>,
+        T: svc::Param<Option<OrigDstAddr>>,
         // Server-side connection
-        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Peek,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Peek + io::Splice,
         I: Debug + Send + Sync + Unpin + 'static,
         // Endpoint discovery
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -87,7 +89,7 @@ impl<C> Outbound<C> {
         // TCP endpoint stack.
         C: svc::MakeConnection<tcp::Connect, Metadata = Local<ClientAddr>, Error = io::Error>,
         C: Clone + Send + Sync + Unpin + 'static,
-        C::Connection: Send + Unpin,
+        C::Connection: io::Splice + Send + Unpin,
         C::Future: Send + Unpin,
     {
         self.push_tcp_endpoint()
@@ -97,7 +99,11 @@ impl<C> Outbound<C> {
                 stk.push_new_idle_cached(config.discovery_idle_timeout)
                     // Use a dedicated target type to configure parameters for
                     // the TLS stack. It also helps narrow the cache key.
-                    .push_map_target(|(sni, parent): (ServerName, T)| Tls { sni, parent })
+                    .push_map_target(|(hello, parent): (ClientHello, T)| Tls {
+                        sni: hello.sni,
+                        parent,
+                    })
+                    .push(filters::NewSession::layer())
                     .push(NewDetectRequiredSni::layer(
                         config.proxy.detect_protocol_timeout,
                     ))
@@ -123,6 +129,15 @@ where
     }
 }
 
+impl<T> svc::Param<Option<OrigDstAddr>> for Tls<T>
+where
+    T: svc::Param<Option<OrigDstAddr>>,
+{
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.parent.param()
+    }
+}
+
 // === impl TlsMetrics ===
 
 impl TlsMetrics {
@@ -0,0 +1,332 @@
+use futures::{future, FutureExt, TryFutureExt};
+use linkerd_app_core::{io, svc, tls::ClientHello, Error, Result};
+use linkerd_proxy_client_policy::tls;
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time,
+};
+
+/// A middleware that annotates each TLS connection with the metadata from its
+/// ClientHello.
+///
+/// This is applied ahead of the cached logical stack, so that the offered ALPN
+/// protocols do not become part of that stack's cache key. Routes are selected
+/// and their filters applied with this metadata.
+#[derive(Clone, Debug)]
+pub struct NewSession<N> {
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct SetSession<S> {
+    session: tls::SessionInfo,
+    inner: S,
+}
+
+/// A server-side connection along with the metadata from its ClientHello.
+#[derive(Debug)]
+pub struct Session<I> {
+    info: tls::SessionInfo,
+    io: I,
+}
+
+/// A middleware that enforces a route's filters on each TLS connection.
+///
+/// Filters are evaluated against the ClientHello metadata, so connections may
+/// be rejected without terminating TLS.
+#[derive(Clone, Debug)]
+pub struct NewApplyFilters<N> {
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct ApplyFilters<S> {
+    filters: Arc<[tls::Filter]>,
+    inner: S,
+}
+
+pub mod errors {
+    use super::*;
+    use linkerd_app_core::tls::ServerName;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("TLS session to {sni} denied by route filter")]
+    pub struct TlsRouteSniDenied {
+        pub sni: ServerName,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("TLS session to {sni} does not offer an allowed ALPN protocol")]
+    pub struct TlsRouteAlpnNotAllowed {
+        pub sni: ServerName,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("TLS session exceeded its maximum lifetime of {max_duration:?}")]
+    pub struct TlsRouteSessionExpired {
+        pub max_duration: time::Duration,
+    }
+}
+
+/// A fatal TLS alert that is sent to clients whose sessions are rejected.
+///
+/// See https://www.rfc-editor.org/rfc/rfc8446#section-6.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum Alert {
+    UnrecognizedName = 112,
+    NoApplicationProtocol = 120,
+}
+
+// === impl NewSession ===
+
+impl<N> NewSession<N> {
+    pub fn layer() -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, N> svc::NewService<(ClientHello, T)> for NewSession<N>
+where
+    N: svc::NewService<(ClientHello, T)>,
+{
+    type Service = SetSession<N::Service>;
+
+    fn new_service(&self, (hello, parent): (ClientHello, T)) -> Self::Service {
+        let session = tls::SessionInfo {
+            sni: hello.sni.clone(),
+            alpn: hello.alpn.clone(),
+        };
+        let inner = self.inner.new_service((hello, parent));
+        SetSession { session, inner }
+    }
+}
+
+// === impl SetSession ===
+
+impl<I, S> svc::Service<I> for SetSession<S>
+where
+    S: svc::Service<Session<I>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[inline]
+    fn call(&mut self, io: I) -> Self::Future {
+        self.inner.call(Session {
+            info: self.session.clone(),
+            io,
+        })
+    }
+}
+
+// === impl Session ===
+
+impl<I> Session<I> {
+    pub fn info(&self) -> &tls::SessionInfo {
+        &self.info
+    }
+}
+
+// === impl NewApplyFilters ===
+
+impl<N> NewApplyFilters<N> {
+    pub fn layer() -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewApplyFilters<N>
+where
+    T: svc::Param<Arc<[tls::Filter]>>,
+    N: svc::NewService<T>,
+{
+    type Service = ApplyFilters<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let filters = target.param();
+        let inner = self.inner.new_service(target);
+        ApplyFilters { filters, inner }
+    }
+}
+
+// === impl ApplyFilters ===
+
+impl<I, S> svc::Service<Session<I>> for ApplyFilters<S>
+where
+    I: io::AsyncWrite + Send + Unpin + 'static,
+    S: svc::Service<I, Response = ()>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = Error;
+    type Future = future::BoxFuture<'static, Result<()>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, Session { info: session, io }: Session<I>) -> Self::Future {
+        let mut max_duration = None::<time::Duration>;
+        for filter in self.filters.iter() {
+            match filter {
+                tls::Filter::DenySni(deny) => {
+                    if deny.is_denied(&session) {
+                        let error = errors::TlsRouteSniDenied {
+                            sni: session.sni.clone(),
+                        };
+                        return reject(io, Alert::UnrecognizedName, error.into()).boxed();
+                    }
+                }
+
+                tls::Filter::AllowAlpn(allow) => {
+                    if !allow.is_allowed(&session) {
+                        let error = errors::TlsRouteAlpnNotAllowed {
+                            sni: session.sni.clone(),
+                        };
+                        return reject(io, Alert::NoApplicationProtocol, error.into()).boxed();
+                    }
+                }
+
+                tls::Filter::SessionLifetime(lifetime) => {
+                    // If multiple lifetimes are configured, the shortest wins.
+                    max_duration = Some(match max_duration {
+                        Some(d) => d.min(lifetime.max_duration),
+                        None => lifetime.max_duration,
+                    });
+                }
+            }
+        }
+
+        let call = self.inner.call(io).err_into::<Error>();
+        match max_duration {
+            None => call.boxed(),
+            Some(max_duration) => tokio::time::timeout(max_duration, call)
+                .map(move |res| match res {
+                    Ok(res) => res,
+                    Err(_) => {
+                        tracing::debug!(?max_duration, "Closing TLS session");
+                        Err(errors::TlsRouteSessionExpired { max_duration }.into())
+                    }
+                })
+                .boxed(),
+        }
+    }
+}
+
+/// Writes a fatal alert record to the client before closing the connection so
+/// that the client observes a TLS failure rather than an unexplained reset.
+async fn reject<I>(mut io: I, alert: Alert, error: Error) -> Result<()>
+where
+    I: io::AsyncWrite + Unpin,
+{
+    use io::AsyncWriteExt;
+
+    tracing::debug!(?alert, %error, "Rejecting TLS session");
+    // An alert record: ContentType::alert, a TLS 1.2 record version, a
+    // two-byte length, AlertLevel::fatal, and the alert description.
+    let record = [21, 0x03, 0x03, 0x00, 0x02, 2, alert as u8];
+    if let Err(error) = io.write_all(&record).await {
+        tracing::debug!(%error, "Failed to write TLS alert");
+    } else {
+        let _ = io.shutdown().await;
+    }
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linkerd_app_core::io::AsyncReadExt;
+    use linkerd_proxy_client_policy::tls::filter;
+    use linkerd_tls_route::sni::MatchSni;
+
+    fn session<I>(sni: &str, alpn: &[&[u8]], io: I) -> Session<I> {
+        let info = tls::SessionInfo {
+            sni: sni.parse().unwrap(),
+            alpn: alpn
+                .iter()
+                .map(|p| linkerd_app_core::tls::NegotiatedProtocol(p.to_vec()))
+                .collect(),
+        };
+        Session { info, io }
+    }
+
+    fn apply(
+        filters: impl IntoIterator<Item = tls::Filter>,
+    ) -> ApplyFilters<svc::BoxService<io::DuplexStream, (), Error>> {
+        ApplyFilters {
+            filters: filters.into_iter().collect(),
+            inner: svc::BoxService::new(svc::mk(|_: io::DuplexStream| future::ok::<(), Error>(()))),
+        }
+    }
+
+    #[tokio::test]
+    async fn deny_sni_sends_alert() {
+        let deny = tls::Filter::DenySni(filter::DenySni {
+            snis: vec![MatchSni::Exact("secret.example.com".to_string())],
+        });
+
+        let (mut client, server) = io::duplex(64);
+        let mut svc = apply([deny]);
+        let err = svc::ServiceExt::oneshot(&mut svc, session("secret.example.com", &[], server))
+            .await
+            .expect_err("session must be rejected");
+        assert!(err.is::<errors::TlsRouteSniDenied>());
+
+        let mut buf = Vec::new();
+        client.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, [21, 3, 3, 0, 2, 2, 112]);
+    }
+
+    #[tokio::test]
+    async fn allow_alpn() {
+        let allow = tls::Filter::AllowAlpn(filter::AllowAlpn {
+            protocols: vec![linkerd_app_core::tls::NegotiatedProtocol(b"h2".to_vec())],
+        });
+
+        let (_client, server) = io::duplex(64);
+        let mut svc = apply([allow.clone()]);
+        svc::ServiceExt::oneshot(&mut svc, session("example.com", &[b"h2"], server))
+            .await
+            .expect("session must be allowed");
+
+        let (mut client, server) = io::duplex(64);
+        let mut svc = apply([allow]);
+        let err =
+            svc::ServiceExt::oneshot(&mut svc, session("example.com", &[b"http/1.1"], server))
+                .await
+                .expect_err("session must be rejected");
+        assert!(err.is::<errors::TlsRouteAlpnNotAllowed>());
+
+        let mut buf = Vec::new();
+        client.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, [21, 3, 3, 0, 2, 2, 120]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn session_lifetime() {
+        let lifetime = tls::Filter::SessionLifetime(filter::SessionLifetime {
+            max_duration: time::Duration::from_secs(10),
+        });
+
+        let (_client, server) = io::duplex(64);
+        let mut svc = ApplyFilters {
+            filters: Arc::new([lifetime]),
+            inner: svc::mk(|_: io::DuplexStream| future::pending::<Result<()>>()),
+        };
+        let err = svc::ServiceExt::oneshot(&mut svc, session("example.com", &[], server))
+            .await
+            .expect_err("session must expire");
+        assert!(err.is::<errors::TlsRouteSessionExpired>());
+    }
+}
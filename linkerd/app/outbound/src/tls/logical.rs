@@ -1,6 +1,6 @@
-use super::concrete;
-use crate::{BackendRef, Outbound, ParentRef};
-use linkerd_app_core::{io, svc, tls::ServerName, Addr, Error};
+use super::{concrete, filters};
+use crate::{tcp, BackendRef, Outbound, ParentRef};
+use linkerd_app_core::{io, svc, tls::ServerName, transport::addrs::OrigDstAddr, Addr, Error};
 use linkerd_proxy_client_policy as client_policy;
 use std::{fmt::Debug, hash::Hash, sync::Arc};
 use tokio::sync::watch;
@@ -29,6 +29,10 @@ pub struct Concrete<T> {
     parent: T,
     parent_ref: ParentRef,
     backend_ref: BackendRef,
+    proxy_protocol: Option<client_policy::ProxyProtocolVersion>,
+    upstream_proxy: Option<client_policy::UpstreamProxy>,
+    forward_name: Option<tcp::ForwardName>,
+    marking: Option<client_policy::Marking>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -51,7 +55,9 @@ impl<N> Outbound<N> {
     /// support per-connection routing over a set of concrete inner services.
     /// Only available inner services are used for routing. When there are no
     /// available backends, requests are failed with a [`svc::stack::LoadShedError`].
-    pub fn push_tls_logical<T, I, NSvc>(self) -> Outbound<svc::ArcNewCloneTcp<T, I>>
+    pub fn push_tls_logical<T, I, NSvc>(
+        self,
+    ) -> Outbound<svc::ArcNewCloneTcp<T, filters::Session<I>>>
     where
         // Logical target.
         T: svc::Param<watch::Receiver<Routes>>,
@@ -102,6 +108,39 @@ impl<T> svc::Param<concrete::Dispatch> for Concrete<T> {
     }
 }
 
+impl<T> svc::Param<Option<client_policy::ProxyProtocolVersion>> for Concrete<T> {
+    fn param(&self) -> Option<client_policy::ProxyProtocolVersion> {
+        self.proxy_protocol
+    }
+}
+
+impl<T> svc::Param<Option<client_policy::UpstreamProxy>> for Concrete<T> {
+    fn param(&self) -> Option<client_policy::UpstreamProxy> {
+        self.upstream_proxy.clone()
+    }
+}
+
+impl<T> svc::Param<Option<tcp::ForwardName>> for Concrete<T> {
+    fn param(&self) -> Option<tcp::ForwardName> {
+        self.forward_name.clone()
+    }
+}
+
+impl<T> svc::Param<Option<client_policy::Marking>> for Concrete<T> {
+    fn param(&self) -> Option<client_policy::Marking> {
+        self.marking
+    }
+}
+
+impl<T> svc::Param<Option<OrigDstAddr>> for Concrete<T>
+where
+    T: svc::Param<Option<OrigDstAddr>>,
+{
+    fn param(&self) -> Option<OrigDstAddr> {
+        self.parent.param()
+    }
+}
+
 impl<T> svc::Param<ServerName> for Concrete<T>
 where
     T: svc::Param<ServerName>,
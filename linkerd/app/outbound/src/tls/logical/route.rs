@@ -1,9 +1,10 @@
-use super::super::Concrete;
+use super::super::{filters, Concrete};
 use crate::{ParentRef, RouteRef};
 use linkerd_app_core::{io, svc, Addr, Error};
 use linkerd_distribute as distribute;
+use linkerd_proxy_client_policy as policy;
 use linkerd_tls_route as tls_route;
-use std::{fmt::Debug, hash::Hash};
+use std::{fmt::Debug, hash::Hash, sync::Arc};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) struct Backend<T> {
@@ -23,6 +24,7 @@ pub(crate) struct Route<T> {
     pub(super) addr: Addr,
     pub(super) parent_ref: ParentRef,
     pub(super) route_ref: RouteRef,
+    pub(super) filters: Arc<[policy::tls::Filter]>,
     pub(super) distribution: BackendDistribution<T>,
 }
 
@@ -57,11 +59,11 @@ where
     T: Debug + Eq + Hash,
     T: Clone + Send + Sync + 'static,
 {
-    /// Builds a route stack that applies policy filters to requests and
-    /// distributes requests over each route's backends. These [`Concrete`]
+    /// Builds a route stack that applies policy filters to connections and
+    /// distributes connections over each route's backends. These [`Concrete`]
     /// backends are expected to be cached/shared by the inner stack.
     pub(crate) fn layer<N, I, NSvc>(
-    ) -> impl svc::Layer<N, Service = svc::ArcNewCloneTcp<Self, I>> + Clone
+    ) -> impl svc::Layer<N, Service = svc::ArcNewCloneTcp<Self, filters::Session<I>>> + Clone
     where
         I: io::AsyncRead + io::AsyncWrite + Debug + Send + Unpin + 'static,
         // Inner stack.
@@ -80,6 +82,7 @@ where
                 // consideration, so we must eagerly fail requests to prevent
                 // leaking tasks onto the runtime.
                 .push_on_service(svc::LoadShed::layer())
+                .push(filters::NewApplyFilters::layer())
                 .push(svc::NewMapErr::layer_with(|rt: &Self| {
                     let route = rt.params.route_ref.clone();
                     move |source| RouteError {
@@ -93,6 +96,12 @@ where
     }
 }
 
+impl<T> svc::Param<Arc<[policy::tls::Filter]>> for MatchedRoute<T> {
+    fn param(&self) -> Arc<[policy::tls::Filter]> {
+        self.params.filters.clone()
+    }
+}
+
 impl<T: Clone> svc::Param<BackendDistribution<T>> for MatchedRoute<T> {
     fn param(&self) -> BackendDistribution<T> {
         self.params.distribution.clone()
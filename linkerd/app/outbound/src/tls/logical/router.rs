@@ -1,8 +1,8 @@
 use super::{
-    super::{concrete, Concrete},
+    super::{concrete, filters, Concrete},
     route, LogicalAddr, NoRoute,
 };
-use crate::{BackendRef, EndpointRef, RouteRef};
+use crate::{tcp, BackendRef, EndpointRef, RouteRef};
 use linkerd_app_core::{
     io, proxy::http, svc, tls::ServerName, transport::addrs::*, Addr, Error, NameAddr, Result,
 };
@@ -29,7 +29,8 @@ where
     T: Eq + Hash + Clone + Debug + Send + Sync + 'static,
     T: svc::Param<ServerName>,
 {
-    pub fn layer<N, I, NSvc>() -> impl svc::Layer<N, Service = svc::ArcNewCloneTcp<Self, I>> + Clone
+    pub fn layer<N, I, NSvc>(
+    ) -> impl svc::Layer<N, Service = svc::ArcNewCloneTcp<Self, filters::Session<I>>> + Clone
     where
         I: io::AsyncRead + io::AsyncWrite + Debug + Send + Unpin + 'static,
         // Concrete stack.
@@ -67,12 +68,27 @@ where
         let mk_concrete = {
             let parent = parent.clone();
             let parent_ref = parent_ref.clone();
-
-            move |backend_ref: BackendRef, target: concrete::Dispatch| Concrete {
-                target,
-                parent: parent.clone(),
-                backend_ref,
-                parent_ref: parent_ref.clone(),
+            let addr = addr.clone();
+
+            move |bke: &policy::Backend, backend_ref: BackendRef, target: concrete::Dispatch| {
+                // Endpoints that are forwarded to, rather than discovered, are
+                // known by the logical name that was targeted.
+                let forward_name = match (&target, &addr) {
+                    (concrete::Dispatch::Forward(..), Addr::Name(name)) => {
+                        Some(tcp::ForwardName(name.clone()))
+                    }
+                    _ => None,
+                };
+                Concrete {
+                    target,
+                    parent: parent.clone(),
+                    backend_ref,
+                    parent_ref: parent_ref.clone(),
+                    proxy_protocol: bke.proxy_protocol,
+                    upstream_proxy: bke.upstream_proxy.clone(),
+                    forward_name,
+                    marking: bke.marking,
+                }
             }
         };
 
@@ -81,6 +97,7 @@ where
                 policy::Load::PeakEwma(policy::PeakEwma { decay, default_rtt }),
                 policy::EndpointDiscovery::DestinationGet { ref path },
             ) => mk_concrete(
+                bke,
                 BackendRef(bke.meta.clone()),
                 concrete::Dispatch::Balance(
                     path.parse::<NameAddr>()
@@ -89,10 +106,12 @@ where
                 ),
             ),
             policy::BackendDispatcher::Forward(addr, ref md) => mk_concrete(
+                bke,
                 EndpointRef::new(md, addr.port().try_into().expect("port must not be 0")).into(),
                 concrete::Dispatch::Forward(Remote(ServerAddr(addr)), md.clone()),
             ),
             policy::BackendDispatcher::Fail { ref message } => mk_concrete(
+                bke,
                 BackendRef(policy::Meta::new_default("fail")),
                 concrete::Dispatch::Fail {
                     message: message.clone(),
@@ -127,22 +146,20 @@ where
                 }
             };
 
-        let mk_policy =
-            |policy::RoutePolicy::<policy::tls::Filter, ()> {
-                 meta, distribution, ..
-             }| {
-                let route_ref = RouteRef(meta);
-                let parent_ref = parent_ref.clone();
+        let mk_policy = |rp: policy::RoutePolicy<policy::tls::Filter, ()>| {
+            let route_ref = RouteRef(rp.meta);
+            let parent_ref = parent_ref.clone();
 
-                let distribution = mk_distribution(&route_ref, &distribution);
-                route::Route {
-                    addr: addr.clone(),
-                    parent: parent.clone(),
-                    parent_ref: parent_ref.clone(),
-                    route_ref,
-                    distribution,
-                }
-            };
+            let distribution = mk_distribution(&route_ref, &rp.distribution);
+            route::Route {
+                addr: addr.clone(),
+                parent: parent.clone(),
+                parent_ref: parent_ref.clone(),
+                route_ref,
+                filters: rp.filters,
+                distribution,
+            }
+        };
 
         let routes = routes
             .iter()
@@ -163,20 +180,18 @@ where
     }
 }
 
-impl<T, I> svc::router::SelectRoute<I> for Router<T>
+impl<T, I> svc::router::SelectRoute<filters::Session<I>> for Router<T>
 where
     T: Clone + Eq + Hash + Debug,
-    T: svc::Param<ServerName>,
 {
     type Key = route::MatchedRoute<T>;
     type Error = NoRoute;
 
-    fn select(&self, _: &I) -> Result<Self::Key, Self::Error> {
-        use linkerd_tls_route::SessionInfo;
-
-        let server_name: ServerName = self.parent.param();
-        tracing::trace!("Selecting TLS route for {:?}", server_name);
-        let si = SessionInfo { sni: server_name };
+    fn select(&self, io: &filters::Session<I>) -> Result<Self::Key, Self::Error> {
+        let si = io.info().clone();
+        tracing::trace!("Selecting TLS route for {:?}", si.sni);
+        // The selected route's filters are applied to the connection by the
+        // route stack.
         let (r#match, params) = policy::tls::find(&self.routes, si).ok_or(NoRoute)?;
         tracing::debug!(meta = ?params.route_ref, "Selected route");
         tracing::trace!(?r#match);
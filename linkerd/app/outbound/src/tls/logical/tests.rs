@@ -142,6 +142,9 @@ fn default_backend(addr: SocketAddr) -> client_policy::Backend {
             failfast_timeout: Duration::from_secs(10),
         },
         dispatcher: BackendDispatcher::Forward(addr, EndpointMetadata::default()),
+        proxy_protocol: None,
+        upstream_proxy: None,
+        marking: None,
     }
 }
 
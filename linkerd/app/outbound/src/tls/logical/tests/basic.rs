@@ -1,8 +1,8 @@
 use super::*;
-use crate::tls::Tls;
+use crate::tls::{filters, Tls};
 use linkerd_app_core::{
     svc::ServiceExt,
-    tls::{NewDetectRequiredSni, ServerName},
+    tls::{ClientHello, NewDetectRequiredSni},
     trace, NameAddr,
 };
 use linkerd_proxy_client_policy as client_policy;
@@ -34,7 +34,11 @@ async fn routes() {
         .push_tls_logical()
         .map_stack(|config, _rt, stk| {
             stk.push_new_idle_cached(config.discovery_idle_timeout)
-                .push_map_target(|(sni, parent): (ServerName, _)| Tls { sni, parent })
+                .push_map_target(|(hello, parent): (ClientHello, _)| Tls {
+                    sni: hello.sni,
+                    parent,
+                })
+                .push(filters::NewSession::layer())
                 .push(NewDetectRequiredSni::layer(Duration::from_secs(1)))
                 .arc_new_clone_tcp()
         })
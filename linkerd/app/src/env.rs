@@ -1,13 +1,16 @@
-use crate::{dns, gateway, identity, inbound, outbound, policy, spire, trace_collector};
+use crate::{
+    dns, ext_authz, gateway, global_rate_limit, identity, inbound, outbound, policy, spire,
+    trace_collector,
+};
 use linkerd_app_core::{
     addr,
     config::*,
     control::{Config as ControlConfig, ControlAddr},
     http_tracing::CollectorProtocol,
-    proxy::http::{h1, h2},
+    proxy::http::{self, h1, h2},
     tls,
-    transport::{DualListenAddr, Keepalive, ListenAddr, UserTimeout},
-    AddrMatch, Conditional, IpNet,
+    transport::{DualListenAddr, Keepalive, ListenAddr, ListenShards, OrigDstMode, UserTimeout},
+    AddrMatch, Conditional, IpMatch, IpNet,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -95,11 +98,44 @@ pub enum ParseError {
     InvalidTrustAnchors,
     #[error("not a valid port policy: {0}")]
     InvalidPortPolicy(String),
+    #[error("not a valid failure mode: {0}")]
+    InvalidFailureMode(String),
+    #[error("not a valid header name: {0}")]
+    InvalidHeaderName(String),
+    #[error("not a valid Unix socket mapping: {0}")]
+    InvalidUnixSocket(String),
+    #[error("not valid policy overrides: {0}")]
+    InvalidPolicyOverrides(String),
 }
 
 // Environment variables to look at when loading the configuration
 pub const ENV_OUTBOUND_LISTEN_ADDR: &str = "LINKERD2_PROXY_OUTBOUND_LISTEN_ADDR";
 pub const ENV_OUTBOUND_LISTEN_ADDRS: &str = "LINKERD2_PROXY_OUTBOUND_LISTEN_ADDRS";
+
+/// Configures the address of an additional outbound listener that serves
+/// clients configured to use the proxy explicitly, via HTTP `CONNECT`,
+/// absolute-form HTTP requests, or SOCKS5.
+///
+/// The address must be a loopback address, as the listener serves any client
+/// that can reach it. If unspecified, no forward-proxy listener is bound.
+pub const ENV_OUTBOUND_FORWARD_PROXY_LISTEN_ADDR: &str =
+    "LINKERD2_PROXY_OUTBOUND_FORWARD_PROXY_LISTEN_ADDR";
+
+/// A JSON document of settings that are applied to each discovered outbound
+/// policy, for settings that the policy API does not express (e.g. opaque
+/// route matches or TLS route filters). Routes are selected by their resource
+/// kind, namespace and name.
+///
+/// If unspecified, discovered policies are used as-is.
+pub const ENV_OUTBOUND_POLICY_OVERRIDES: &str = "LINKERD2_PROXY_OUTBOUND_POLICY_OVERRIDES";
+
+/// A JSON document of settings that are applied to each inbound server policy,
+/// for settings that the policy API does not express (e.g. route rate limits).
+/// Servers, routes and authorizations are selected by their resource kind and
+/// name.
+///
+/// If unspecified, discovered policies are used as-is.
+pub const ENV_INBOUND_POLICY_OVERRIDES: &str = "LINKERD2_PROXY_INBOUND_POLICY_OVERRIDES";
 pub const ENV_INBOUND_LISTEN_ADDR: &str = "LINKERD2_PROXY_INBOUND_LISTEN_ADDR";
 pub const ENV_CONTROL_LISTEN_ADDR: &str = "LINKERD2_PROXY_CONTROL_LISTEN_ADDR";
 pub const ENV_ADMIN_LISTEN_ADDR: &str = "LINKERD2_PROXY_ADMIN_LISTEN_ADDR";
@@ -118,6 +154,30 @@ const ENV_OUTBOUND_TCP_FAILFAST_TIMEOUT: &str = "LINKERD2_PROXY_OUTBOUND_TCP_FAI
 const ENV_OUTBOUND_HTTP_QUEUE_CAPACITY: &str = "LINKERD2_PROXY_OUTBOUND_HTTP_QUEUE_CAPACITY";
 const ENV_OUTBOUND_HTTP_FAILFAST_TIMEOUT: &str = "LINKERD2_PROXY_OUTBOUND_HTTP_FAILFAST_TIMEOUT";
 
+/// Enables CoDel-style load shedding in outbound HTTP balancer queues: requests
+/// are dropped from the head of the queue once queueing delay has exceeded
+/// this target for a full interval.
+const ENV_OUTBOUND_HTTP_QUEUE_CODEL_TARGET: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_QUEUE_CODEL_TARGET";
+const ENV_OUTBOUND_HTTP_QUEUE_CODEL_INTERVAL: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_QUEUE_CODEL_INTERVAL";
+
+/// Enables adaptive concurrency limits for each outbound HTTP backend, using
+/// either the `gradient` or `aimd` algorithm.
+const ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY";
+const ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_INITIAL_LIMIT: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_INITIAL_LIMIT";
+const ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MIN_LIMIT: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MIN_LIMIT";
+const ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MAX_LIMIT: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MAX_LIMIT";
+
+/// Requests that take longer than this are treated as a sign of overload by
+/// the `aimd` algorithm.
+const ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_LATENCY_THRESHOLD: &str =
+    "LINKERD2_PROXY_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_LATENCY_THRESHOLD";
+
 pub const ENV_INBOUND_DETECT_TIMEOUT: &str = "LINKERD2_PROXY_INBOUND_DETECT_TIMEOUT";
 const ENV_OUTBOUND_DETECT_TIMEOUT: &str = "LINKERD2_PROXY_OUTBOUND_DETECT_TIMEOUT";
 
@@ -133,6 +193,12 @@ const ENV_OUTBOUND_CONNECT_KEEPALIVE: &str = "LINKERD2_PROXY_OUTBOUND_CONNECT_KE
 const ENV_INBOUND_ACCEPT_USER_TIMEOUT: &str = "LINKERD2_PROXY_INBOUND_ACCEPT_USER_TIMEOUT";
 const ENV_OUTBOUND_ACCEPT_USER_TIMEOUT: &str = "LINKERD2_PROXY_OUTBOUND_ACCEPT_USER_TIMEOUT";
 
+/// Configures the number of sockets bound for the inbound and outbound
+/// listeners with `SO_REUSEPORT`, each of which accepts connections on its own
+/// task. Only supported on Linux. Defaults to 1.
+const ENV_INBOUND_LISTEN_SHARDS: &str = "LINKERD2_PROXY_INBOUND_LISTEN_SHARDS";
+const ENV_OUTBOUND_LISTEN_SHARDS: &str = "LINKERD2_PROXY_OUTBOUND_LISTEN_SHARDS";
+
 const ENV_INBOUND_CONNECT_USER_TIMEOUT: &str = "LINKERD2_PROXY_INBOUND_CONNECT_USER_TIMEOUT";
 const ENV_OUTBOUND_CONNECT_USER_TIMEOUT: &str = "LINKERD2_PROXY_OUTBOUND_CONNECT_USER_TIMEOUT";
 
@@ -186,6 +252,50 @@ pub const ENV_INBOUND_PORTS_REQUIRE_IDENTITY: &str =
 
 pub const ENV_INBOUND_PORTS_REQUIRE_TLS: &str = "LINKERD2_PROXY_INBOUND_PORTS_REQUIRE_TLS";
 
+/// Configures the inbound ports on which connections must be prefaced by a
+/// PROXY protocol (v1 or v2) header.
+///
+/// Headers are also read from connections to servers for which the PROXY
+/// protocol is enabled by `LINKERD2_PROXY_INBOUND_POLICY_OVERRIDES`.
+pub const ENV_INBOUND_PROXY_PROTOCOL_PORTS: &str = "LINKERD2_PROXY_INBOUND_PROXY_PROTOCOL_PORTS";
+
+/// Constrains the networks from which PROXY protocol headers are accepted.
+///
+/// The value is a comma-separated list of networks. Connections to PROXY
+/// protocol ports (or servers) from other clients are rejected. If unspecified, all such
+/// connections are rejected.
+pub const ENV_INBOUND_PROXY_PROTOCOL_TRUSTED_NETWORKS: &str =
+    "LINKERD2_PROXY_INBOUND_PROXY_PROTOCOL_TRUSTED_NETWORKS";
+
+/// The maximum amount of time to wait for a client to send a PROXY protocol
+/// header.
+const ENV_INBOUND_PROXY_PROTOCOL_TIMEOUT: &str = "LINKERD2_PROXY_INBOUND_PROXY_PROTOCOL_TIMEOUT";
+
+/// Maps inbound application ports to Unix domain sockets.
+///
+/// The value is a comma-separated list of `port=path` entries. Connections to
+/// a listed port are forwarded to the application over the socket at `path`
+/// instead of the loopback TCP address.
+pub const ENV_INBOUND_UNIX_SOCKETS: &str = "LINKERD2_PROXY_INBOUND_UNIX_SOCKETS";
+
+/// When `true`, connections to the application originate from each client's IP
+/// address rather than the proxy's, so that the application observes its
+/// clients' addresses.
+///
+/// This requires the `CAP_NET_ADMIN` capability and routing rules that deliver
+/// the application's responses to the proxy. Defaults to `false`.
+pub const ENV_INBOUND_TRANSPARENT_SOURCE: &str = "LINKERD2_PROXY_INBOUND_TRANSPARENT_SOURCE";
+
+/// When `true`, connections are delivered to the inbound and outbound
+/// listeners, respectively, by TPROXY rules rather than `REDIRECT` rules, so
+/// that original destination addresses are read from each connection's local
+/// address rather than from `SO_ORIGINAL_DST`.
+///
+/// TPROXY listeners are bound with `IP_TRANSPARENT`, which requires the
+/// `CAP_NET_ADMIN` capability. Defaults to `false`.
+pub const ENV_INBOUND_TPROXY: &str = "LINKERD2_PROXY_INBOUND_TPROXY";
+pub const ENV_OUTBOUND_TPROXY: &str = "LINKERD2_PROXY_OUTBOUND_TPROXY";
+
 /// Configures the default port policy for inbound connections.
 ///
 /// This must parse to a valid port policy (one of: `deny`, `authenticated`,
@@ -236,6 +346,74 @@ pub const ENV_HOSTNAME: &str = "HOSTNAME";
 
 pub const ENV_TRACE_COLLECTOR_SVC_BASE: &str = "LINKERD2_PROXY_TRACE_COLLECTOR_SVC";
 
+/// Configures an external rate limit service that implements the Envoy
+/// `ratelimit.v3.RateLimitService` API. Inbound routes that configure global
+/// rate limits are only enforced when this is set.
+pub const ENV_INBOUND_GLOBAL_RATE_LIMIT_SVC_BASE: &str =
+    "LINKERD2_PROXY_INBOUND_GLOBAL_RATE_LIMIT_SVC";
+
+/// Bounds the time spent waiting for the rate limit service to respond.
+const ENV_INBOUND_GLOBAL_RATE_LIMIT_TIMEOUT: &str =
+    "LINKERD2_PROXY_INBOUND_GLOBAL_RATE_LIMIT_TIMEOUT";
+
+/// Determines whether requests are allowed (`open`) or rejected (`closed`) when
+/// the rate limit service cannot be reached.
+const ENV_INBOUND_GLOBAL_RATE_LIMIT_FAILURE_MODE: &str =
+    "LINKERD2_PROXY_INBOUND_GLOBAL_RATE_LIMIT_FAILURE_MODE";
+
+/// Configures an external authorization service. Inbound routes that configure
+/// external authorization are only checked when this is set. In `http` mode,
+/// the `_NAME` may be omitted to reach the service without TLS.
+pub const ENV_INBOUND_EXT_AUTHZ_SVC_BASE: &str = "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_SVC";
+
+/// Either `grpc`, for services that implement the Envoy `ext_authz.v3` API, or
+/// `http`, for services that authorize plain HTTP requests. Defaults to `grpc`.
+const ENV_INBOUND_EXT_AUTHZ_PROTOCOL: &str = "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_PROTOCOL";
+
+/// In `http` mode, a prefix prepended to the paths of authorization requests.
+const ENV_INBOUND_EXT_AUTHZ_HTTP_PATH_PREFIX: &str =
+    "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_HTTP_PATH_PREFIX";
+
+/// In `http` mode, a comma-separated list of the authorization response's
+/// headers that are added to allowed requests.
+const ENV_INBOUND_EXT_AUTHZ_HTTP_ALLOWED_HEADERS: &str =
+    "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_HTTP_ALLOWED_HEADERS";
+
+const ENV_INBOUND_EXT_AUTHZ_TIMEOUT: &str = "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_TIMEOUT";
+
+/// Determines whether requests are allowed (`open`) or denied (`closed`) when
+/// the authorization service cannot be reached.
+const ENV_INBOUND_EXT_AUTHZ_FAILURE_MODE: &str = "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_FAILURE_MODE";
+
+/// Enables caching of authorization decisions for the given duration.
+const ENV_INBOUND_EXT_AUTHZ_CACHE_TTL: &str = "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_CACHE_TTL";
+const ENV_INBOUND_EXT_AUTHZ_CACHE_CAPACITY: &str =
+    "LINKERD2_PROXY_INBOUND_EXT_AUTHZ_CACHE_CAPACITY";
+
+/// Records inbound authorization decisions as JSON events. Either `stdout` or
+/// the path of a file to which events are appended.
+const ENV_INBOUND_AUTHZ_AUDIT_LOG: &str = "LINKERD2_PROXY_INBOUND_AUTHZ_AUDIT_LOG";
+
+/// Determines whether allowed connections and requests are recorded in
+/// addition to denied ones. Defaults to `false`.
+const ENV_INBOUND_AUTHZ_AUDIT_LOG_ALLOWED: &str = "LINKERD2_PROXY_INBOUND_AUTHZ_AUDIT_LOG_ALLOWED";
+
+/// The maximum number of audit events recorded per second.
+const ENV_INBOUND_AUTHZ_AUDIT_LOG_MAX_RATE: &str =
+    "LINKERD2_PROXY_INBOUND_AUTHZ_AUDIT_LOG_MAX_RATE";
+
+/// Enables inbound policy learning. The value bounds the number of distinct
+/// (server, route, client identity, client network) tuples that are retained.
+/// Suggested authorizations are served by the admin server.
+const ENV_INBOUND_POLICY_LEARNING_CAPACITY: &str =
+    "LINKERD2_PROXY_INBOUND_POLICY_LEARNING_CAPACITY";
+
+/// The prefix lengths used to aggregate learned client addresses into networks.
+const ENV_INBOUND_POLICY_LEARNING_IPV4_PREFIX_LEN: &str =
+    "LINKERD2_PROXY_INBOUND_POLICY_LEARNING_IPV4_PREFIX_LEN";
+const ENV_INBOUND_POLICY_LEARNING_IPV6_PREFIX_LEN: &str =
+    "LINKERD2_PROXY_INBOUND_POLICY_LEARNING_IPV6_PREFIX_LEN";
+
 pub const ENV_DESTINATION_CONTEXT: &str = "LINKERD2_PROXY_DESTINATION_CONTEXT";
 pub const ENV_DESTINATION_PROFILE_INITIAL_TIMEOUT: &str =
     "LINKERD2_PROXY_DESTINATION_PROFILE_INITIAL_TIMEOUT";
@@ -277,6 +455,7 @@ const DEFAULT_METRICS_RETAIN_IDLE: Duration = Duration::from_secs(10 * 60);
 const DEFAULT_INBOUND_HTTP_QUEUE_CAPACITY: usize = 10_000;
 const DEFAULT_INBOUND_HTTP_FAILFAST_TIMEOUT: Duration = Duration::from_secs(1);
 const DEFAULT_INBOUND_DETECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_INBOUND_PROXY_PROTOCOL_TIMEOUT: Duration = Duration::from_secs(10);
 const DEFAULT_INBOUND_CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
 const DEFAULT_INBOUND_CONNECT_BACKOFF: ExponentialBackoff =
     ExponentialBackoff::new_unchecked(Duration::from_millis(100), Duration::from_millis(500), 0.1);
@@ -285,6 +464,12 @@ const DEFAULT_OUTBOUND_TCP_QUEUE_CAPACITY: usize = 10_000;
 const DEFAULT_OUTBOUND_TCP_FAILFAST_TIMEOUT: Duration = Duration::from_secs(3);
 const DEFAULT_OUTBOUND_HTTP_QUEUE_CAPACITY: usize = 10_000;
 const DEFAULT_OUTBOUND_HTTP_FAILFAST_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_OUTBOUND_HTTP_QUEUE_CODEL_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_INITIAL_LIMIT: usize = 20;
+const DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MIN_LIMIT: usize = 1;
+const DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MAX_LIMIT: usize = 1_000;
+const DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_LATENCY_THRESHOLD: Duration =
+    Duration::from_millis(500);
 const DEFAULT_OUTBOUND_DETECT_TIMEOUT: Duration = Duration::from_secs(10);
 const DEFAULT_OUTBOUND_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 const DEFAULT_OUTBOUND_CONNECT_BACKOFF: ExponentialBackoff =
@@ -293,6 +478,13 @@ const DEFAULT_OUTBOUND_CONNECT_BACKOFF: ExponentialBackoff =
 const DEFAULT_CONTROL_QUEUE_CAPACITY: usize = 100;
 const DEFAULT_CONTROL_FAILFAST_TIMEOUT: Duration = Duration::from_secs(10);
 
+const DEFAULT_INBOUND_GLOBAL_RATE_LIMIT_TIMEOUT: Duration = Duration::from_millis(20);
+const DEFAULT_INBOUND_EXT_AUTHZ_TIMEOUT: Duration = Duration::from_millis(200);
+const DEFAULT_INBOUND_EXT_AUTHZ_CACHE_CAPACITY: usize = 10_000;
+const DEFAULT_INBOUND_AUTHZ_AUDIT_LOG_MAX_RATE: u32 = 100;
+const DEFAULT_INBOUND_POLICY_LEARNING_IPV4_PREFIX_LEN: u8 = 24;
+const DEFAULT_INBOUND_POLICY_LEARNING_IPV6_PREFIX_LEN: u8 = 64;
+
 const DEFAULT_RESOLV_CONF: &str = "/etc/resolv.conf";
 
 const DEFAULT_INITIAL_STREAM_WINDOW_SIZE: u32 = 65_535; // Protocol default
@@ -375,6 +567,16 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
         parse(strings, ENV_OUTBOUND_HTTP_QUEUE_CAPACITY, parse_number);
     let outbound_http_failfast_timeout =
         parse(strings, ENV_OUTBOUND_HTTP_FAILFAST_TIMEOUT, parse_duration);
+    let outbound_http_queue_codel_target = parse(
+        strings,
+        ENV_OUTBOUND_HTTP_QUEUE_CODEL_TARGET,
+        parse_duration,
+    );
+    let outbound_http_queue_codel_interval = parse(
+        strings,
+        ENV_OUTBOUND_HTTP_QUEUE_CODEL_INTERVAL,
+        parse_duration,
+    );
     let outbound_connect_timeout = parse(strings, ENV_OUTBOUND_CONNECT_TIMEOUT, parse_duration);
 
     let inbound_accept_keepalive = parse(strings, ENV_INBOUND_ACCEPT_KEEPALIVE, parse_duration);
@@ -388,6 +590,18 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
     let outbound_accept_user_timeout =
         parse(strings, ENV_OUTBOUND_ACCEPT_USER_TIMEOUT, parse_duration);
 
+    // At least one socket must be bound for each listener.
+    let inbound_listen_shards = parse(
+        strings,
+        ENV_INBOUND_LISTEN_SHARDS,
+        parse_number::<std::num::NonZeroUsize>,
+    );
+    let outbound_listen_shards = parse(
+        strings,
+        ENV_OUTBOUND_LISTEN_SHARDS,
+        parse_number::<std::num::NonZeroUsize>,
+    );
+
     let inbound_connect_user_timeout =
         parse(strings, ENV_INBOUND_CONNECT_USER_TIMEOUT, parse_duration);
     let outbound_connect_user_timeout =
@@ -420,6 +634,13 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
 
     let shutdown_endpoint_enabled = parse(strings, ENV_SHUTDOWN_ENDPOINT_ENABLED, parse_bool);
 
+    let orig_dst_mode = |tproxy: Option<bool>| match tproxy {
+        Some(true) => OrigDstMode::Tproxy,
+        _ => OrigDstMode::Redirect,
+    };
+    let inbound_orig_dst = parse(strings, ENV_INBOUND_TPROXY, parse_bool).map(orig_dst_mode);
+    let outbound_orig_dst = parse(strings, ENV_OUTBOUND_TPROXY, parse_bool).map(orig_dst_mode);
+
     // DNS
 
     let resolv_conf_path = strings.get(ENV_RESOLV_CONF);
@@ -499,10 +720,14 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
 
         let keepalive = Keepalive(outbound_accept_keepalive?);
         let user_timeout = UserTimeout(outbound_accept_user_timeout?);
+        let shards = outbound_listen_shards?
+            .map(|n| ListenShards(n.get()))
+            .unwrap_or_default();
         let server = ServerConfig {
             addr,
             keepalive,
             user_timeout,
+            shards,
             http2: http2::parse_server(strings, "LINKERD2_PROXY_OUTBOUND_SERVER_HTTP2")?,
         };
         let discovery_idle_timeout =
@@ -551,6 +776,105 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
             outbound_http_queue_capacity?.unwrap_or(DEFAULT_OUTBOUND_HTTP_QUEUE_CAPACITY);
         let http_failfast_timeout =
             outbound_http_failfast_timeout?.unwrap_or(DEFAULT_OUTBOUND_HTTP_FAILFAST_TIMEOUT);
+        let http_queue_codel = {
+            use linkerd_app_core::proxy::http::balance::CodelConfig;
+
+            let interval = outbound_http_queue_codel_interval?
+                .unwrap_or(DEFAULT_OUTBOUND_HTTP_QUEUE_CODEL_INTERVAL);
+            if interval.is_zero() {
+                error!("{ENV_OUTBOUND_HTTP_QUEUE_CODEL_INTERVAL} must be positive");
+                return Err(EnvError::InvalidEnvVar);
+            }
+            outbound_http_queue_codel_target?.map(|target| CodelConfig { target, interval })
+        };
+
+        let http_adaptive_concurrency = {
+            use outbound::http::concrete::adaptive_concurrency as adaptive;
+
+            let algorithm = match strings
+                .get(ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY)?
+                .as_deref()
+            {
+                None => None,
+                Some("gradient") => Some(adaptive::Algorithm::Gradient),
+                Some("aimd") => Some(adaptive::Algorithm::Aimd {
+                    latency_threshold: parse(
+                        strings,
+                        ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_LATENCY_THRESHOLD,
+                        parse_duration,
+                    )?
+                    .unwrap_or(DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_LATENCY_THRESHOLD),
+                }),
+                Some(algorithm) => {
+                    error!("{ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY}={algorithm:?} is not valid");
+                    return Err(EnvError::InvalidEnvVar);
+                }
+            };
+            match algorithm {
+                None => None,
+                Some(algorithm) => {
+                    let initial_limit = parse(
+                        strings,
+                        ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_INITIAL_LIMIT,
+                        parse_number,
+                    )?
+                    .unwrap_or(DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_INITIAL_LIMIT);
+                    let min_limit = parse(
+                        strings,
+                        ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MIN_LIMIT,
+                        parse_number,
+                    )?
+                    .unwrap_or(DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MIN_LIMIT);
+                    let max_limit = parse(
+                        strings,
+                        ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MAX_LIMIT,
+                        parse_number,
+                    )?
+                    .unwrap_or(DEFAULT_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MAX_LIMIT);
+                    if min_limit == 0 || min_limit > max_limit {
+                        error!(
+                            "{ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MIN_LIMIT}={min_limit} must be \
+                            positive and must not exceed \
+                            {ENV_OUTBOUND_HTTP_ADAPTIVE_CONCURRENCY_MAX_LIMIT}={max_limit}"
+                        );
+                        return Err(EnvError::InvalidEnvVar);
+                    }
+                    Some(adaptive::Config {
+                        algorithm,
+                        initial_limit,
+                        min_limit,
+                        max_limit,
+                    })
+                }
+            }
+        };
+
+        let forward_proxy = parse(
+            strings,
+            ENV_OUTBOUND_FORWARD_PROXY_LISTEN_ADDR,
+            parse_socket_addr,
+        )?;
+        if let Some(addr) = forward_proxy {
+            // The forward proxy serves any client that can reach it, so it must
+            // not be reachable from outside of the pod.
+            if !addr.ip().is_loopback() {
+                error!(
+                    "{ENV_OUTBOUND_FORWARD_PROXY_LISTEN_ADDR}={addr} must be a loopback address"
+                );
+                return Err(EnvError::InvalidEnvVar);
+            }
+        }
+        let forward_proxy = forward_proxy.map(|addr| ServerConfig {
+            addr: DualListenAddr(addr, None),
+            ..server.clone()
+        });
+
+        let policy_overrides = parse(
+            strings,
+            ENV_OUTBOUND_POLICY_OVERRIDES,
+            parse_outbound_policy_overrides,
+        )?
+        .unwrap_or_default();
 
         outbound::Config {
             ingress_mode,
@@ -573,6 +897,10 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
                 capacity: http_queue_capacity,
                 failfast_timeout: http_failfast_timeout,
             },
+            http_balancer_codel: http_queue_codel,
+            http_adaptive_concurrency,
+            forward_proxy,
+            policy_overrides: std::sync::Arc::new(policy_overrides),
         }
     };
 
@@ -591,10 +919,14 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
         );
         let keepalive = Keepalive(inbound_accept_keepalive?);
         let user_timeout = UserTimeout(inbound_accept_user_timeout?);
+        let shards = inbound_listen_shards?
+            .map(|n| ListenShards(n.get()))
+            .unwrap_or_default();
         let server = ServerConfig {
             addr,
             keepalive,
             user_timeout,
+            shards,
             http2: http2::parse_server(strings, "LINKERD2_PROXY_INBOUND_SERVER_HTTP2")?,
         };
         let discovery_idle_timeout =
@@ -691,14 +1023,53 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
             // and that's fine.
             .unwrap_or_default();
 
+            let overrides = parse(
+                strings,
+                ENV_INBOUND_POLICY_OVERRIDES,
+                parse_inbound_policy_overrides,
+            )?
+            .unwrap_or_default();
+
             inbound::policy::Config::Discover {
                 default,
                 ports,
                 cache_max_idle_age: discovery_idle_timeout,
                 opaque_ports,
+                overrides: std::sync::Arc::new(overrides),
             }
         };
 
+        let proxy_protocol = {
+            let ports = parse(
+                strings,
+                ENV_INBOUND_PROXY_PROTOCOL_PORTS,
+                parse_port_range_set,
+            )?
+            .unwrap_or_default();
+            let trusted_networks = parse(
+                strings,
+                ENV_INBOUND_PROXY_PROTOCOL_TRUSTED_NETWORKS,
+                parse_networks,
+            )?
+            .unwrap_or_default();
+            if !ports.is_empty() && trusted_networks.is_empty() {
+                warn!(
+                    "{ENV_INBOUND_PROXY_PROTOCOL_TRUSTED_NETWORKS} not set; PROXY protocol connections will be rejected"
+                );
+            }
+            inbound::proxy_protocol::Config {
+                ports,
+                trusted_networks: IpMatch::new(trusted_networks),
+                timeout: parse(strings, ENV_INBOUND_PROXY_PROTOCOL_TIMEOUT, parse_duration)?
+                    .unwrap_or(DEFAULT_INBOUND_PROXY_PROTOCOL_TIMEOUT),
+            }
+        };
+
+        let unix_sockets =
+            parse(strings, ENV_INBOUND_UNIX_SOCKETS, parse_unix_sockets)?.unwrap_or_default();
+        let transparent_source =
+            parse(strings, ENV_INBOUND_TRANSPARENT_SOURCE, parse_bool)?.unwrap_or(false);
+
         inbound::Config {
             allow_discovery: dst_profile_suffixes.into_iter().collect(),
             proxy: ProxyConfig {
@@ -709,6 +1080,9 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
                 detect_protocol_timeout,
             },
             policy,
+            proxy_protocol,
+            unix_sockets,
+            transparent_source,
             profile_skip_timeout: dst_profile_skip_timeout?
                 .unwrap_or(DEFAULT_DESTINATION_PROFILE_SKIP_TIMEOUT),
             allowed_ips: inbound_ips.into(),
@@ -793,12 +1167,147 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
         }
     };
 
+    let global_rate_limit =
+        match parse_control_addr(strings, ENV_INBOUND_GLOBAL_RATE_LIMIT_SVC_BASE)? {
+            None => None,
+            Some(addr) => {
+                let timeout = parse(
+                    strings,
+                    ENV_INBOUND_GLOBAL_RATE_LIMIT_TIMEOUT,
+                    parse_duration,
+                )?
+                .unwrap_or(DEFAULT_INBOUND_GLOBAL_RATE_LIMIT_TIMEOUT);
+                let failure_mode = parse(
+                    strings,
+                    ENV_INBOUND_GLOBAL_RATE_LIMIT_FAILURE_MODE,
+                    parse_failure_mode,
+                )?
+                .unwrap_or(inbound::policy::global_rate_limit::FailureMode::Open);
+
+                let connect = if addr.addr.is_loopback() {
+                    inbound.proxy.connect.clone()
+                } else {
+                    outbound.proxy.connect.clone()
+                };
+                Some(global_rate_limit::Config {
+                    control: ControlConfig {
+                        addr,
+                        connect,
+                        buffer: QueueConfig {
+                            capacity: DEFAULT_CONTROL_QUEUE_CAPACITY,
+                            // Requests should not wait on the queue for longer
+                            // than they would wait on the service itself.
+                            failfast_timeout: timeout,
+                        },
+                    },
+                    client: inbound::policy::global_rate_limit::Config {
+                        timeout,
+                        failure_mode,
+                    },
+                })
+            }
+        };
+
+    let ext_authz = match parse_ext_authz_addr(strings)? {
+        None => None,
+        Some((addr, protocol)) => {
+            use inbound::policy::ext_authz as client;
+
+            let timeout = parse(strings, ENV_INBOUND_EXT_AUTHZ_TIMEOUT, parse_duration)?
+                .unwrap_or(DEFAULT_INBOUND_EXT_AUTHZ_TIMEOUT);
+            let failure_mode = parse(
+                strings,
+                ENV_INBOUND_EXT_AUTHZ_FAILURE_MODE,
+                parse_failure_mode,
+            )?
+            .unwrap_or(client::FailureMode::Closed);
+            let cache = parse(strings, ENV_INBOUND_EXT_AUTHZ_CACHE_TTL, parse_duration)?.map(
+                |ttl| -> Result<_, EnvError> {
+                    let capacity =
+                        parse(strings, ENV_INBOUND_EXT_AUTHZ_CACHE_CAPACITY, parse_number)?
+                            .unwrap_or(DEFAULT_INBOUND_EXT_AUTHZ_CACHE_CAPACITY);
+                    Ok(client::CacheConfig { capacity, ttl })
+                },
+            );
+
+            let connect = if addr.addr.is_loopback() {
+                inbound.proxy.connect.clone()
+            } else {
+                outbound.proxy.connect.clone()
+            };
+            Some(ext_authz::Config {
+                control: ControlConfig {
+                    addr,
+                    connect,
+                    buffer: QueueConfig {
+                        capacity: DEFAULT_CONTROL_QUEUE_CAPACITY,
+                        failfast_timeout: timeout,
+                    },
+                },
+                client: client::Config {
+                    protocol,
+                    timeout,
+                    failure_mode,
+                    cache: cache.transpose()?,
+                },
+            })
+        }
+    };
+
+    let authz_audit = match strings.get(ENV_INBOUND_AUTHZ_AUDIT_LOG)? {
+        None => None,
+        Some(log) => {
+            use inbound::policy::audit;
+
+            let sink = match log.as_str() {
+                "stdout" => audit::Sink::Stdout,
+                "" => {
+                    error!("{ENV_INBOUND_AUTHZ_AUDIT_LOG} must not be empty");
+                    return Err(EnvError::InvalidEnvVar);
+                }
+                path => audit::Sink::File(path.into()),
+            };
+            let include_allowed =
+                parse(strings, ENV_INBOUND_AUTHZ_AUDIT_LOG_ALLOWED, parse_bool)?.unwrap_or(false);
+            let max_events_per_second =
+                parse(strings, ENV_INBOUND_AUTHZ_AUDIT_LOG_MAX_RATE, parse_number)?.unwrap_or_else(
+                    || std::num::NonZeroU32::new(DEFAULT_INBOUND_AUTHZ_AUDIT_LOG_MAX_RATE).unwrap(),
+                );
+            Some(audit::Config {
+                sink,
+                include_allowed,
+                max_events_per_second,
+            })
+        }
+    };
+
+    let policy_learning = parse(strings, ENV_INBOUND_POLICY_LEARNING_CAPACITY, parse_number)?
+        .map(|capacity| -> Result<_, EnvError> {
+            Ok(inbound::policy::learn::Config {
+                capacity,
+                ipv4_prefix_len: parse(
+                    strings,
+                    ENV_INBOUND_POLICY_LEARNING_IPV4_PREFIX_LEN,
+                    parse_number,
+                )?
+                .unwrap_or(DEFAULT_INBOUND_POLICY_LEARNING_IPV4_PREFIX_LEN),
+                ipv6_prefix_len: parse(
+                    strings,
+                    ENV_INBOUND_POLICY_LEARNING_IPV6_PREFIX_LEN,
+                    parse_number,
+                )?
+                .unwrap_or(DEFAULT_INBOUND_POLICY_LEARNING_IPV6_PREFIX_LEN),
+            })
+        })
+        .transpose()?;
+
     let admin = super::admin::Config {
         metrics_retain_idle: metrics_retain_idle?.unwrap_or(DEFAULT_METRICS_RETAIN_IDLE),
         server: ServerConfig {
             addr: DualListenAddr(admin_listener_addr, None),
             keepalive: inbound.proxy.server.keepalive,
             user_timeout: inbound.proxy.server.user_timeout,
+            shards: ListenShards::default(),
             http2: inbound.proxy.server.http2.clone(),
         },
 
@@ -870,6 +1379,7 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
                 addr: DualListenAddr(addr, None),
                 keepalive: inbound.proxy.server.keepalive,
                 user_timeout: inbound.proxy.server.user_timeout,
+                shards: ListenShards::default(),
                 http2: inbound.proxy.server.http2.clone(),
             },
         })
@@ -943,7 +1453,13 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
         identity,
         outbound,
         gateway,
+        global_rate_limit,
+        ext_authz,
+        authz_audit,
+        policy_learning,
         inbound,
+        inbound_orig_dst: inbound_orig_dst?,
+        outbound_orig_dst: outbound_orig_dst?,
         shutdown_grace_period: shutdown_grace_period?.unwrap_or(DEFAULT_SHUTDOWN_GRACE_PERIOD),
     })
 }
@@ -1039,6 +1555,27 @@ where
     }
 }
 
+fn parse_failure_mode(
+    s: &str,
+) -> Result<inbound::policy::global_rate_limit::FailureMode, ParseError> {
+    match s {
+        "open" => Ok(inbound::policy::global_rate_limit::FailureMode::Open),
+        "closed" => Ok(inbound::policy::global_rate_limit::FailureMode::Closed),
+        mode => Err(ParseError::InvalidFailureMode(mode.to_string())),
+    }
+}
+
+fn parse_header_names(s: &str) -> Result<Vec<http::HeaderName>, ParseError> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| ParseError::InvalidHeaderName(s.to_string()))
+        })
+        .collect()
+}
+
 fn parse_default_policy(
     s: &str,
     cluster_nets: HashSet<IpNet>,
@@ -1125,6 +1662,58 @@ pub fn parse_control_addr<S: Strings>(
     }
 }
 
+/// Parses the external authorization service's address and protocol.
+///
+/// Unlike gRPC services, which are expected to be meshed, plain HTTP services
+/// may be reached without TLS when no identity is configured.
+fn parse_ext_authz_addr<S: Strings>(
+    strings: &S,
+) -> Result<Option<(ControlAddr, inbound::policy::ext_authz::Protocol)>, EnvError> {
+    use inbound::policy::ext_authz::Protocol;
+
+    let protocol = match strings
+        .get(ENV_INBOUND_EXT_AUTHZ_PROTOCOL)?
+        .as_deref()
+        .unwrap_or("grpc")
+    {
+        "grpc" => Protocol::Grpc,
+        "http" => Protocol::Http {
+            path_prefix: strings
+                .get(ENV_INBOUND_EXT_AUTHZ_HTTP_PATH_PREFIX)?
+                .unwrap_or_default(),
+            allowed_upstream_headers: parse(
+                strings,
+                ENV_INBOUND_EXT_AUTHZ_HTTP_ALLOWED_HEADERS,
+                parse_header_names,
+            )?
+            .unwrap_or_default(),
+        },
+        protocol => {
+            error!("{ENV_INBOUND_EXT_AUTHZ_PROTOCOL}={protocol:?} is not valid");
+            return Err(EnvError::InvalidEnvVar);
+        }
+    };
+
+    let base = ENV_INBOUND_EXT_AUTHZ_SVC_BASE;
+    let addr = match protocol {
+        Protocol::Http { .. } if strings.get(&format!("{base}_NAME"))?.is_none() => {
+            parse(strings, &format!("{base}_ADDR"), parse_addr)?.map(|addr| {
+                let no_tls = if addr.is_loopback() {
+                    tls::NoClientTls::Loopback
+                } else {
+                    tls::NoClientTls::Disabled
+                };
+                ControlAddr {
+                    addr,
+                    identity: Conditional::None(no_tls),
+                }
+            })
+        }
+        _ => parse_control_addr(strings, base)?,
+    };
+    Ok(addr.map(|addr| (addr, protocol)))
+}
+
 pub fn parse_tls_params<S: Strings>(strings: &S) -> Result<identity::TlsParams, EnvError> {
     let ta = parse(strings, ENV_IDENTITY_TRUST_ANCHORS, |s| {
         if s.is_empty() {
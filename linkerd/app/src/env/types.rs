@@ -1,8 +1,9 @@
 use super::ParseError;
-use linkerd_app_core::{dns, identity, Addr, IpNet};
+use crate::{inbound, outbound};
+use linkerd_app_core::{dns, identity, transport::UnixAddr, Addr, IpNet};
 use rangemap::RangeInclusiveSet;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     net::{IpAddr, SocketAddr},
     str::FromStr,
     time::Duration,
@@ -166,6 +167,49 @@ pub(super) fn parse_networks(list: &str) -> Result<HashSet<IpNet>, ParseError> {
     Ok(nets)
 }
 
+pub(super) fn parse_unix_sockets(list: &str) -> Result<HashMap<u16, UnixAddr>, ParseError> {
+    let mut sockets = HashMap::new();
+    for input in list.split(',') {
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        let Some((port, path)) = input.split_once('=') else {
+            error!(%input, "Unix socket mappings must be of the form `port=path`");
+            return Err(ParseError::InvalidUnixSocket(input.to_string()));
+        };
+        let port = parse_number::<u16>(port.trim())?;
+        let path = path.trim();
+        if path.is_empty() {
+            error!(%input, "Unix socket path must not be empty");
+            return Err(ParseError::InvalidUnixSocket(input.to_string()));
+        }
+        if sockets.insert(port, UnixAddr::from(path)).is_some() {
+            error!(%input, "Port {port} is mapped to more than one Unix socket");
+            return Err(ParseError::InvalidUnixSocket(input.to_string()));
+        }
+    }
+    Ok(sockets)
+}
+
+pub(super) fn parse_outbound_policy_overrides(
+    s: &str,
+) -> Result<outbound::policy::Overrides, ParseError> {
+    s.parse().map_err(|error| {
+        error!(%error, "Invalid outbound policy overrides");
+        ParseError::InvalidPolicyOverrides(error.to_string())
+    })
+}
+
+pub(super) fn parse_inbound_policy_overrides(
+    s: &str,
+) -> Result<inbound::policy::Overrides, ParseError> {
+    s.parse().map_err(|error| {
+        error!(%error, "Invalid inbound policy overrides");
+        ParseError::InvalidPolicyOverrides(error.to_string())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -358,4 +402,98 @@ mod tests {
         assert!(dbg!(parse_port_range_set("69420")).is_err());
         assert!(dbg!(parse_port_range_set("1-69420")).is_err());
     }
+
+    #[test]
+    fn unix_sockets() {
+        let sockets =
+            parse_unix_sockets("8080=/var/run/app.sock, 9090 = /tmp/admin.sock,").unwrap();
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[&8080], UnixAddr::from("/var/run/app.sock"));
+        assert_eq!(sockets[&9090], UnixAddr::from("/tmp/admin.sock"));
+        assert!(parse_unix_sockets("").unwrap().is_empty());
+
+        assert!(parse_unix_sockets("8080").is_err());
+        assert!(parse_unix_sockets("8080=").is_err());
+        assert!(parse_unix_sockets("http=/var/run/app.sock").is_err());
+        assert!(parse_unix_sockets("8080=/a.sock,8080=/b.sock").is_err());
+    }
+
+    #[test]
+    fn outbound_policy_overrides() {
+        let overrides = parse_outbound_policy_overrides(
+            r#"{"routes": [{
+                "kind": "TCPRoute",
+                "name": "voting",
+                "rules": [
+                    {"matches": [{"ports": ["8080", "9000-9099"], "clientNetworks": ["10.0.0.0/8"]}]},
+                    {}
+                ]
+            }]}"#,
+        )
+        .unwrap();
+        assert!(!overrides.is_empty());
+        assert!(parse_outbound_policy_overrides("{}").unwrap().is_empty());
+
+        assert!(parse_outbound_policy_overrides("").is_err());
+        assert!(parse_outbound_policy_overrides(r#"{"routes": [{"kind": "TCPRoute"}]}"#).is_err());
+        assert!(parse_outbound_policy_overrides(r#"{"route": []}"#).is_err());
+        assert!(parse_outbound_policy_overrides(
+            r#"{"routes": [{"name": "r", "rules": [{"matches": [{"ports": ["9099-9000"]}]}]}]}"#
+        )
+        .is_err());
+
+        assert!(parse_outbound_policy_overrides(
+            r#"{"routes": [{
+                "kind": "TLSRoute",
+                "name": "egress",
+                "filters": [
+                    {"denySni": ["*.internal.example.com"]},
+                    {"allowAlpn": ["h2"]},
+                    {"sessionLifetime": "1.5s"}
+                ]
+            }]}"#
+        )
+        .is_ok());
+        assert!(parse_outbound_policy_overrides(
+            r#"{"routes": [{"name": "r", "filters": [{"sessionLifetime": "10m"}]}]}"#
+        )
+        .is_err());
+        assert!(parse_outbound_policy_overrides(
+            r#"{"routes": [{"name": "r", "filters": [{"sessionLifetime": "0s"}]}]}"#
+        )
+        .is_err());
+        assert!(parse_outbound_policy_overrides(
+            r#"{"routes": [{"name": "r", "filters": [{"rewrite": "x"}]}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn inbound_policy_overrides() {
+        let overrides = parse_inbound_policy_overrides(
+            r#"{"servers": [{
+                "kind": "server",
+                "name": "web-http",
+                "routes": [{
+                    "name": "books",
+                    "localRateLimit": {
+                        "total": 100,
+                        "identity": 20,
+                        "overrides": [{"requestsPerSecond": 50, "identities": ["web.ns.serviceaccount.identity.linkerd.cluster.local"]}],
+                        "header": {"name": "x-tenant-id", "requestsPerSecond": 10}
+                    }
+                }]
+            }]}"#,
+        )
+        .unwrap();
+        assert!(!overrides.is_empty());
+        assert!(parse_inbound_policy_overrides("{}").unwrap().is_empty());
+
+        assert!(parse_inbound_policy_overrides("").is_err());
+        assert!(parse_inbound_policy_overrides(r#"{"servers": [{"kind": "server"}]}"#).is_err());
+        assert!(parse_inbound_policy_overrides(
+            r#"{"servers": [{"name": "s", "routes": [{"name": "r", "localRateLimit": {"total": 0}}]}]}"#
+        )
+        .is_err());
+    }
 }
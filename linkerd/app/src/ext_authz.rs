@@ -0,0 +1,38 @@
+use linkerd_app_core::{control, dns, identity, metrics, svc::NewService};
+use linkerd_app_inbound::policy::ext_authz::{
+    self as client, ExtAuthzClient, ExtAuthzMetrics, Protocol,
+};
+
+/// Configures a client for an external authorization service.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub control: control::Config,
+    pub client: client::Config,
+}
+
+// === impl Config ===
+
+impl Config {
+    pub fn build(
+        self,
+        dns: dns::Resolver,
+        legacy_metrics: metrics::ControlHttp,
+        control_metrics: control::Metrics,
+        metrics: ExtAuthzMetrics,
+        identity: identity::NewClient,
+        server_name: dns::Name,
+    ) -> ExtAuthzClient {
+        // Plain HTTP authorization services need not support HTTP/2.
+        let svc = match self.client.protocol {
+            Protocol::Grpc => self
+                .control
+                .build(dns, legacy_metrics, control_metrics, identity),
+            Protocol::Http { .. } => {
+                self.control
+                    .build_http1(dns, legacy_metrics, control_metrics, identity)
+            }
+        }
+        .new_service(());
+        ExtAuthzClient::new(svc, self.client, metrics).with_server_name(server_name)
+    }
+}
@@ -0,0 +1,30 @@
+use linkerd_app_core::{control, dns, identity, metrics, svc::NewService};
+use linkerd_app_inbound::policy::global_rate_limit::{
+    self as client, GlobalRateLimitClient, GlobalRateLimitMetrics,
+};
+
+/// Configures a client for an external rate limit service.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub control: control::Config,
+    pub client: client::Config,
+}
+
+// === impl Config ===
+
+impl Config {
+    pub fn build(
+        self,
+        dns: dns::Resolver,
+        legacy_metrics: metrics::ControlHttp,
+        control_metrics: control::Metrics,
+        metrics: GlobalRateLimitMetrics,
+        identity: identity::NewClient,
+    ) -> GlobalRateLimitClient {
+        let svc = self
+            .control
+            .build(dns, legacy_metrics, control_metrics, identity)
+            .new_service(());
+        GlobalRateLimitClient::new(svc, self.client, metrics)
+    }
+}
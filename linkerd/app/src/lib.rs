@@ -6,6 +6,8 @@
 
 pub mod dst;
 pub mod env;
+pub mod ext_authz;
+pub mod global_rate_limit;
 pub mod identity;
 pub mod policy;
 pub mod spire;
@@ -22,10 +24,14 @@ use linkerd_app_core::{
     metrics::{prom, FmtMetrics},
     serve,
     svc::Param,
-    transport::{addrs::*, listen::Bind},
+    transport::{addrs::*, listen::Bind, SetDscp},
     Error, ProxyRuntime,
 };
-pub use linkerd_app_core::{metrics, trace, transport::BindTcp, BUILD_INFO};
+pub use linkerd_app_core::{
+    metrics, trace,
+    transport::{AcceptMetrics, BindTcp, OrigDstMode},
+    BUILD_INFO,
+};
 use linkerd_app_gateway as gateway;
 use linkerd_app_inbound::{self as inbound, Inbound};
 use linkerd_app_outbound::{self as outbound, Outbound};
@@ -62,11 +68,32 @@ pub struct Config {
     pub tap: tap::Config,
     pub trace_collector: trace_collector::Config,
 
+    /// Configures an external rate limit service for inbound HTTP routes.
+    pub global_rate_limit: Option<global_rate_limit::Config>,
+
+    /// Configures an external authorization service for inbound HTTP routes.
+    pub ext_authz: Option<ext_authz::Config>,
+
+    /// Configures an event stream of inbound authorization decisions.
+    pub authz_audit: Option<inbound::policy::audit::Config>,
+
+    /// Configures inbound policy learning, which records permitted clients so
+    /// that authorizations can be suggested for them.
+    pub policy_learning: Option<inbound::policy::learn::Config>,
+
     /// Grace period for graceful shutdowns.
     ///
     /// If the proxy does not shut down gracefully within this timeout, it will
     /// terminate forcefully, closing any remaining connections.
     pub shutdown_grace_period: time::Duration,
+
+    /// Determines how the inbound listener obtains the original destination
+    /// addresses of accepted connections.
+    pub inbound_orig_dst: OrigDstMode,
+
+    /// Determines how the outbound listeners obtain the original destination
+    /// addresses of accepted connections.
+    pub outbound_orig_dst: OrigDstMode,
 }
 
 pub struct App {
@@ -78,6 +105,7 @@ pub struct App {
     trace_collector: trace_collector::TraceCollector,
     outbound_addr: Local<ServerAddr>,
     outbound_addr_additional: Option<Local<ServerAddr>>,
+    forward_proxy_addr: Option<Local<ServerAddr>>,
     start_proxy: Pin<Box<dyn std::future::Future<Output = ()> + Send + 'static>>,
     tap: tap::Tap,
 }
@@ -104,6 +132,7 @@ impl Config {
     ) -> Result<App, Error>
     where
         BIn: Bind<ServerConfig, BoundAddrs = Local<ServerAddr>> + 'static,
+        BIn::Io: SetDscp,
         BIn::Addrs: Param<Remote<ClientAddr>>
             + Param<Local<ServerAddr>>
             + Param<OrigDstAddr>
@@ -124,6 +153,10 @@ impl Config {
             identity,
             inbound,
             trace_collector,
+            global_rate_limit,
+            ext_authz,
+            authz_audit,
+            policy_learning,
             outbound,
             gateway,
             tap,
@@ -188,6 +221,61 @@ impl Config {
             })
         }?;
 
+        let global_rate_limit = global_rate_limit.map(|config| {
+            debug!(addr = %config.control.addr, "Building global rate limit client");
+            let control_metrics = ControlMetrics::register(
+                registry.sub_registry_with_prefix("control_global_rate_limit"),
+            );
+            let client_metrics =
+                inbound::policy::global_rate_limit::GlobalRateLimitMetrics::register(
+                    registry.sub_registry_with_prefix("inbound_global_rate_limit"),
+                );
+            let dns = dns.resolver.clone();
+            let metrics = metrics.control.clone();
+            info_span!("global_rate_limit").in_scope(|| {
+                config.build(
+                    dns,
+                    metrics,
+                    control_metrics,
+                    client_metrics,
+                    identity.receiver().new_client(),
+                )
+            })
+        });
+
+        let ext_authz = ext_authz.map(|config| {
+            debug!(addr = %config.control.addr, "Building external authorization client");
+            let control_metrics =
+                ControlMetrics::register(registry.sub_registry_with_prefix("control_ext_authz"));
+            let client_metrics = inbound::policy::ext_authz::ExtAuthzMetrics::register(
+                registry.sub_registry_with_prefix("inbound_ext_authz"),
+            );
+            let dns = dns.resolver.clone();
+            let metrics = metrics.control.clone();
+            info_span!("ext_authz").in_scope(|| {
+                config.build(
+                    dns,
+                    metrics,
+                    control_metrics,
+                    client_metrics,
+                    identity.receiver().new_client(),
+                    identity.receiver().server_name().clone(),
+                )
+            })
+        });
+
+        let authz_audit = authz_audit
+            .map(|config| {
+                debug!(sink = ?config.sink, "Building authorization audit sink");
+                let metrics = inbound::policy::audit::AuditMetrics::register(
+                    registry.sub_registry_with_prefix("inbound_authz_audit"),
+                );
+                inbound::policy::AuditSink::spawn(config, metrics)
+            })
+            .transpose()?;
+
+        let policy_learner = policy_learning.map(inbound::policy::PolicyLearner::new);
+
         debug!(config = ?trace_collector, "Building client");
         let trace_collector = {
             let control_metrics = if let Some(prefix) = trace_collector.metrics_prefix() {
@@ -196,7 +284,7 @@ impl Config {
                 ControlMetrics::register(&mut prom::Registry::default())
             };
             let identity = identity.receiver().new_client();
-            let dns = dns.resolver;
+            let dns = dns.resolver.clone();
             let client_metrics = metrics.control.clone();
             let otel_metrics = metrics.opentelemetry;
             let oc_metrics = metrics.opencensus;
@@ -219,7 +307,22 @@ impl Config {
             span_sink: trace_collector.span_sink(),
             drain: drain_rx.clone(),
         };
-        let inbound = Inbound::new(inbound, runtime.clone());
+        let inbound = {
+            let mut inbound = Inbound::new(inbound, runtime.clone());
+            if let Some(client) = global_rate_limit {
+                inbound = inbound.with_global_rate_limit(client);
+            }
+            if let Some(client) = ext_authz {
+                inbound = inbound.with_ext_authz(client);
+            }
+            if let Some(sink) = authz_audit {
+                inbound = inbound.with_authz_audit(sink);
+            }
+            if let Some(learner) = policy_learner.clone() {
+                inbound = inbound.with_policy_learner(learner);
+            }
+            inbound
+        };
         let outbound = Outbound::new(
             outbound,
             runtime,
@@ -251,8 +354,10 @@ impl Config {
 
         // Bind the proxy sockets eagerly (so they're reserved and known) but defer building the
         // stacks until the proxy starts running.
-        let (inbound_addr, inbound_listen) = bind_in
-            .bind(&inbound.config().proxy.server)
+        //
+        // Each shard of a listener is served by its own serve loop.
+        let (inbound_addr, inbound_shards) = bind_in
+            .bind_shards(&inbound.config().proxy.server)
             .expect("Failed to bind inbound listener");
         let inbound_metrics = inbound.metrics();
         let inbound = inbound.mk(
@@ -262,10 +367,30 @@ impl Config {
             gateway.into_inner(),
         );
 
-        let ((outbound_addr, outbound_addr_additional), outbound_listen) = bind_out
-            .bind(&outbound.config().proxy.server)
+        let ((outbound_addr, outbound_addr_additional), outbound_shards) = bind_out
+            .bind_shards(&outbound.config().proxy.server)
             .expect("Failed to bind outbound listener");
         let outbound_metrics = outbound.metrics();
+
+        // The forward-proxy listener serves clients that are configured to use
+        // the proxy explicitly, so its connections have no original
+        // destination.
+        let (forward_proxy_addr, forward_proxy) = match outbound.config().forward_proxy {
+            Some(ref config) => {
+                let (addr, listen) = BindTcp::default()
+                    .bind(config)
+                    .expect("Failed to bind forward proxy listener");
+                let stack = outbound.mk_forward_proxy(
+                    dst.profiles.clone(),
+                    outbound_policies.clone(),
+                    dst.resolve.clone(),
+                    dns.resolver.clone(),
+                );
+                (Some(addr), Some((listen, stack)))
+            }
+            None => (None, None),
+        };
+
         let outbound = outbound.mk(dst.profiles.clone(), outbound_policies, dst.resolve.clone());
 
         // Build a task that initializes and runs the proxy stacks.
@@ -276,15 +401,26 @@ impl Config {
             Box::pin(async move {
                 Self::await_identity(identity_ready).await;
 
-                tokio::spawn(
-                    serve::serve(outbound_listen, outbound, drain_rx.clone().signaled())
-                        .instrument(info_span!("outbound").or_current()),
-                );
+                for listen in outbound_shards {
+                    tokio::spawn(
+                        serve::serve(listen, outbound.clone(), drain_rx.clone().signaled())
+                            .instrument(info_span!("outbound").or_current()),
+                    );
+                }
 
-                tokio::spawn(
-                    serve::serve(inbound_listen, inbound, drain_rx.signaled())
-                        .instrument(info_span!("inbound").or_current()),
-                );
+                if let Some((listen, stack)) = forward_proxy {
+                    tokio::spawn(
+                        serve::serve(listen, stack, drain_rx.clone().signaled())
+                            .instrument(info_span!("forward_proxy").or_current()),
+                    );
+                }
+
+                for listen in inbound_shards {
+                    tokio::spawn(
+                        serve::serve(listen, inbound.clone(), drain_rx.clone().signaled())
+                            .instrument(info_span!("inbound").or_current()),
+                    );
+                }
             })
         };
 
@@ -310,6 +446,7 @@ impl Config {
                     log_level,
                     drain_rx,
                     shutdown_tx,
+                    policy_learner,
                 )
             })?
         };
@@ -323,6 +460,7 @@ impl Config {
             trace_collector,
             outbound_addr,
             outbound_addr_additional,
+            forward_proxy_addr,
             start_proxy,
             tap,
         })
@@ -361,6 +499,10 @@ impl App {
         self.outbound_addr_additional
     }
 
+    pub fn forward_proxy_addr(&self) -> Option<Local<ServerAddr>> {
+        self.forward_proxy_addr
+    }
+
     pub fn tap_addr(&self) -> Option<Local<ServerAddr>> {
         match self.tap {
             tap::Tap::Disabled { .. } => None,
@@ -62,6 +62,9 @@ impl ClientPolicies {
                 meta: Meta::new_default("test"),
                 queue,
                 dispatcher,
+                proxy_protocol: None,
+                upstream_proxy: None,
+                marking: None,
             }
         };
 
@@ -93,15 +96,20 @@ impl ClientPolicies {
             },
             opaque: opaq::Opaque {
                 routes: Some(opaq::Route {
-                    policy: opaq::Policy {
-                        meta: Meta::new_default("default"),
-                        filters: Arc::new([]),
-                        params: Default::default(),
-                        distribution: RouteDistribution::FirstAvailable(Arc::new([RouteBackend {
+                    rules: vec![opaq::Rule {
+                        matches: vec![],
+                        policy: opaq::Policy {
+                            meta: Meta::new_default("default"),
                             filters: Arc::new([]),
-                            backend: backend.clone(),
-                        }])),
-                    },
+                            params: Default::default(),
+                            distribution: RouteDistribution::FirstAvailable(Arc::new([
+                                RouteBackend {
+                                    filters: Arc::new([]),
+                                    backend: backend.clone(),
+                                },
+                            ])),
+                        },
+                    }],
                 }),
             },
         };
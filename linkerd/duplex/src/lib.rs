@@ -1,6 +1,7 @@
 //! A utility for copying data bi-directionally between two sockets.
 //!
-//! This module uses unsafe code to implement [`BufMut`].
+//! This module uses unsafe code to implement [`BufMut`] and to splice data
+//! between sockets on Linux.
 
 #![deny(
     rust_2018_idioms,
@@ -15,7 +16,13 @@ use linkerd_io::{self as io, AsyncRead, AsyncWrite};
 use pin_project::pin_project;
 use std::task::{Context, Poll};
 use std::{future::Future, pin::Pin};
-use tracing::{error, trace};
+use tokio::net::TcpStream;
+use tracing::{debug, error, trace};
+
+mod splice;
+
+/// The default size of the buffer used to copy bytes between streams.
+const COPY_BUF_CAPACITY: usize = 8 * 1024;
 
 /// A future piping data bi-directionally to In and Out.
 #[pin_project]
@@ -33,6 +40,17 @@ struct HalfDuplex<T> {
     io: T,
     direction: &'static str,
     flushing: bool,
+    splice: Option<Splice<T>>,
+}
+
+/// Accessors for a stream's socket, captured when the stream is known to
+/// implement [`io::Splice`].
+struct Splice<T> {
+    socket: fn(&T) -> Option<&TcpStream>,
+    record_read: fn(&mut T, usize),
+    record_write: fn(&mut T, usize),
+    record_error: fn(&mut T, io::Error) -> io::Error,
+    pipe: Option<splice::Pipe>,
 }
 
 /// A buffer used to copy bytes from one IO to another.
@@ -75,6 +93,24 @@ where
     }
 }
 
+impl<In, Out> Duplex<In, Out>
+where
+    In: AsyncRead + AsyncWrite + io::Splice + Unpin,
+    Out: AsyncRead + AsyncWrite + io::Splice + Unpin,
+{
+    /// Like [`Duplex::new`], but data is spliced directly between the
+    /// streams' sockets, without being copied through userspace, whenever
+    /// neither stream buffers or transforms data.
+    ///
+    /// Splicing is only supported on Linux. Otherwise, data is copied.
+    pub fn with_splice(in_io: In, out_io: Out) -> Self {
+        Duplex {
+            half_in: HalfDuplex::new(in_io, "client->server").with_splice(),
+            half_out: HalfDuplex::new(out_io, "server->client").with_splice(),
+        }
+    }
+}
+
 impl<In, Out> Future for Duplex<In, Out>
 where
     In: AsyncRead + AsyncWrite + Unpin,
@@ -109,6 +145,23 @@ where
             io,
             direction,
             flushing: false,
+            splice: None,
+        }
+    }
+
+    fn with_splice(self) -> Self
+    where
+        T: io::Splice,
+    {
+        Self {
+            splice: Some(Splice {
+                socket: T::splice_socket,
+                record_read: T::record_splice_read,
+                record_write: T::record_splice_write,
+                record_error: T::record_splice_error,
+                pipe: None,
+            }),
+            ..self
         }
     }
 
@@ -142,6 +195,19 @@ where
         let mut needs_flush = false;
 
         loop {
+            // When neither stream buffers data, move data between their sockets
+            // directly until the source reaches EOF.
+            if self.can_splice(dst) {
+                if needs_flush {
+                    let _ = self.poll_flush(dst, cx)?;
+                }
+                if ready!(self.poll_splice(dst, cx))? {
+                    continue;
+                }
+                trace!(direction = %self.direction, "eof");
+                self.buf = None;
+            }
+
             // As long as the underlying socket is alive, ensure we've read data
             // from it into the local buffer.
             match self.poll_buffer(cx)? {
@@ -285,6 +351,95 @@ where
     fn is_done(&self) -> bool {
         self.is_shutdown
     }
+
+    /// Returns true if data may be spliced from this stream's socket into the
+    /// destination's socket.
+    ///
+    /// Streams may only be spliced once all buffered data has been written,
+    /// and once neither stream has data buffered in userspace (e.g. a prefix
+    /// that was read during protocol detection).
+    fn can_splice<U>(&self, dst: &HalfDuplex<U>) -> bool {
+        let (Some(src_splice), Some(dst_splice)) = (self.splice.as_ref(), dst.splice.as_ref())
+        else {
+            return false;
+        };
+        match self.buf.as_ref() {
+            Some(buf) if !buf.has_remaining() => {}
+            _ => return false,
+        }
+        (src_splice.socket)(&self.io).is_some() && (dst_splice.socket)(&dst.io).is_some()
+    }
+
+    /// Splices data from this stream's socket into the destination's socket.
+    ///
+    /// Returns `false` when the source has reached EOF and all of its data
+    /// has been written. Returns `true` if splicing is not possible and data
+    /// must be copied instead.
+    ///
+    /// Errors on either socket are recorded by the stream that owns it. If
+    /// `splice(2)` fails because it does not support the sockets, rather than
+    /// because the connection failed, data is copied instead.
+    fn poll_splice<U>(&mut self, dst: &mut HalfDuplex<U>, cx: &mut Context<'_>) -> io::Poll<bool> {
+        let src_splice = self.splice.as_mut().expect("source must be spliceable");
+        let dst_splice = dst.splice.as_ref().expect("destination must be spliceable");
+        let pipe = match src_splice.pipe {
+            Some(ref mut pipe) => pipe,
+            None => match splice::Pipe::new() {
+                Ok(pipe) => src_splice.pipe.insert(pipe),
+                Err(error) => {
+                    debug!(direction = %self.direction, %error, "Splicing unavailable");
+                    self.splice = None;
+                    return Poll::Ready(Ok(true));
+                }
+            },
+        };
+
+        let error = loop {
+            if !pipe.is_empty() {
+                let socket = (dst_splice.socket)(&dst.io).expect("destination must have a socket");
+                match ready!(pipe.poll_splice_into(socket, cx)) {
+                    Ok(sz) => {
+                        trace!(direction = %self.direction, "spliced {}B", sz);
+                        (dst_splice.record_write)(&mut dst.io, sz);
+                        continue;
+                    }
+                    Err(error) if splice::is_unsupported(&error) => break error,
+                    Err(error) => {
+                        return Poll::Ready(Err((dst_splice.record_error)(&mut dst.io, error)))
+                    }
+                }
+            }
+
+            let socket = (src_splice.socket)(&self.io).expect("source must have a socket");
+            match ready!(pipe.poll_splice_from(socket, cx)) {
+                Ok(0) => return Poll::Ready(Ok(false)),
+                Ok(sz) => (src_splice.record_read)(&mut self.io, sz),
+                Err(error) if splice::is_unsupported(&error) => break error,
+                Err(error) => {
+                    return Poll::Ready(Err((src_splice.record_error)(&mut self.io, error)))
+                }
+            }
+        };
+
+        debug!(direction = %self.direction, %error, "Splicing failed; copying instead");
+        self.stop_splicing()?;
+        Poll::Ready(Ok(true))
+    }
+
+    /// Stops splicing, moving any bytes held by the pipe into the copy buffer
+    /// so that they are written before any others.
+    fn stop_splicing(&mut self) -> io::Result<()> {
+        let Some(mut pipe) = self.splice.take().and_then(|s| s.pipe) else {
+            return Ok(());
+        };
+        let mut buf = CopyBuf::with_capacity(pipe.len().max(COPY_BUF_CAPACITY));
+        while !pipe.is_empty() {
+            let sz = pipe.read(&mut buf.buf[buf.write_pos..])?;
+            buf.write_pos += sz;
+        }
+        self.buf = Some(buf);
+        Ok(())
+    }
 }
 
 fn write_zero() -> io::Error {
@@ -293,8 +448,12 @@ fn write_zero() -> io::Error {
 
 impl CopyBuf {
     fn new() -> Self {
+        Self::with_capacity(COPY_BUF_CAPACITY)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
         CopyBuf {
-            buf: Box::new([0; 8 * 1024]),
+            buf: vec![0; capacity].into_boxed_slice(),
             read_pos: 0,
             write_pos: 0,
         }
@@ -344,3 +503,96 @@ unsafe impl BufMut for CopyBuf {
         self.write_pos += cnt;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use io::{AsyncReadExt, AsyncWriteExt};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tokio::net::TcpListener;
+
+    #[derive(Clone, Debug, Default)]
+    struct Counts {
+        read: Arc<AtomicUsize>,
+        written: Arc<AtomicUsize>,
+    }
+
+    impl io::Sensor for Counts {
+        fn record_read(&mut self, sz: usize) {
+            self.read.fetch_add(sz, Ordering::Relaxed);
+        }
+
+        fn record_write(&mut self, sz: usize) {
+            self.written.fetch_add(sz, Ordering::Relaxed);
+        }
+
+        fn record_close(&mut self, _: Option<linkerd_errno::Errno>) {}
+
+        fn record_error<T>(&mut self, op: io::Poll<T>) -> io::Poll<T> {
+            op
+        }
+    }
+
+    /// Returns both ends of a TCP connection.
+    async fn connected() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn splices_with_accurate_counts() {
+        let (mut client, in_io) = connected().await;
+        let (out_io, mut server) = connected().await;
+        let (in_counts, out_counts) = (Counts::default(), Counts::default());
+
+        // The prefix must be copied before the sockets may be spliced.
+        let in_io = io::PrefixedIo::new(&b"pre"[..], io::SensorIo::new(in_io, in_counts.clone()));
+        let out_io = io::SensorIo::new(out_io, out_counts.clone());
+        let duplex = tokio::spawn(Duplex::with_splice(in_io, out_io));
+
+        client.write_all(b"hello").await.unwrap();
+        client.shutdown().await.unwrap();
+        let mut buf = Vec::new();
+        server.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, b"prehello");
+
+        server.write_all(b"world").await.unwrap();
+        server.shutdown().await.unwrap();
+        buf.clear();
+        client.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, b"world");
+
+        duplex.await.unwrap().expect("duplex must complete");
+        // The prefix was not read from the socket, so it is not counted.
+        assert_eq!(in_counts.read.load(Ordering::Relaxed), 5);
+        assert_eq!(in_counts.written.load(Ordering::Relaxed), 5);
+        assert_eq!(out_counts.read.load(Ordering::Relaxed), 5);
+        assert_eq!(out_counts.written.load(Ordering::Relaxed), 8);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn copies_spliced_bytes_when_splicing_stops() {
+        let (mut client, in_io) = connected().await;
+        client.write_all(b"hello").await.unwrap();
+
+        let mut half = HalfDuplex::new(in_io, "test").with_splice();
+        let mut pipe = splice::Pipe::new().expect("must create pipe");
+        let sz = std::future::poll_fn(|cx| pipe.poll_splice_from(&half.io, cx))
+            .await
+            .expect("must splice");
+        assert_eq!(sz, 5);
+        half.splice.as_mut().unwrap().pipe = Some(pipe);
+
+        half.stop_splicing().expect("must stop splicing");
+        assert!(half.splice.is_none());
+        assert_eq!(half.buf.as_ref().unwrap().chunk(), b"hello");
+    }
+}
@@ -0,0 +1,201 @@
+//! Moves bytes between sockets with `splice(2)`, through a pipe, so that they
+//! are not copied through userspace.
+
+use futures::ready;
+use linkerd_io as io;
+use std::task::{Context, Poll};
+use tokio::net::TcpStream;
+
+/// The maximum number of bytes moved by each call to `splice(2)`. This matches
+/// the default capacity of a pipe on Linux, so that splicing from a socket into
+/// an empty pipe never blocks on the pipe.
+#[cfg(target_os = "linux")]
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// A pipe holding bytes that have been read from one socket but not yet
+/// written to the other.
+pub(crate) struct Pipe {
+    #[cfg(target_os = "linux")]
+    read: std::os::fd::OwnedFd,
+    #[cfg(target_os = "linux")]
+    write: std::os::fd::OwnedFd,
+    len: usize,
+}
+
+// === impl Pipe ===
+
+impl Pipe {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new() -> io::Result<Self> {
+        let (read, write) = linux::pipe()?;
+        Ok(Self {
+            read,
+            write,
+            len: 0,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn new() -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "splice not supported on this operating system",
+        ))
+    }
+
+    /// Returns true if the pipe holds bytes that must be written before more
+    /// may be read.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes held by the pipe.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Reads bytes held by the pipe into `buf`, returning the number of bytes
+    /// read.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        use std::os::fd::AsRawFd;
+        let sz = linux::read(self.read.as_raw_fd(), buf)?;
+        if sz == 0 && !buf.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.len -= sz;
+        Ok(sz)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        unreachable!("pipes cannot be created on this operating system")
+    }
+
+    /// Reads bytes from `src` into the pipe, returning the number of bytes
+    /// read. Zero bytes are read when `src` has reached EOF.
+    pub(crate) fn poll_splice_from(
+        &mut self,
+        src: &TcpStream,
+        cx: &mut Context<'_>,
+    ) -> io::Poll<usize> {
+        debug_assert!(self.is_empty(), "must only read into an empty pipe");
+        loop {
+            ready!(src.poll_read_ready(cx))?;
+            match src.try_io(tokio::io::Interest::READABLE, || self.splice_from(src)) {
+                Ok(sz) => {
+                    self.len += sz;
+                    return Poll::Ready(Ok(sz));
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+
+    /// Writes bytes from the pipe to `dst`, returning the number of bytes
+    /// written.
+    pub(crate) fn poll_splice_into(
+        &mut self,
+        dst: &TcpStream,
+        cx: &mut Context<'_>,
+    ) -> io::Poll<usize> {
+        debug_assert!(!self.is_empty(), "must only write from a non-empty pipe");
+        loop {
+            ready!(dst.poll_write_ready(cx))?;
+            match dst.try_io(tokio::io::Interest::WRITABLE, || self.splice_into(dst)) {
+                Ok(0) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Ok(sz) => {
+                    self.len -= sz;
+                    return Poll::Ready(Ok(sz));
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn splice_from(&self, src: &TcpStream) -> io::Result<usize> {
+        use std::os::fd::AsRawFd;
+        linux::splice(src.as_raw_fd(), self.write.as_raw_fd(), PIPE_CAPACITY)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn splice_into(&self, dst: &TcpStream) -> io::Result<usize> {
+        use std::os::fd::AsRawFd;
+        linux::splice(self.read.as_raw_fd(), dst.as_raw_fd(), self.len)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn splice_from(&self, _: &TcpStream) -> io::Result<usize> {
+        unreachable!("pipes cannot be created on this operating system")
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn splice_into(&self, _: &TcpStream) -> io::Result<usize> {
+        unreachable!("pipes cannot be created on this operating system")
+    }
+}
+
+/// Returns true if `splice(2)` failed because it does not support the sockets,
+/// rather than because the connection failed, so that data may be copied
+/// instead.
+pub(crate) fn is_unsupported(error: &io::Error) -> bool {
+    #[cfg(target_os = "linux")]
+    if let Some(code) = error.raw_os_error() {
+        return matches!(code, libc::EINVAL | libc::ENOSYS | libc::EOPNOTSUPP);
+    }
+    error.kind() == io::ErrorKind::Unsupported
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod linux {
+    use std::{
+        io,
+        os::fd::{FromRawFd, OwnedFd, RawFd},
+        ptr,
+    };
+
+    pub(super) fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+        let mut fds = [0 as libc::c_int; 2];
+        // Safety: `fds` has room for the two descriptors written by `pipe2`.
+        let ret = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC) };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Safety: `pipe2` succeeded, so both descriptors are open and owned
+        // by no one else.
+        unsafe { Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1]))) }
+    }
+
+    pub(super) fn splice(from: RawFd, to: RawFd, len: usize) -> io::Result<usize> {
+        // Safety: Null offsets are required for sockets and pipes, and the
+        // descriptors are borrowed for the duration of the call.
+        let ret = unsafe {
+            libc::splice(
+                from,
+                ptr::null_mut(),
+                to,
+                ptr::null_mut(),
+                len,
+                libc::SPLICE_F_MOVE | libc::SPLICE_F_NONBLOCK,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ret as usize)
+    }
+
+    pub(super) fn read(fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+        // Safety: `buf` is valid for writes of `buf.len()` bytes, and the
+        // descriptor is borrowed for the duration of the call.
+        let ret = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ret as usize)
+    }
+}
@@ -0,0 +1,130 @@
+//! HTTP/1 `CONNECT` and absolute-form proxy requests.
+
+use super::{Error, Protocol, Request};
+use bytes::{Buf, BufMut, BytesMut};
+use linkerd_addr::Addr;
+use linkerd_io::{self as io, AsyncReadExt, AsyncWriteExt};
+
+pub(super) const CONNECTED: &[u8] = b"HTTP/1.1 200 Connection established\r\n\r\n";
+pub(super) const BAD_GATEWAY: &[u8] =
+    b"HTTP/1.1 502 Bad Gateway\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+const BAD_REQUEST: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+/// The maximum size of a request head.
+const MAX_HEAD_LEN: usize = 16 * 1024;
+
+const MAX_HEADERS: usize = 100;
+
+/// Headers that apply only to the client's connection to the proxy.
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authorization",
+    "proxy-connection",
+];
+
+pub(super) async fn accept<I>(io: &mut I, mut buf: BytesMut) -> Result<Request, Error>
+where
+    I: io::AsyncRead + io::AsyncWrite + Unpin,
+{
+    let res = read(io, &mut buf).await;
+    if let Err(
+        Error::HeadTooLarge(_)
+        | Error::InvalidHttp(_)
+        | Error::NotProxyRequest
+        | Error::InvalidTarget(_),
+    ) = res
+    {
+        // The client may already be gone, in which case there's no one to
+        // inform.
+        let _ = io.write_all(BAD_REQUEST).await;
+    }
+    res
+}
+
+async fn read(io: &mut (impl io::AsyncRead + Unpin), buf: &mut BytesMut) -> Result<Request, Error> {
+    loop {
+        if let Some((len, protocol, target, head)) = decode(buf)? {
+            buf.advance(len);
+            let prefix = match head {
+                Some(mut head) => {
+                    head.extend_from_slice(buf);
+                    head.freeze()
+                }
+                None => buf.split().freeze(),
+            };
+            return Ok(Request {
+                protocol,
+                target,
+                prefix,
+            });
+        }
+
+        if buf.len() >= MAX_HEAD_LEN {
+            return Err(Error::HeadTooLarge(MAX_HEAD_LEN));
+        }
+        if io.read_buf(buf).await? == 0 {
+            return Err(Error::Closed);
+        }
+    }
+}
+
+/// Decodes a complete request head, returning its length, the request's
+/// target, and, for absolute-form requests, the head to forward to the target.
+#[allow(clippy::type_complexity)]
+fn decode(buf: &[u8]) -> Result<Option<(usize, Protocol, Addr, Option<BytesMut>)>, Error> {
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut req = httparse::Request::new(&mut headers);
+    let httparse::Status::Complete(len) = req.parse(buf)? else {
+        return Ok(None);
+    };
+    let method = req.method.unwrap_or_default();
+    let path = req.path.unwrap_or_default();
+
+    if method == "CONNECT" {
+        let target = path.parse::<Addr>()?;
+        return Ok(Some((len, Protocol::Connect, target, None)));
+    }
+
+    let uri = path
+        .parse::<http::Uri>()
+        .map_err(|_| Error::NotProxyRequest)?;
+    let authority = uri.authority().ok_or(Error::NotProxyRequest)?;
+    let default_port = if uri.scheme() == Some(&http::uri::Scheme::HTTPS) {
+        443
+    } else {
+        80
+    };
+    let host = authority
+        .host()
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = authority.port_u16().unwrap_or(default_port);
+    let target = Addr::from_str_and_port(host, port)?;
+
+    // The client may reuse its connection to the proxy for requests to other
+    // authorities, but the forwarded connection is bound to this request's
+    // target. Proxy-specific headers are dropped and the connection is closed
+    // after the response so that each request is routed independently.
+    let mut head = BytesMut::with_capacity(len);
+    head.put_slice(method.as_bytes());
+    head.put_u8(b' ');
+    head.put_slice(path.as_bytes());
+    head.put_slice(match req.version {
+        Some(0) => b" HTTP/1.0\r\n",
+        _ => b" HTTP/1.1\r\n",
+    });
+    for h in req.headers.iter() {
+        if HOP_BY_HOP.iter().any(|n| h.name.eq_ignore_ascii_case(n)) {
+            continue;
+        }
+        head.put_slice(h.name.as_bytes());
+        head.put_slice(b": ");
+        head.put_slice(h.value);
+        head.put_slice(b"\r\n");
+    }
+    head.put_slice(b"connection: close\r\n\r\n");
+
+    Ok(Some((len, Protocol::AbsoluteForm, target, Some(head))))
+}
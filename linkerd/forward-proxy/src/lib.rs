@@ -0,0 +1,308 @@
+//! Accepts connections from clients that are explicitly configured to use the
+//! proxy (e.g. via `HTTP_PROXY`), so that each connection's target is read from
+//! its handshake rather than from its original destination address.
+//!
+//! Three handshakes are supported:
+//!
+//! - HTTP `CONNECT` requests, which open a tunnel to the requested authority;
+//! - absolute-form HTTP/1 requests (e.g. `GET http://example.com/ HTTP/1.1`),
+//!   which are forwarded to the URI's authority; and
+//! - [SOCKS5] `CONNECT` commands that do not require authentication.
+//!
+//! Connections may also be tunneled through an upstream HTTP proxy with
+//! [`connect`].
+//!
+//! [SOCKS5]: https://www.rfc-editor.org/rfc/rfc1928
+
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![forbid(unsafe_code)]
+
+mod http;
+mod socks5;
+mod upstream;
+
+use bytes::{Bytes, BytesMut};
+use linkerd_io::{self as io, AsyncReadExt, AsyncWriteExt};
+use thiserror::Error;
+use tracing::trace;
+
+pub use self::upstream::connect;
+pub use linkerd_addr::Addr;
+
+/// A client's request to forward its connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub protocol: Protocol,
+
+    /// The address to which the connection should be forwarded.
+    pub target: Addr,
+
+    /// Bytes read from the client that must be forwarded to the target before
+    /// the remainder of the connection.
+    pub prefix: Bytes,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Connect,
+    AbsoluteForm,
+    Socks5,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("connection closed before completing a handshake")]
+    Closed,
+
+    #[error("HTTP request head exceeds {0} bytes")]
+    HeadTooLarge(usize),
+
+    #[error("invalid HTTP request: {0}")]
+    InvalidHttp(#[from] httparse::Error),
+
+    #[error("HTTP requests must use CONNECT or an absolute-form URI")]
+    NotProxyRequest,
+
+    #[error("invalid target address: {0}")]
+    InvalidTarget(#[from] linkerd_addr::Error),
+
+    #[error("SOCKS5 client does not support unauthenticated connections")]
+    NoAcceptableAuth,
+
+    #[error("unsupported SOCKS version {0:#04x}")]
+    UnsupportedVersion(u8),
+
+    #[error("unsupported SOCKS5 command {0:#04x}")]
+    UnsupportedCommand(u8),
+
+    #[error("unsupported SOCKS5 address type {0:#04x}")]
+    UnsupportedAddressType(u8),
+
+    #[error("invalid HTTP response from upstream proxy: {0}")]
+    InvalidResponse(#[source] httparse::Error),
+
+    #[error("upstream proxy refused CONNECT with status {0}")]
+    Refused(u16),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Reads a forward-proxy handshake from the client.
+///
+/// Clients that send an invalid or unsupported request are sent an error
+/// response before the error is returned.
+pub async fn accept<I>(io: &mut I) -> Result<Request, Error>
+where
+    I: io::AsyncRead + io::AsyncWrite + Unpin,
+{
+    let mut buf = BytesMut::with_capacity(1024);
+    if io.read_buf(&mut buf).await? == 0 {
+        return Err(Error::Closed);
+    }
+    let req = if buf[0] == socks5::VERSION {
+        socks5::accept(io, buf).await?
+    } else {
+        http::accept(io, buf).await?
+    };
+    trace!(?req.protocol, %req.target, prefix = req.prefix.len(), "Accepted");
+    Ok(req)
+}
+
+// === impl Request ===
+
+impl Request {
+    /// Informs the client that its connection is being forwarded.
+    ///
+    /// Absolute-form requests do not have a handshake response, so nothing is
+    /// written to them.
+    pub async fn accepted(&self, io: &mut (impl io::AsyncWrite + Unpin)) -> io::Result<()> {
+        match self.protocol {
+            Protocol::Connect => io.write_all(http::CONNECTED).await,
+            Protocol::AbsoluteForm => Ok(()),
+            Protocol::Socks5 => io.write_all(&socks5::reply(socks5::SUCCEEDED)).await,
+        }
+    }
+
+    /// Informs the client that its target could not be reached.
+    pub async fn rejected(&self, io: &mut (impl io::AsyncWrite + Unpin)) -> io::Result<()> {
+        match self.protocol {
+            Protocol::Connect | Protocol::AbsoluteForm => io.write_all(http::BAD_GATEWAY).await,
+            Protocol::Socks5 => io.write_all(&socks5::reply(socks5::HOST_UNREACHABLE)).await,
+        }
+    }
+}
+
+/// Reads from `io` until `buf` holds at least `len` bytes.
+async fn fill(
+    io: &mut (impl io::AsyncRead + Unpin),
+    buf: &mut BytesMut,
+    len: usize,
+) -> Result<(), Error> {
+    while buf.len() < len {
+        if io.read_buf(buf).await? == 0 {
+            return Err(Error::Closed);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linkerd_addr::NameAddr;
+
+    fn name(s: &str) -> Addr {
+        Addr::Name(s.parse::<NameAddr>().unwrap())
+    }
+
+    #[tokio::test]
+    async fn http_connect() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(b"CONNECT example.com:443 HTTP/1.1\r\n")
+            .read(b"Host: example.com:443\r\n\r\n\x16\x03\x01")
+            .write(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .build();
+        let req = accept(&mut io).await.expect("must accept");
+        assert_eq!(req.protocol, Protocol::Connect);
+        assert_eq!(req.target, name("example.com:443"));
+        assert_eq!(req.prefix, &b"\x16\x03\x01"[..]);
+        req.accepted(&mut io).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_connect_ip() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(b"CONNECT [2001:db8::1]:8080 HTTP/1.1\r\n\r\n")
+            .write(b"HTTP/1.1 502 Bad Gateway\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .build();
+        let req = accept(&mut io).await.expect("must accept");
+        assert_eq!(
+            req.target,
+            Addr::Socket("[2001:db8::1]:8080".parse().unwrap())
+        );
+        assert!(req.prefix.is_empty());
+        req.rejected(&mut io).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_absolute_form() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(b"POST http://example.com/path?q=1 HTTP/1.1\r\nHost: example.com\r\n")
+            .read(b"Proxy-Connection: keep-alive\r\nProxy-Authorization: Basic Zm9vOmJhcg==\r\n")
+            .read(b"Connection: keep-alive\r\nContent-Length: 4\r\n\r\nbody")
+            .build();
+        let req = accept(&mut io).await.expect("must accept");
+        assert_eq!(req.protocol, Protocol::AbsoluteForm);
+        assert_eq!(req.target, name("example.com:80"));
+        assert_eq!(
+            req.prefix,
+            &b"POST http://example.com/path?q=1 HTTP/1.1\r\nHost: example.com\r\n\
+               Content-Length: 4\r\nconnection: close\r\n\r\nbody"[..]
+        );
+        req.accepted(&mut io).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_origin_form() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n")
+            .write(b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .build();
+        let err = accept(&mut io).await.expect_err("must reject");
+        assert!(matches!(err, Error::NotProxyRequest), "{err}");
+    }
+
+    #[tokio::test]
+    async fn http_connect_without_port() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(b"CONNECT example.com HTTP/1.1\r\n\r\n")
+            .write(b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .build();
+        let err = accept(&mut io).await.expect_err("must reject");
+        assert!(matches!(err, Error::InvalidTarget(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn socks5_domain() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(&[0x05, 0x02, 0x02, 0x00])
+            .write(&[0x05, 0x00])
+            .read(&[0x05, 0x01, 0x00, 0x03, 11])
+            .read(b"example.com")
+            .read(&[0x01, 0xbb])
+            .write(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+            .build();
+        let req = accept(&mut io).await.expect("must accept");
+        assert_eq!(req.protocol, Protocol::Socks5);
+        assert_eq!(req.target, name("example.com:443"));
+        assert!(req.prefix.is_empty());
+        req.accepted(&mut io).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn socks5_ipv4() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(&[0x05, 0x01, 0x00])
+            .write(&[0x05, 0x00])
+            .read(&[0x05, 0x01, 0x00, 0x01, 192, 0, 2, 1, 0x1f, 0x90])
+            .write(&[0x05, 0x04, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+            .build();
+        let req = accept(&mut io).await.expect("must accept");
+        assert_eq!(req.target, Addr::Socket(([192, 0, 2, 1], 8080).into()));
+        req.rejected(&mut io).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn socks5_requires_auth() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(&[0x05, 0x01, 0x02])
+            .write(&[0x05, 0xff])
+            .build();
+        let err = accept(&mut io).await.expect_err("must reject");
+        assert!(matches!(err, Error::NoAcceptableAuth), "{err}");
+    }
+
+    #[tokio::test]
+    async fn socks5_bind() {
+        let mut io = tokio_test::io::Builder::new()
+            .read(&[0x05, 0x01, 0x00])
+            .write(&[0x05, 0x00])
+            .read(&[0x05, 0x02, 0x00, 0x01, 192, 0, 2, 1, 0x1f, 0x90])
+            .write(&[0x05, 0x07, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+            .build();
+        let err = accept(&mut io).await.expect_err("must reject");
+        assert!(matches!(err, Error::UnsupportedCommand(0x02)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn upstream_connect() {
+        let mut io = tokio_test::io::Builder::new()
+            .write(b"CONNECT 192.0.2.2:443 HTTP/1.1\r\nhost: 192.0.2.2:443\r\n")
+            .write(b"proxy-authorization: Basic Zm9vOmJhcg==\r\n\r\n")
+            .read(b"HTTP/1.1 200 Connection established\r\n")
+            .read(b"Proxy-Agent: test\r\n\r\n\x16\x03\x03")
+            .build();
+        let auth = ::http::HeaderValue::from_static("Basic Zm9vOmJhcg==");
+        let prefix = connect(
+            &mut io,
+            &Addr::Socket(([192, 0, 2, 2], 443).into()),
+            Some(&auth),
+        )
+        .await
+        .expect("must connect");
+        assert_eq!(prefix, &b"\x16\x03\x03"[..]);
+    }
+
+    #[tokio::test]
+    async fn upstream_refused() {
+        let mut io = tokio_test::io::Builder::new()
+            .write(b"CONNECT example.com:443 HTTP/1.1\r\nhost: example.com:443\r\n\r\n")
+            .read(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+            .build();
+        let err = connect(&mut io, &name("example.com:443"), None)
+            .await
+            .expect_err("must fail");
+        assert!(matches!(err, Error::Refused(407)), "{err}");
+    }
+}
@@ -0,0 +1,116 @@
+//! SOCKS5 `CONNECT` commands, as described in [RFC 1928][rfc].
+//!
+//! [rfc]: https://www.rfc-editor.org/rfc/rfc1928
+
+use super::{fill, Error, Protocol, Request};
+use bytes::{Buf, BytesMut};
+use linkerd_addr::Addr;
+use linkerd_io::{self as io, AsyncWriteExt};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub(super) const VERSION: u8 = 0x05;
+
+const NO_AUTHENTICATION: u8 = 0x00;
+const NO_ACCEPTABLE_METHODS: u8 = 0xff;
+
+const CMD_CONNECT: u8 = 0x01;
+
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+pub(super) const SUCCEEDED: u8 = 0x00;
+const GENERAL_FAILURE: u8 = 0x01;
+pub(super) const HOST_UNREACHABLE: u8 = 0x04;
+const COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const ADDRESS_TYPE_NOT_SUPPORTED: u8 = 0x08;
+
+pub(super) async fn accept<I>(io: &mut I, mut buf: BytesMut) -> Result<Request, Error>
+where
+    I: io::AsyncRead + io::AsyncWrite + Unpin,
+{
+    // The client offers a list of authentication methods, of which only
+    // unauthenticated connections are supported.
+    fill(io, &mut buf, 2).await?;
+    let len = 2 + buf[1] as usize;
+    fill(io, &mut buf, len).await?;
+    let greeting = buf.split_to(len);
+    if !greeting[2..].contains(&NO_AUTHENTICATION) {
+        io.write_all(&[VERSION, NO_ACCEPTABLE_METHODS]).await?;
+        return Err(Error::NoAcceptableAuth);
+    }
+    io.write_all(&[VERSION, NO_AUTHENTICATION]).await?;
+
+    match read_target(io, &mut buf).await {
+        Ok(target) => Ok(Request {
+            protocol: Protocol::Socks5,
+            target,
+            prefix: buf.freeze(),
+        }),
+        Err(error) => {
+            let code = match error {
+                Error::UnsupportedCommand(_) => COMMAND_NOT_SUPPORTED,
+                Error::UnsupportedAddressType(_) => ADDRESS_TYPE_NOT_SUPPORTED,
+                Error::Closed | Error::Io(_) => return Err(error),
+                _ => GENERAL_FAILURE,
+            };
+            // The client may already be gone, in which case there's no one to
+            // inform.
+            let _ = io.write_all(&reply(code)).await;
+            Err(error)
+        }
+    }
+}
+
+/// Reads the target of a request, consuming it from `buf`.
+async fn read_target(
+    io: &mut (impl io::AsyncRead + Unpin),
+    buf: &mut BytesMut,
+) -> Result<Addr, Error> {
+    // VER | CMD | RSV | ATYP | DST.ADDR | DST.PORT
+    fill(io, buf, 4).await?;
+    if buf[0] != VERSION {
+        return Err(Error::UnsupportedVersion(buf[0]));
+    }
+    if buf[1] != CMD_CONNECT {
+        return Err(Error::UnsupportedCommand(buf[1]));
+    }
+    let atyp = buf[3];
+    let addr_len = match atyp {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => {
+            fill(io, buf, 5).await?;
+            1 + buf[4] as usize
+        }
+        atyp => return Err(Error::UnsupportedAddressType(atyp)),
+    };
+    fill(io, buf, 4 + addr_len + 2).await?;
+    let mut req = buf.split_to(4 + addr_len + 2);
+    req.advance(4);
+
+    let target = match atyp {
+        ATYP_IPV4 => {
+            let ip = Ipv4Addr::from(req.get_u32());
+            Addr::Socket((ip, req.get_u16()).into())
+        }
+        ATYP_IPV6 => {
+            let ip = Ipv6Addr::from(req.get_u128());
+            Addr::Socket((ip, req.get_u16()).into())
+        }
+        _ => {
+            let len = req.get_u8() as usize;
+            let name = req.split_to(len);
+            let name = std::str::from_utf8(&name)
+                .map_err(|_| Error::InvalidTarget(linkerd_addr::Error::InvalidHost))?;
+            Addr::from_str_and_port(name, req.get_u16())?
+        }
+    };
+    Ok(target)
+}
+
+/// Encodes a reply to a request. The bound address is not meaningful to
+/// clients of a forward proxy, so it is left unspecified.
+pub(super) fn reply(code: u8) -> [u8; 10] {
+    [VERSION, code, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0]
+}
@@ -0,0 +1,64 @@
+//! Tunnels connections through an upstream HTTP proxy with `CONNECT` requests.
+
+use super::{Addr, Error};
+use bytes::{BufMut, Bytes, BytesMut};
+use linkerd_io::{self as io, AsyncReadExt, AsyncWriteExt};
+use tracing::debug;
+
+/// The maximum size of a response head.
+const MAX_HEAD_LEN: usize = 16 * 1024;
+
+const MAX_HEADERS: usize = 100;
+
+/// Requests a tunnel to `target` from the upstream proxy on the other end of
+/// `io`, authenticating with `authorization` if it is set.
+///
+/// Returns any bytes read from the target after the proxy's response, which
+/// must be read before the remainder of the connection.
+pub async fn connect<I>(
+    io: &mut I,
+    target: &Addr,
+    authorization: Option<&http::HeaderValue>,
+) -> Result<Bytes, Error>
+where
+    I: io::AsyncRead + io::AsyncWrite + Unpin,
+{
+    let mut req = BytesMut::with_capacity(256);
+    req.put_slice(format!("CONNECT {target} HTTP/1.1\r\nhost: {target}\r\n").as_bytes());
+    if let Some(auth) = authorization {
+        req.put_slice(b"proxy-authorization: ");
+        req.put_slice(auth.as_bytes());
+        req.put_slice(b"\r\n");
+    }
+    req.put_slice(b"\r\n");
+    io.write_all(&req).await?;
+
+    let mut buf = BytesMut::with_capacity(1024);
+    loop {
+        if let Some(len) = decode(&buf)? {
+            debug!(%target, "Connected through upstream proxy");
+            return Ok(buf.split_off(len).freeze());
+        }
+
+        if buf.len() >= MAX_HEAD_LEN {
+            return Err(Error::HeadTooLarge(MAX_HEAD_LEN));
+        }
+        if io.read_buf(&mut buf).await? == 0 {
+            return Err(Error::Closed);
+        }
+    }
+}
+
+/// Decodes a complete response head, returning its length if the proxy
+/// established the tunnel.
+fn decode(buf: &[u8]) -> Result<Option<usize>, Error> {
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut rsp = httparse::Response::new(&mut headers);
+    let httparse::Status::Complete(len) = rsp.parse(buf).map_err(Error::InvalidResponse)? else {
+        return Ok(None);
+    };
+    match rsp.code {
+        Some(code) if (200..300).contains(&code) => Ok(Some(len)),
+        code => Err(Error::Refused(code.unwrap_or_default())),
+    }
+}
@@ -33,6 +33,40 @@ impl<L: io::PeerAddr, R: io::PeerAddr> io::PeerAddr for EitherIo<L, R> {
     }
 }
 
+impl<L: io::Splice, R: io::Splice> io::Splice for EitherIo<L, R> {
+    #[inline]
+    fn splice_socket(&self) -> Option<&tokio::net::TcpStream> {
+        match self {
+            Self::Left(l) => l.splice_socket(),
+            Self::Right(r) => r.splice_socket(),
+        }
+    }
+
+    #[inline]
+    fn record_splice_read(&mut self, sz: usize) {
+        match self {
+            Self::Left(l) => l.record_splice_read(sz),
+            Self::Right(r) => r.record_splice_read(sz),
+        }
+    }
+
+    #[inline]
+    fn record_splice_write(&mut self, sz: usize) {
+        match self {
+            Self::Left(l) => l.record_splice_write(sz),
+            Self::Right(r) => r.record_splice_write(sz),
+        }
+    }
+
+    #[inline]
+    fn record_splice_error(&mut self, error: io::Error) -> io::Error {
+        match self {
+            Self::Left(l) => l.record_splice_error(error),
+            Self::Right(r) => r.record_splice_error(error),
+        }
+    }
+}
+
 impl<L: io::AsyncRead, R: io::AsyncRead> io::AsyncRead for EitherIo<L, R> {
     #[inline]
     fn poll_read(
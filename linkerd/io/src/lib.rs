@@ -76,3 +76,42 @@ impl PeerAddr for tokio::io::DuplexStream {
         Ok(([0, 0, 0, 0], 0).into())
     }
 }
+
+// === Splice ===
+
+/// A stream whose bytes may be moved to and from other sockets by the kernel
+/// (e.g. with Linux's `splice(2)`), without being copied through userspace.
+pub trait Splice {
+    /// Returns the stream's socket, if bytes may be read from and written to
+    /// it directly. Streams that buffer or transform data (e.g. TLS) return
+    /// `None`.
+    fn splice_socket(&self) -> Option<&tokio::net::TcpStream> {
+        None
+    }
+
+    /// Records that `sz` bytes were read from the stream's socket directly.
+    fn record_splice_read(&mut self, _sz: usize) {}
+
+    /// Records that `sz` bytes were written to the stream's socket directly.
+    fn record_splice_write(&mut self, _sz: usize) {}
+
+    /// Records that reading from or writing to the stream's socket directly
+    /// failed, returning the error.
+    fn record_splice_error(&mut self, error: Error) -> Error {
+        error
+    }
+}
+
+impl Splice for tokio::net::TcpStream {
+    fn splice_socket(&self) -> Option<&tokio::net::TcpStream> {
+        Some(self)
+    }
+}
+
+#[cfg(unix)]
+impl Splice for tokio::net::UnixStream {}
+
+#[cfg(feature = "tokio-test")]
+impl Splice for tokio_test::io::Mock {}
+
+impl Splice for tokio::io::DuplexStream {}
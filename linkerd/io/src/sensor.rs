@@ -1,4 +1,4 @@
-use crate::{IoSlice, Peek, PeerAddr, Poll};
+use crate::{IoSlice, Peek, PeerAddr, Poll, Splice};
 use futures::ready;
 use linkerd_errno::Errno;
 use pin_project::pin_project;
@@ -83,6 +83,30 @@ impl<T: PeerAddr, S> PeerAddr for SensorIo<T, S> {
     }
 }
 
+impl<T: Splice, S: Sensor> Splice for SensorIo<T, S> {
+    fn splice_socket(&self) -> Option<&tokio::net::TcpStream> {
+        self.io.splice_socket()
+    }
+
+    fn record_splice_read(&mut self, sz: usize) {
+        self.sensor.record_read(sz);
+        self.io.record_splice_read(sz);
+    }
+
+    fn record_splice_write(&mut self, sz: usize) {
+        self.sensor.record_write(sz);
+        self.io.record_splice_write(sz);
+    }
+
+    fn record_splice_error(&mut self, error: std::io::Error) -> std::io::Error {
+        let error = match self.sensor.record_error::<()>(Poll::Ready(Err(error))) {
+            Poll::Ready(Err(error)) => error,
+            _ => unreachable!("sensors must not discard errors"),
+        };
+        self.io.record_splice_error(error)
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Peek + Send + Sync, S: Sensor + Sync> Peek for SensorIo<I, S> {
     async fn peek(&self, buf: &mut [u8]) -> Result<usize> {
@@ -4,15 +4,91 @@
 #![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
 #![forbid(unsafe_code)]
 
-/// Groups routing rules under a common set of SNIs.
+use linkerd_dns_name::Name;
+use std::net::IpAddr;
+use tracing::trace;
+
+pub mod r#match;
+#[cfg(test)]
+mod tests;
+
+pub use self::r#match::{InvalidPortRange, MatchSession, PortRange, SessionMatch};
+
+/// Groups routing rules for an opaque TCP target.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Route<P> {
     /// Must not be empty.
-    pub policy: P,
+    pub rules: Vec<Rule<P>>,
 }
 
 /// Policies for a given set of route matches.
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
 pub struct Rule<P> {
+    /// A list of session matchers, *any* of which may apply.
+    ///
+    /// The "best" match is used when comparing rules. When no matches are
+    /// present, the rule matches all sessions.
+    pub matches: Vec<MatchSession>,
+
     pub policy: P,
 }
+
+/// Summarizes a matched route so that route matches may be compared/ordered. A
+/// greater match is preferred over a lesser match.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct RouteMatch {
+    session: SessionMatch,
+}
+
+/// Provides metadata about an opaque TCP session that may be used to select a
+/// route.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SessionInfo {
+    /// The port to which the client connected.
+    pub dst_port: u16,
+
+    /// The client's source address.
+    pub client_addr: IpAddr,
+
+    /// The logical name of the session's target, if one is known.
+    ///
+    /// The outbound proxy sets this to the name for which routes were
+    /// discovered: the transport-header name on gateway connections, or the
+    /// name resolved for the original destination address otherwise.
+    pub name: Option<Name>,
+}
+
+/// Finds the best matching route policy for a session.
+pub fn find<'r, P>(routes: &'r [Route<P>], session: &SessionInfo) -> Option<(RouteMatch, &'r P)> {
+    trace!(routes = ?routes.len(), "Finding matching route");
+
+    best(routes.iter().filter_map(|rt| {
+        trace!(rules = %rt.rules.len());
+        let (session, policy) = best(rt.rules.iter().filter_map(|rule| {
+            // If there are no matches in the list, then the rule has an
+            // implicit default match.
+            if rule.matches.is_empty() {
+                trace!("implicit match");
+                return Some((SessionMatch::default(), &rule.policy));
+            }
+            // Find the best match to compare against other rules/routes (if
+            // any apply). The order/precedence of matches is not relevant.
+            let summary = rule
+                .matches
+                .iter()
+                .filter_map(|m| m.summarize_match(session))
+                .max()?;
+            trace!("matches!");
+            Some((summary, &rule.policy))
+        }))?;
+
+        Some((RouteMatch { session }, policy))
+    }))
+}
+
+#[inline]
+fn best<M: Ord, P>(matches: impl Iterator<Item = (M, P)>) -> Option<(M, P)> {
+    // This is roughly equivalent to `max_by(...)` but we want to ensure
+    // that the first match wins.
+    matches.reduce(|(m0, p0), (m1, p1)| if m0 >= m1 { (m0, p0) } else { (m1, p1) })
+}
@@ -0,0 +1,284 @@
+use crate::SessionInfo;
+use ipnet::IpNet;
+use linkerd_dns_name::Name;
+
+/// Matches opaque TCP sessions.
+///
+/// Each non-empty list must contain at least one value that applies to the
+/// session for the match to apply. Empty lists match all sessions.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct MatchSession {
+    /// Destination port ranges.
+    pub ports: Vec<PortRange>,
+
+    /// Client source networks.
+    pub client_networks: Vec<IpNet>,
+
+    /// Logical target names, e.g. as carried in a transport header. Sessions
+    /// without a known name never match a non-empty list.
+    pub names: Vec<Name>,
+}
+
+/// An inclusive range of destination ports.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PortRange {
+    min: u16,
+    max: u16,
+}
+
+/// Summarizes a matched session.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct SessionMatch {
+    name: bool,
+
+    /// The number of ports in the narrowest matching port range.
+    ports: Option<u32>,
+
+    /// The prefix length of the most specific matching client network.
+    client_network: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidPortRange {
+    #[error("invalid port: {0}")]
+    Port(#[from] std::num::ParseIntError),
+
+    #[error("port range minimum {min} must not exceed its maximum {max}")]
+    Inverted { min: u16, max: u16 },
+}
+
+// === impl MatchSession ===
+
+impl MatchSession {
+    pub fn summarize_match(&self, session: &SessionInfo) -> Option<SessionMatch> {
+        let mut summary = SessionMatch::default();
+
+        if !self.names.is_empty() {
+            let name = session.name.as_ref()?;
+            if !self.names.contains(name) {
+                return None;
+            }
+            summary.name = true;
+        }
+
+        if !self.ports.is_empty() {
+            let ports = self
+                .ports
+                .iter()
+                .filter(|r| r.contains(session.dst_port))
+                .map(PortRange::num_ports)
+                .min()?;
+            summary.ports = Some(ports);
+        }
+
+        if !self.client_networks.is_empty() {
+            let prefix_len = self
+                .client_networks
+                .iter()
+                .filter(|net| net.contains(&session.client_addr))
+                .map(IpNet::prefix_len)
+                .max()?;
+            summary.client_network = Some(prefix_len);
+        }
+
+        Some(summary)
+    }
+}
+
+// === impl PortRange ===
+
+impl PortRange {
+    pub fn new(min: u16, max: u16) -> Result<Self, InvalidPortRange> {
+        if min > max {
+            return Err(InvalidPortRange::Inverted { min, max });
+        }
+        Ok(Self { min, max })
+    }
+
+    #[inline]
+    pub fn contains(&self, port: u16) -> bool {
+        self.min <= port && port <= self.max
+    }
+
+    /// Returns the number of ports in the range.
+    #[inline]
+    pub fn num_ports(&self) -> u32 {
+        u32::from(self.max - self.min) + 1
+    }
+}
+
+impl From<u16> for PortRange {
+    fn from(port: u16) -> Self {
+        Self {
+            min: port,
+            max: port,
+        }
+    }
+}
+
+impl std::str::FromStr for PortRange {
+    type Err = InvalidPortRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('-') {
+            Some((min, max)) => Self::new(min.trim().parse()?, max.trim().parse()?),
+            None => Ok(Self::from(s.trim().parse::<u16>()?)),
+        }
+    }
+}
+
+// === impl SessionMatch ===
+
+impl std::cmp::PartialOrd for SessionMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::cmp::Ord for SessionMatch {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        // A name match is the most specific, followed by the
+        // narrowest port range and then the longest client network prefix.
+        self.name
+            .cmp(&other.name)
+            .then_with(|| match (self.ports, other.ports) {
+                (Some(l), Some(r)) => r.cmp(&l),
+                (Some(_), None) => Ordering::Greater,
+                (None, Some(_)) => Ordering::Less,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| self.client_network.cmp(&other.client_network))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(dst_port: u16, client: &str, name: Option<&str>) -> SessionInfo {
+        SessionInfo {
+            dst_port,
+            client_addr: client.parse().unwrap(),
+            name: name.map(|n| n.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn port_range_parse() {
+        assert_eq!("80".parse::<PortRange>().unwrap(), PortRange::from(80));
+        assert_eq!(
+            "8000-8080".parse::<PortRange>().unwrap(),
+            PortRange::new(8000, 8080).unwrap()
+        );
+        assert_eq!(
+            "8080-8000".parse::<PortRange>(),
+            Err(InvalidPortRange::Inverted {
+                min: 8080,
+                max: 8000
+            })
+        );
+        assert!("http".parse::<PortRange>().is_err());
+        assert!("0-65536".parse::<PortRange>().is_err());
+    }
+
+    #[test]
+    fn ports() {
+        let m = MatchSession {
+            ports: vec!["8000-8999".parse().unwrap(), 8080.into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            m.summarize_match(&session(8080, "10.0.0.1", None)),
+            Some(SessionMatch {
+                ports: Some(1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            m.summarize_match(&session(8081, "10.0.0.1", None)),
+            Some(SessionMatch {
+                ports: Some(1000),
+                ..Default::default()
+            })
+        );
+        assert_eq!(m.summarize_match(&session(9000, "10.0.0.1", None)), None);
+    }
+
+    #[test]
+    fn client_networks() {
+        let m = MatchSession {
+            client_networks: vec![
+                "10.0.0.0/8".parse().unwrap(),
+                "10.1.0.0/16".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            m.summarize_match(&session(80, "10.1.2.3", None)),
+            Some(SessionMatch {
+                client_network: Some(16),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            m.summarize_match(&session(80, "10.2.2.3", None)),
+            Some(SessionMatch {
+                client_network: Some(8),
+                ..Default::default()
+            })
+        );
+        assert_eq!(m.summarize_match(&session(80, "192.168.0.1", None)), None);
+    }
+
+    #[test]
+    fn names() {
+        let m = MatchSession {
+            names: vec!["web.ns.svc.cluster.local".parse().unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(
+            m.summarize_match(&session(80, "10.0.0.1", Some("web.ns.svc.cluster.local"))),
+            Some(SessionMatch {
+                name: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            m.summarize_match(&session(80, "10.0.0.1", Some("api.ns.svc.cluster.local"))),
+            None
+        );
+        assert_eq!(m.summarize_match(&session(80, "10.0.0.1", None)), None);
+    }
+
+    #[test]
+    fn cmp() {
+        let name = SessionMatch {
+            name: true,
+            ..Default::default()
+        };
+        let port = SessionMatch {
+            ports: Some(1),
+            ..Default::default()
+        };
+        let range = SessionMatch {
+            ports: Some(100),
+            ..Default::default()
+        };
+        let net = SessionMatch {
+            client_network: Some(32),
+            ..Default::default()
+        };
+        assert!(name > port);
+        assert!(port > range);
+        assert!(range > net);
+        assert!(net > SessionMatch::default());
+        assert!(
+            SessionMatch {
+                client_network: Some(24),
+                ..range.clone()
+            } > range
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Policy {
+    Expected,
+    Unexpected,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::Unexpected
+    }
+}
+
+fn session(dst_port: u16, client: &str) -> SessionInfo {
+    SessionInfo {
+        dst_port,
+        client_addr: client.parse().expect("must parse"),
+        name: None,
+    }
+}
+
+/// Given two rules, choose the one with a narrower port match.
+#[test]
+fn port_precedence() {
+    let rts = vec![Route {
+        rules: vec![
+            Rule {
+                matches: vec![MatchSession {
+                    ports: vec!["8000-8999".parse().unwrap()],
+                    ..Default::default()
+                }],
+                policy: Policy::Unexpected,
+            },
+            Rule {
+                matches: vec![MatchSession {
+                    ports: vec![8080.into()],
+                    ..Default::default()
+                }],
+                policy: Policy::Expected,
+            },
+        ],
+    }];
+
+    let (_, policy) = find(&rts, &session(8080, "10.0.0.1")).expect("must match");
+    assert_eq!(*policy, Policy::Expected, "incorrect rule matched");
+}
+
+/// A port match is preferred over a client network match.
+#[test]
+fn port_over_client_network() {
+    let rts = vec![Route {
+        rules: vec![
+            Rule {
+                matches: vec![MatchSession {
+                    client_networks: vec!["10.0.0.1/32".parse().unwrap()],
+                    ..Default::default()
+                }],
+                policy: Policy::Unexpected,
+            },
+            Rule {
+                matches: vec![MatchSession {
+                    ports: vec![8080.into()],
+                    ..Default::default()
+                }],
+                policy: Policy::Expected,
+            },
+        ],
+    }];
+
+    let (_, policy) = find(&rts, &session(8080, "10.0.0.1")).expect("must match");
+    assert_eq!(*policy, Policy::Expected, "incorrect rule matched");
+}
+
+/// A name match is preferred over all other matches.
+#[test]
+fn name_precedence() {
+    let rts = vec![Route {
+        rules: vec![
+            Rule {
+                matches: vec![MatchSession {
+                    ports: vec![8080.into()],
+                    client_networks: vec!["10.0.0.1/32".parse().unwrap()],
+                    ..Default::default()
+                }],
+                policy: Policy::Unexpected,
+            },
+            Rule {
+                matches: vec![MatchSession {
+                    names: vec!["web.example.com".parse().unwrap()],
+                    ..Default::default()
+                }],
+                policy: Policy::Expected,
+            },
+        ],
+    }];
+
+    let si = SessionInfo {
+        name: Some("web.example.com".parse().unwrap()),
+        ..session(8080, "10.0.0.1")
+    };
+    let (_, policy) = find(&rts, &si).expect("must match");
+    assert_eq!(*policy, Policy::Expected, "incorrect rule matched");
+}
+
+#[test]
+fn implicit_match_fallback() {
+    let rts = vec![Route {
+        rules: vec![
+            Rule {
+                matches: vec![MatchSession {
+                    client_networks: vec!["192.168.0.0/16".parse().unwrap()],
+                    ..Default::default()
+                }],
+                policy: Policy::Unexpected,
+            },
+            Rule {
+                matches: vec![],
+                policy: Policy::Expected,
+            },
+        ],
+    }];
+
+    let (_, policy) = find(&rts, &session(8080, "10.0.0.1")).expect("must match");
+    assert_eq!(*policy, Policy::Expected, "incorrect rule matched");
+}
+
+#[test]
+fn first_identical_wins() {
+    let rts = vec![Route {
+        rules: vec![
+            Rule {
+                matches: vec![],
+                policy: Policy::Expected,
+            },
+            // Redundant rule.
+            Rule {
+                matches: vec![],
+                policy: Policy::Unexpected,
+            },
+        ],
+    }];
+
+    let (_, policy) = find(&rts, &session(8080, "10.0.0.1")).expect("must match");
+    assert_eq!(*policy, Policy::Expected, "incorrect rule matched");
+}
+
+#[test]
+fn no_match() {
+    let rts = vec![Route {
+        rules: vec![Rule {
+            matches: vec![MatchSession {
+                ports: vec!["9000-9999".parse().unwrap()],
+                ..Default::default()
+            }],
+            policy: Policy::Unexpected,
+        }],
+    }];
+
+    assert!(
+        find(&rts, &session(8080, "10.0.0.1")).is_none(),
+        "should have no matches"
+    );
+}
@@ -0,0 +1,333 @@
+//! Decodes and encodes [PROXY protocol][spec] headers, which load balancers
+//! use to convey the original addresses of the connections they proxy.
+//!
+//! [spec]: https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt
+
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![forbid(unsafe_code)]
+
+mod v1;
+mod v2;
+
+use bytes::{Buf, Bytes, BytesMut};
+use linkerd_io::{self as io, AsyncReadExt, AsyncWriteExt};
+use std::net::{IpAddr, SocketAddr};
+use tracing::trace;
+
+/// A decoded PROXY protocol header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProxyHeader {
+    pub version: Version,
+
+    /// The addresses of the proxied connection.
+    ///
+    /// This is `None` when the sender did not proxy the connection on behalf
+    /// of a client (e.g. for health checks) or when the addresses are not
+    /// IP addresses.
+    pub addrs: Option<Addrs>,
+
+    /// Additional information about the connection. Only v2 headers include
+    /// TLVs.
+    pub tlvs: Vec<Tlv>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Version {
+    V1,
+    V2,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Addrs {
+    /// The client's address.
+    pub source: SocketAddr,
+
+    /// The address to which the client connected.
+    pub destination: SocketAddr,
+}
+
+/// A type-length-value field from a v2 header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tlv {
+    pub kind: u8,
+    pub value: Bytes,
+}
+
+/// Well-known TLV types.
+pub mod tlv {
+    pub const ALPN: u8 = 0x01;
+    pub const AUTHORITY: u8 = 0x02;
+    pub const CRC32C: u8 = 0x03;
+    pub const NOOP: u8 = 0x04;
+    pub const UNIQUE_ID: u8 = 0x05;
+    pub const SSL: u8 = 0x20;
+    pub const NETNS: u8 = 0x30;
+}
+
+#[derive(Debug)]
+enum Decode {
+    /// More data is needed to decode the header.
+    Incomplete,
+
+    /// The data does not begin with a PROXY protocol signature.
+    NotProxy,
+
+    /// A header was decoded from the given number of bytes.
+    Header(ProxyHeader, usize),
+}
+
+// === impl ProxyHeader ===
+
+impl ProxyHeader {
+    /// Attempts to read a PROXY protocol header from an I/O stream.
+    ///
+    /// If the stream does not begin with a PROXY protocol signature, `None` is
+    /// returned and the bytes that were read are left in `buf`. Otherwise, the
+    /// header is consumed from `buf`, leaving any bytes that were read after
+    /// it.
+    ///
+    /// An I/O error is returned if the header is invalid.
+    pub async fn read(
+        io: &mut (impl io::AsyncRead + Unpin),
+        buf: &mut BytesMut,
+    ) -> io::Result<Option<Self>> {
+        loop {
+            match decode(buf.chunk())? {
+                Decode::NotProxy => return Ok(None),
+                Decode::Header(header, len) => {
+                    trace!(len, "Decoded PROXY protocol header");
+                    buf.advance(len);
+                    return Ok(Some(header));
+                }
+                Decode::Incomplete => {
+                    if io.read_buf(buf).await? == 0 {
+                        if buf.starts_with(v1::SIGNATURE) || buf.starts_with(v2::SIGNATURE) {
+                            return Err(io::Error::new(
+                                io::ErrorKind::UnexpectedEof,
+                                "Full PROXY protocol header not provided",
+                            ));
+                        }
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes the header to an I/O stream, returning the number of bytes
+    /// written.
+    pub async fn write(&self, io: &mut (impl io::AsyncWrite + Unpin)) -> io::Result<usize> {
+        let mut buf = BytesMut::new();
+        self.encode(&mut buf)?;
+        io.write_all(&buf).await?;
+        trace!(len = buf.len(), "Wrote PROXY protocol header");
+        Ok(buf.len())
+    }
+
+    /// Encodes the header to a byte buffer.
+    ///
+    /// TLVs are only encoded in v2 headers. An error is returned if they do not
+    /// fit in a single header.
+    pub fn encode(&self, buf: &mut BytesMut) -> io::Result<()> {
+        let addrs = self.addrs.map(Addrs::unify);
+        match self.version {
+            Version::V1 => {
+                v1::encode(addrs, buf);
+                Ok(())
+            }
+            Version::V2 => v2::encode(addrs, &self.tlvs, buf),
+        }
+    }
+
+    /// Returns the value of the first TLV of the given type.
+    pub fn tlv(&self, kind: u8) -> Option<&Bytes> {
+        self.tlvs.iter().find(|t| t.kind == kind).map(|t| &t.value)
+    }
+
+    /// Returns the authority (e.g. a TLS SNI value) that the client used to
+    /// connect, if the sender provided one.
+    pub fn authority(&self) -> Option<&str> {
+        std::str::from_utf8(self.tlv(tlv::AUTHORITY)?).ok()
+    }
+}
+
+// === impl Addrs ===
+
+impl Addrs {
+    /// Headers encode both addresses in a single address family, so IPv4
+    /// addresses are mapped to IPv6 when the families differ.
+    fn unify(self) -> Self {
+        if self.source.is_ipv4() == self.destination.is_ipv4() {
+            return self;
+        }
+        let to_ipv6 = |addr: SocketAddr| match addr.ip() {
+            IpAddr::V4(ip) => SocketAddr::new(ip.to_ipv6_mapped().into(), addr.port()),
+            IpAddr::V6(_) => addr,
+        };
+        Self {
+            source: to_ipv6(self.source),
+            destination: to_ipv6(self.destination),
+        }
+    }
+}
+
+fn decode(buf: &[u8]) -> io::Result<Decode> {
+    if buf.starts_with(v2::SIGNATURE) {
+        return v2::decode(buf);
+    }
+    if buf.starts_with(v1::SIGNATURE) {
+        return v1::decode(buf);
+    }
+    if v2::SIGNATURE.starts_with(buf) || v1::SIGNATURE.starts_with(buf) {
+        return Ok(Decode::Incomplete);
+    }
+    Ok(Decode::NotProxy)
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn no_header() {
+        const MSG: &[u8] = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let (mut rx, _tx) = tokio_test::io::Builder::new().read(MSG).build_with_handle();
+        let mut buf = BytesMut::new();
+        let h = ProxyHeader::read(&mut rx, &mut buf)
+            .await
+            .expect("must not fail");
+        assert!(h.is_none(), "must not decode");
+        assert_eq!(&buf[..], MSG);
+    }
+
+    #[tokio::test]
+    async fn short_non_header() {
+        let mut rx = tokio_test::io::Builder::new().read(b"PR").build();
+        let mut buf = BytesMut::new();
+        let h = ProxyHeader::read(&mut rx, &mut buf)
+            .await
+            .expect("must not fail");
+        assert!(h.is_none(), "must not decode");
+        assert_eq!(&buf[..], b"PR");
+    }
+
+    #[tokio::test]
+    async fn v1_many_reads() {
+        let mut rx = tokio_test::io::Builder::new()
+            .read(b"PRO")
+            .read(b"XY TCP4 192.0.2.1 ")
+            .read(b"192.0.2.2 56324 443\r")
+            .read(b"\n12345")
+            .build();
+        let mut buf = BytesMut::new();
+        let h = ProxyHeader::read(&mut rx, &mut buf)
+            .await
+            .expect("I/O must not error")
+            .expect("header must be present");
+        assert_eq!(
+            h,
+            ProxyHeader {
+                version: Version::V1,
+                addrs: Some(Addrs {
+                    source: ([192, 0, 2, 1], 56324).into(),
+                    destination: ([192, 0, 2, 2], 443).into(),
+                }),
+                tlvs: vec![],
+            }
+        );
+        assert_eq!(&buf[..], b"12345");
+    }
+
+    #[tokio::test]
+    async fn truncated_header() {
+        let mut rx = tokio_test::io::Builder::new()
+            .read(b"PROXY TCP4 192.0.2.1")
+            .build();
+        let mut buf = BytesMut::new();
+        let error = ProxyHeader::read(&mut rx, &mut buf)
+            .await
+            .expect_err("must fail");
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let v4 = Addrs {
+            source: ([192, 0, 2, 1], 56324).into(),
+            destination: ([192, 0, 2, 2], 443).into(),
+        };
+        let v6 = Addrs {
+            source: "[2001:db8::1]:56324".parse().unwrap(),
+            destination: "[2001:db8::2]:443".parse().unwrap(),
+        };
+        let tlvs = vec![Tlv {
+            kind: tlv::AUTHORITY,
+            value: Bytes::from_static(b"example.com"),
+        }];
+        for header in [
+            ProxyHeader {
+                version: Version::V1,
+                addrs: Some(v4),
+                tlvs: vec![],
+            },
+            ProxyHeader {
+                version: Version::V1,
+                addrs: Some(v6),
+                tlvs: vec![],
+            },
+            ProxyHeader {
+                version: Version::V1,
+                addrs: None,
+                tlvs: vec![],
+            },
+            ProxyHeader {
+                version: Version::V2,
+                addrs: Some(v4),
+                tlvs: tlvs.clone(),
+            },
+            ProxyHeader {
+                version: Version::V2,
+                addrs: Some(v6),
+                tlvs: vec![],
+            },
+            ProxyHeader {
+                version: Version::V2,
+                addrs: None,
+                tlvs,
+            },
+        ] {
+            let (mut io, mut peer) = io::duplex(1024);
+            let len = header.write(&mut io).await.expect("must write");
+            drop(io);
+            let mut buf = BytesMut::new();
+            let decoded = ProxyHeader::read(&mut peer, &mut buf)
+                .await
+                .expect("must not fail")
+                .expect("header must be present");
+            assert_eq!(decoded, header);
+            assert!(buf.is_empty(), "{len} bytes must be consumed");
+        }
+    }
+
+    #[test]
+    fn encode_mixed_families() {
+        let header = ProxyHeader {
+            version: Version::V1,
+            addrs: Some(Addrs {
+                source: ([192, 0, 2, 1], 56324).into(),
+                destination: "[2001:db8::2]:443".parse().unwrap(),
+            }),
+            tlvs: vec![],
+        };
+        let mut buf = BytesMut::new();
+        header.encode(&mut buf).expect("must encode");
+        assert_eq!(
+            &buf[..],
+            b"PROXY TCP6 ::ffff:192.0.2.1 2001:db8::2 56324 443\r\n"
+        );
+    }
+}
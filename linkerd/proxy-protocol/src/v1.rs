@@ -0,0 +1,140 @@
+//! The human-readable (v1) header format, e.g.:
+//!
+//! ```text
+//! PROXY TCP4 192.0.2.1 192.0.2.2 56324 443\r\n
+//! ```
+
+use super::{invalid, Addrs, Decode, ProxyHeader, Version};
+use bytes::{BufMut, BytesMut};
+use linkerd_io as io;
+use std::{
+    fmt::Write,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+};
+
+pub(super) const SIGNATURE: &[u8] = b"PROXY ";
+
+/// The maximum length of a v1 header, including its terminating CRLF.
+const MAX_LEN: usize = 107;
+
+pub(super) fn decode(buf: &[u8]) -> io::Result<Decode> {
+    let Some(end) = buf.windows(2).take(MAX_LEN - 1).position(|w| w == b"\r\n") else {
+        if buf.len() >= MAX_LEN {
+            return Err(invalid("PROXY protocol v1 header is too long"));
+        }
+        return Ok(Decode::Incomplete);
+    };
+
+    let line = std::str::from_utf8(&buf[SIGNATURE.len()..end])
+        .map_err(|_| invalid("PROXY protocol v1 header is not ASCII"))?;
+    let mut parts = line.split(' ');
+    let addrs = match parts.next() {
+        // The remainder of the line must be ignored.
+        Some("UNKNOWN") => None,
+        Some("TCP4") => Some(parse_addrs::<Ipv4Addr>(&mut parts)?),
+        Some("TCP6") => Some(parse_addrs::<Ipv6Addr>(&mut parts)?),
+        _ => return Err(invalid("PROXY protocol v1 header has an invalid protocol")),
+    };
+
+    let header = ProxyHeader {
+        version: Version::V1,
+        addrs,
+        tlvs: Vec::new(),
+    };
+    Ok(Decode::Header(header, end + 2))
+}
+
+/// Encodes a header. The addresses must be in a single address family.
+pub(super) fn encode(addrs: Option<Addrs>, buf: &mut BytesMut) {
+    buf.put_slice(SIGNATURE);
+    let Some(Addrs {
+        source,
+        destination,
+    }) = addrs
+    else {
+        buf.put_slice(b"UNKNOWN\r\n");
+        return;
+    };
+    let protocol = if source.is_ipv4() { "TCP4" } else { "TCP6" };
+    write!(
+        buf,
+        "{protocol} {} {} {} {}\r\n",
+        source.ip(),
+        destination.ip(),
+        source.port(),
+        destination.port()
+    )
+    .expect("writing to a buffer must not fail");
+}
+
+fn parse_addrs<'a, A>(parts: &mut impl Iterator<Item = &'a str>) -> io::Result<Addrs>
+where
+    A: FromStr + Into<IpAddr>,
+{
+    let source_ip = parse::<A>(parts)?.into();
+    let destination_ip = parse::<A>(parts)?.into();
+    let source_port = parse::<u16>(parts)?;
+    let destination_port = parse::<u16>(parts)?;
+    if parts.next().is_some() {
+        return Err(invalid("PROXY protocol v1 header has trailing fields"));
+    }
+    Ok(Addrs {
+        source: SocketAddr::new(source_ip, source_port),
+        destination: SocketAddr::new(destination_ip, destination_port),
+    })
+}
+
+fn parse<'a, T: FromStr>(parts: &mut impl Iterator<Item = &'a str>) -> io::Result<T> {
+    parts
+        .next()
+        .ok_or_else(|| invalid("PROXY protocol v1 header is missing fields"))?
+        .parse()
+        .map_err(|_| invalid("PROXY protocol v1 header has an invalid address"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tcp6() {
+        let buf = b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\nGET /";
+        let Decode::Header(header, len) = decode(buf).expect("must decode") else {
+            panic!("header must be decoded");
+        };
+        assert_eq!(len, buf.len() - 5);
+        assert_eq!(
+            header.addrs,
+            Some(Addrs {
+                source: "[2001:db8::1]:56324".parse().unwrap(),
+                destination: "[2001:db8::2]:443".parse().unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        let buf = b"PROXY UNKNOWN ffff::1 ffff::2 1 2\r\n";
+        let Decode::Header(header, len) = decode(buf).expect("must decode") else {
+            panic!("header must be decoded");
+        };
+        assert_eq!(len, buf.len());
+        assert_eq!(header.addrs, None);
+    }
+
+    #[test]
+    fn invalid_headers() {
+        for buf in [
+            &b"PROXY TCP4 192.0.2.1 192.0.2.2 56324\r\n"[..],
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 56324 443 1\r\n",
+            b"PROXY TCP4 2001:db8::1 2001:db8::2 56324 443\r\n",
+            b"PROXY UDP4 192.0.2.1 192.0.2.2 56324 443\r\n",
+            &[b'A'; MAX_LEN][..],
+        ] {
+            let mut buf = buf.to_vec();
+            buf[..SIGNATURE.len()].copy_from_slice(SIGNATURE);
+            assert!(decode(&buf).is_err(), "{:?}", String::from_utf8_lossy(&buf));
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! The binary (v2) header format.
+
+use super::{invalid, Addrs, Decode, ProxyHeader, Tlv, Version};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use linkerd_io as io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+pub(super) const SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The length of the signature and the fixed header fields that follow it.
+const HEADER_LEN: usize = 16;
+
+const VERSION: u8 = 0x2;
+const CMD_LOCAL: u8 = 0x0;
+const CMD_PROXY: u8 = 0x1;
+
+const AF_UNSPEC: u8 = 0x0;
+const AF_INET: u8 = 0x1;
+const AF_INET6: u8 = 0x2;
+const AF_UNIX: u8 = 0x3;
+
+const TRANSPORT_STREAM: u8 = 0x1;
+
+pub(super) fn decode(buf: &[u8]) -> io::Result<Decode> {
+    if buf.len() < HEADER_LEN {
+        return Ok(Decode::Incomplete);
+    }
+
+    let mut hdr = &buf[SIGNATURE.len()..HEADER_LEN];
+    let version_command = hdr.get_u8();
+    if version_command >> 4 != VERSION {
+        return Err(invalid("PROXY protocol v2 header has an invalid version"));
+    }
+    let command = version_command & 0xf;
+    if command != CMD_LOCAL && command != CMD_PROXY {
+        return Err(invalid("PROXY protocol v2 header has an invalid command"));
+    }
+    let family_transport = hdr.get_u8();
+    let family = family_transport >> 4;
+    // Only stream (i.e. TCP) connections are proxied, so datagram headers
+    // cannot describe the connection that carries them.
+    if command == CMD_PROXY && family_transport & 0xf != TRANSPORT_STREAM {
+        return Err(invalid(
+            "PROXY protocol v2 header has an unsupported transport protocol",
+        ));
+    }
+    let len = usize::from(hdr.get_u16());
+    if buf.len() < HEADER_LEN + len {
+        return Ok(Decode::Incomplete);
+    }
+
+    let mut payload = &buf[HEADER_LEN..HEADER_LEN + len];
+    let addrs_len = match family {
+        AF_UNSPEC => 0,
+        AF_INET => 12,
+        AF_INET6 => 36,
+        AF_UNIX => 216,
+        _ => return Err(invalid("PROXY protocol v2 header has an invalid family")),
+    };
+    if payload.len() < addrs_len {
+        return Err(invalid("PROXY protocol v2 header addresses are truncated"));
+    }
+
+    // Addresses are ignored for LOCAL connections, as well as for UNIX
+    // sockets, which are not meaningful to the receiver.
+    let mut addrs = &payload[..addrs_len];
+    payload.advance(addrs_len);
+    let addrs = match family {
+        AF_INET if command == CMD_PROXY => {
+            let source = Ipv4Addr::from(addrs.get_u32());
+            let destination = Ipv4Addr::from(addrs.get_u32());
+            Some(Addrs {
+                source: SocketAddr::new(source.into(), addrs.get_u16()),
+                destination: SocketAddr::new(destination.into(), addrs.get_u16()),
+            })
+        }
+        AF_INET6 if command == CMD_PROXY => {
+            let source = Ipv6Addr::from(addrs.get_u128());
+            let destination = Ipv6Addr::from(addrs.get_u128());
+            Some(Addrs {
+                source: SocketAddr::new(source.into(), addrs.get_u16()),
+                destination: SocketAddr::new(destination.into(), addrs.get_u16()),
+            })
+        }
+        _ => None,
+    };
+
+    let mut tlvs = Vec::new();
+    while payload.has_remaining() {
+        if payload.remaining() < 3 {
+            return Err(invalid("PROXY protocol v2 header TLV is truncated"));
+        }
+        let kind = payload.get_u8();
+        let len = usize::from(payload.get_u16());
+        if payload.remaining() < len {
+            return Err(invalid("PROXY protocol v2 header TLV is truncated"));
+        }
+        let value = Bytes::copy_from_slice(&payload[..len]);
+        payload.advance(len);
+        tlvs.push(Tlv { kind, value });
+    }
+
+    let header = ProxyHeader {
+        version: Version::V2,
+        addrs,
+        tlvs,
+    };
+    Ok(Decode::Header(header, HEADER_LEN + len))
+}
+
+/// Encodes a header. The addresses must be in a single address family.
+pub(super) fn encode(addrs: Option<Addrs>, tlvs: &[Tlv], buf: &mut BytesMut) -> io::Result<()> {
+    let (command, family, addrs_len) = match addrs {
+        None => (CMD_LOCAL, AF_UNSPEC, 0),
+        Some(Addrs { source, .. }) if source.is_ipv4() => (CMD_PROXY, AF_INET, 12),
+        Some(_) => (CMD_PROXY, AF_INET6, 36),
+    };
+    let len = addrs_len + tlvs.iter().map(|t| 3 + t.value.len()).sum::<usize>();
+    let len = u16::try_from(len).map_err(|_| invalid("PROXY protocol v2 header is too long"))?;
+
+    buf.reserve(HEADER_LEN + usize::from(len));
+    buf.put_slice(SIGNATURE);
+    buf.put_u8((VERSION << 4) | command);
+    buf.put_u8((family << 4) | TRANSPORT_STREAM);
+    buf.put_u16(len);
+    if let Some(Addrs {
+        source,
+        destination,
+    }) = addrs
+    {
+        match (source.ip(), destination.ip()) {
+            (IpAddr::V4(src), IpAddr::V4(dst)) => {
+                buf.put_u32(src.into());
+                buf.put_u32(dst.into());
+            }
+            (IpAddr::V6(src), IpAddr::V6(dst)) => {
+                buf.put_u128(src.into());
+                buf.put_u128(dst.into());
+            }
+            _ => unreachable!("addresses must be in a single family"),
+        }
+        buf.put_u16(source.port());
+        buf.put_u16(destination.port());
+    }
+    for Tlv { kind, value } in tlvs {
+        buf.put_u8(*kind);
+        // The total length has been checked, so each value must fit.
+        buf.put_u16(value.len() as u16);
+        buf.put_slice(value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tlv;
+    use bytes::BufMut;
+
+    fn header(command: u8, family: u8, payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.put_slice(SIGNATURE);
+        buf.put_u8((VERSION << 4) | command);
+        buf.put_u8((family << 4) | TRANSPORT_STREAM);
+        buf.put_u16(payload.len() as u16);
+        buf.put_slice(payload);
+        buf
+    }
+
+    #[test]
+    fn inet_with_tlvs() {
+        let mut payload = Vec::new();
+        payload.put_slice(&[192, 0, 2, 1]);
+        payload.put_slice(&[192, 0, 2, 2]);
+        payload.put_u16(56324);
+        payload.put_u16(443);
+        payload.put_u8(tlv::AUTHORITY);
+        payload.put_u16(11);
+        payload.put_slice(b"example.com");
+        let mut buf = header(CMD_PROXY, AF_INET, &payload);
+        let len = buf.len();
+        buf.put_slice(b"12345");
+
+        let Decode::Header(header, decoded) = decode(&buf).expect("must decode") else {
+            panic!("header must be decoded");
+        };
+        assert_eq!(decoded, len);
+        assert_eq!(
+            header.addrs,
+            Some(Addrs {
+                source: ([192, 0, 2, 1], 56324).into(),
+                destination: ([192, 0, 2, 2], 443).into(),
+            })
+        );
+        assert_eq!(header.authority(), Some("example.com"));
+
+        assert!(matches!(
+            decode(&buf[..len - 1]).expect("must not fail"),
+            Decode::Incomplete
+        ));
+    }
+
+    #[test]
+    fn inet6() {
+        let mut payload = Vec::new();
+        payload.put_u128(Ipv6Addr::LOCALHOST.into());
+        payload.put_u128(Ipv6Addr::LOCALHOST.into());
+        payload.put_u16(56324);
+        payload.put_u16(443);
+        let buf = header(CMD_PROXY, AF_INET6, &payload);
+        let Decode::Header(header, _) = decode(&buf).expect("must decode") else {
+            panic!("header must be decoded");
+        };
+        assert_eq!(
+            header.addrs,
+            Some(Addrs {
+                source: (Ipv6Addr::LOCALHOST, 56324).into(),
+                destination: (Ipv6Addr::LOCALHOST, 443).into(),
+            })
+        );
+    }
+
+    #[test]
+    fn local() {
+        let buf = header(CMD_LOCAL, AF_INET, &[0; 12]);
+        let Decode::Header(header, len) = decode(&buf).expect("must decode") else {
+            panic!("header must be decoded");
+        };
+        assert_eq!(len, buf.len());
+        assert_eq!(header.addrs, None);
+    }
+
+    #[test]
+    fn invalid_headers() {
+        // Truncated addresses.
+        assert!(decode(&header(CMD_PROXY, AF_INET, &[0; 8])).is_err());
+        // Truncated TLV.
+        assert!(decode(&header(CMD_PROXY, AF_UNSPEC, &[tlv::NOOP, 0, 2, 0])).is_err());
+        // Invalid command.
+        assert!(decode(&header(0x2, AF_UNSPEC, &[])).is_err());
+    }
+
+    #[test]
+    fn non_stream_transport() {
+        const TRANSPORT_DGRAM: u8 = 0x2;
+
+        let with_transport = |command, transport| {
+            let mut buf = header(command, AF_INET, &[0; 12]);
+            buf[13] = (AF_INET << 4) | transport;
+            buf
+        };
+        for transport in [0x0, TRANSPORT_DGRAM] {
+            assert!(decode(&with_transport(CMD_PROXY, transport)).is_err());
+        }
+
+        // The transport is ignored for LOCAL connections.
+        assert!(matches!(
+            decode(&with_transport(CMD_LOCAL, TRANSPORT_DGRAM)).expect("must decode"),
+            Decode::Header(..)
+        ));
+    }
+}
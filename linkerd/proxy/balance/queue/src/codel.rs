@@ -0,0 +1,129 @@
+use tokio::time;
+
+/// Configures CoDel-style management of queueing delay.
+///
+/// When every request dequeued over an `interval` has waited in the queue for
+/// longer than `target`, the queue starts dropping requests from its head,
+/// failing them immediately. Drops are spaced at decreasing intervals until
+/// the queueing delay falls back below the target.
+///
+/// See [RFC 8289](https://www.rfc-editor.org/rfc/rfc8289).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CodelConfig {
+    /// The acceptable amount of time that requests may wait in the queue.
+    pub target: time::Duration,
+
+    /// The amount of time over which the queueing delay must stay above the
+    /// target before requests are dropped.
+    pub interval: time::Duration,
+}
+
+/// Tracks queueing delay to determine when requests should be dropped.
+#[derive(Debug)]
+pub(crate) struct Codel {
+    config: CodelConfig,
+
+    /// When the queueing delay is above the target, the time at which it will
+    /// have been above the target for a full interval.
+    first_above: Option<time::Instant>,
+
+    dropping: bool,
+
+    /// The time at which the next request will be dropped, when dropping.
+    drop_next: time::Instant,
+
+    /// The number of requests dropped since entering the dropping state.
+    count: u32,
+    last_count: u32,
+}
+
+// === impl Codel ===
+
+impl Codel {
+    pub(crate) fn new(config: CodelConfig) -> Self {
+        Self {
+            config,
+            first_above: None,
+            dropping: false,
+            drop_next: time::Instant::now(),
+            count: 0,
+            last_count: 0,
+        }
+    }
+
+    /// Returns true if a request that has been queued since `t0` should be
+    /// dropped instead of being dispatched.
+    ///
+    /// `backlogged` indicates whether other requests remain in the queue.
+    pub(crate) fn should_drop(
+        &mut self,
+        now: time::Instant,
+        t0: time::Instant,
+        backlogged: bool,
+    ) -> bool {
+        let sojourn = now.saturating_duration_since(t0);
+        let ok_to_drop = self.ok_to_drop(now, sojourn, backlogged);
+
+        if self.dropping {
+            if !ok_to_drop {
+                tracing::debug!(?sojourn, "Queueing delay below target");
+                self.dropping = false;
+                return false;
+            }
+            if now < self.drop_next {
+                return false;
+            }
+            self.count = self.count.saturating_add(1);
+            self.drop_next = self.control_law(self.drop_next);
+            return true;
+        }
+
+        if !ok_to_drop {
+            return false;
+        }
+
+        // If we were dropping recently, resume at a rate close to the one that
+        // last controlled the queue.
+        tracing::debug!(?sojourn, "Queueing delay above target");
+        self.dropping = true;
+        let delta = self.count.saturating_sub(self.last_count);
+        self.count = if delta > 1
+            && now.saturating_duration_since(self.drop_next) < self.config.interval * 16
+        {
+            delta
+        } else {
+            1
+        };
+        self.last_count = self.count;
+        self.drop_next = self.control_law(now);
+        true
+    }
+
+    /// Returns true when the minimum queueing delay has been above the target
+    /// for at least an interval.
+    fn ok_to_drop(
+        &mut self,
+        now: time::Instant,
+        sojourn: time::Duration,
+        backlogged: bool,
+    ) -> bool {
+        // Dropping the only queued request does nothing to reduce the
+        // queueing delay of others.
+        if sojourn < self.config.target || !backlogged {
+            self.first_above = None;
+            return false;
+        }
+
+        match self.first_above {
+            Some(t) => now >= t,
+            None => {
+                self.first_above = Some(now + self.config.interval);
+                false
+            }
+        }
+    }
+
+    fn control_law(&self, t: time::Instant) -> time::Instant {
+        t + self.config.interval.div_f64(f64::from(self.count).sqrt())
+    }
+}
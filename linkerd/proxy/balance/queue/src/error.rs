@@ -1,13 +1,30 @@
 //! Error types for the `PoolQueue` middleware.
 
 use linkerd_error::Error;
-use std::{fmt, sync::Arc};
+use linkerd_proxy_core::Priority;
+use std::{fmt, sync::Arc, time::Duration};
 
 /// A shareable, terminal error produced by either a service or discovery
 /// resolution.
 #[derive(Clone, Debug)]
 pub struct TerminalFailure(Arc<Error>);
 
+/// An error indicating that a request was shed from a full queue in favor of
+/// higher priority requests.
+#[derive(Debug, thiserror::Error)]
+#[error("request shed from full queue: {priority} priority")]
+pub struct QueueShedError {
+    priority: Priority,
+}
+
+/// An error indicating that a request was dropped from the head of the queue
+/// because queueing delay has persistently exceeded its target.
+#[derive(Debug, thiserror::Error)]
+#[error("request dropped from queue after {}ms: queueing delay exceeds target", .sojourn.as_millis())]
+pub struct QueueDelayError {
+    sojourn: Duration,
+}
+
 // === impl TerminalFailure ===
 
 impl TerminalFailure {
@@ -27,3 +44,28 @@ impl std::error::Error for TerminalFailure {
         Some(&**self.0)
     }
 }
+
+// === impl QueueShedError ===
+
+impl QueueShedError {
+    pub(crate) fn new(priority: Priority) -> Self {
+        Self { priority }
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+}
+
+// === impl QueueDelayError ===
+
+impl QueueDelayError {
+    pub(crate) fn new(sojourn: Duration) -> Self {
+        Self { sojourn }
+    }
+
+    /// Returns the amount of time the request waited in the queue.
+    pub fn sojourn(&self) -> Duration {
+        self.sojourn
+    }
+}
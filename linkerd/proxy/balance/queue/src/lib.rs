@@ -9,18 +9,25 @@
 
 use linkerd_metrics::prom;
 
+mod codel;
 mod error;
 mod failfast;
 mod future;
 mod message;
+mod priority;
 mod service;
 #[cfg(test)]
 mod tests;
 mod worker;
 
-pub use self::service::PoolQueue;
+pub use self::{
+    codel::CodelConfig,
+    error::{QueueDelayError, QueueShedError},
+    priority::Prioritize,
+    service::PoolQueue,
+};
 pub use linkerd_pool::Pool;
-pub use linkerd_proxy_core::Update;
+pub use linkerd_proxy_core::{Priority, Update};
 
 use self::failfast::{GateMetricFamilies, GateMetrics};
 
@@ -29,6 +36,9 @@ pub struct QueueMetricFamilies<L> {
     length: prom::Family<L, prom::Gauge>,
     requests: prom::Family<L, prom::Counter>,
     latency: prom::Family<L, prom::Histogram, fn() -> prom::Histogram>,
+    priority_length: prom::Family<PriorityLabels<L>, prom::Gauge>,
+    shed: prom::Family<PriorityLabels<L>, prom::Counter>,
+    dropped: prom::Family<PriorityLabels<L>, prom::Counter>,
     gate: GateMetricFamilies<L>,
 }
 
@@ -38,9 +48,24 @@ pub struct QueueMetrics {
     length: prom::Gauge,
     requests: prom::Counter,
     latency: prom::Histogram,
+    /// Metrics for each priority class, in the order of [`Priority::ALL`].
+    priorities: [PriorityMetrics; Priority::ALL.len()],
     gate: GateMetrics,
 }
 
+#[derive(Clone, Debug, Default)]
+struct PriorityMetrics {
+    length: prom::Gauge,
+    shed: prom::Counter,
+    dropped: prom::Counter,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct PriorityLabels<L> {
+    priority: Priority,
+    labels: L,
+}
+
 // === impl QueueMetricsFamilies ===
 
 impl<L> Default for QueueMetricFamilies<L>
@@ -58,6 +83,9 @@ where
                 // of buckets.
                 prom::Histogram::new([0.0005, 0.005, 0.05, 0.5, 1.0, 3.0].iter().copied())
             }),
+            priority_length: prom::Family::default(),
+            shed: prom::Family::default(),
+            dropped: prom::Family::default(),
             gate: GateMetricFamilies::default(),
         }
     }
@@ -96,12 +124,36 @@ where
             latency.clone(),
         );
 
+        let priority_length = prom::Family::default();
+        reg.register(
+            "priority_length",
+            "The current count of requests of each priority class waiting in the queue",
+            priority_length.clone(),
+        );
+
+        let shed = prom::Family::default();
+        reg.register(
+            "shed",
+            "The total number of requests that have been shed from a full queue",
+            shed.clone(),
+        );
+
+        let dropped = prom::Family::default();
+        reg.register(
+            "dropped",
+            "The total number of requests that have been dropped because queueing delay exceeded its target",
+            dropped.clone(),
+        );
+
         let gate = GateMetricFamilies::register(reg.sub_registry_with_prefix("gate"));
 
         Self {
             length,
             requests,
             latency,
+            priority_length,
+            shed,
+            dropped,
             gate,
         }
     }
@@ -110,11 +162,23 @@ where
         let length = self.length.get_or_create(labels).clone();
         let requests = self.requests.get_or_create(labels).clone();
         let latency = self.latency.get_or_create(labels).clone();
+        let priorities = Priority::ALL.map(|priority| {
+            let labels = PriorityLabels {
+                priority,
+                labels: labels.clone(),
+            };
+            PriorityMetrics {
+                length: self.priority_length.get_or_create(&labels).clone(),
+                shed: self.shed.get_or_create(&labels).clone(),
+                dropped: self.dropped.get_or_create(&labels).clone(),
+            }
+        });
         let gate = self.gate.metrics(labels);
         QueueMetrics {
             length,
             requests,
             latency,
+            priorities,
             gate,
         }
     }
@@ -128,7 +192,24 @@ impl Default for QueueMetrics {
             length: prom::Gauge::default(),
             requests: prom::Counter::default(),
             latency: prom::Histogram::new(std::iter::empty()),
+            priorities: Default::default(),
             gate: GateMetrics::default(),
         }
     }
 }
+
+impl QueueMetrics {
+    fn priority(&self, priority: Priority) -> &PriorityMetrics {
+        &self.priorities[priority::index(priority)]
+    }
+}
+
+// === impl PriorityLabels ===
+
+impl<L: prom::encoding::EncodeLabelSet> prom::encoding::EncodeLabelSet for PriorityLabels<L> {
+    fn encode(&self, mut enc: prom::encoding::LabelSetEncoder<'_>) -> std::fmt::Result {
+        use prom::encoding::EncodeLabel;
+        ("priority", self.priority.as_str()).encode(enc.encode_label())?;
+        self.labels.encode(enc)
+    }
+}
@@ -1,10 +1,12 @@
 use linkerd_error::{Error, Result};
+use linkerd_proxy_core::Priority;
 use tokio::{sync::oneshot, time};
 
 /// Message sent over buffer
 #[derive(Debug)]
 pub(crate) struct Message<Req, Fut> {
     pub(crate) req: Req,
+    pub(crate) priority: Priority,
     pub(crate) tx: Tx<Fut>,
     pub(crate) span: tracing::Span,
     pub(crate) t0: time::Instant,
@@ -17,11 +19,20 @@ type Tx<Fut> = oneshot::Sender<Result<Fut>>;
 pub(crate) type Rx<Fut> = oneshot::Receiver<Result<Fut>>;
 
 impl<Req, Fut> Message<Req, Fut> {
-    pub(crate) fn channel(req: Req) -> (Self, Rx<Fut>) {
+    pub(crate) fn channel(req: Req, priority: Priority) -> (Self, Rx<Fut>) {
         let (tx, rx) = oneshot::channel();
         let t0 = time::Instant::now();
         let span = tracing::Span::current();
-        (Message { req, span, tx, t0 }, rx)
+        (
+            Message {
+                req,
+                priority,
+                span,
+                tx,
+                t0,
+            },
+            rx,
+        )
     }
 
     pub(crate) fn fail(self, err: impl Into<Error>) {
@@ -0,0 +1,153 @@
+use crate::{
+    codel::{Codel, CodelConfig},
+    error::{QueueDelayError, QueueShedError},
+    message::Message,
+    QueueMetrics,
+};
+use linkerd_proxy_core::Priority;
+use std::collections::VecDeque;
+use tokio::time;
+
+/// Determines the [`Priority`] of `Req`-typed requests.
+pub trait Prioritize<Req> {
+    fn priority(req: &Req) -> Priority;
+}
+
+/// Requests that have been received by the worker but not yet dispatched,
+/// ordered by priority.
+///
+/// When the buffer is at capacity, the most recent request of the lowest
+/// buffered priority is shed to make room for a strictly higher priority
+/// request. Requests are never shed to make room for requests of the same or
+/// lower priority. If CoDel is configured, requests are also dropped from the
+/// head of the buffer while queueing delay persistently exceeds its target.
+#[derive(Debug)]
+pub(crate) struct Pending<Req, F> {
+    capacity: usize,
+    len: usize,
+    /// Requests for each priority class, in the order of [`Priority::ALL`].
+    classes: [VecDeque<Message<Req, F>>; Priority::ALL.len()],
+    codel: Option<Codel>,
+    metrics: QueueMetrics,
+}
+
+/// All requests are assigned the default priority.
+impl<Req> Prioritize<Req> for () {
+    #[inline]
+    fn priority(_: &Req) -> Priority {
+        Priority::default()
+    }
+}
+
+// === impl Pending ===
+
+impl<Req, F> Pending<Req, F> {
+    pub(crate) fn new(capacity: usize, codel: Option<CodelConfig>, metrics: QueueMetrics) -> Self {
+        Self {
+            capacity,
+            len: 0,
+            classes: Default::default(),
+            codel: codel.map(Codel::new),
+            metrics,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Indicates whether a new request may be buffered, either because the
+    /// buffer has room or because it holds requests that a higher priority
+    /// request could displace.
+    pub(crate) fn accepts(&self) -> bool {
+        self.len < self.capacity || self.lowest_class().is_some_and(|i| i > 0)
+    }
+
+    /// Buffers a request.
+    ///
+    /// If the buffer is full, the most recent request of the lowest buffered
+    /// priority is shed to make room, provided that it has a strictly lower
+    /// priority than the new request. Otherwise, the new request is returned
+    /// so that it may wait for room.
+    pub(crate) fn push(&mut self, msg: Message<Req, F>) -> Result<(), Message<Req, F>> {
+        let class = index(msg.priority);
+        if self.len >= self.capacity {
+            let Some(lowest) = self.lowest_class().filter(|&i| i > class) else {
+                return Err(msg);
+            };
+            let shed = self.classes[lowest]
+                .pop_back()
+                .expect("class must not be empty");
+            self.metrics.priority(shed.priority).length.dec();
+            self.len -= 1;
+            self.shed(shed);
+        }
+
+        self.metrics.priority(msg.priority).length.inc();
+        self.classes[class].push_back(msg);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the index of the lowest priority class with buffered requests.
+    fn lowest_class(&self) -> Option<usize> {
+        (0..self.classes.len())
+            .rev()
+            .find(|&i| !self.classes[i].is_empty())
+    }
+
+    /// Returns the oldest request of the highest buffered priority.
+    pub(crate) fn pop(&mut self) -> Option<Message<Req, F>> {
+        let msg = self.classes.iter_mut().find_map(VecDeque::pop_front)?;
+        self.metrics.priority(msg.priority).length.dec();
+        self.len -= 1;
+        Some(msg)
+    }
+
+    /// Returns the next request to be dispatched.
+    ///
+    /// If queueing delay has persistently exceeded the CoDel target, requests
+    /// are dropped from the head of the buffer first.
+    pub(crate) fn dequeue(&mut self) -> Option<Message<Req, F>> {
+        let now = time::Instant::now();
+        while let Some(msg) = self.pop() {
+            let backlogged = !self.is_empty();
+            let Some(codel) = self.codel.as_mut() else {
+                return Some(msg);
+            };
+            if !codel.should_drop(now, msg.t0, backlogged) {
+                return Some(msg);
+            }
+            self.drop_delayed(msg, now);
+        }
+        None
+    }
+
+    fn drop_delayed(&self, msg: Message<Req, F>, now: time::Instant) {
+        let priority = msg.priority;
+        let sojourn = now.saturating_duration_since(msg.t0);
+        tracing::debug!(%priority, ?sojourn, "Dropping request from delayed queue");
+        self.metrics.priority(priority).dropped.inc();
+        self.metrics.latency.observe(sojourn.as_secs_f64());
+        self.metrics.length.dec();
+        msg.fail(QueueDelayError::new(sojourn));
+    }
+
+    fn shed(&self, msg: Message<Req, F>) {
+        let priority = msg.priority;
+        tracing::debug!(%priority, "Shedding request from full queue");
+        self.metrics.priority(priority).shed.inc();
+        self.metrics
+            .latency
+            .observe((time::Instant::now() - msg.t0).as_secs_f64());
+        self.metrics.length.dec();
+        msg.fail(QueueShedError::new(priority));
+    }
+}
+
+pub(crate) fn index(priority: Priority) -> usize {
+    Priority::ALL
+        .iter()
+        .position(|p| *p == priority)
+        .expect("all priorities must be enumerated")
+}
@@ -1,12 +1,13 @@
 use crate::{
     future::ResponseFuture,
     message::Message,
+    priority::Pending,
     worker::{self, Terminate},
-    Pool, QueueMetrics,
+    CodelConfig, Pool, Prioritize, QueueMetrics,
 };
 use futures::TryStream;
 use linkerd_error::{Error, Result};
-use linkerd_proxy_core::Update;
+use linkerd_proxy_core::{Priority, Update};
 use linkerd_stack::{gate, Service};
 use std::{
     future::Future,
@@ -19,6 +20,7 @@ use tokio_util::sync::PollSender;
 #[derive(Debug)]
 pub struct PoolQueue<Req, F> {
     tx: PollSender<Message<Req, F>>,
+    prioritize: fn(&Req) -> Priority,
     metrics: QueueMetrics,
     terminal: Terminate,
 }
@@ -28,6 +30,7 @@ where
     Req: Send + 'static,
     F: Send + 'static,
 {
+    /// Spawns a queue that serves requests in the order they are received.
     pub fn spawn<T, R, P>(
         capacity: usize,
         failfast: time::Duration,
@@ -35,6 +38,44 @@ where
         resolution: R,
         pool: P,
     ) -> gate::Gate<Self>
+    where
+        T: Clone + Eq + std::fmt::Debug + Send,
+        R: TryStream<Ok = Update<T>> + Send + Unpin + 'static,
+        R::Error: Into<Error> + Send,
+        P: Pool<T, Req, Future = F> + Send + 'static,
+        P::Error: Into<Error> + Send + Sync,
+        Req: Send + 'static,
+    {
+        Self::spawn_prioritized(
+            capacity,
+            failfast,
+            None,
+            <() as Prioritize<Req>>::priority,
+            metrics,
+            resolution,
+            pool,
+        )
+    }
+
+    /// Spawns a queue that serves higher priority requests first and, when
+    /// the queue is full, sheds lower priority requests first.
+    ///
+    /// If a [`CodelConfig`] is provided, requests are dropped from the head of
+    /// the queue while queueing delay persistently exceeds its target.
+    ///
+    /// The worker buffers up to `capacity` requests. Once its buffer is full,
+    /// callers wait for up to `capacity` additional requests to be received by
+    /// the worker, so that requests are only shed to make room for strictly
+    /// higher priority requests.
+    pub fn spawn_prioritized<T, R, P>(
+        capacity: usize,
+        failfast: time::Duration,
+        codel: Option<CodelConfig>,
+        prioritize: fn(&Req) -> Priority,
+        metrics: QueueMetrics,
+        resolution: R,
+        pool: P,
+    ) -> gate::Gate<Self>
     where
         T: Clone + Eq + std::fmt::Debug + Send,
         R: TryStream<Ok = Update<T>> + Send + Unpin + 'static,
@@ -44,14 +85,21 @@ where
         Req: Send + 'static,
     {
         let (gate_tx, gate_rx) = gate::channel();
+        // The channel hands requests off to the worker, which moves them into
+        // the pending buffer as they arrive. Callers wait for capacity in the
+        // channel while the pending buffer is full.
         let (tx, rx) = mpsc::channel(capacity);
         let terminal = Terminate::default();
         let inner = Self {
             tx: PollSender::new(tx),
+            prioritize,
             terminal: terminal.clone(),
             metrics: metrics.clone(),
         };
-        worker::spawn(rx, failfast, gate_tx, terminal, metrics, resolution, pool);
+        let pending = Pending::new(capacity, codel, metrics.clone());
+        worker::spawn(
+            rx, pending, failfast, gate_tx, terminal, metrics, resolution, pool,
+        );
         gate::Gate::new(gate_rx, inner)
     }
 }
@@ -78,7 +126,8 @@ where
 
     fn call(&mut self, req: Req) -> Self::Future {
         tracing::trace!("Sending request to worker");
-        let (msg, rx) = Message::channel(req);
+        let priority = (self.prioritize)(&req);
+        let (msg, rx) = Message::channel(req, priority);
         if self.tx.send_item(msg).is_err() {
             // The channel closed since poll_ready was called, so propagate the
             // failure in the response future.
@@ -103,6 +152,7 @@ where
         Self {
             terminal: self.terminal.clone(),
             tx: self.tx.clone(),
+            prioritize: self.prioritize,
             metrics: self.metrics.clone(),
         }
     }
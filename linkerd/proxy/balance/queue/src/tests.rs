@@ -1,6 +1,6 @@
 #![allow(clippy::ok_expect)]
 
-use crate::PoolQueue;
+use crate::{CodelConfig, PoolQueue, Priority, QueueDelayError, QueueShedError};
 use futures::prelude::*;
 use linkerd_pool_mock as mock;
 use linkerd_proxy_core::Update;
@@ -394,3 +394,201 @@ async fn failfast_interrupted() {
     assert!(call.await.is_ok(), "call should not failfast");
     assert!(poolq.ready().await.is_ok(), "poolq must be ready");
 }
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn prioritizes_requests() {
+    let _trace = linkerd_tracing::test::with_default_filter("linkerd=trace");
+
+    let (pool, mut handle) = mock::pool::<(), Priority, ()>();
+    let (_updates, u) = mpsc::channel::<Result<Update<()>, mock::ResolutionError>>(1);
+    let mut poolq = PoolQueue::spawn_prioritized(
+        10,
+        time::Duration::from_secs(1),
+        None,
+        |p: &Priority| *p,
+        Default::default(),
+        ReceiverStream::from(u),
+        pool,
+    );
+
+    handle.svc.allow(0);
+    let mut calls = Vec::new();
+    for priority in [Priority::Low, Priority::Normal, Priority::High] {
+        assert!(poolq.ready().await.is_ok(), "poolq must be ready");
+        calls.push(poolq.call(priority));
+    }
+    tokio::task::yield_now().await;
+
+    handle.svc.allow(3);
+    for expected in [Priority::High, Priority::Normal, Priority::Low] {
+        let (priority, respond) = handle.svc.next_request().await.expect("request");
+        assert_eq!(priority, expected);
+        respond.send_response(());
+    }
+    for call in calls {
+        call.await.expect("response");
+    }
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn sheds_lowest_priority() {
+    let _trace = linkerd_tracing::test::with_default_filter("linkerd=trace");
+
+    let (pool, mut handle) = mock::pool::<(), Priority, ()>();
+    let (_updates, u) = mpsc::channel::<Result<Update<()>, mock::ResolutionError>>(1);
+    let mut poolq = PoolQueue::spawn_prioritized(
+        2,
+        time::Duration::from_secs(1),
+        None,
+        |p: &Priority| *p,
+        Default::default(),
+        ReceiverStream::from(u),
+        pool,
+    );
+
+    handle.svc.allow(0);
+    let mut send = |priority| {
+        assert!(poolq.ready().now_or_never().expect("ready").is_ok());
+        poolq.call(priority)
+    };
+    let normal0 = send(Priority::Normal);
+    tokio::task::yield_now().await;
+    let mut normal1 = send(Priority::Normal);
+    tokio::task::yield_now().await;
+
+    // The queue is full, so a higher priority request displaces the most
+    // recent lower priority request.
+    let high = send(Priority::High);
+    tokio::task::yield_now().await;
+    let error = (&mut normal1)
+        .await
+        .expect_err("normal priority request must be shed");
+    assert!(error.is::<QueueShedError>(), "{error}");
+
+    // A request with no higher priority waits for room instead of being shed.
+    let mut low = send(Priority::Low);
+    tokio::task::yield_now().await;
+    assert!(
+        (&mut low).now_or_never().is_none(),
+        "low priority request must wait"
+    );
+
+    handle.svc.allow(3);
+    for expected in [Priority::High, Priority::Normal, Priority::Low] {
+        let (priority, respond) = handle.svc.next_request().await.expect("request");
+        assert_eq!(priority, expected);
+        respond.send_response(());
+    }
+    high.await.expect("response");
+    normal0.await.expect("response");
+    low.await.expect("response");
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn waits_when_full() {
+    let _trace = linkerd_tracing::test::with_default_filter("linkerd=trace");
+
+    let (pool, mut handle) = mock::pool::<(), usize, ()>();
+    let (_updates, u) = mpsc::channel::<Result<Update<()>, mock::ResolutionError>>(1);
+    let mut poolq = PoolQueue::spawn(
+        1,
+        time::Duration::from_secs(10),
+        Default::default(),
+        ReceiverStream::from(u),
+        pool,
+    );
+
+    // Fill the worker's buffer and the channel, letting the worker run after
+    // each request so that it receives whatever it will accept.
+    handle.svc.allow(0);
+    let mut calls = Vec::new();
+    for i in 0..3 {
+        assert!(
+            poolq.ready().now_or_never().expect("ready").is_ok(),
+            "poolq must be ready"
+        );
+        calls.push(poolq.call(i));
+        tokio::task::yield_now().await;
+    }
+
+    // Callers wait for capacity rather than having requests shed.
+    assert!(
+        poolq.ready().now_or_never().is_none(),
+        "poolq must not be ready when full"
+    );
+    for call in calls.iter_mut() {
+        assert!(call.now_or_never().is_none(), "request must not be shed");
+    }
+
+    handle.svc.allow(3);
+    for expected in 0..3 {
+        let (i, respond) = handle.svc.next_request().await.expect("request");
+        assert_eq!(i, expected);
+        respond.send_response(());
+    }
+    for call in calls {
+        call.await.expect("response");
+    }
+    assert!(poolq.ready().await.is_ok(), "poolq must be ready");
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn drops_persistently_delayed_requests() {
+    let _trace = linkerd_tracing::test::with_default_filter("linkerd=trace");
+
+    let (pool, mut handle) = mock::pool::<(), usize, ()>();
+    let (_updates, u) = mpsc::channel::<Result<Update<()>, mock::ResolutionError>>(1);
+    let mut poolq = PoolQueue::spawn_prioritized(
+        10,
+        time::Duration::from_secs(10),
+        Some(CodelConfig {
+            target: time::Duration::from_millis(10),
+            interval: time::Duration::from_millis(100),
+        }),
+        |_: &usize| Priority::default(),
+        Default::default(),
+        ReceiverStream::from(u),
+        pool,
+    );
+
+    handle.svc.allow(0);
+    let mut calls = Vec::new();
+    for i in 0..4 {
+        assert!(poolq.ready().await.is_ok(), "poolq must be ready");
+        calls.push(poolq.call(i));
+    }
+    tokio::task::yield_now().await;
+    let mut calls = calls.into_iter();
+
+    // The first request has been delayed beyond the target, but it has not
+    // been delayed for a full interval, so it is dispatched.
+    time::sleep(time::Duration::from_millis(200)).await;
+    handle.svc.allow(1);
+    let (i, respond) = handle.svc.next_request().await.expect("request");
+    assert_eq!(i, 0);
+    respond.send_response(());
+    calls.next().unwrap().await.expect("response");
+
+    // Once the delay has stayed above the target for an interval, the request
+    // at the head of the queue is dropped and the next one is dispatched.
+    time::sleep(time::Duration::from_millis(150)).await;
+    handle.svc.allow(1);
+    let (i, respond) = handle.svc.next_request().await.expect("request");
+    assert_eq!(i, 2);
+    respond.send_response(());
+    let error = calls
+        .next()
+        .unwrap()
+        .await
+        .expect_err("delayed request must be dropped");
+    assert!(error.is::<QueueDelayError>(), "{error}");
+    calls.next().unwrap().await.expect("response");
+
+    // The last request is not dropped, since no other requests are waiting
+    // behind it.
+    handle.svc.allow(1);
+    let (i, respond) = handle.svc.next_request().await.expect("request");
+    assert_eq!(i, 3);
+    respond.send_response(());
+    calls.next().unwrap().await.expect("response");
+}
@@ -4,6 +4,7 @@ use crate::{
     error,
     failfast::{self, Failfast},
     message::Message,
+    priority::Pending,
     Pool, QueueMetrics,
 };
 use futures::{future, TryStream, TryStreamExt};
@@ -44,12 +45,24 @@ struct Discovery<R> {
 /// Spawns a task that simultaneously updates a pool of services from a
 /// discovery stream and dispatches requests to it.
 ///
+/// Requests are buffered by the worker while it waits for the pool to become
+/// ready so that they may be dispatched in priority order. When the buffer is
+/// full, the worker stops receiving requests, so callers wait for capacity in
+/// the channel. Requests are only received from a full buffer to displace
+/// strictly lower priority requests, which are shed. A received request that
+/// cannot displace another is held, and no further requests are received,
+/// until the buffer has room for it. Requests may also be dropped as they are
+/// dequeued when queueing delay persistently exceeds the buffer's CoDel
+/// target.
+///
 /// If the pool service does not become ready within the failfast timeout, then
 /// request are failed with a FailFastError until the pool becomes ready. While
 /// in the failfast state, the provided gate is shut so that the caller may
 /// exert backpressure to eliminate requests from being added to the queue.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn spawn<T, Req, R, P>(
     mut reqs_rx: mpsc::Receiver<Message<Req, P::Future>>,
+    mut pending: Pending<Req, P::Future>,
     failfast: time::Duration,
     gate: gate::Tx,
     terminal: Terminate,
@@ -73,48 +86,85 @@ where
                 discovery: Discovery::new(updates_rx),
             };
 
+            // A request that was received while the buffer was full but that
+            // could not displace a lower priority request.
+            let mut held = None;
+
             loop {
-                // Drive the pool with discovery updates while waiting for a
-                // request.
-                //
-                // NOTE: We do NOT require that pool become ready before
-                // processing a request, so this technically means that the
-                // queue supports capacity + 1 items. This behavior is
-                // inherrited from tower::buffer. Correcting this is not worth
-                // the complexity.
-                let msg = tokio::select! {
-                    biased;
-
-                    // If either the discovery stream or the pool fail, close
-                    // the request stream and process any remaining requests.
-                    e = worker.drive_pool() => {
-                        terminal.close(reqs_rx, metrics, error::TerminalFailure::new(e)).await;
-                        return Ok(());
-                    }
+                if let Some(msg) = held.take() {
+                    held = pending.push(msg).err();
+                }
 
-                    msg = reqs_rx.recv() => match msg {
-                        Some(msg) => msg,
-                        None => {
-                            tracing::debug!("Callers dropped");
+                if pending.is_empty() {
+                    // Drive the pool with discovery updates while waiting for
+                    // a request.
+                    //
+                    // NOTE: We do NOT require that pool become ready before
+                    // receiving requests. Requests are moved from the channel
+                    // into the pending buffer as they arrive, until the buffer
+                    // is full.
+                    let msg = tokio::select! {
+                        biased;
+
+                        // If either the discovery stream or the pool fail,
+                        // close the request stream and process any remaining
+                        // requests.
+                        e = worker.drive_pool() => {
+                            let error = error::TerminalFailure::new(e);
+                            terminal.close(reqs_rx, pending, held, metrics, error).await;
                             return Ok(());
                         }
-                    },
-                };
-                metrics.length.dec();
 
-                // Wait for the pool to be ready to process a request. If this fails, we enter
+                        msg = reqs_rx.recv() => match msg {
+                            Some(msg) => msg,
+                            None => {
+                                tracing::debug!("Callers dropped");
+                                return Ok(());
+                            }
+                        },
+                    };
+                    held = pending.push(msg).err();
+                }
+
+                // Buffer all requests that are already waiting so that the
+                // highest priority request is dispatched first.
+                while held.is_none() && pending.accepts() {
+                    let Ok(msg) = reqs_rx.try_recv() else { break };
+                    held = pending.push(msg).err();
+                }
+
+                // Wait for the pool to be ready to process a request,
+                // continuing to buffer requests as they arrive. If this fails,
+                // we fail all pending requests and close the queue.
                 tracing::trace!("Waiting for inner service readiness");
-                if let Err(e) = worker.ready_pool_for_request().await {
+                let ready = loop {
+                    tokio::select! {
+                        biased;
+
+                        res = worker.ready_pool_for_request() => break res,
+
+                        Some(msg) = reqs_rx.recv(), if held.is_none() && pending.accepts() => {
+                            held = pending.push(msg).err();
+                        }
+                    }
+                };
+                if let Err(e) = ready {
                     let error = error::TerminalFailure::new(e);
-                    msg.fail(error.clone());
-                    terminal.close(reqs_rx, metrics, error).await;
+                    terminal.close(reqs_rx, pending, held, metrics, error).await;
                     return Ok(());
                 }
                 tracing::trace!("Pool ready");
 
                 // Process requests, either by dispatching them to the pool or
                 // by serving errors directly.
-                let Message { req, tx, span, t0 } = msg;
+                let Some(Message {
+                    req, tx, span, t0, ..
+                }) = pending.dequeue()
+                else {
+                    tracing::trace!("All pending requests dropped");
+                    continue;
+                };
+                metrics.length.dec();
                 let latency = time::Instant::now() - t0;
                 metrics.latency.observe(latency.as_secs_f64());
 
@@ -381,6 +431,8 @@ impl Terminate {
     async fn close<Req, F>(
         self,
         mut reqs_rx: mpsc::Receiver<Message<Req, F>>,
+        mut pending: Pending<Req, F>,
+        mut held: Option<Message<Req, F>>,
         metrics: QueueMetrics,
         error: error::TerminalFailure,
     ) {
@@ -388,6 +440,14 @@ impl Terminate {
         *self.inner.write() = Some(error.clone());
         reqs_rx.close();
 
+        while let Some(msg) = pending.pop().or_else(|| held.take()) {
+            metrics
+                .latency
+                .observe((time::Instant::now() - msg.t0).as_secs_f64());
+            metrics.length.dec();
+            msg.fail(error.clone());
+        }
+
         while let Some(msg) = reqs_rx.recv().await {
             metrics
                 .latency
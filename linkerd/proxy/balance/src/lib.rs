@@ -15,7 +15,10 @@ use std::{fmt::Debug, marker::PhantomData, net::SocketAddr};
 use tokio::time;
 use tower::load::{self, PeakEwma};
 
-pub use linkerd_proxy_balance_queue::{Pool, QueueMetricFamilies, QueueMetrics, Update};
+pub use linkerd_proxy_balance_queue::{
+    CodelConfig, Pool, Prioritize, Priority, QueueDelayError, QueueMetricFamilies, QueueMetrics,
+    QueueShedError, Update,
+};
 pub use tower::load::peak_ewma;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -40,12 +43,15 @@ pub struct Metrics {
 
 /// Configures a stack to resolve targets to balance requests over `N`-typed
 /// endpoint stacks.
+///
+/// Requests are queued in the order determined by the `P`-typed
+/// [`Prioritize`] implementation.
 #[derive(Debug)]
-pub struct NewBalance<C, Req, X, R, N> {
+pub struct NewBalance<C, Req, X, R, N, P = ()> {
     resolve: R,
     inner: N,
     params: X,
-    _marker: PhantomData<fn(Req) -> C>,
+    _marker: PhantomData<fn(Req) -> (C, P)>,
 }
 
 pub type Balance<Req, F> = Gate<PoolQueue<Req, F>>;
@@ -61,7 +67,7 @@ struct NewPeakEwma<C, Req, N> {
 
 // === impl NewBalance ===
 
-impl<C, Req, X, R, N> NewBalance<C, Req, X, R, N> {
+impl<C, Req, X, R, N, P> NewBalance<C, Req, X, R, N, P> {
     pub fn new(inner: N, resolve: R, params: X) -> Self {
         Self {
             resolve,
@@ -81,9 +87,10 @@ impl<C, Req, X, R, N> NewBalance<C, Req, X, R, N> {
     }
 }
 
-impl<C, T, Req, X, R, M, N, S> NewService<T> for NewBalance<C, Req, X, R, M>
+impl<C, T, Req, X, R, M, N, S, P> NewService<T> for NewBalance<C, Req, X, R, M, P>
 where
-    T: Param<EwmaConfig> + Param<queue::Capacity> + Param<queue::Timeout> + Clone + Send,
+    T: Param<EwmaConfig> + Param<queue::Capacity> + Param<queue::Timeout>,
+    T: Param<Option<CodelConfig>> + Clone + Send,
     X: ExtractParam<Metrics, T>,
     R: Resolve<T>,
     R::Resolution: Unpin,
@@ -95,6 +102,7 @@ where
     S::Error: Into<Error>,
     C: load::TrackCompletion<load::peak_ewma::Handle, S::Response> + Default + Send + 'static,
     Req: Send + 'static,
+    P: Prioritize<Req>,
     Balance<Req, future::ErrInto<<PeakEwma<S, C> as Service<Req>>::Future, Error>>: Service<Req>,
 {
     type Service = Balance<Req, future::ErrInto<<PeakEwma<S, C> as Service<Req>>::Future, Error>>;
@@ -113,6 +121,7 @@ where
 
         let queue::Capacity(capacity) = target.param();
         let queue::Timeout(failfast) = target.param();
+        let codel: Option<CodelConfig> = target.param();
         let metrics = self.params.extract_param(&target);
 
         // The pool wraps the inner endpoint stack so that its inner ready cache
@@ -128,12 +137,20 @@ where
         // that allows passing requests to the service. When all clones of the
         // service are dropped, the queue task completes, dropping the
         // resolution and all inner services.
-        tracing::debug!(capacity, ?failfast, "Spawning p2c pool queue");
-        PoolQueue::spawn(capacity, failfast, metrics.queue, disco, pool)
+        tracing::debug!(capacity, ?failfast, ?codel, "Spawning p2c pool queue");
+        PoolQueue::spawn_prioritized(
+            capacity,
+            failfast,
+            codel,
+            P::priority,
+            metrics.queue,
+            disco,
+            pool,
+        )
     }
 }
 
-impl<C, Req, X: Clone, R: Clone, N: Clone> Clone for NewBalance<C, Req, X, R, N> {
+impl<C, Req, X: Clone, R: Clone, N: Clone, P> Clone for NewBalance<C, Req, X, R, N, P> {
     fn clone(&self) -> Self {
         Self {
             resolve: self.resolve.clone(),
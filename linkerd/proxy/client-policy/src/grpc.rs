@@ -1,6 +1,7 @@
 use crate::FailureAccrual;
 use linkerd_exp_backoff::ExponentialBackoff;
 use linkerd_http_route::{grpc, http};
+use linkerd_proxy_core::Priority;
 use std::{sync::Arc, time};
 
 pub use linkerd_http_route::grpc::{filter, find, r#match, RouteMatch};
@@ -14,6 +15,10 @@ pub struct RouteParams {
     pub timeouts: crate::http::Timeouts,
     pub retry: Option<Retry>,
     pub allow_l5d_request_headers: bool,
+
+    /// The priority of the route's requests when they are queued for a
+    /// backend.
+    pub priority: Option<Priority>,
 }
 
 // TODO HTTP2 settings
@@ -289,6 +294,9 @@ pub mod proto {
                     .transpose()?
                     .unwrap_or_default(),
                 allow_l5d_request_headers,
+                // The API does not express route priorities; they may be
+                // configured by local overrides.
+                priority: None,
             })
         }
     }
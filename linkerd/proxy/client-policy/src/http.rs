@@ -1,6 +1,7 @@
 use crate::FailureAccrual;
 use linkerd_exp_backoff::ExponentialBackoff;
 use linkerd_http_route::http;
+use linkerd_proxy_core::Priority;
 use std::{ops::RangeInclusive, sync::Arc, time};
 
 pub use linkerd_http_route::http::{filter, find, r#match, RouteMatch};
@@ -14,6 +15,10 @@ pub struct RouteParams {
     pub timeouts: Timeouts,
     pub retry: Option<Retry>,
     pub allow_l5d_request_headers: bool,
+
+    /// The priority of the route's requests when they are queued for a
+    /// backend.
+    pub priority: Option<Priority>,
 }
 
 // TODO: keepalive settings, etc.
@@ -327,6 +332,9 @@ pub mod proto {
                     .transpose()?
                     .unwrap_or_default(),
                 allow_l5d_request_headers,
+                // The API does not express route priorities; they may be
+                // configured by local overrides.
+                priority: None,
             })
         }
     }
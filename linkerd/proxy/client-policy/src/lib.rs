@@ -7,10 +7,14 @@ use std::{borrow::Cow, fmt, hash::Hash, net::SocketAddr, num::NonZeroU16, sync::
 pub mod grpc;
 pub mod http;
 pub mod opaq;
+pub mod overrides;
 pub mod tls;
 
+pub use self::overrides::Overrides;
 pub use linkerd_http_route as route;
 pub use linkerd_proxy_api_resolve::Metadata as EndpointMetadata;
+pub use linkerd_proxy_protocol::Version as ProxyProtocolVersion;
+pub use linkerd_proxy_transport::Marking;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ClientPolicy {
@@ -85,6 +89,33 @@ pub struct Backend {
     pub meta: Arc<Meta>,
     pub queue: Queue,
     pub dispatcher: BackendDispatcher,
+
+    /// When set, connections to the backend's endpoints are prefaced by a
+    /// PROXY protocol header of the given version, so that endpoints that are
+    /// not part of the mesh can learn the addresses of the original
+    /// connection.
+    pub proxy_protocol: Option<ProxyProtocolVersion>,
+
+    /// When set, connections to the backend's endpoints are tunneled through
+    /// an upstream HTTP proxy (e.g. for egress traffic that may only leave the
+    /// cluster through a forward proxy).
+    pub upstream_proxy: Option<UpstreamProxy>,
+
+    /// When set, packets on connections to the backend's endpoints are marked
+    /// with a DSCP value and/or a firewall mark.
+    pub marking: Option<Marking>,
+}
+
+/// An HTTP proxy through which connections are tunneled with `CONNECT`
+/// requests.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct UpstreamProxy {
+    pub addr: SocketAddr,
+
+    /// A `proxy-authorization` header value (e.g. `Basic` credentials) to
+    /// send with each `CONNECT` request. It should be marked as sensitive so
+    /// that it is not logged.
+    pub authorization: Option<::http::HeaderValue>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -295,6 +326,21 @@ impl fmt::Display for Meta {
     }
 }
 
+// === impl UpstreamProxy ===
+
+impl fmt::Debug for UpstreamProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Credentials are never logged.
+        f.debug_struct("UpstreamProxy")
+            .field("addr", &self.addr)
+            .field(
+                "authorization",
+                &self.authorization.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
 // === impl FailureAccrual ===
 
 impl Default for FailureAccrual {
@@ -673,6 +719,15 @@ pub mod proto {
                 queue,
                 dispatcher,
                 meta,
+                // The policy API does not yet configure PROXY protocol headers;
+                // they may be enabled by local overrides.
+                proxy_protocol: None,
+                // The policy API does not yet configure upstream proxies; they
+                // may be configured by local overrides.
+                upstream_proxy: None,
+                // The policy API does not yet configure packet marking; it may
+                // be configured by local overrides.
+                marking: None,
             };
 
             Ok(backend)
@@ -1,5 +1,7 @@
 use linkerd_opaq_route as opaq;
 
+pub use linkerd_opaq_route::{find, r#match, RouteMatch, SessionInfo};
+
 pub type Policy = crate::RoutePolicy<Filter, ()>;
 pub type Route = opaq::Route<Policy>;
 pub type Rule = opaq::Rule<Policy>;
@@ -44,10 +46,8 @@ pub(crate) mod proto {
         #[error("invalid distribution: {0}")]
         Distribution(#[from] InvalidDistribution),
 
-        /// Note: this restriction may be removed in the future, if a way of
-        /// actually matching rules for opaque routes is added.
-        #[error("an opaque route must have exactly one rule, but {0} were provided")]
-        OnlyOneRule(usize),
+        #[error("an opaque route must have at least one rule")]
+        NoRules,
 
         /// Note: this restriction may be removed in the future, if a way of
         /// actually matching rules for opaque routes is added.
@@ -62,8 +62,10 @@ pub(crate) mod proto {
     }
 
     pub(crate) fn fill_route_backends(rts: Option<&Route>, set: &mut BackendSet) {
-        if let Some(Route { policy, .. }) = rts {
-            policy.distribution.fill_backends(set);
+        if let Some(Route { rules }) = rts {
+            for Rule { policy, .. } in rules {
+                policy.distribution.fill_backends(set);
+            }
         }
     }
 
@@ -92,16 +94,24 @@ pub(crate) mod proto {
                 .try_into()?,
         );
 
-        // Currently, opaque rules have no match expressions, so if there's
-        // more than one rule, we have no way of determining which one to
-        // use. Therefore, require that there's exactly one rule.
-        if rules.len() != 1 {
-            return Err(InvalidOpaqueRoute::OnlyOneRule(rules.len()));
+        if rules.is_empty() {
+            return Err(InvalidOpaqueRoute::NoRules);
         }
 
-        let rule = rules.first().cloned().expect("already checked");
-        let policy = try_rule(&meta, rule)?;
-        Ok(Route { policy })
+        // The API does not express match criteria for opaque rules, so every
+        // rule matches all sessions and the first rule is used unless matches
+        // are configured by local overrides.
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let policy = try_rule(&meta, rule)?;
+                Ok(Rule {
+                    matches: vec![],
+                    policy,
+                })
+            })
+            .collect::<Result<_, InvalidOpaqueRoute>>()?;
+        Ok(Route { rules })
     }
 
     fn try_rule(
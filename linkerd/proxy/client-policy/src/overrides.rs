@@ -0,0 +1,697 @@
+//! Locally-configured overrides for discovered client policies.
+//!
+//! The policy API does not describe every setting that the proxy supports.
+//! Overrides are configured as a JSON document and are applied to each client
+//! policy as it is discovered, matching routes and backends by their resource
+//! metadata.
+//!
+//! ```json
+//! {
+//!   "routes": [{
+//!     "kind": "TCPRoute",
+//!     "namespace": "emojivoto",
+//!     "name": "voting",
+//!     "rules": [{
+//!       "matches": [{
+//!         "ports": ["8080", "9000-9099"],
+//!         "clientNetworks": ["10.0.0.0/8"],
+//!         "names": ["voting.emojivoto.svc.cluster.local"]
+//!       }]
+//!     }]
+//!   }, {
+//!     "kind": "HTTPRoute",
+//!     "namespace": "emojivoto",
+//!     "name": "web",
+//!     "rules": [{"priority": "high"}, {
+//!       "priority": "low",
+//!       "marking": {"dscp": 8}
+//!     }]
+//!   }, {
+//!     "kind": "TLSRoute",
+//!     "name": "egress",
+//!     "filters": [
+//!       {"denySni": ["*.internal.example.com"]},
+//!       {"allowAlpn": ["h2", "http/1.1"]},
+//!       {"sessionLifetime": "3600s"}
+//!     ]
+//!   }],
+//!   "backends": [{
+//!     "kind": "Service",
+//!     "namespace": "legacy",
+//!     "name": "mysql",
+//!     "proxyProtocol": "v2"
+//!   }, {
+//!     "kind": "EgressNetwork",
+//!     "name": "internet",
+//!     "upstreamProxy": {
+//!       "addr": "10.0.0.1:3128",
+//!       "authorization": "Basic dXNlcjpwYXNz"
+//!     },
+//!     "marking": {"dscp": 10, "mark": 4096}
+//!   }]
+//! }
+//! ```
+//!
+//! Durations are expressed as in the protobuf JSON mapping, i.e. as a number
+//! of seconds with an `s` suffix.
+
+use crate::{
+    grpc, http, opaq, tls, Backend, ClientPolicy, Marking, Meta, Protocol, ProxyProtocolVersion,
+    RouteDistribution, RoutePolicy, UpstreamProxy,
+};
+use linkerd_proxy_core::{priority::InvalidPriority, Priority};
+use linkerd_tls::NegotiatedProtocol;
+use std::{str::FromStr, sync::Arc, time};
+
+#[cfg(test)]
+mod tests;
+
+/// Overrides applied to each discovered [`ClientPolicy`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overrides {
+    routes: Vec<RouteOverride>,
+    backends: Vec<BackendOverride>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RouteOverride {
+    selector: Selector,
+
+    /// Overrides for each of the route's rules, in order.
+    rules: Vec<RuleOverride>,
+
+    /// Replaces a TLS route's filters.
+    tls_filters: Option<Arc<[tls::Filter]>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct RuleOverride {
+    /// Replaces an opaque rule's session matches.
+    opaque_matches: Option<Vec<opaq::r#match::MatchSession>>,
+
+    /// Sets the priority of an HTTP or gRPC rule's requests.
+    priority: Option<Priority>,
+
+    /// Marks packets on connections to the rule's backends. Marked backends
+    /// are distinct from the rule's unmarked backends, so connections are
+    /// never shared across markings.
+    marking: Option<Marking>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BackendOverride {
+    selector: Selector,
+
+    /// Prefaces connections to the backend's endpoints with a PROXY protocol
+    /// header.
+    proxy_protocol: Option<ProxyProtocolVersion>,
+
+    /// Tunnels connections to the backend's endpoints through an HTTP proxy.
+    upstream_proxy: Option<UpstreamProxy>,
+
+    /// Marks packets on connections to the backend's endpoints.
+    marking: Option<Marking>,
+}
+
+/// Selects a resource by its metadata. Unset fields match any value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Selector {
+    kind: Option<String>,
+    namespace: Option<String>,
+    name: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidOverrides {
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid port range: {0}")]
+    PortRange(#[from] opaq::r#match::InvalidPortRange),
+
+    #[error("invalid client network: {0}")]
+    Network(#[from] ipnet::AddrParseError),
+
+    #[error("invalid name: {0}")]
+    Name(String),
+
+    #[error("{0}")]
+    Sni(#[from] tls::sni::InvalidSni),
+
+    #[error("invalid duration: {0}")]
+    Duration(String),
+
+    #[error("{0}")]
+    Priority(#[from] InvalidPriority),
+
+    #[error("invalid upstream proxy address: {0}")]
+    UpstreamProxyAddr(#[from] std::net::AddrParseError),
+
+    #[error("invalid upstream proxy authorization")]
+    UpstreamProxyAuthorization(#[from] ::http::header::InvalidHeaderValue),
+
+    #[error("invalid marking: {0}")]
+    Marking(&'static str),
+}
+
+mod spec {
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Overrides {
+        pub(super) routes: Vec<Route>,
+        pub(super) backends: Vec<Backend>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Route {
+        pub(super) kind: Option<String>,
+        pub(super) namespace: Option<String>,
+        pub(super) name: String,
+        #[serde(default)]
+        pub(super) rules: Vec<Rule>,
+        pub(super) filters: Option<Vec<TlsFilter>>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Rule {
+        pub(super) matches: Option<Vec<SessionMatch>>,
+        pub(super) priority: Option<String>,
+        pub(super) marking: Option<Marking>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct SessionMatch {
+        pub(super) ports: Vec<String>,
+        pub(super) client_networks: Vec<String>,
+        pub(super) names: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Backend {
+        pub(super) kind: Option<String>,
+        pub(super) namespace: Option<String>,
+        pub(super) name: String,
+        pub(super) proxy_protocol: Option<ProxyProtocolVersion>,
+        pub(super) upstream_proxy: Option<UpstreamProxy>,
+        pub(super) marking: Option<Marking>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Marking {
+        pub(super) dscp: Option<u8>,
+        pub(super) mark: Option<u32>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct UpstreamProxy {
+        pub(super) addr: String,
+        pub(super) authorization: Option<String>,
+    }
+
+    // Credentials are never logged.
+    impl std::fmt::Debug for UpstreamProxy {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("UpstreamProxy")
+                .field("addr", &self.addr)
+                .finish_non_exhaustive()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub(super) enum ProxyProtocolVersion {
+        V1,
+        V2,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) enum TlsFilter {
+        DenySni(Vec<String>),
+        AllowAlpn(Vec<String>),
+        SessionLifetime(String),
+    }
+}
+
+// === impl Overrides ===
+
+impl Overrides {
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty() && self.backends.is_empty()
+    }
+
+    /// Applies the overrides to a discovered policy.
+    pub fn apply(&self, mut policy: ClientPolicy) -> ClientPolicy {
+        if self.is_empty() {
+            return policy;
+        }
+
+        // Backends that are marked by a rule override are referenced by the
+        // rule's distribution and must also be listed with the policy's
+        // backends, so that a concrete stack is built for each of them.
+        let mut marked = Vec::new();
+        match policy.protocol {
+            Protocol::Detect {
+                ref mut http1,
+                ref mut http2,
+                ref mut opaque,
+                ..
+            } => {
+                self.apply_http(&mut http1.routes, &mut marked);
+                self.apply_http(&mut http2.routes, &mut marked);
+                self.apply_opaque(opaque, &mut marked);
+            }
+            Protocol::Http1(ref mut http1) => self.apply_http(&mut http1.routes, &mut marked),
+            Protocol::Http2(ref mut http2) => self.apply_http(&mut http2.routes, &mut marked),
+            Protocol::Grpc(ref mut grpc) => self.apply_grpc(&mut grpc.routes, &mut marked),
+            Protocol::Opaque(ref mut opaque) => self.apply_opaque(opaque, &mut marked),
+            Protocol::Tls(ref mut tls) => self.apply_tls(tls),
+        }
+
+        if !self.backends.is_empty() || !marked.is_empty() {
+            let mut backends = policy
+                .backends
+                .iter()
+                .cloned()
+                .map(|mut backend| {
+                    self.apply_backend(&mut backend);
+                    backend
+                })
+                .collect::<Vec<_>>();
+            for backend in marked {
+                if !backends.contains(&backend) {
+                    backends.push(backend);
+                }
+            }
+            policy.backends = backends.into();
+        }
+
+        policy
+    }
+
+    fn apply_http(&self, routes: &mut Arc<[http::Route]>, marked: &mut Vec<Backend>) {
+        *routes = routes
+            .iter()
+            .cloned()
+            .map(|mut route| {
+                for rule in route.rules.iter_mut() {
+                    self.apply_distribution(&mut rule.policy);
+                }
+                if let Some(ovr) = route
+                    .rules
+                    .first()
+                    .and_then(|rule| self.route(&rule.policy.meta))
+                {
+                    for (rule, ovr) in route.rules.iter_mut().zip(&ovr.rules) {
+                        if let Some(priority) = ovr.priority {
+                            rule.policy.params.priority = Some(priority);
+                        }
+                        if let Some(marking) = ovr.marking {
+                            apply_marking(&mut rule.policy, marking, marked);
+                        }
+                    }
+                }
+                route
+            })
+            .collect();
+    }
+
+    fn apply_grpc(&self, routes: &mut Arc<[grpc::Route]>, marked: &mut Vec<Backend>) {
+        *routes = routes
+            .iter()
+            .cloned()
+            .map(|mut route| {
+                for rule in route.rules.iter_mut() {
+                    self.apply_distribution(&mut rule.policy);
+                }
+                if let Some(ovr) = route
+                    .rules
+                    .first()
+                    .and_then(|rule| self.route(&rule.policy.meta))
+                {
+                    for (rule, ovr) in route.rules.iter_mut().zip(&ovr.rules) {
+                        if let Some(priority) = ovr.priority {
+                            rule.policy.params.priority = Some(priority);
+                        }
+                        if let Some(marking) = ovr.marking {
+                            apply_marking(&mut rule.policy, marking, marked);
+                        }
+                    }
+                }
+                route
+            })
+            .collect();
+    }
+
+    fn apply_tls(&self, tls: &mut tls::Tls) {
+        tls.routes = tls
+            .routes
+            .iter()
+            .cloned()
+            .map(|mut route| {
+                if let Some(filters) = self
+                    .route(&route.policy.meta)
+                    .and_then(|ovr| ovr.tls_filters.as_ref())
+                {
+                    route.policy.filters = filters.clone();
+                }
+                self.apply_distribution(&mut route.policy);
+                route
+            })
+            .collect();
+    }
+
+    fn apply_opaque(&self, opaque: &mut opaq::Opaque, marked: &mut Vec<Backend>) {
+        let Some(route) = opaque.routes.as_mut() else {
+            return;
+        };
+        for rule in route.rules.iter_mut() {
+            self.apply_distribution(&mut rule.policy);
+        }
+        let Some(ovr) = route
+            .rules
+            .first()
+            .and_then(|rule| self.route(&rule.policy.meta))
+        else {
+            return;
+        };
+
+        for (rule, ovr) in route.rules.iter_mut().zip(&ovr.rules) {
+            if let Some(matches) = ovr.opaque_matches.as_ref() {
+                rule.matches = matches.clone();
+            }
+            if let Some(marking) = ovr.marking {
+                apply_marking(&mut rule.policy, marking, marked);
+            }
+        }
+    }
+
+    /// Applies backend overrides to each of a route's backends.
+    fn apply_distribution<F: Clone, P>(&self, policy: &mut RoutePolicy<F, P>) {
+        if self.backends.is_empty() {
+            return;
+        }
+
+        policy.distribution = match &policy.distribution {
+            RouteDistribution::Empty => RouteDistribution::Empty,
+            RouteDistribution::FirstAvailable(backends) => RouteDistribution::FirstAvailable(
+                backends
+                    .iter()
+                    .cloned()
+                    .map(|mut rb| {
+                        self.apply_backend(&mut rb.backend);
+                        rb
+                    })
+                    .collect(),
+            ),
+            RouteDistribution::RandomAvailable(backends) => RouteDistribution::RandomAvailable(
+                backends
+                    .iter()
+                    .cloned()
+                    .map(|(mut rb, weight)| {
+                        self.apply_backend(&mut rb.backend);
+                        (rb, weight)
+                    })
+                    .collect(),
+            ),
+        };
+    }
+
+    fn apply_backend(&self, backend: &mut Backend) {
+        let Some(ovr) = self
+            .backends
+            .iter()
+            .find(|b| b.selector.matches(&backend.meta))
+        else {
+            return;
+        };
+
+        if let Some(version) = ovr.proxy_protocol {
+            backend.proxy_protocol = Some(version);
+        }
+        if let Some(proxy) = ovr.upstream_proxy.as_ref() {
+            backend.upstream_proxy = Some(proxy.clone());
+        }
+        if let Some(marking) = ovr.marking {
+            backend.marking = Some(marking);
+        }
+    }
+
+    fn route(&self, meta: &Meta) -> Option<&RouteOverride> {
+        self.routes.iter().find(|r| r.selector.matches(meta))
+    }
+}
+
+/// Marks each of a rule's backends, recording the marked backends.
+///
+/// Because the marking is part of the backend, each marked backend is built as
+/// a distinct concrete target with its own endpoint connections.
+fn apply_marking<F: Clone, P>(
+    policy: &mut RoutePolicy<F, P>,
+    marking: Marking,
+    marked: &mut Vec<Backend>,
+) {
+    let mut mark = |backend: &mut Backend| {
+        backend.marking = Some(marking);
+        if !marked.contains(backend) {
+            marked.push(backend.clone());
+        }
+    };
+    policy.distribution = match &policy.distribution {
+        RouteDistribution::Empty => RouteDistribution::Empty,
+        RouteDistribution::FirstAvailable(backends) => RouteDistribution::FirstAvailable(
+            backends
+                .iter()
+                .cloned()
+                .map(|mut rb| {
+                    mark(&mut rb.backend);
+                    rb
+                })
+                .collect(),
+        ),
+        RouteDistribution::RandomAvailable(backends) => RouteDistribution::RandomAvailable(
+            backends
+                .iter()
+                .cloned()
+                .map(|(mut rb, weight)| {
+                    mark(&mut rb.backend);
+                    (rb, weight)
+                })
+                .collect(),
+        ),
+    };
+}
+
+impl FromStr for Overrides {
+    type Err = InvalidOverrides;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = serde_json::from_str::<spec::Overrides>(s)?;
+        Self::try_from(spec)
+    }
+}
+
+impl TryFrom<spec::Overrides> for Overrides {
+    type Error = InvalidOverrides;
+
+    fn try_from(spec: spec::Overrides) -> Result<Self, Self::Error> {
+        let routes = spec
+            .routes
+            .into_iter()
+            .map(RouteOverride::try_from)
+            .collect::<Result<_, _>>()?;
+        let backends = spec
+            .backends
+            .into_iter()
+            .map(BackendOverride::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { routes, backends })
+    }
+}
+
+// === impl RouteOverride ===
+
+impl TryFrom<spec::Route> for RouteOverride {
+    type Error = InvalidOverrides;
+
+    fn try_from(
+        spec::Route {
+            kind,
+            namespace,
+            name,
+            rules,
+            filters,
+        }: spec::Route,
+    ) -> Result<Self, Self::Error> {
+        let rules = rules
+            .into_iter()
+            .map(RuleOverride::try_from)
+            .collect::<Result<_, _>>()?;
+        let tls_filters = filters
+            .map(|fs| fs.into_iter().map(try_tls_filter).collect())
+            .transpose()?;
+        Ok(Self {
+            selector: Selector {
+                kind,
+                namespace,
+                name,
+            },
+            rules,
+            tls_filters,
+        })
+    }
+}
+
+fn try_tls_filter(filter: spec::TlsFilter) -> Result<tls::Filter, InvalidOverrides> {
+    Ok(match filter {
+        spec::TlsFilter::DenySni(snis) => tls::Filter::DenySni(tls::filter::DenySni {
+            snis: snis.iter().map(|s| s.parse()).collect::<Result<_, _>>()?,
+        }),
+        spec::TlsFilter::AllowAlpn(protocols) => tls::Filter::AllowAlpn(tls::filter::AllowAlpn {
+            protocols: protocols
+                .into_iter()
+                .map(|p| NegotiatedProtocol(p.into_bytes()))
+                .collect(),
+        }),
+        spec::TlsFilter::SessionLifetime(max_duration) => {
+            tls::Filter::SessionLifetime(tls::filter::SessionLifetime {
+                max_duration: parse_duration(&max_duration)?,
+            })
+        }
+    })
+}
+
+// === impl BackendOverride ===
+
+impl TryFrom<spec::Backend> for BackendOverride {
+    type Error = InvalidOverrides;
+
+    fn try_from(
+        spec::Backend {
+            kind,
+            namespace,
+            name,
+            proxy_protocol,
+            upstream_proxy,
+            marking,
+        }: spec::Backend,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            selector: Selector {
+                kind,
+                namespace,
+                name,
+            },
+            proxy_protocol: proxy_protocol.map(|v| match v {
+                spec::ProxyProtocolVersion::V1 => ProxyProtocolVersion::V1,
+                spec::ProxyProtocolVersion::V2 => ProxyProtocolVersion::V2,
+            }),
+            upstream_proxy: upstream_proxy.map(try_upstream_proxy).transpose()?,
+            marking: marking.map(try_marking).transpose()?,
+        })
+    }
+}
+
+fn try_upstream_proxy(
+    spec::UpstreamProxy {
+        addr,
+        authorization,
+    }: spec::UpstreamProxy,
+) -> Result<UpstreamProxy, InvalidOverrides> {
+    let authorization = authorization
+        .map(|auth| {
+            let mut value = ::http::HeaderValue::try_from(auth)?;
+            value.set_sensitive(true);
+            Ok::<_, InvalidOverrides>(value)
+        })
+        .transpose()?;
+    Ok(UpstreamProxy {
+        addr: addr.parse()?,
+        authorization,
+    })
+}
+
+fn try_marking(spec::Marking { dscp, mark }: spec::Marking) -> Result<Marking, InvalidOverrides> {
+    if dscp.is_none() && mark.is_none() {
+        return Err(InvalidOverrides::Marking("one of dscp or mark must be set"));
+    }
+    // A DSCP value occupies the upper six bits of the TOS field.
+    if dscp.is_some_and(|d| d > 0x3f) {
+        return Err(InvalidOverrides::Marking("dscp must be at most 63"));
+    }
+    Ok(Marking { dscp, mark })
+}
+
+// === impl RuleOverride ===
+
+impl TryFrom<spec::Rule> for RuleOverride {
+    type Error = InvalidOverrides;
+
+    fn try_from(
+        spec::Rule {
+            matches,
+            priority,
+            marking,
+        }: spec::Rule,
+    ) -> Result<Self, Self::Error> {
+        let opaque_matches = matches
+            .map(|ms| ms.into_iter().map(try_session_match).collect())
+            .transpose()?;
+        let priority = priority.map(|p| p.parse()).transpose()?;
+        Ok(Self {
+            opaque_matches,
+            priority,
+            marking: marking.map(try_marking).transpose()?,
+        })
+    }
+}
+
+fn try_session_match(
+    spec::SessionMatch {
+        ports,
+        client_networks,
+        names,
+    }: spec::SessionMatch,
+) -> Result<opaq::r#match::MatchSession, InvalidOverrides> {
+    Ok(opaq::r#match::MatchSession {
+        ports: ports.iter().map(|p| p.parse()).collect::<Result<_, _>>()?,
+        client_networks: client_networks
+            .iter()
+            .map(|n| n.parse())
+            .collect::<Result<_, _>>()?,
+        names: names
+            .into_iter()
+            .map(|n| n.parse().map_err(|_| InvalidOverrides::Name(n)))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Parses a positive duration, expressed as seconds with an `s` suffix.
+fn parse_duration(s: &str) -> Result<time::Duration, InvalidOverrides> {
+    s.strip_suffix('s')
+        .and_then(|secs| secs.parse::<f64>().ok())
+        .and_then(|secs| time::Duration::try_from_secs_f64(secs).ok())
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| InvalidOverrides::Duration(s.to_string()))
+}
+
+// === impl Selector ===
+
+impl Selector {
+    fn matches(&self, meta: &Meta) -> bool {
+        self.name == meta.name()
+            && self.kind.as_deref().map_or(true, |k| k == meta.kind())
+            && self
+                .namespace
+                .as_deref()
+                .map_or(true, |ns| ns == meta.namespace())
+    }
+}
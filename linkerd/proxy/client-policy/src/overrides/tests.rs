@@ -0,0 +1,316 @@
+use super::*;
+use crate::{BackendDispatcher, Queue, RouteBackend};
+
+fn mk_meta(kind: &str, name: &str) -> Arc<Meta> {
+    Arc::new(Meta::Resource {
+        group: "gateway.networking.k8s.io".into(),
+        kind: kind.into(),
+        name: name.into(),
+        namespace: "ns".into(),
+        section: None,
+        port: None,
+    })
+}
+
+fn mk_http_policy(route: &str) -> ClientPolicy {
+    let meta = mk_meta("HTTPRoute", route);
+    let rule = |meta: &Arc<Meta>| http::Rule {
+        matches: vec![],
+        policy: http::Policy {
+            meta: meta.clone(),
+            filters: Arc::new([]),
+            distribution: RouteDistribution::Empty,
+            params: Default::default(),
+        },
+    };
+    let routes: Arc<[http::Route]> = Arc::new([http::Route {
+        hosts: vec![],
+        rules: vec![rule(&meta), rule(&meta)],
+    }]);
+    ClientPolicy {
+        parent: Meta::new_default("parent"),
+        protocol: Protocol::Detect {
+            timeout: time::Duration::from_secs(10),
+            http1: http::Http1 {
+                routes: routes.clone(),
+                failure_accrual: Default::default(),
+            },
+            http2: http::Http2 {
+                routes,
+                failure_accrual: Default::default(),
+            },
+            opaque: opaq::Opaque { routes: None },
+        },
+        backends: Arc::new([]),
+    }
+}
+
+fn http_priorities(policy: &ClientPolicy) -> Vec<Option<Priority>> {
+    match &policy.protocol {
+        Protocol::Detect { http1, http2, .. } => {
+            assert_eq!(http1.routes, http2.routes);
+            http1.routes[0]
+                .rules
+                .iter()
+                .map(|rule| rule.policy.params.priority)
+                .collect()
+        }
+        protocol => panic!("unexpected protocol: {protocol:?}"),
+    }
+}
+
+#[test]
+fn route_priority() {
+    let overrides = r#"{
+        "routes": [{
+            "kind": "HTTPRoute",
+            "name": "web",
+            "rules": [{"priority": "high"}, {}]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    assert_eq!(
+        http_priorities(&overrides.apply(mk_http_policy("web"))),
+        [Some(Priority::High), None]
+    );
+    assert_eq!(
+        http_priorities(&overrides.apply(mk_http_policy("api"))),
+        [None, None]
+    );
+}
+
+fn mk_backend(name: &str) -> Backend {
+    Backend {
+        meta: Arc::new(Meta::Resource {
+            group: "core".into(),
+            kind: "Service".into(),
+            name: name.into(),
+            namespace: "ns".into(),
+            section: None,
+            port: None,
+        }),
+        queue: Queue {
+            capacity: 100,
+            failfast_timeout: time::Duration::from_secs(3),
+        },
+        dispatcher: BackendDispatcher::Fail {
+            message: "fail".into(),
+        },
+        proxy_protocol: None,
+        upstream_proxy: None,
+        marking: None,
+    }
+}
+
+fn mk_opaque_policy(backends: &[&str]) -> ClientPolicy {
+    let backends = backends
+        .iter()
+        .map(|name| mk_backend(name))
+        .collect::<Vec<_>>();
+    let route = opaq::Route {
+        rules: vec![opaq::Rule {
+            matches: vec![],
+            policy: opaq::Policy {
+                meta: mk_meta("TCPRoute", "db"),
+                filters: Arc::new([]),
+                distribution: RouteDistribution::FirstAvailable(
+                    backends
+                        .iter()
+                        .map(|backend| RouteBackend {
+                            filters: Arc::new([]),
+                            backend: backend.clone(),
+                        })
+                        .collect(),
+                ),
+                params: (),
+            },
+        }],
+    };
+    ClientPolicy {
+        parent: Meta::new_default("parent"),
+        protocol: Protocol::Opaque(opaq::Opaque {
+            routes: Some(route),
+        }),
+        backends: backends.into(),
+    }
+}
+
+#[test]
+fn backend_proxy_protocol() {
+    let overrides = r#"{
+        "backends": [{
+            "kind": "Service",
+            "name": "mysql",
+            "proxyProtocol": "v2"
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_opaque_policy(&["mysql", "redis"]));
+    let versions = policy
+        .backends
+        .iter()
+        .map(|b| (b.meta.name().to_string(), b.proxy_protocol))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        versions,
+        [
+            ("mysql".to_string(), Some(ProxyProtocolVersion::V2)),
+            ("redis".to_string(), None),
+        ]
+    );
+
+    let Protocol::Opaque(opaq::Opaque {
+        routes: Some(route),
+    }) = &policy.protocol
+    else {
+        panic!("unexpected protocol: {:?}", policy.protocol);
+    };
+    let RouteDistribution::FirstAvailable(backends) = &route.rules[0].policy.distribution else {
+        panic!("unexpected distribution");
+    };
+    assert_eq!(
+        backends[0].backend.proxy_protocol,
+        Some(ProxyProtocolVersion::V2)
+    );
+    assert_eq!(backends[1].backend.proxy_protocol, None);
+}
+
+#[test]
+fn backend_upstream_proxy() {
+    let overrides = r#"{
+        "backends": [{
+            "name": "redis",
+            "upstreamProxy": {
+                "addr": "10.0.0.1:3128",
+                "authorization": "Basic dXNlcjpwYXNz"
+            }
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_opaque_policy(&["mysql", "redis"]));
+    assert_eq!(policy.backends[0].upstream_proxy, None);
+    let proxy = policy.backends[1]
+        .upstream_proxy
+        .as_ref()
+        .expect("upstream proxy must be set");
+    assert_eq!(proxy.addr, ([10, 0, 0, 1], 3128).into());
+    let auth = proxy
+        .authorization
+        .as_ref()
+        .expect("authorization must be set");
+    assert_eq!(auth, "Basic dXNlcjpwYXNz");
+    assert!(auth.is_sensitive());
+    assert!(
+        !format!("{proxy:?}").contains("dXNlcjpwYXNz"),
+        "credentials must not be logged"
+    );
+}
+
+#[test]
+fn backend_marking() {
+    let overrides = r#"{
+        "backends": [{
+            "name": "mysql",
+            "marking": {"dscp": 10, "mark": 4096}
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_opaque_policy(&["mysql", "redis"]));
+    assert_eq!(
+        policy.backends[0].marking,
+        Some(Marking {
+            dscp: Some(10),
+            mark: Some(4096),
+        })
+    );
+    assert_eq!(policy.backends[1].marking, None);
+}
+
+#[test]
+fn rule_marking() {
+    let overrides = r#"{
+        "routes": [{
+            "kind": "HTTPRoute",
+            "name": "web",
+            "rules": [{"marking": {"dscp": 8}}, {}]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let mut policy = mk_http_policy("web");
+    let backend = mk_backend("web");
+    let Protocol::Detect {
+        ref mut http1,
+        ref mut http2,
+        ..
+    } = policy.protocol
+    else {
+        unreachable!()
+    };
+    let mut routes = http1.routes.to_vec();
+    for rule in routes[0].rules.iter_mut() {
+        rule.policy.distribution = RouteDistribution::FirstAvailable(Arc::new([RouteBackend {
+            filters: Arc::new([]),
+            backend: backend.clone(),
+        }]));
+    }
+    http1.routes = routes.into();
+    http2.routes = http1.routes.clone();
+    policy.backends = Arc::new([backend.clone()]);
+
+    let policy = overrides.apply(policy);
+    let Protocol::Detect { http1, .. } = &policy.protocol else {
+        unreachable!()
+    };
+    let markings = http1.routes[0]
+        .rules
+        .iter()
+        .map(|rule| match &rule.policy.distribution {
+            RouteDistribution::FirstAvailable(backends) => backends[0].backend.marking,
+            distribution => panic!("unexpected distribution: {distribution:?}"),
+        })
+        .collect::<Vec<_>>();
+    let marking = Marking {
+        dscp: Some(8),
+        mark: None,
+    };
+    assert_eq!(markings, [Some(marking), None]);
+
+    // The marked backend is distinct from the unmarked backend, so that
+    // connections are not shared across markings.
+    let marked = Backend {
+        marking: Some(marking),
+        ..backend.clone()
+    };
+    assert_eq!(*policy.backends, [backend, marked]);
+}
+
+#[test]
+fn invalid() {
+    for doc in [
+        "",
+        r#"{"routes": [{"kind": "HTTPRoute"}]}"#,
+        r#"{"routes": [{"name": "web", "rules": [{"priority": "urgent"}]}]}"#,
+        r#"{"routes": [{"name": "web", "rules": [{"matches": [{"ports": ["http"]}]}]}]}"#,
+        r#"{"routes": [{"name": "web", "filters": [{"sessionLifetime": "0s"}]}]}"#,
+        r#"{"routes": [{"name": "web", "rules": [{"marking": {}}]}]}"#,
+        r#"{"backends": [{"kind": "Service"}]}"#,
+        r#"{"backends": [{"name": "mysql", "proxyProtocol": "v3"}]}"#,
+        r#"{"backends": [{"name": "mysql", "upstreamProxy": {"addr": "proxy:3128"}}]}"#,
+        r#"{"backends": [{"name": "mysql", "marking": {}}]}"#,
+        r#"{"backends": [{"name": "mysql", "marking": {"dscp": 64}}]}"#,
+        r#"{"backends": [{"name": "mysql", "marking": {"tos": 1}}]}"#,
+        r#"{"backends": [{"name": "mysql", "upstreamProxy": {"addr": "10.0.0.1:3128", "authorization": "\n"}}]}"#,
+    ] {
+        assert!(doc.parse::<Overrides>().is_err(), "{doc:?} must not parse");
+    }
+}
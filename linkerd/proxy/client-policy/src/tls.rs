@@ -1,7 +1,7 @@
 use linkerd_tls_route as tls;
 use std::sync::Arc;
 
-pub use linkerd_tls_route::{find, sni, RouteMatch};
+pub use linkerd_tls_route::{filter, find, sni, RouteMatch, SessionInfo};
 
 pub type Policy = crate::RoutePolicy<Filter, ()>;
 pub type Route = tls::Route<Policy>;
@@ -12,7 +12,11 @@ pub struct Tls {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub enum Filter {}
+pub enum Filter {
+    DenySni(filter::DenySni),
+    AllowAlpn(filter::AllowAlpn),
+    SessionLifetime(filter::SessionLifetime),
+}
 
 pub fn default(distribution: crate::RouteDistribution<Filter>) -> Route {
     Route {
@@ -65,9 +69,6 @@ pub(crate) mod proto {
         #[error("a TLS route must have exactly one rule, but {0} were provided")]
         OnlyOneRule(usize),
 
-        #[error("no filters can be configured on opaque routes yet")]
-        NoFilters,
-
         #[error("missing {0}")]
         Missing(&'static str),
     }
@@ -137,6 +138,8 @@ pub(crate) mod proto {
 
         Ok(Policy {
             meta: meta.clone(),
+            // The API does not express TLS route filters; they may be
+            // configured by local overrides.
             filters: NO_FILTERS.clone(),
             params: (),
             distribution,
@@ -199,11 +202,11 @@ pub(crate) mod proto {
     }
 
     // Necessary to satisfy `RouteBackend::try_from_proto` type constraints.
-    // TODO(eliza): if filters are added to opaque routes, change this to a
-    // proper `TryFrom` impl...
+    // The API does not express backend filters for TLS routes; route filters
+    // are configured by local overrides.
     impl From<()> for Filter {
         fn from(_: ()) -> Self {
-            unreachable!("no filters can be configured on opaque routes yet")
+            unreachable!("no filters can be configured on TLS route backends")
         }
     }
 }
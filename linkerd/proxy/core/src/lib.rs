@@ -1,6 +1,10 @@
 #![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
 #![forbid(unsafe_code)]
 
+pub mod priority;
 pub mod resolve;
 
-pub use self::resolve::{Resolve, ResolveService, Update};
+pub use self::{
+    priority::Priority,
+    resolve::{Resolve, ResolveService, Update},
+};
@@ -0,0 +1,66 @@
+use std::{fmt, str::FromStr};
+
+/// A request's priority class.
+///
+/// When requests must wait for capacity, higher priority requests are served
+/// before lower priority requests and, when a queue is full, lower priority
+/// requests are shed first.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidPriority(String);
+
+// === impl Priority ===
+
+impl Priority {
+    /// All priority classes, from highest to lowest.
+    pub const ALL: [Self; 3] = [Self::High, Self::Normal, Self::Low];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = InvalidPriority;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("low") {
+            return Ok(Self::Low);
+        }
+        if s.eq_ignore_ascii_case("normal") {
+            return Ok(Self::Normal);
+        }
+        if s.eq_ignore_ascii_case("high") {
+            return Ok(Self::High);
+        }
+        Err(InvalidPriority(s.to_string()))
+    }
+}
+
+// === impl InvalidPriority ===
+
+impl fmt::Display for InvalidPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid priority: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPriority {}
@@ -3,5 +3,28 @@ pub use linkerd_proxy_balance::*;
 
 pub type Body<B> = PendingUntilFirstDataBody<peak_ewma::Handle, B>;
 
-pub type NewBalance<B, X, R, N> =
-    linkerd_proxy_balance::NewBalance<PendingUntilFirstData, http::Request<B>, X, R, N>;
+pub type NewBalance<B, X, R, N> = linkerd_proxy_balance::NewBalance<
+    PendingUntilFirstData,
+    http::Request<B>,
+    X,
+    R,
+    N,
+    PriorityExtension,
+>;
+
+/// Prioritizes requests by their [`Priority`] request extension, if one is
+/// set.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PriorityExtension(());
+
+// === impl PriorityExtension ===
+
+impl<B> Prioritize<http::Request<B>> for PriorityExtension {
+    #[inline]
+    fn priority(req: &http::Request<B>) -> Priority {
+        req.extensions()
+            .get::<Priority>()
+            .copied()
+            .unwrap_or_default()
+    }
+}
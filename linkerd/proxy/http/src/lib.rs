@@ -38,7 +38,7 @@ pub use self::{
     header_from_target::NewHeaderFromTarget,
     normalize_uri::{MarkAbsoluteForm, NewNormalizeUri},
     override_authority::{AuthorityOverride, NewOverrideAuthority},
-    retain::Retain,
+    retain::{Retain, RetainBody},
     server::{NewServeHttp, Params as ServerParams, ServeHttp},
     stream_timeouts::{EnforceTimeouts, StreamTimeouts},
     strip_header::StripHeader,
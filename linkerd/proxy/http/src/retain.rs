@@ -87,6 +87,16 @@ where
 
 // === impl RetainBody ===
 
+impl<T, B> RetainBody<T, B> {
+    /// Holds `retain` until the body is dropped.
+    pub fn new(inner: B, retain: T) -> Self {
+        Self {
+            inner,
+            _retain: Some(retain),
+        }
+    }
+}
+
 impl<T, B: Default> Default for RetainBody<T, B> {
     fn default() -> Self {
         Self {
@@ -1,15 +1,36 @@
 use super::Meta;
 use std::{collections::BTreeSet, sync::Arc};
 
+pub mod jwt;
 mod network;
+mod pattern;
 
-pub use self::network::Network;
+pub use self::{
+    jwt::Jwt,
+    network::Network,
+    pattern::{IdentityPattern, InvalidIdentityPattern},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Authorization {
     pub networks: Vec<Network>,
     pub authentication: Authentication,
     pub meta: Arc<Meta>,
+
+    /// Determines whether clients that match the authorization are allowed or
+    /// denied. Deny authorizations take precedence over allow authorizations.
+    pub action: Action,
+
+    /// When set, the authorization never causes a request to be denied.
+    /// Instead, requests that would have been denied are recorded and allowed.
+    pub audit: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Action {
+    #[default]
+    Allow,
+    Deny,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -19,7 +40,18 @@ pub enum Authentication {
     TlsAuthenticated {
         identities: BTreeSet<String>,
         suffixes: Vec<Suffix>,
+
+        /// Glob patterns matched against both DNS-like and URI identities.
+        ///
+        /// The policy API only describes exact identities and suffixes, so
+        /// patterns are configured explicitly by local overrides. Identities
+        /// that include a `*` are never treated as patterns.
+        patterns: Vec<IdentityPattern>,
     },
+
+    /// Authenticates each HTTP request by its JWT bearer token. Connections
+    /// are never authorized by JWT authentication.
+    Jwt(Arc<Jwt>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -127,6 +159,9 @@ pub mod proto {
                                 Authentication::TlsUnauthenticated
                             }
                             api::authn::permit_mesh_tls::Clients::Identities(ids) => {
+                                // Identities from the policy API are always
+                                // matched exactly, even if they include a `*`.
+                                // Patterns are only configured by overrides.
                                 let identities = ids
                                     .identities
                                     .into_iter()
@@ -140,6 +175,7 @@ pub mod proto {
                                 Authentication::TlsAuthenticated {
                                     identities,
                                     suffixes,
+                                    patterns: Vec::new(),
                                 }
                             }
                         }
@@ -160,6 +196,10 @@ pub mod proto {
                 networks,
                 authentication: authn,
                 meta,
+                // The API does not describe deny or audit authorizations; they
+                // are configured with policy overrides.
+                action: Action::Allow,
+                audit: false,
             })
         }
     }
@@ -0,0 +1,426 @@
+//! Authenticates HTTP requests with JWT bearer tokens.
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde_json::Value;
+use std::{collections::BTreeSet, fmt, hash, path::Path, sync::Arc};
+
+/// Authenticates requests that carry a JWT, signed by one of a set of keys, in
+/// their `Authorization: Bearer` header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Jwt {
+    /// The keys with which tokens may be signed.
+    pub jwks: Jwks,
+
+    /// The required value of the token's `iss` claim.
+    pub issuer: String,
+
+    /// The token's `aud` claim must include at least one of these audiences.
+    /// When empty, the audience is not checked.
+    pub audiences: BTreeSet<String>,
+
+    /// Claims that must be present in the token for the authorization to
+    /// apply.
+    pub claims: Vec<ClaimMatch>,
+
+    /// Claims that are forwarded to the application as request headers.
+    pub claim_headers: Vec<ClaimHeader>,
+}
+
+/// Requires that a top-level claim has one of a set of values. Array-valued
+/// claims match when any of their elements has one of the values.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClaimMatch {
+    pub name: String,
+    pub values: BTreeSet<String>,
+}
+
+/// Forwards a top-level claim as a request header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClaimHeader {
+    pub claim: String,
+    pub header: HeaderName,
+}
+
+/// A JSON Web Key Set.
+#[derive(Clone)]
+pub struct Jwks {
+    json: Arc<str>,
+    keys: Arc<[Key]>,
+}
+
+struct Key {
+    id: Option<String>,
+    algorithm: Option<Algorithm>,
+    key: DecodingKey,
+}
+
+/// The claims of a validated token.
+pub type Claims = serde_json::Map<String, Value>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum JwtError {
+    #[error("missing bearer token")]
+    Missing,
+
+    #[error("malformed token")]
+    Malformed,
+
+    #[error("token not signed by a known key")]
+    UnknownKey,
+
+    #[error("invalid token signature")]
+    InvalidSignature,
+
+    #[error("token expired")]
+    Expired,
+
+    #[error("token not yet valid")]
+    Immature,
+
+    #[error("invalid token issuer")]
+    InvalidIssuer,
+
+    #[error("invalid token audience")]
+    InvalidAudience,
+
+    #[error("token claims do not match")]
+    ClaimsMismatch,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidJwks {
+    #[error("failed to read JWKS: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid JWKS: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid JWK: {0}")]
+    Key(#[from] jsonwebtoken::errors::Error),
+}
+
+// === impl Jwt ===
+
+impl Jwt {
+    /// Validates the request's bearer token, returning its claims.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Claims, JwtError> {
+        // Authentication schemes are case-insensitive (RFC 9110 §11.1).
+        let token = headers
+            .get(http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim_start().split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim())
+            .ok_or(JwtError::Missing)?;
+
+        let header = jsonwebtoken::decode_header(token).map_err(|_| JwtError::Malformed)?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        if self.audiences.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&self.audiences.iter().collect::<Vec<_>>());
+        }
+        validation.set_required_spec_claims(&["exp", "iss"]);
+        validation.validate_nbf = true;
+
+        // If the token identifies its key, only that key is tried. Otherwise,
+        // each key that is compatible with the token's algorithm is tried.
+        let mut error = JwtError::UnknownKey;
+        for key in self.jwks.keys.iter() {
+            if header.kid.is_some() && key.id != header.kid {
+                continue;
+            }
+            if key.algorithm.map_or(false, |alg| alg != header.alg) {
+                continue;
+            }
+            match jsonwebtoken::decode::<Claims>(token, &key.key, &validation) {
+                Ok(data) => {
+                    if !self.claims.iter().all(|m| m.matches(&data.claims)) {
+                        return Err(JwtError::ClaimsMismatch);
+                    }
+                    return Ok(data.claims);
+                }
+                Err(e) => {
+                    use jsonwebtoken::errors::ErrorKind;
+                    error = match e.kind() {
+                        // The key is not compatible with the token.
+                        ErrorKind::InvalidAlgorithm => continue,
+                        ErrorKind::InvalidSignature => JwtError::InvalidSignature,
+                        ErrorKind::ExpiredSignature => JwtError::Expired,
+                        ErrorKind::ImmatureSignature => JwtError::Immature,
+                        ErrorKind::InvalidIssuer => JwtError::InvalidIssuer,
+                        ErrorKind::InvalidAudience => JwtError::InvalidAudience,
+                        ErrorKind::MissingRequiredClaim(claim) if claim == "iss" => {
+                            JwtError::InvalidIssuer
+                        }
+                        ErrorKind::MissingRequiredClaim(claim) if claim == "aud" => {
+                            JwtError::InvalidAudience
+                        }
+                        _ => JwtError::Malformed,
+                    };
+                    // Only signature failures may be resolved by another key.
+                    if error != JwtError::InvalidSignature {
+                        return Err(error);
+                    }
+                }
+            }
+        }
+        Err(error)
+    }
+
+    /// Removes the configured claim headers from a request, so that clients
+    /// cannot set them.
+    pub fn remove_claim_headers(&self, headers: &mut HeaderMap) {
+        for ClaimHeader { header, .. } in &self.claim_headers {
+            headers.remove(header);
+        }
+    }
+
+    /// Sets the configured claim headers on a request, replacing any values
+    /// the client may have set.
+    pub fn apply_claim_headers(&self, claims: &Claims, headers: &mut HeaderMap) {
+        for ClaimHeader { claim, header } in &self.claim_headers {
+            headers.remove(header);
+            let value = match claims.get(claim) {
+                Some(Value::String(s)) => s.clone(),
+                Some(v @ (Value::Number(_) | Value::Bool(_))) => v.to_string(),
+                Some(Value::Array(vs)) => vs
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+                _ => continue,
+            };
+            if let Ok(value) = HeaderValue::try_from(value) {
+                headers.insert(header, value);
+            }
+        }
+    }
+}
+
+// === impl ClaimMatch ===
+
+impl ClaimMatch {
+    fn matches(&self, claims: &Claims) -> bool {
+        let matches = |v: &Value| match v {
+            Value::String(s) => self.values.contains(s),
+            Value::Number(_) | Value::Bool(_) => self.values.contains(&v.to_string()),
+            _ => false,
+        };
+        match claims.get(&self.name) {
+            Some(Value::Array(vs)) => vs.iter().any(matches),
+            Some(v) => matches(v),
+            None => false,
+        }
+    }
+}
+
+// === impl JwtError ===
+
+impl JwtError {
+    /// A short description of the failure, suitable for use as a metric
+    /// label.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::Malformed => "malformed",
+            Self::UnknownKey => "unknown_key",
+            Self::InvalidSignature => "invalid_signature",
+            Self::Expired => "expired",
+            Self::Immature => "immature",
+            Self::InvalidIssuer => "invalid_issuer",
+            Self::InvalidAudience => "invalid_audience",
+            Self::ClaimsMismatch => "claims_mismatch",
+        }
+    }
+}
+
+// === impl Jwks ===
+
+impl Jwks {
+    /// Parses a JSON-encoded JWKS.
+    ///
+    /// Keys that are not used for signatures are ignored.
+    pub fn from_json(json: &str) -> Result<Self, InvalidJwks> {
+        let set = serde_json::from_str::<JwkSet>(json)?;
+        let keys = set
+            .keys
+            .iter()
+            .filter(|jwk| {
+                !matches!(
+                    jwk.common.public_key_use,
+                    Some(jsonwebtoken::jwk::PublicKeyUse::Encryption)
+                )
+            })
+            .map(|jwk| {
+                Ok(Key {
+                    id: jwk.common.key_id.clone(),
+                    algorithm: jwk
+                        .common
+                        .key_algorithm
+                        .and_then(|alg| alg.to_string().parse().ok()),
+                    key: DecodingKey::from_jwk(jwk)?,
+                })
+            })
+            .collect::<Result<Arc<[_]>, InvalidJwks>>()?;
+        Ok(Self {
+            json: json.into(),
+            keys,
+        })
+    }
+
+    /// Reads a JSON-encoded JWKS from a local file.
+    pub fn from_file(path: &Path) -> Result<Self, InvalidJwks> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+}
+
+impl fmt::Debug for Jwks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Jwks")
+            .field("keys", &self.keys.iter().map(|k| &k.id).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PartialEq for Jwks {
+    fn eq(&self, other: &Self) -> bool {
+        self.json == other.json
+    }
+}
+
+impl Eq for Jwks {}
+
+impl hash::Hash for Jwks {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.json.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"an-example-secret-of-sufficient-length";
+
+    fn jwt() -> Jwt {
+        let jwks = json!({
+            "keys": [{
+                "kty": "oct",
+                "alg": "HS256",
+                "kid": "k1",
+                // base64url("an-example-secret-of-sufficient-length")
+                "k": "YW4tZXhhbXBsZS1zZWNyZXQtb2Ytc3VmZmljaWVudC1sZW5ndGg",
+            }],
+        });
+        Jwt {
+            jwks: Jwks::from_json(&jwks.to_string()).unwrap(),
+            issuer: "https://issuer.example.com".to_string(),
+            audiences: ["web".to_string()].into(),
+            claims: vec![ClaimMatch {
+                name: "groups".to_string(),
+                values: ["admin".to_string()].into(),
+            }],
+            claim_headers: vec![ClaimHeader {
+                claim: "sub".to_string(),
+                header: HeaderName::from_static("x-jwt-sub"),
+            }],
+        }
+    }
+
+    fn headers(claims: Value, secret: &[u8]) -> HeaderMap {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("k1".to_string());
+        let token =
+            jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::AUTHORIZATION,
+            format!("Bearer {token}").parse().unwrap(),
+        );
+        headers
+    }
+
+    fn claims() -> Value {
+        json!({
+            "iss": "https://issuer.example.com",
+            "aud": "web",
+            "sub": "alice",
+            "groups": ["dev", "admin"],
+            "exp": jsonwebtoken::get_current_timestamp() + 60,
+        })
+    }
+
+    #[test]
+    fn valid() {
+        let jwt = jwt();
+        let mut headers = headers(claims(), SECRET);
+        let claims = jwt.authenticate(&headers).expect("token must be valid");
+        assert_eq!(claims["sub"], "alice");
+
+        headers.insert("x-jwt-sub", "mallory".parse().unwrap());
+        jwt.apply_claim_headers(&claims, &mut headers);
+        assert_eq!(headers.get("x-jwt-sub").unwrap(), "alice");
+
+        jwt.remove_claim_headers(&mut headers);
+        assert!(headers.get("x-jwt-sub").is_none());
+    }
+
+    #[test]
+    fn scheme_is_case_insensitive() {
+        let jwt = jwt();
+        let mut headers = headers(claims(), SECRET);
+        let value = headers
+            .get(http::header::AUTHORIZATION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .replacen("Bearer", "bEARER", 1);
+        headers.insert(http::header::AUTHORIZATION, value.parse().unwrap());
+        assert!(jwt.authenticate(&headers).is_ok());
+
+        headers.insert(http::header::AUTHORIZATION, "Basic abc".parse().unwrap());
+        assert_eq!(jwt.authenticate(&headers), Err(JwtError::Missing));
+    }
+
+    #[test]
+    fn invalid() {
+        let jwt = jwt();
+        let with = |f: fn(&mut Value)| {
+            let mut claims = claims();
+            f(&mut claims);
+            headers(claims, SECRET)
+        };
+
+        assert_eq!(jwt.authenticate(&HeaderMap::new()), Err(JwtError::Missing));
+        assert_eq!(
+            jwt.authenticate(&headers(claims(), b"another-secret-of-sufficient-length")),
+            Err(JwtError::InvalidSignature)
+        );
+        assert_eq!(
+            jwt.authenticate(&with(|c| c["exp"] = json!(1))),
+            Err(JwtError::Expired)
+        );
+        assert_eq!(
+            jwt.authenticate(&with(|c| c["iss"] = json!("https://other.example.com"))),
+            Err(JwtError::InvalidIssuer)
+        );
+        assert_eq!(
+            jwt.authenticate(&with(|c| c["aud"] = json!("api"))),
+            Err(JwtError::InvalidAudience)
+        );
+        assert_eq!(
+            jwt.authenticate(&with(|c| c["groups"] = json!(["dev"]))),
+            Err(JwtError::ClaimsMismatch)
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::AUTHORIZATION, "Bearer abc".parse().unwrap());
+        assert_eq!(jwt.authenticate(&headers), Err(JwtError::Malformed));
+    }
+}
@@ -0,0 +1,252 @@
+use std::{fmt, str::FromStr, sync::Arc};
+
+/// Matches client identities against a glob pattern.
+///
+/// Patterns apply to both DNS-like identities (e.g.
+/// `*.ns.serviceaccount.identity.linkerd.cluster.local`) and SPIFFE URI
+/// identities (e.g. `spiffe://td/ns/*/sa/payments-*`). A `*` matches any
+/// sequence of characters within a single segment--a DNS label or a URI path
+/// segment--and `**` matches any sequence of characters, including segment
+/// separators.
+///
+/// Patterns are compiled when they are parsed so that matching does not
+/// allocate.
+#[derive(Clone)]
+pub struct IdentityPattern {
+    pattern: Arc<str>,
+    tokens: Arc<[Token]>,
+    separator: char,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidIdentityPattern {
+    #[error("identity pattern must not be empty")]
+    Empty,
+
+    #[error("identity pattern must not include more than two consecutive wildcards")]
+    Wildcards,
+}
+
+#[derive(Clone, Debug)]
+enum Token {
+    Literal(Box<str>),
+    /// Matches within a single segment.
+    Star,
+    /// Matches across segments.
+    DoubleStar,
+}
+
+// === impl IdentityPattern ===
+
+impl IdentityPattern {
+    pub fn matches(&self, id: &str) -> bool {
+        matches(&self.tokens, id, self.separator)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl FromStr for IdentityPattern {
+    type Err = InvalidIdentityPattern;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        if pattern.is_empty() {
+            return Err(InvalidIdentityPattern::Empty);
+        }
+
+        // URI identities are split into path segments; all other identities
+        // are treated as DNS-like names split into labels.
+        let separator = if pattern.contains("://") { '/' } else { '.' };
+
+        let mut tokens = Vec::new();
+        let mut rest = pattern;
+        while !rest.is_empty() {
+            let stars = rest.len() - rest.trim_start_matches('*').len();
+            let token = match stars {
+                0 => {
+                    let end = rest.find('*').unwrap_or(rest.len());
+                    let (lit, tail) = rest.split_at(end);
+                    tokens.push(Token::Literal(lit.into()));
+                    rest = tail;
+                    continue;
+                }
+                1 => Token::Star,
+                2 => Token::DoubleStar,
+                _ => return Err(InvalidIdentityPattern::Wildcards),
+            };
+            tokens.push(token);
+            rest = &rest[stars..];
+        }
+
+        Ok(Self {
+            pattern: pattern.into(),
+            tokens: tokens.into(),
+            separator,
+        })
+    }
+}
+
+impl fmt::Debug for IdentityPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IdentityPattern")
+            .field(&self.pattern)
+            .finish()
+    }
+}
+
+impl fmt::Display for IdentityPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pattern.fmt(f)
+    }
+}
+
+impl PartialEq for IdentityPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Eq for IdentityPattern {}
+
+impl std::hash::Hash for IdentityPattern {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.pattern.hash(state);
+    }
+}
+
+/// Matches a string against a compiled pattern.
+///
+/// Like the classic two-pointer glob algorithm, only the most recent
+/// wildcard's match is extended on a mismatch, so matching takes at most
+/// `O(tokens * len)` steps rather than backtracking exponentially. A `*` may not
+/// be extended past a separator; when it cannot be extended, the most recent
+/// `**` is extended instead. Since everything between a `*` and the following
+/// separator is fixed once the separator has been matched, earlier `*`s never
+/// need to be revisited.
+fn matches(tokens: &[Token], s: &str, separator: char) -> bool {
+    // The index of the token following the wildcard, and the end of the
+    // wildcard's current match.
+    let mut star: Option<(usize, usize)> = None;
+    let mut double_star: Option<(usize, usize)> = None;
+
+    let (mut t, mut i) = (0, 0);
+    loop {
+        match tokens.get(t) {
+            Some(Token::Literal(lit)) if s[i..].starts_with(&**lit) => {
+                i += lit.len();
+                t += 1;
+                continue;
+            }
+            Some(Token::Star) => {
+                t += 1;
+                star = Some((t, i));
+                continue;
+            }
+            Some(Token::DoubleStar) => {
+                t += 1;
+                double_star = Some((t, i));
+                star = None;
+                continue;
+            }
+            None if i == s.len() => return true,
+            _ => {}
+        }
+
+        // The pattern does not match at this position, so extend the most
+        // recent wildcard by a character and try again.
+        if let Some((st, si)) = star {
+            match s[si..].chars().next() {
+                Some(c) if c != separator => {
+                    i = si + c.len_utf8();
+                    t = st;
+                    star = Some((st, i));
+                    continue;
+                }
+                _ => star = None,
+            }
+        }
+        if let Some((dt, di)) = double_star {
+            if let Some(c) = s[di..].chars().next() {
+                i = di + c.len_utf8();
+                t = dt;
+                double_star = Some((dt, i));
+                continue;
+            }
+        }
+        return false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> IdentityPattern {
+        s.parse().expect("pattern must be valid")
+    }
+
+    #[test]
+    fn spiffe() {
+        let p = pattern("spiffe://td/ns/*/sa/payments-*");
+        assert!(p.matches("spiffe://td/ns/prod/sa/payments-api"));
+        assert!(p.matches("spiffe://td/ns/dev/sa/payments-"));
+        assert!(!p.matches("spiffe://td/ns/prod/sa/billing"));
+        assert!(!p.matches("spiffe://td/ns/a/b/sa/payments-api"));
+        assert!(!p.matches("spiffe://other/ns/prod/sa/payments-api"));
+
+        let p = pattern("spiffe://td/**");
+        assert!(p.matches("spiffe://td/ns/prod/sa/payments-api"));
+        assert!(!p.matches("spiffe://other/ns/prod"));
+    }
+
+    #[test]
+    fn dns() {
+        let p = pattern("*.prod.serviceaccount.identity.linkerd.cluster.local");
+        assert!(p.matches("web.prod.serviceaccount.identity.linkerd.cluster.local"));
+        assert!(!p.matches("a.web.prod.serviceaccount.identity.linkerd.cluster.local"));
+        assert!(!p.matches("web.dev.serviceaccount.identity.linkerd.cluster.local"));
+
+        let p = pattern("**.cluster.local");
+        assert!(p.matches("a.web.prod.serviceaccount.identity.linkerd.cluster.local"));
+        assert!(!p.matches("cluster.local"));
+
+        let p = pattern("web-*-*.prod.serviceaccount.identity.linkerd.cluster.local");
+        assert!(p.matches("web-a-b.prod.serviceaccount.identity.linkerd.cluster.local"));
+        assert!(!p.matches("web-a.prod.serviceaccount.identity.linkerd.cluster.local"));
+    }
+
+    #[test]
+    fn multibyte() {
+        let p = pattern("spiffe://td/ns/*/sa/é*");
+        assert!(p.matches("spiffe://td/ns/ü/sa/éü"));
+        assert!(!p.matches("spiffe://td/ns/ü/sa/ü"));
+    }
+
+    #[test]
+    fn pathological() {
+        // Backtracking over each wildcard would take exponential time.
+        let id = "a".repeat(64);
+        assert!(!pattern(&format!("{}b", "*a".repeat(16))).matches(&id));
+        assert!(!pattern(&format!("{}b", "**a".repeat(16))).matches(&id));
+        assert!(pattern(&"*a".repeat(16)).matches(&id));
+        assert!(pattern(&"**a".repeat(16)).matches(&id));
+
+        let id = format!("{}.{}", "a".repeat(32), "a".repeat(32));
+        assert!(!pattern(&format!("{}.b", "*a".repeat(8))).matches(&id));
+        assert!(pattern(&format!("**.{}", "*a".repeat(8))).matches(&id));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            "".parse::<IdentityPattern>(),
+            Err(InvalidIdentityPattern::Empty)
+        ));
+        assert!(matches!(
+            "***.cluster.local".parse::<IdentityPattern>(),
+            Err(InvalidIdentityPattern::Wildcards)
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Limits the number of requests that a server or route processes
+/// concurrently.
+///
+/// Limits are stateful, so two limits are only considered equal if they are the
+/// same instance.
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    max_in_flight: usize,
+    queue: Option<Queue>,
+    semaphore: Arc<Semaphore>,
+    queued: AtomicUsize,
+}
+
+/// Configures how requests wait when a concurrency limit is reached.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Queue {
+    /// The maximum number of requests that may wait for capacity.
+    pub capacity: usize,
+
+    /// The maximum amount of time a request may wait for capacity.
+    pub timeout: Duration,
+}
+
+/// Holds one unit of a limit's capacity until it is dropped.
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ConcurrencyLimitExceeded {
+    #[error("concurrency limit exceeded: {0} requests in flight")]
+    InFlight(usize),
+    #[error("concurrency limit queue full: {0} requests waiting")]
+    QueueFull(usize),
+    #[error("concurrency limit not available after {0:?}")]
+    Timeout(Duration),
+}
+
+/// Releases a reserved queue position when a waiting request is dequeued.
+struct Dequeue<'a>(&'a AtomicUsize);
+
+// === impl ConcurrencyLimit ===
+
+impl ConcurrencyLimit {
+    pub fn new(max_in_flight: usize, queue: Option<Queue>) -> Self {
+        Self {
+            max_in_flight,
+            queue,
+            semaphore: Arc::new(Semaphore::new(max_in_flight)),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    pub fn queue(&self) -> Option<Queue> {
+        self.queue
+    }
+
+    /// Acquires a permit, waiting in the queue (if one is configured) when the
+    /// limit has been reached.
+    pub async fn acquire(&self) -> Result<ConcurrencyPermit, ConcurrencyLimitExceeded> {
+        if let Ok(permit) = self.semaphore.clone().try_acquire_owned() {
+            return Ok(ConcurrencyPermit { _permit: permit });
+        }
+
+        let Some(queue) = self.queue else {
+            return Err(ConcurrencyLimitExceeded::InFlight(self.max_in_flight));
+        };
+        if self.queued.fetch_add(1, Ordering::AcqRel) >= queue.capacity {
+            self.queued.fetch_sub(1, Ordering::AcqRel);
+            return Err(ConcurrencyLimitExceeded::QueueFull(queue.capacity));
+        }
+        let _dequeue = Dequeue(&self.queued);
+
+        match tokio::time::timeout(queue.timeout, self.semaphore.clone().acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(ConcurrencyPermit { _permit: permit }),
+            // The semaphore is never closed.
+            Ok(Err(_)) => Err(ConcurrencyLimitExceeded::InFlight(self.max_in_flight)),
+            Err(_) => Err(ConcurrencyLimitExceeded::Timeout(queue.timeout)),
+        }
+    }
+}
+
+impl PartialEq for ConcurrencyLimit {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for ConcurrencyLimit {}
+
+impl std::hash::Hash for ConcurrencyLimit {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self, state)
+    }
+}
+
+// === impl Dequeue ===
+
+impl Drop for Dequeue<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn without_queue() {
+        let limit = ConcurrencyLimit::new(1, None);
+        let permit = limit.acquire().await.expect("must acquire");
+        assert_eq!(
+            limit.acquire().await.unwrap_err(),
+            ConcurrencyLimitExceeded::InFlight(1)
+        );
+        drop(permit);
+        limit.acquire().await.expect("must acquire after release");
+    }
+
+    #[tokio::test]
+    async fn with_queue() {
+        let limit = Arc::new(ConcurrencyLimit::new(
+            1,
+            Some(Queue {
+                capacity: 1,
+                timeout: Duration::from_millis(100),
+            }),
+        ));
+        let permit = limit.acquire().await.expect("must acquire");
+
+        // One request may wait for capacity; others are rejected.
+        let waiting = tokio::spawn({
+            let limit = limit.clone();
+            async move { limit.acquire().await.map(drop) }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(
+            limit.acquire().await.unwrap_err(),
+            ConcurrencyLimitExceeded::QueueFull(1)
+        );
+
+        // Releasing capacity admits the waiting request.
+        drop(permit);
+        waiting
+            .await
+            .unwrap()
+            .expect("queued request must be admitted");
+
+        // Waiting requests time out.
+        let _permit = limit.acquire().await.expect("must acquire");
+        assert_eq!(
+            limit.acquire().await.unwrap_err(),
+            ConcurrencyLimitExceeded::Timeout(Duration::from_millis(100))
+        );
+        assert_eq!(limit.queued.load(Ordering::Acquire), 0);
+    }
+}
@@ -0,0 +1,249 @@
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::NoOpMiddleware,
+    state::keyed::HashMapStateStore,
+    Quota, RateLimiter,
+};
+use linkerd_identity::Id;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Limits the rate at which each client may open connections to a server and
+/// the number of connections each client may hold open concurrently.
+///
+/// Limits are stateful, so two limits are only considered equal if they are the
+/// same instance.
+#[derive(Debug)]
+pub struct ConnectionLimit {
+    key: ConnectionLimitKey,
+    rate: Option<Rate>,
+    max_concurrent: Option<NonZeroUsize>,
+    active: Mutex<HashMap<ConnectionKey, usize>>,
+}
+
+/// Determines how connections are attributed to clients.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionLimitKey {
+    /// Connections are attributed to the client's IP address. These limits
+    /// are enforced as connections are accepted.
+    ClientIp,
+
+    /// Connections are attributed to the client's mesh identity. Since a
+    /// client's identity is only known once TLS has been terminated, these
+    /// limits are enforced after the handshake completes. Clients without an
+    /// identity are attributed to their IP address.
+    ClientIdentity,
+}
+
+/// Identifies the client to which a connection is attributed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionKey {
+    Ip(IpAddr),
+    Identity(Id),
+}
+
+/// Holds a client's concurrency capacity until the connection is closed.
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    limit: Arc<ConnectionLimit>,
+    key: Option<ConnectionKey>,
+}
+
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ConnectionLimitExceeded {
+    #[error("connection rate limit exceeded: {0} connections per second")]
+    Rate(NonZeroU32),
+    #[error("concurrent connection limit exceeded: {0} connections")]
+    Concurrency(NonZeroUsize),
+}
+
+#[derive(Debug)]
+struct Rate {
+    per_second: NonZeroU32,
+    limiter: RateLimiter<
+        ConnectionKey,
+        HashMapStateStore<ConnectionKey>,
+        DefaultClock,
+        NoOpMiddleware<<DefaultClock as Clock>::Instant>,
+    >,
+    checks: AtomicUsize,
+}
+
+/// Bounds the number of clients tracked by a rate limiter before stale
+/// entries are evicted.
+const MAX_RATE_KEYS: usize = 10_000;
+
+/// The number of checks between attempts to evict stale clients. Eviction
+/// visits every entry, so it is amortized over many connections.
+const EVICT_INTERVAL: usize = 1_000;
+
+// === impl ConnectionLimit ===
+
+impl ConnectionLimit {
+    pub fn new(
+        key: ConnectionLimitKey,
+        rate: Option<NonZeroU32>,
+        max_concurrent: Option<NonZeroUsize>,
+    ) -> Self {
+        Self {
+            key,
+            rate: rate.map(|per_second| Rate {
+                per_second,
+                limiter: RateLimiter::hashmap(Quota::per_second(per_second)),
+                checks: AtomicUsize::new(0),
+            }),
+            max_concurrent,
+            active: Default::default(),
+        }
+    }
+
+    pub fn key(&self) -> ConnectionLimitKey {
+        self.key
+    }
+
+    /// Admits a new connection from the given client, returning a permit that
+    /// must be held for the lifetime of the connection.
+    ///
+    /// The concurrency limit is checked first, so that connections rejected
+    /// for exceeding it do not count against the client's rate.
+    pub fn acquire(
+        self: &Arc<Self>,
+        key: ConnectionKey,
+    ) -> Result<ConnectionPermit, ConnectionLimitExceeded> {
+        let Some(max) = self.max_concurrent else {
+            self.check_rate(&key)?;
+            return Ok(ConnectionPermit {
+                limit: self.clone(),
+                key: None,
+            });
+        };
+
+        let mut active = self.active.lock();
+        if active.get(&key).is_some_and(|&count| count >= max.get()) {
+            return Err(ConnectionLimitExceeded::Concurrency(max));
+        }
+        self.check_rate(&key)?;
+        *active.entry(key.clone()).or_default() += 1;
+        Ok(ConnectionPermit {
+            limit: self.clone(),
+            key: Some(key),
+        })
+    }
+
+    fn check_rate(&self, key: &ConnectionKey) -> Result<(), ConnectionLimitExceeded> {
+        let Some(rate) = &self.rate else {
+            return Ok(());
+        };
+        let res = rate.limiter.check_key(key);
+        // Clients are identified by address, so periodically evict replenished
+        // entries before the state grows without bound.
+        if rate.checks.fetch_add(1, Ordering::Relaxed) % EVICT_INTERVAL == 0
+            && rate.limiter.len() > MAX_RATE_KEYS
+        {
+            rate.limiter.retain_recent();
+        }
+        res.map_err(|_| ConnectionLimitExceeded::Rate(rate.per_second))
+    }
+}
+
+impl PartialEq for ConnectionLimit {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for ConnectionLimit {}
+
+impl std::hash::Hash for ConnectionLimit {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self, state)
+    }
+}
+
+// === impl ConnectionPermit ===
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else { return };
+        let mut active = self.limit.active.lock();
+        if let Some(count) = active.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                active.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last: u8) -> ConnectionKey {
+        ConnectionKey::Ip(IpAddr::from([192, 0, 2, last]))
+    }
+
+    #[test]
+    fn concurrency() {
+        let limit = Arc::new(ConnectionLimit::new(
+            ConnectionLimitKey::ClientIp,
+            None,
+            NonZeroUsize::new(2),
+        ));
+        let a0 = limit.acquire(ip(1)).expect("must admit");
+        let _a1 = limit.acquire(ip(1)).expect("must admit");
+        assert_eq!(
+            limit.acquire(ip(1)).unwrap_err(),
+            ConnectionLimitExceeded::Concurrency(NonZeroUsize::new(2).unwrap())
+        );
+
+        // Other clients are limited independently.
+        let b = limit.acquire(ip(2)).expect("must admit");
+
+        drop(a0);
+        limit.acquire(ip(1)).expect("must admit after close");
+        drop(b);
+        assert!(!limit.active.lock().contains_key(&ip(2)));
+    }
+
+    #[test]
+    fn rate() {
+        let limit = Arc::new(ConnectionLimit::new(
+            ConnectionLimitKey::ClientIp,
+            NonZeroU32::new(1),
+            None,
+        ));
+        limit.acquire(ip(1)).expect("must admit");
+        assert_eq!(
+            limit.acquire(ip(1)).unwrap_err(),
+            ConnectionLimitExceeded::Rate(NonZeroU32::new(1).unwrap())
+        );
+        limit.acquire(ip(2)).expect("must admit");
+    }
+
+    #[test]
+    fn concurrency_rejections_are_not_rate_limited() {
+        let limit = Arc::new(ConnectionLimit::new(
+            ConnectionLimitKey::ClientIp,
+            NonZeroU32::new(2),
+            NonZeroUsize::new(1),
+        ));
+        let a = limit.acquire(ip(1)).expect("must admit");
+        assert_eq!(
+            limit.acquire(ip(1)).unwrap_err(),
+            ConnectionLimitExceeded::Concurrency(NonZeroUsize::new(1).unwrap())
+        );
+
+        // The rejected connection did not consume the client's rate.
+        drop(a);
+        let _b = limit.acquire(ip(1)).expect("must admit");
+    }
+}
@@ -0,0 +1,63 @@
+//! Configures routes to consult an external authorization service before
+//! requests are dispatched to the application.
+
+use http::HeaderName;
+use std::collections::BTreeMap;
+
+/// Configures a route to consult an external authorization service.
+///
+/// External authorization applies in addition to the route's authorizations:
+/// a request must be permitted by the route before the service is consulted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExtAuthz {
+    /// Additional context that is sent to the authorization service with each
+    /// request but never to the application.
+    pub context: BTreeMap<String, String>,
+
+    /// The request headers that are sent to the authorization service. When
+    /// empty, all request headers are sent and the service's decisions are not
+    /// cached.
+    pub include_headers: Vec<HeaderName>,
+}
+
+// === impl ExtAuthz ===
+
+impl ExtAuthz {
+    /// Returns the request headers that should be sent to the authorization
+    /// service.
+    pub fn headers<'r>(
+        &'r self,
+        headers: &'r http::HeaderMap,
+    ) -> impl Iterator<Item = (&'r HeaderName, &'r http::HeaderValue)> + 'r {
+        headers.iter().filter(|(name, _)| {
+            self.include_headers.is_empty() || self.include_headers.contains(name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_headers() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-tenant", "acme".parse().unwrap());
+        headers.insert("authorization", "Bearer token".parse().unwrap());
+
+        let all = ExtAuthz::default();
+        assert_eq!(all.headers(&headers).count(), 2);
+
+        let some = ExtAuthz {
+            include_headers: vec![HeaderName::from_static("authorization")],
+            ..Default::default()
+        };
+        assert_eq!(
+            some.headers(&headers).collect::<Vec<_>>(),
+            vec![(
+                &HeaderName::from_static("authorization"),
+                &http::HeaderValue::from_static("Bearer token")
+            )]
+        );
+    }
+}
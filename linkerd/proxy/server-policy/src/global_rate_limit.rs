@@ -0,0 +1,151 @@
+//! Configures requests to an external rate limit service, so that limits may be
+//! enforced across all replicas of a workload.
+
+use http::HeaderName;
+use linkerd_identity::Id;
+
+/// Configures a route to consult an external rate limit service.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlobalRateLimit {
+    /// Scopes the descriptors within the rate limit service's configuration.
+    pub domain: String,
+
+    /// Each descriptor is checked independently. The request is limited if any
+    /// descriptor is over its limit.
+    pub descriptors: Vec<Descriptor>,
+}
+
+/// An ordered list of entries that identifies a limit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Descriptor {
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Entry {
+    /// A fixed key and value.
+    Generic { key: String, value: String },
+
+    /// The value of a request header. The descriptor is omitted if the request
+    /// does not have a valid UTF-8 value for the header.
+    RequestHeader { key: String, header: HeaderName },
+
+    /// The client's mesh identity. The descriptor is omitted if the client is
+    /// not authenticated.
+    ClientIdentity { key: String },
+
+    /// The request's path.
+    Path { key: String },
+}
+
+/// A descriptor's key-value pairs, as resolved for a request.
+pub type DescriptorEntries = Vec<(String, String)>;
+
+// === impl GlobalRateLimit ===
+
+impl GlobalRateLimit {
+    /// Resolves the descriptors that apply to a request.
+    pub fn descriptors<B>(
+        &self,
+        client_id: Option<&Id>,
+        req: &http::Request<B>,
+    ) -> Vec<DescriptorEntries> {
+        self.descriptors
+            .iter()
+            .filter_map(|d| d.resolve(client_id, req))
+            .collect()
+    }
+}
+
+// === impl Descriptor ===
+
+impl Descriptor {
+    fn resolve<B>(
+        &self,
+        client_id: Option<&Id>,
+        req: &http::Request<B>,
+    ) -> Option<DescriptorEntries> {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Generic { key, value } => Some((key.clone(), value.clone())),
+                Entry::RequestHeader { key, header } => {
+                    let value = req.headers().get(header)?.to_str().ok()?;
+                    Some((key.clone(), value.to_string()))
+                }
+                Entry::ClientIdentity { key } => Some((key.clone(), client_id?.to_string())),
+                Entry::Path { key } => Some((key.clone(), req.uri().path().to_string())),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(pairs: &[(&str, &str)]) -> DescriptorEntries {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn descriptors() {
+        let rl = GlobalRateLimit {
+            domain: "test".to_string(),
+            descriptors: vec![
+                Descriptor {
+                    entries: vec![
+                        Entry::Generic {
+                            key: "route".to_string(),
+                            value: "search".to_string(),
+                        },
+                        Entry::Path {
+                            key: "path".to_string(),
+                        },
+                    ],
+                },
+                Descriptor {
+                    entries: vec![Entry::ClientIdentity {
+                        key: "client".to_string(),
+                    }],
+                },
+                Descriptor {
+                    entries: vec![Entry::RequestHeader {
+                        key: "tenant".to_string(),
+                        header: HeaderName::from_static("x-tenant"),
+                    }],
+                },
+            ],
+        };
+        let id = "client.ns.serviceaccount.identity.linkerd.cluster.local"
+            .parse::<Id>()
+            .unwrap();
+
+        let req = http::Request::builder()
+            .uri("http://example.com/search?q=foo")
+            .header("x-tenant", "acme")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            rl.descriptors(Some(&id), &req),
+            vec![
+                entries(&[("route", "search"), ("path", "/search")]),
+                entries(&[("client", &id.to_string())]),
+                entries(&[("tenant", "acme")]),
+            ]
+        );
+
+        // Descriptors are omitted when their entries do not apply.
+        let req = http::Request::builder()
+            .uri("http://example.com/search")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            rl.descriptors(None, &req),
+            vec![entries(&[("route", "search"), ("path", "/search")])]
+        );
+    }
+}
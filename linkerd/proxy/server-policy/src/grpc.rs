@@ -29,6 +29,10 @@ pub fn default(authorizations: std::sync::Arc<[crate::Authorization]>) -> Route
                 meta: crate::Meta::new_default("default"),
                 authorizations,
                 filters: vec![],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
             },
         }],
     }
@@ -135,6 +139,12 @@ pub mod proto {
             crate::RoutePolicy {
                 authorizations,
                 filters,
+                // The API does not express route-level limits or external
+                // authorization; these may be configured by local overrides.
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
                 meta,
             }
         };
@@ -4,15 +4,25 @@
 use std::{hash::Hash, sync::Arc, time};
 
 pub mod authz;
+pub mod concurrency_limit;
+pub mod connection_limit;
+pub mod ext_authz;
+pub mod global_rate_limit;
 pub mod grpc;
 pub mod http;
 pub mod local_rate_limit;
 pub mod meta;
+pub mod overrides;
 
 pub use self::{
     authz::{Authentication, Authorization},
-    local_rate_limit::{LocalRateLimit, RateLimitError},
+    concurrency_limit::{ConcurrencyLimit, ConcurrencyLimitExceeded},
+    connection_limit::{ConnectionLimit, ConnectionLimitExceeded},
+    ext_authz::ExtAuthz,
+    global_rate_limit::GlobalRateLimit,
+    local_rate_limit::{LocalRateLimit, RateLimitError, RateLimited, Rejection},
     meta::Meta,
+    overrides::Overrides,
 };
 pub use linkerd_http_route as route;
 
@@ -21,6 +31,22 @@ pub struct ServerPolicy {
     pub protocol: Protocol,
     pub meta: Arc<Meta>,
     pub local_rate_limit: Arc<LocalRateLimit>,
+
+    /// Limits the number of HTTP requests processed concurrently by the
+    /// server.
+    pub concurrency_limit: Option<Arc<ConcurrencyLimit>>,
+
+    /// Limits the rate and number of connections each client may open to the
+    /// server.
+    pub connection_limit: Option<Arc<ConnectionLimit>>,
+
+    /// A 6-bit Differentiated Services Code Point with which to mark packets
+    /// sent to the server's clients (i.e. response traffic).
+    pub dscp: Option<u8>,
+
+    /// Whether connections to the server are prefaced by a PROXY protocol
+    /// header that identifies the original client.
+    pub proxy_protocol: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -42,6 +68,21 @@ pub struct RoutePolicy<T> {
     pub meta: Arc<Meta>,
     pub authorizations: Arc<[Authorization]>,
     pub filters: Vec<T>,
+
+    /// Limits the rate of requests on this route, in addition to any limits
+    /// configured on the server.
+    pub local_rate_limit: Arc<LocalRateLimit>,
+
+    /// Consults an external rate limit service for each request on this route.
+    pub global_rate_limit: Option<Arc<GlobalRateLimit>>,
+
+    /// Consults an external authorization service for each request on this
+    /// route.
+    pub ext_authz: Option<Arc<ExtAuthz>>,
+
+    /// Limits the number of requests processed concurrently on this route, in
+    /// addition to any limit configured on the server.
+    pub concurrency_limit: Option<Arc<ConcurrencyLimit>>,
 }
 
 impl ServerPolicy {
@@ -63,12 +104,20 @@ impl ServerPolicy {
                             filters: vec![http::Filter::InternalError(
                                 "invalid server configuration",
                             )],
+                            local_rate_limit: Default::default(),
+                            global_rate_limit: None,
+                            ext_authz: None,
+                            concurrency_limit: None,
                         },
                     }],
                 }]),
                 tcp_authorizations: Arc::new([]),
             },
             local_rate_limit: Arc::new(LocalRateLimit::default()),
+            concurrency_limit: None,
+            connection_limit: None,
+            dscp: None,
+            proxy_protocol: false,
         }
     }
 }
@@ -167,6 +216,8 @@ pub mod proto {
                     meta: Arc::new(Meta::Default {
                         name: "localhost".into(),
                     }),
+                    action: authz::Action::Allow,
+                    audit: false,
                 };
 
                 authz::proto::mk_authorizations(authorizations, &[localhost])?
@@ -214,6 +265,19 @@ pub mod proto {
                 protocol,
                 meta,
                 local_rate_limit: Arc::new(local_rate_limit),
+                // The API does not express concurrency limits; they may be
+                // configured by local overrides, which preserve each limit's
+                // state across policy updates.
+                concurrency_limit: None,
+                // The API does not express connection limits; they may be
+                // configured by local overrides.
+                connection_limit: None,
+                // The policy API does not yet configure packet marking; it may
+                // be configured by local overrides.
+                dscp: None,
+                // The API does not express whether clients use the PROXY
+                // protocol; it may be configured by local overrides.
+                proxy_protocol: false,
             })
         }
     }
@@ -4,21 +4,69 @@ use governor::{
     clock::{Clock, DefaultClock},
     middleware::NoOpMiddleware,
     state::{keyed::HashMapStateStore, InMemoryState, RateLimiter, StateStore},
+    NotUntil,
 };
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
 use linkerd_identity::Id;
-use std::{collections::HashMap, num::NonZeroU32, sync::Arc};
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 #[cfg(test)]
 mod tests;
 
 type Direct = InMemoryState;
 type Keyed = HashMapStateStore<Option<Id>>;
+type HeaderKeyed = HashMapStateStore<Option<HeaderValue>>;
+
+static RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+static RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+static RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Bounds the number of header values tracked by a per-header limiter before
+/// stale entries are evicted.
+const MAX_HEADER_KEYS: usize = 10_000;
+
+/// The number of checks between attempts to evict stale header values. Eviction
+/// visits every entry, so it is amortized over many requests.
+const EVICT_INTERVAL: usize = 1_000;
 
+/// Limits the rate of requests on a server or route.
+///
+/// Rate limiters are stateful, so two limits are only considered equal if they
+/// are the same instance.
 #[derive(Debug, Default)]
 pub struct LocalRateLimit<C: Clock = DefaultClock> {
     total: Option<RateLimit<Direct, C>>,
     per_identity: Option<RateLimit<Keyed, C>>,
+    per_header: Option<PerHeader<C>>,
     overrides: HashMap<Id, Arc<RateLimit<Direct, C>>>,
+    rejection: Rejection,
+}
+
+/// Configures the response to rate-limited requests.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rejection {
+    /// The HTTP status, typically 429 (Too Many Requests) or 503 (Service
+    /// Unavailable).
+    pub http_status: StatusCode,
+
+    /// The gRPC status code, typically `RESOURCE_EXHAUSTED` or `UNAVAILABLE`.
+    pub grpc_code: tonic::Code,
+}
+
+/// Limits requests for each distinct value of a request header.
+#[derive(Debug)]
+struct PerHeader<C: Clock> {
+    header: HeaderName,
+    limit: RateLimit<HeaderKeyed, C>,
+    checks: AtomicUsize,
 }
 
 #[derive(Debug)]
@@ -31,19 +79,33 @@ where
     limiter: RateLimiter<S::Key, S, C, NoOpMiddleware<C::Instant>>,
 }
 
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
 pub enum RateLimitError {
     #[error("total rate limit exceeded: {0}rps")]
     Total(NonZeroU32),
     #[error("per-identity rate limit exceeded: {0}rps")]
     PerIdentity(NonZeroU32),
+    #[error("per-{0} rate limit exceeded: {1}rps")]
+    PerHeader(HeaderName, NonZeroU32),
     #[error("override rate limit exceeded: {0}rps")]
     Override(NonZeroU32),
 }
 
+/// A rate-limited request, with the information needed to inform the client
+/// when it may retry.
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+#[error("{limit}")]
+pub struct RateLimited {
+    pub limit: RateLimitError,
+
+    /// The amount of time until the exhausted limit permits another request.
+    pub retry_after: Duration,
+
+    pub rejection: Rejection,
+}
+
 // === impl LocalRateLimit ===
 
-#[cfg(any(feature = "proto", feature = "test-util"))]
 impl RateLimit<Direct, DefaultClock> {
     fn direct(rps: NonZeroU32) -> Self {
         let limiter = RateLimiter::direct(governor::Quota::per_second(rps));
@@ -51,7 +113,6 @@ impl RateLimit<Direct, DefaultClock> {
     }
 }
 
-#[cfg(any(feature = "proto", feature = "test-util"))]
 impl RateLimit<Keyed, DefaultClock> {
     fn keyed(rps: NonZeroU32) -> Self {
         let limiter = RateLimiter::hashmap(governor::Quota::per_second(rps));
@@ -59,6 +120,51 @@ impl RateLimit<Keyed, DefaultClock> {
     }
 }
 
+impl RateLimit<HeaderKeyed, DefaultClock> {
+    fn header_keyed(rps: NonZeroU32) -> Self {
+        let limiter = RateLimiter::hashmap(governor::Quota::per_second(rps));
+        Self { rps, limiter }
+    }
+}
+
+impl LocalRateLimit {
+    /// Limits the total rate of requests and the rate of requests from each
+    /// client identity.
+    pub fn new(total: Option<NonZeroU32>, per_identity: Option<NonZeroU32>) -> Self {
+        Self {
+            total: total.map(RateLimit::direct),
+            per_identity: per_identity.map(RateLimit::keyed),
+            ..Default::default()
+        }
+    }
+
+    /// Limits requests from each of the given clients to a shared rate, in
+    /// place of the per-identity limit.
+    pub fn with_override(mut self, rps: NonZeroU32, clients: impl IntoIterator<Item = Id>) -> Self {
+        let limit = Arc::new(RateLimit::direct(rps));
+        self.overrides
+            .extend(clients.into_iter().map(|id| (id, limit.clone())));
+        self
+    }
+
+    /// Additionally limits requests for each distinct value of the given
+    /// request header. Requests without the header share a single limit.
+    pub fn with_per_header(mut self, header: HeaderName, rps: NonZeroU32) -> Self {
+        self.per_header = Some(PerHeader {
+            header,
+            limit: RateLimit::header_keyed(rps),
+            checks: AtomicUsize::new(0),
+        });
+        self
+    }
+
+    /// Configures the response to rate-limited requests.
+    pub fn with_rejection(mut self, rejection: Rejection) -> Self {
+        self.rejection = rejection;
+        self
+    }
+}
+
 #[cfg(feature = "test-util")]
 impl LocalRateLimit {
     pub fn new_no_overrides_for_test(
@@ -68,23 +174,64 @@ impl LocalRateLimit {
         LocalRateLimit {
             total: total.and_then(NonZeroU32::new).map(RateLimit::direct),
             per_identity: per_identity.and_then(NonZeroU32::new).map(RateLimit::keyed),
+            per_header: None,
             overrides: HashMap::new(),
+            rejection: Rejection::default(),
         }
     }
 }
 
 impl<C: Clock> LocalRateLimit<C> {
     pub fn check(&self, id: Option<&Id>) -> Result<(), RateLimitError> {
+        self.check_request(id, &HeaderMap::new())
+            .map_err(|RateLimited { limit, .. }| limit)
+    }
+
+    pub fn check_request(&self, id: Option<&Id>, headers: &HeaderMap) -> Result<(), RateLimited> {
+        let limited = |limit, retry_after| RateLimited {
+            limit,
+            retry_after,
+            rejection: self.rejection,
+        };
+
         if let Some(lim) = &self.total {
-            if lim.limiter.check().is_err() {
-                return Err(RateLimitError::Total(lim.rps));
+            if let Err(nu) = lim.limiter.check() {
+                return Err(limited(RateLimitError::Total(lim.rps), lim.wait_time(nu)));
+            }
+        }
+
+        if let Some(PerHeader {
+            header,
+            limit,
+            checks,
+        }) = &self.per_header
+        {
+            // Like unauthenticated clients, requests without the header share
+            // the same rate limit.
+            let key = headers.get(header).cloned();
+            let res = limit.limiter.check_key(&key);
+            // Header values are chosen by clients, so periodically evict
+            // replenished entries before the state grows without bound.
+            if checks.fetch_add(1, Ordering::Relaxed) % EVICT_INTERVAL == 0
+                && limit.limiter.len() > MAX_HEADER_KEYS
+            {
+                limit.limiter.retain_recent();
+            }
+            if let Err(nu) = res {
+                return Err(limited(
+                    RateLimitError::PerHeader(header.clone(), limit.rps),
+                    limit.wait_time(nu),
+                ));
             }
         }
 
         if let Some(id) = id {
             if let Some(lim) = self.overrides.get(id) {
-                if lim.limiter.check().is_err() {
-                    return Err(RateLimitError::Override(lim.rps));
+                if let Err(nu) = lim.limiter.check() {
+                    return Err(limited(
+                        RateLimitError::Override(lim.rps),
+                        lim.wait_time(nu),
+                    ));
                 }
                 return Ok(());
             }
@@ -92,8 +239,11 @@ impl<C: Clock> LocalRateLimit<C> {
 
         if let Some(lim) = &self.per_identity {
             // Note that clients with no identity share the same rate limit (Id = None)
-            if lim.limiter.check_key(&id.cloned()).is_err() {
-                return Err(RateLimitError::PerIdentity(lim.rps));
+            if let Err(nu) = lim.limiter.check_key(&id.cloned()) {
+                return Err(limited(
+                    RateLimitError::PerIdentity(lim.rps),
+                    lim.wait_time(nu),
+                ));
             }
         }
 
@@ -101,8 +251,70 @@ impl<C: Clock> LocalRateLimit<C> {
     }
 }
 
+impl<C: Clock> PartialEq for LocalRateLimit<C> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl<C: Clock> Eq for LocalRateLimit<C> {}
+
+impl<C: Clock> std::hash::Hash for LocalRateLimit<C> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self, state)
+    }
+}
+
+// === impl RateLimited ===
+
+impl RateLimited {
+    /// Returns the `Retry-After` and IETF `RateLimit-*` headers that describe
+    /// this rejection to clients.
+    pub fn headers(&self) -> HeaderMap {
+        let rps = match &self.limit {
+            RateLimitError::Total(rps)
+            | RateLimitError::PerIdentity(rps)
+            | RateLimitError::PerHeader(_, rps)
+            | RateLimitError::Override(rps) => *rps,
+        };
+
+        // Clients may only wait for whole seconds, so round up to avoid
+        // retrying before the limit has replenished.
+        let mut reset = self.retry_after.as_secs();
+        if self.retry_after.subsec_nanos() > 0 {
+            reset += 1;
+        }
+        let reset = HeaderValue::from(reset);
+
+        let mut headers = HeaderMap::with_capacity(4);
+        headers.insert(http::header::RETRY_AFTER, reset.clone());
+        headers.insert(RATELIMIT_LIMIT.clone(), HeaderValue::from(rps.get()));
+        headers.insert(RATELIMIT_REMAINING.clone(), HeaderValue::from_static("0"));
+        headers.insert(RATELIMIT_RESET.clone(), reset);
+        headers
+    }
+}
+
+// === impl Rejection ===
+
+impl Default for Rejection {
+    fn default() -> Self {
+        Self {
+            http_status: StatusCode::TOO_MANY_REQUESTS,
+            grpc_code: tonic::Code::ResourceExhausted,
+        }
+    }
+}
+
 // === impl RateLimit ===
 
+impl<S: StateStore, C: Clock> RateLimit<S, C> {
+    /// Returns the amount of time until the limiter permits another request.
+    fn wait_time(&self, not_until: NotUntil<C::Instant>) -> Duration {
+        not_until.wait_time_from(self.limiter.clock().now())
+    }
+}
+
 #[cfg(test)]
 impl RateLimit<Direct, FakeRelativeClock> {
     fn direct_for_test(rps: u32) -> Self {
@@ -115,7 +327,10 @@ impl RateLimit<Direct, FakeRelativeClock> {
 }
 
 #[cfg(test)]
-impl RateLimit<Keyed, FakeRelativeClock> {
+impl<K> RateLimit<HashMapStateStore<K>, FakeRelativeClock>
+where
+    K: Clone + Eq + std::hash::Hash,
+{
     fn keyed_for_test(rps: u32) -> Self {
         let rps = NonZeroU32::new(rps).expect("non-zero RPS");
         let quota = governor::Quota::per_second(rps);
@@ -168,7 +383,9 @@ pub mod proto {
             Self {
                 total,
                 per_identity,
+                per_header: None,
                 overrides,
+                rejection: Rejection::default(),
             }
         }
     }
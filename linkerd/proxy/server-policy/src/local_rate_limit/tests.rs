@@ -79,7 +79,9 @@ async fn check_rate_limits() {
     let rl = LocalRateLimit {
         total: Some(total),
         per_identity: Some(per_identity),
+        per_header: None,
         overrides,
+        rejection: Rejection::default(),
     };
 
     // These clients will be rate-limited by the per_identity rate-limiter
@@ -163,3 +165,88 @@ async fn check_rate_limits() {
         client_4_clock.advance(Duration::from_secs(1));
     }
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn check_per_header_rate_limits() {
+    let header = HeaderName::from_static("x-tenant");
+    let rl = LocalRateLimit {
+        total: None,
+        per_identity: None,
+        per_header: Some(PerHeader {
+            header: header.clone(),
+            limit: RateLimit::keyed_for_test(2),
+            checks: AtomicUsize::new(0),
+        }),
+        overrides: HashMap::new(),
+        rejection: Rejection::default(),
+    };
+    let clock = rl.per_header.as_ref().unwrap().limit.limiter.clock();
+
+    let tenant = |value: &'static str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(header.clone(), HeaderValue::from_static(value));
+        headers
+    };
+    let tenant_a = tenant("a");
+    let tenant_b = tenant("b");
+    let client: Id = "client-1".parse().unwrap();
+
+    for _ in 1..=3 {
+        // Requests are limited per header value, regardless of identity.
+        assert!(rl.check_request(Some(&client), &tenant_a).is_ok());
+        assert!(rl.check_request(None, &tenant_a).is_ok());
+        assert_eq!(
+            rl.check_request(None, &tenant_a).map_err(|e| e.limit),
+            Err(RateLimitError::PerHeader(
+                header.clone(),
+                NonZeroU32::new(2).unwrap()
+            ))
+        );
+        assert!(rl.check_request(Some(&client), &tenant_b).is_ok());
+
+        // Requests without the header share a limit.
+        assert!(rl.check_request(None, &HeaderMap::new()).is_ok());
+        assert!(rl.check(Some(&client)).is_ok());
+        assert!(rl.check(None).is_err());
+
+        clock.advance(Duration::from_secs(1));
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rate_limited_headers() {
+    let rl = LocalRateLimit {
+        total: Some(RateLimit::direct_for_test(4)),
+        per_identity: None,
+        per_header: None,
+        overrides: HashMap::new(),
+        rejection: Rejection {
+            http_status: StatusCode::SERVICE_UNAVAILABLE,
+            grpc_code: tonic::Code::Unavailable,
+        },
+    };
+
+    for _ in 1..=4 {
+        assert!(rl.check_request(None, &HeaderMap::new()).is_ok());
+    }
+    let limited = rl
+        .check_request(None, &HeaderMap::new())
+        .expect_err("must be rate limited");
+    assert_eq!(
+        limited.limit,
+        RateLimitError::Total(NonZeroU32::new(4).unwrap())
+    );
+    // A token is replenished every 250ms.
+    assert_eq!(limited.retry_after, Duration::from_millis(250));
+    assert_eq!(
+        limited.rejection.http_status,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(limited.rejection.grpc_code, tonic::Code::Unavailable);
+
+    let headers = limited.headers();
+    assert_eq!(headers.get(http::header::RETRY_AFTER).unwrap(), "1");
+    assert_eq!(headers.get("ratelimit-limit").unwrap(), "4");
+    assert_eq!(headers.get("ratelimit-remaining").unwrap(), "0");
+    assert_eq!(headers.get("ratelimit-reset").unwrap(), "1");
+}
@@ -0,0 +1,920 @@
+//! Locally-configured overrides for discovered server policies.
+//!
+//! The policy API does not describe every setting that the proxy supports.
+//! Overrides are configured as a JSON document and are applied to each server
+//! policy as it is discovered, matching servers and routes by their resource
+//! metadata.
+//!
+//! ```json
+//! {
+//!   "servers": [{
+//!     "kind": "server",
+//!     "name": "web-http",
+//!     "localRateLimit": {
+//!       "total": 1000,
+//!       "rejection": {"httpStatus": 503, "grpcCode": 14}
+//!     },
+//!     "concurrencyLimit": {
+//!       "maxInFlight": 100,
+//!       "queue": {"capacity": 50, "timeout": "0.5s"}
+//!     },
+//!     "connectionLimit": {
+//!       "key": "clientIp",
+//!       "connectionsPerSecond": 10,
+//!       "maxConcurrent": 100
+//!     },
+//!     "proxyProtocol": true,
+//!     "dscp": 46,
+//!     "routes": [{
+//!       "kind": "httproute",
+//!       "name": "books",
+//!       "localRateLimit": {
+//!         "total": 100,
+//!         "identity": 20,
+//!         "overrides": [{
+//!           "requestsPerSecond": 50,
+//!           "identities": ["books.default.serviceaccount.identity.linkerd.cluster.local"]
+//!         }],
+//!         "header": {"name": "x-tenant-id", "requestsPerSecond": 10}
+//!       },
+//!       "globalRateLimit": {
+//!         "domain": "books",
+//!         "descriptors": [{
+//!           "entries": [
+//!             {"generic": {"key": "route", "value": "books"}},
+//!             {"clientIdentity": {"key": "client"}}
+//!           ]
+//!         }]
+//!       },
+//!       "extAuthz": {
+//!         "context": {"tier": "gold"},
+//!         "includeHeaders": ["authorization"]
+//!       },
+//!       "concurrencyLimit": {"maxInFlight": 10}
+//!     }],
+//!     "authorizations": [{
+//!       "kind": "authorizationpolicy",
+//!       "name": "web-users",
+//!       "jwt": {
+//!         "jwksFile": "/var/run/linkerd/jwks/web.json",
+//!         "issuer": "https://issuer.example.com",
+//!         "audiences": ["web"],
+//!         "claims": [{"name": "groups", "values": ["admin"]}],
+//!         "claimHeaders": [{"claim": "sub", "header": "x-jwt-sub"}]
+//!       }
+//!     }, {
+//!       "kind": "authorizationpolicy",
+//!       "name": "payments-clients",
+//!       "identityPatterns": ["spiffe://td/ns/*/sa/payments-*"]
+//!     }, {
+//!       "kind": "authorizationpolicy",
+//!       "name": "deny-legacy",
+//!       "action": "deny",
+//!       "audit": true
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! Authorization overrides apply to each of the server's authorizations,
+//! including those of its routes, that match the selector.
+//!
+//! Identity patterns (see [`IdentityPattern`]) permit clients of a mesh TLS
+//! authorization in addition to its discovered identities. Identities
+//! discovered from the policy API are always matched exactly, even if they
+//! include a `*`.
+//!
+//! Durations are expressed as in the protobuf JSON mapping, i.e. as a number
+//! of seconds with an `s` suffix.
+//!
+//! Stateful settings, like rate and concurrency limits, are constructed once
+//! when the overrides are loaded, so their state is preserved across policy
+//! updates.
+//! Likewise, JWKS files are only read when the overrides are loaded.
+
+use crate::{
+    authz::{
+        jwt::{self, Jwks, Jwt},
+        Action, IdentityPattern, InvalidIdentityPattern,
+    },
+    concurrency_limit::Queue,
+    connection_limit::ConnectionLimitKey,
+    grpc, http, Authentication, Authorization, ConcurrencyLimit, ConnectionLimit, ExtAuthz,
+    GlobalRateLimit, LocalRateLimit, Meta, Protocol, Rejection, RoutePolicy, ServerPolicy,
+};
+use linkerd_identity::Id;
+use std::{str::FromStr, sync::Arc, time};
+
+#[cfg(test)]
+mod tests;
+
+/// Overrides applied to each discovered [`ServerPolicy`].
+#[derive(Clone, Debug, Default)]
+pub struct Overrides {
+    servers: Vec<ServerOverride>,
+}
+
+#[derive(Clone, Debug)]
+struct ServerOverride {
+    selector: Selector,
+
+    /// Replaces the server's local rate limit.
+    local_rate_limit: Option<Arc<LocalRateLimit>>,
+
+    /// Limits the number of requests the server processes concurrently.
+    concurrency_limit: Option<Arc<ConcurrencyLimit>>,
+
+    /// Limits the connections each client may open to the server.
+    connection_limit: Option<Arc<ConnectionLimit>>,
+
+    /// Whether connections to the server are prefaced by a PROXY protocol
+    /// header.
+    proxy_protocol: Option<bool>,
+
+    /// Marks packets sent to the server's clients with a DSCP value.
+    dscp: Option<u8>,
+
+    routes: Vec<RouteOverride>,
+
+    authorizations: Vec<AuthzOverride>,
+}
+
+#[derive(Clone, Debug)]
+struct RouteOverride {
+    selector: Selector,
+
+    /// Replaces the route's local rate limit.
+    local_rate_limit: Option<Arc<LocalRateLimit>>,
+
+    /// Configures the route to consult the external rate limit service.
+    global_rate_limit: Option<Arc<GlobalRateLimit>>,
+
+    /// Configures the route to consult the external authorization service.
+    ext_authz: Option<Arc<ExtAuthz>>,
+
+    /// Limits the number of requests the route processes concurrently.
+    concurrency_limit: Option<Arc<ConcurrencyLimit>>,
+}
+
+#[derive(Clone, Debug)]
+struct AuthzOverride {
+    selector: Selector,
+
+    /// Replaces the authorization's authentication with JWT authentication.
+    jwt: Option<Arc<Jwt>>,
+
+    /// Replaces whether matching clients are allowed or denied.
+    action: Option<Action>,
+
+    /// Replaces whether the authorization only records denials.
+    audit: Option<bool>,
+
+    /// Additionally authenticates mesh TLS clients whose identities match any
+    /// of these patterns.
+    identity_patterns: Vec<IdentityPattern>,
+}
+
+/// Selects a resource by its metadata. Unset fields match any value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Selector {
+    kind: Option<String>,
+    name: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidOverrides {
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid identity: {0}")]
+    Identity(String),
+
+    #[error("invalid header name: {0}")]
+    HeaderName(#[from] ::http::header::InvalidHeaderName),
+
+    #[error("invalid HTTP status: {0}")]
+    HttpStatus(u16),
+
+    #[error("invalid gRPC status code: {0}")]
+    GrpcCode(i32),
+
+    #[error("invalid global rate limit: {0}")]
+    GlobalRateLimit(&'static str),
+
+    #[error("invalid duration: {0}")]
+    Duration(String),
+
+    #[error("invalid connection limit: {0}")]
+    ConnectionLimit(&'static str),
+
+    #[error("invalid JWT authentication: {0}")]
+    Jwt(&'static str),
+
+    #[error("{0}")]
+    Jwks(#[from] jwt::InvalidJwks),
+
+    #[error("invalid DSCP value {0}; must be at most 63")]
+    Dscp(u8),
+
+    #[error("invalid identity pattern: {0}")]
+    IdentityPattern(#[from] InvalidIdentityPattern),
+}
+
+mod spec {
+    use serde::Deserialize;
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        num::{NonZeroU32, NonZeroUsize},
+    };
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Overrides {
+        pub(super) servers: Vec<Server>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Server {
+        pub(super) kind: Option<String>,
+        pub(super) name: String,
+        pub(super) local_rate_limit: Option<LocalRateLimit>,
+        pub(super) concurrency_limit: Option<ConcurrencyLimit>,
+        pub(super) connection_limit: Option<ConnectionLimit>,
+        pub(super) proxy_protocol: Option<bool>,
+        pub(super) dscp: Option<u8>,
+        #[serde(default)]
+        pub(super) routes: Vec<Route>,
+        #[serde(default)]
+        pub(super) authorizations: Vec<Authorization>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Route {
+        pub(super) kind: Option<String>,
+        pub(super) name: String,
+        pub(super) local_rate_limit: Option<LocalRateLimit>,
+        pub(super) global_rate_limit: Option<GlobalRateLimit>,
+        pub(super) ext_authz: Option<ExtAuthz>,
+        pub(super) concurrency_limit: Option<ConcurrencyLimit>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct ConcurrencyLimit {
+        pub(super) max_in_flight: NonZeroUsize,
+        pub(super) queue: Option<Queue>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Queue {
+        pub(super) capacity: NonZeroUsize,
+        pub(super) timeout: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct ConnectionLimit {
+        pub(super) key: ConnectionLimitKey,
+        pub(super) connections_per_second: Option<NonZeroU32>,
+        pub(super) max_concurrent: Option<NonZeroUsize>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) enum ConnectionLimitKey {
+        ClientIp,
+        ClientIdentity,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Authorization {
+        pub(super) kind: Option<String>,
+        pub(super) name: String,
+        pub(super) jwt: Option<Jwt>,
+        pub(super) action: Option<Action>,
+        pub(super) audit: Option<bool>,
+        #[serde(default)]
+        pub(super) identity_patterns: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) enum Action {
+        Allow,
+        Deny,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Jwt {
+        /// An inline JSON Web Key Set.
+        pub(super) jwks: Option<serde_json::Value>,
+        /// The path of a file containing a JSON Web Key Set.
+        pub(super) jwks_file: Option<String>,
+        pub(super) issuer: String,
+        #[serde(default)]
+        pub(super) audiences: BTreeSet<String>,
+        #[serde(default)]
+        pub(super) claims: Vec<ClaimMatch>,
+        #[serde(default)]
+        pub(super) claim_headers: Vec<ClaimHeader>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct ClaimMatch {
+        pub(super) name: String,
+        pub(super) values: BTreeSet<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct ClaimHeader {
+        pub(super) claim: String,
+        pub(super) header: String,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct LocalRateLimit {
+        pub(super) total: Option<NonZeroU32>,
+        pub(super) identity: Option<NonZeroU32>,
+        pub(super) overrides: Vec<RateLimitOverride>,
+        pub(super) header: Option<HeaderRateLimit>,
+        pub(super) rejection: Option<Rejection>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct RateLimitOverride {
+        pub(super) requests_per_second: NonZeroU32,
+        pub(super) identities: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct HeaderRateLimit {
+        pub(super) name: String,
+        pub(super) requests_per_second: NonZeroU32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Rejection {
+        pub(super) http_status: u16,
+        pub(super) grpc_code: i32,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct ExtAuthz {
+        pub(super) context: BTreeMap<String, String>,
+        pub(super) include_headers: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct GlobalRateLimit {
+        pub(super) domain: String,
+        pub(super) descriptors: Vec<Descriptor>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) struct Descriptor {
+        pub(super) entries: Vec<DescriptorEntry>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields, rename_all = "camelCase")]
+    pub(super) enum DescriptorEntry {
+        Generic { key: String, value: String },
+        RequestHeader { key: String, header: String },
+        ClientIdentity { key: String },
+        Path { key: String },
+    }
+}
+
+// === impl Overrides ===
+
+impl Overrides {
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+
+    /// Applies the overrides to a discovered policy.
+    pub fn apply(&self, mut policy: ServerPolicy) -> ServerPolicy {
+        let Some(server) = self
+            .servers
+            .iter()
+            .find(|s| s.selector.matches(&policy.meta))
+        else {
+            return policy;
+        };
+
+        if let Some(limit) = &server.local_rate_limit {
+            policy.local_rate_limit = limit.clone();
+        }
+        if let Some(limit) = &server.concurrency_limit {
+            policy.concurrency_limit = Some(limit.clone());
+        }
+        if let Some(limit) = &server.connection_limit {
+            policy.connection_limit = Some(limit.clone());
+        }
+        if let Some(enabled) = server.proxy_protocol {
+            policy.proxy_protocol = enabled;
+        }
+        if let Some(dscp) = server.dscp {
+            policy.dscp = Some(dscp);
+        }
+
+        policy.protocol = match policy.protocol {
+            Protocol::Detect {
+                http,
+                timeout,
+                tcp_authorizations,
+            } => Protocol::Detect {
+                http: server.apply_http(&http),
+                timeout,
+                tcp_authorizations: server.apply_authorizations(&tcp_authorizations),
+            },
+            Protocol::Http1(routes) => Protocol::Http1(server.apply_http(&routes)),
+            Protocol::Http2(routes) => Protocol::Http2(server.apply_http(&routes)),
+            Protocol::Grpc(routes) => Protocol::Grpc(server.apply_grpc(&routes)),
+            Protocol::Tls(authzs) => Protocol::Tls(server.apply_authorizations(&authzs)),
+            Protocol::Opaque(authzs) => Protocol::Opaque(server.apply_authorizations(&authzs)),
+        };
+
+        policy
+    }
+}
+
+impl FromStr for Overrides {
+    type Err = InvalidOverrides;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = serde_json::from_str::<spec::Overrides>(s)?;
+        Self::try_from(spec)
+    }
+}
+
+impl TryFrom<spec::Overrides> for Overrides {
+    type Error = InvalidOverrides;
+
+    fn try_from(spec: spec::Overrides) -> Result<Self, Self::Error> {
+        let servers = spec
+            .servers
+            .into_iter()
+            .map(ServerOverride::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { servers })
+    }
+}
+
+// === impl ServerOverride ===
+
+impl ServerOverride {
+    fn apply_http(&self, routes: &[http::Route]) -> Arc<[http::Route]> {
+        routes
+            .iter()
+            .cloned()
+            .map(|mut route| {
+                for rule in &mut route.rules {
+                    self.apply_route(&mut rule.policy);
+                }
+                route
+            })
+            .collect()
+    }
+
+    fn apply_grpc(&self, routes: &[grpc::Route]) -> Arc<[grpc::Route]> {
+        routes
+            .iter()
+            .cloned()
+            .map(|mut route| {
+                for rule in &mut route.rules {
+                    self.apply_route(&mut rule.policy);
+                }
+                route
+            })
+            .collect()
+    }
+
+    fn apply_route<F>(&self, policy: &mut RoutePolicy<F>) {
+        if !self.authorizations.is_empty() {
+            policy.authorizations = self.apply_authorizations(&policy.authorizations);
+        }
+
+        let Some(route) = self
+            .routes
+            .iter()
+            .find(|r| r.selector.matches(&policy.meta))
+        else {
+            return;
+        };
+
+        if let Some(limit) = &route.local_rate_limit {
+            policy.local_rate_limit = limit.clone();
+        }
+        if let Some(limit) = &route.global_rate_limit {
+            policy.global_rate_limit = Some(limit.clone());
+        }
+        if let Some(ext_authz) = &route.ext_authz {
+            policy.ext_authz = Some(ext_authz.clone());
+        }
+        if let Some(limit) = &route.concurrency_limit {
+            policy.concurrency_limit = Some(limit.clone());
+        }
+    }
+
+    fn apply_authorizations(&self, authzs: &Arc<[Authorization]>) -> Arc<[Authorization]> {
+        if self.authorizations.is_empty() {
+            return authzs.clone();
+        }
+        authzs
+            .iter()
+            .cloned()
+            .map(|mut authz| {
+                if let Some(ovr) = self
+                    .authorizations
+                    .iter()
+                    .find(|a| a.selector.matches(&authz.meta))
+                {
+                    if let Some(jwt) = &ovr.jwt {
+                        authz.authentication = Authentication::Jwt(jwt.clone());
+                    }
+                    if let Some(action) = ovr.action {
+                        authz.action = action;
+                    }
+                    if let Some(audit) = ovr.audit {
+                        authz.audit = audit;
+                    }
+                    // Patterns only extend mesh TLS authentication; other
+                    // authentications don't authenticate client identities.
+                    if let Authentication::TlsAuthenticated {
+                        ref mut patterns, ..
+                    } = authz.authentication
+                    {
+                        patterns.extend(ovr.identity_patterns.iter().cloned());
+                    }
+                }
+                authz
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<spec::Server> for ServerOverride {
+    type Error = InvalidOverrides;
+
+    fn try_from(
+        spec::Server {
+            kind,
+            name,
+            local_rate_limit,
+            concurrency_limit,
+            connection_limit,
+            proxy_protocol,
+            dscp,
+            routes,
+            authorizations,
+        }: spec::Server,
+    ) -> Result<Self, Self::Error> {
+        // A DSCP value occupies the upper six bits of the TOS field.
+        if let Some(dscp) = dscp.filter(|&d| d > 0x3f) {
+            return Err(InvalidOverrides::Dscp(dscp));
+        }
+        let local_rate_limit = local_rate_limit
+            .map(try_local_rate_limit)
+            .transpose()?
+            .map(Arc::new);
+        let concurrency_limit = concurrency_limit
+            .map(try_concurrency_limit)
+            .transpose()?
+            .map(Arc::new);
+        let connection_limit = connection_limit
+            .map(try_connection_limit)
+            .transpose()?
+            .map(Arc::new);
+        let routes = routes
+            .into_iter()
+            .map(RouteOverride::try_from)
+            .collect::<Result<_, _>>()?;
+        let authorizations = authorizations
+            .into_iter()
+            .map(AuthzOverride::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            selector: Selector { kind, name },
+            local_rate_limit,
+            concurrency_limit,
+            connection_limit,
+            proxy_protocol,
+            dscp,
+            routes,
+            authorizations,
+        })
+    }
+}
+
+// === impl RouteOverride ===
+
+impl TryFrom<spec::Route> for RouteOverride {
+    type Error = InvalidOverrides;
+
+    fn try_from(
+        spec::Route {
+            kind,
+            name,
+            local_rate_limit,
+            global_rate_limit,
+            ext_authz,
+            concurrency_limit,
+        }: spec::Route,
+    ) -> Result<Self, Self::Error> {
+        let local_rate_limit = local_rate_limit
+            .map(try_local_rate_limit)
+            .transpose()?
+            .map(Arc::new);
+        let global_rate_limit = global_rate_limit
+            .map(try_global_rate_limit)
+            .transpose()?
+            .map(Arc::new);
+        let ext_authz = ext_authz.map(try_ext_authz).transpose()?.map(Arc::new);
+        let concurrency_limit = concurrency_limit
+            .map(try_concurrency_limit)
+            .transpose()?
+            .map(Arc::new);
+        Ok(Self {
+            selector: Selector { kind, name },
+            local_rate_limit,
+            global_rate_limit,
+            ext_authz,
+            concurrency_limit,
+        })
+    }
+}
+
+// === impl AuthzOverride ===
+
+impl TryFrom<spec::Authorization> for AuthzOverride {
+    type Error = InvalidOverrides;
+
+    fn try_from(
+        spec::Authorization {
+            kind,
+            name,
+            jwt,
+            action,
+            audit,
+            identity_patterns,
+        }: spec::Authorization,
+    ) -> Result<Self, Self::Error> {
+        let jwt = jwt.map(try_jwt).transpose()?.map(Arc::new);
+        let action = action.map(|action| match action {
+            spec::Action::Allow => Action::Allow,
+            spec::Action::Deny => Action::Deny,
+        });
+        let identity_patterns = identity_patterns
+            .iter()
+            .map(|p| p.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            selector: Selector { kind, name },
+            jwt,
+            action,
+            audit,
+            identity_patterns,
+        })
+    }
+}
+
+fn try_jwt(
+    spec::Jwt {
+        jwks,
+        jwks_file,
+        issuer,
+        audiences,
+        claims,
+        claim_headers,
+    }: spec::Jwt,
+) -> Result<Jwt, InvalidOverrides> {
+    let jwks = match (jwks, jwks_file) {
+        (Some(jwks), None) => Jwks::from_json(&jwks.to_string())?,
+        (None, Some(path)) => Jwks::from_file(std::path::Path::new(&path))?,
+        _ => {
+            return Err(InvalidOverrides::Jwt(
+                "exactly one of jwks or jwksFile must be set",
+            ))
+        }
+    };
+    if issuer.is_empty() {
+        return Err(InvalidOverrides::Jwt("missing issuer"));
+    }
+    let claims = claims
+        .into_iter()
+        .map(|spec::ClaimMatch { name, values }| jwt::ClaimMatch { name, values })
+        .collect();
+    let claim_headers = claim_headers
+        .into_iter()
+        .map(|spec::ClaimHeader { claim, header }| {
+            let header = header.parse::<::http::HeaderName>()?;
+            // The token itself must not be replaced before it is validated.
+            if header == ::http::header::AUTHORIZATION {
+                return Err(InvalidOverrides::Jwt(
+                    "claims may not be forwarded as authorization headers",
+                ));
+            }
+            Ok(jwt::ClaimHeader { claim, header })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Jwt {
+        jwks,
+        issuer,
+        audiences,
+        claims,
+        claim_headers,
+    })
+}
+
+fn try_local_rate_limit(
+    spec::LocalRateLimit {
+        total,
+        identity,
+        overrides,
+        header,
+        rejection,
+    }: spec::LocalRateLimit,
+) -> Result<LocalRateLimit, InvalidOverrides> {
+    let mut limit = LocalRateLimit::new(total, identity);
+    for spec::RateLimitOverride {
+        requests_per_second,
+        identities,
+    } in overrides
+    {
+        let clients = identities
+            .into_iter()
+            .map(|id| id.parse::<Id>().map_err(|_| InvalidOverrides::Identity(id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        limit = limit.with_override(requests_per_second, clients);
+    }
+    if let Some(spec::HeaderRateLimit {
+        name,
+        requests_per_second,
+    }) = header
+    {
+        limit = limit.with_per_header(name.parse()?, requests_per_second);
+    }
+    if let Some(rejection) = rejection {
+        limit = limit.with_rejection(try_rejection(rejection)?);
+    }
+    Ok(limit)
+}
+
+fn try_rejection(
+    spec::Rejection {
+        http_status,
+        grpc_code,
+    }: spec::Rejection,
+) -> Result<Rejection, InvalidOverrides> {
+    // Rejections must be reported to clients as errors.
+    let http_status = ::http::StatusCode::from_u16(http_status)
+        .ok()
+        .filter(|s| s.is_client_error() || s.is_server_error())
+        .ok_or(InvalidOverrides::HttpStatus(http_status))?;
+    // Unknown codes are otherwise coerced to UNKNOWN.
+    if !(1..=16).contains(&grpc_code) {
+        return Err(InvalidOverrides::GrpcCode(grpc_code));
+    }
+    Ok(Rejection {
+        http_status,
+        grpc_code: tonic::Code::from_i32(grpc_code),
+    })
+}
+
+fn try_ext_authz(
+    spec::ExtAuthz {
+        context,
+        include_headers,
+    }: spec::ExtAuthz,
+) -> Result<ExtAuthz, InvalidOverrides> {
+    Ok(ExtAuthz {
+        context,
+        include_headers: include_headers
+            .iter()
+            .map(|h| h.parse())
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn try_concurrency_limit(
+    spec::ConcurrencyLimit {
+        max_in_flight,
+        queue,
+    }: spec::ConcurrencyLimit,
+) -> Result<ConcurrencyLimit, InvalidOverrides> {
+    let queue = queue
+        .map(|spec::Queue { capacity, timeout }| {
+            Ok::<_, InvalidOverrides>(Queue {
+                capacity: capacity.get(),
+                timeout: parse_duration(&timeout)?,
+            })
+        })
+        .transpose()?;
+    Ok(ConcurrencyLimit::new(max_in_flight.get(), queue))
+}
+
+fn try_connection_limit(
+    spec::ConnectionLimit {
+        key,
+        connections_per_second,
+        max_concurrent,
+    }: spec::ConnectionLimit,
+) -> Result<ConnectionLimit, InvalidOverrides> {
+    if connections_per_second.is_none() && max_concurrent.is_none() {
+        return Err(InvalidOverrides::ConnectionLimit(
+            "one of connectionsPerSecond or maxConcurrent must be set",
+        ));
+    }
+    let key = match key {
+        spec::ConnectionLimitKey::ClientIp => ConnectionLimitKey::ClientIp,
+        spec::ConnectionLimitKey::ClientIdentity => ConnectionLimitKey::ClientIdentity,
+    };
+    Ok(ConnectionLimit::new(
+        key,
+        connections_per_second,
+        max_concurrent,
+    ))
+}
+
+fn try_global_rate_limit(
+    spec::GlobalRateLimit {
+        domain,
+        descriptors,
+    }: spec::GlobalRateLimit,
+) -> Result<GlobalRateLimit, InvalidOverrides> {
+    use crate::global_rate_limit::{Descriptor, Entry};
+
+    if domain.is_empty() {
+        return Err(InvalidOverrides::GlobalRateLimit("missing domain"));
+    }
+    if descriptors.is_empty() {
+        return Err(InvalidOverrides::GlobalRateLimit("missing descriptors"));
+    }
+
+    let descriptors = descriptors
+        .into_iter()
+        .map(|spec::Descriptor { entries }| {
+            if entries.is_empty() {
+                return Err(InvalidOverrides::GlobalRateLimit("empty descriptor"));
+            }
+            let entries = entries
+                .into_iter()
+                .map(|entry| {
+                    Ok(match entry {
+                        spec::DescriptorEntry::Generic { key, value } => {
+                            Entry::Generic { key, value }
+                        }
+                        spec::DescriptorEntry::RequestHeader { key, header } => {
+                            Entry::RequestHeader {
+                                key,
+                                header: header.parse()?,
+                            }
+                        }
+                        spec::DescriptorEntry::ClientIdentity { key } => {
+                            Entry::ClientIdentity { key }
+                        }
+                        spec::DescriptorEntry::Path { key } => Entry::Path { key },
+                    })
+                })
+                .collect::<Result<_, InvalidOverrides>>()?;
+            Ok(Descriptor { entries })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(GlobalRateLimit {
+        domain,
+        descriptors,
+    })
+}
+
+/// Parses a positive duration, expressed as seconds with an `s` suffix.
+fn parse_duration(s: &str) -> Result<time::Duration, InvalidOverrides> {
+    s.strip_suffix('s')
+        .and_then(|secs| secs.parse::<f64>().ok())
+        .and_then(|secs| time::Duration::try_from_secs_f64(secs).ok())
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| InvalidOverrides::Duration(s.to_string()))
+}
+
+// === impl Selector ===
+
+impl Selector {
+    fn matches(&self, meta: &Meta) -> bool {
+        self.name == meta.name() && self.kind.as_deref().map_or(true, |k| k == meta.kind())
+    }
+}
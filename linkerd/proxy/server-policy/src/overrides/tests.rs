@@ -0,0 +1,509 @@
+use super::*;
+use crate::RateLimitError;
+use std::num::NonZeroU32;
+
+fn mk_policy(server: &str, route: &str) -> ServerPolicy {
+    let mut policy = ServerPolicy::invalid(std::time::Duration::from_secs(10));
+    policy.meta = Arc::new(Meta::Resource {
+        group: "policy.linkerd.io".into(),
+        kind: "server".into(),
+        name: server.into(),
+    });
+    policy.protocol = Protocol::Http1(Arc::new([http::Route {
+        hosts: vec![],
+        rules: vec![http::Rule {
+            matches: vec![],
+            policy: RoutePolicy {
+                meta: Arc::new(Meta::Resource {
+                    group: "gateway.networking.k8s.io".into(),
+                    kind: "httproute".into(),
+                    name: route.into(),
+                }),
+                authorizations: Arc::new([]),
+                filters: vec![],
+                local_rate_limit: Default::default(),
+                global_rate_limit: None,
+                ext_authz: None,
+                concurrency_limit: None,
+            },
+        }],
+    }]));
+    policy
+}
+
+fn route_policy(policy: &ServerPolicy) -> &http::Policy {
+    match &policy.protocol {
+        Protocol::Http1(routes) => &routes[0].rules[0].policy,
+        protocol => panic!("unexpected protocol: {protocol:?}"),
+    }
+}
+
+#[test]
+fn route_local_rate_limit() {
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "routes": [{
+                "kind": "httproute",
+                "name": "books",
+                "localRateLimit": {"total": 1}
+            }]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_policy("web", "books"));
+    let limit = route_policy(&policy).local_rate_limit.clone();
+    assert_eq!(limit.check(None), Ok(()));
+    assert_eq!(
+        limit.check(None),
+        Err(RateLimitError::Total(NonZeroU32::new(1).unwrap()))
+    );
+
+    // The limiter is preserved across policy updates.
+    let updated = overrides.apply(mk_policy("web", "books"));
+    assert!(Arc::ptr_eq(
+        &route_policy(&updated).local_rate_limit,
+        &limit
+    ));
+
+    // Other servers and routes are not modified.
+    for (server, route) in [("api", "books"), ("web", "authors")] {
+        let policy = overrides.apply(mk_policy(server, route));
+        assert!(!Arc::ptr_eq(
+            &route_policy(&policy).local_rate_limit,
+            &limit
+        ));
+    }
+}
+
+#[test]
+fn rate_limit_rejection() {
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "localRateLimit": {
+                "total": 1,
+                "rejection": {"httpStatus": 503, "grpcCode": 14}
+            }
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_policy("web", "books"));
+    let headers = ::http::HeaderMap::new();
+    assert!(policy
+        .local_rate_limit
+        .check_request(None, &headers)
+        .is_ok());
+    let limited = policy
+        .local_rate_limit
+        .check_request(None, &headers)
+        .expect_err("must be rate limited");
+    assert_eq!(
+        limited.rejection,
+        Rejection {
+            http_status: ::http::StatusCode::SERVICE_UNAVAILABLE,
+            grpc_code: tonic::Code::Unavailable,
+        }
+    );
+}
+
+#[test]
+fn route_global_rate_limit() {
+    use crate::global_rate_limit::{Descriptor, Entry};
+
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "routes": [{
+                "name": "books",
+                "globalRateLimit": {
+                    "domain": "books",
+                    "descriptors": [{
+                        "entries": [
+                            {"generic": {"key": "route", "value": "books"}},
+                            {"requestHeader": {"key": "tenant", "header": "x-tenant-id"}}
+                        ]
+                    }, {
+                        "entries": [{"clientIdentity": {"key": "client"}}, {"path": {"key": "path"}}]
+                    }]
+                }
+            }]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_policy("web", "books"));
+    assert_eq!(
+        route_policy(&policy).global_rate_limit.as_deref(),
+        Some(&GlobalRateLimit {
+            domain: "books".to_string(),
+            descriptors: vec![
+                Descriptor {
+                    entries: vec![
+                        Entry::Generic {
+                            key: "route".to_string(),
+                            value: "books".to_string(),
+                        },
+                        Entry::RequestHeader {
+                            key: "tenant".to_string(),
+                            header: ::http::HeaderName::from_static("x-tenant-id"),
+                        },
+                    ],
+                },
+                Descriptor {
+                    entries: vec![
+                        Entry::ClientIdentity {
+                            key: "client".to_string(),
+                        },
+                        Entry::Path {
+                            key: "path".to_string(),
+                        },
+                    ],
+                },
+            ],
+        })
+    );
+
+    let policy = overrides.apply(mk_policy("web", "authors"));
+    assert_eq!(route_policy(&policy).global_rate_limit, None);
+}
+
+#[test]
+fn route_ext_authz() {
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "routes": [{
+                "name": "books",
+                "extAuthz": {
+                    "context": {"tier": "gold"},
+                    "includeHeaders": ["authorization"]
+                }
+            }]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_policy("web", "books"));
+    assert_eq!(
+        route_policy(&policy).ext_authz.as_deref(),
+        Some(&ExtAuthz {
+            context: [("tier".to_string(), "gold".to_string())].into(),
+            include_headers: vec![::http::header::AUTHORIZATION],
+        })
+    );
+
+    let policy = overrides.apply(mk_policy("web", "authors"));
+    assert_eq!(route_policy(&policy).ext_authz, None);
+}
+
+#[test]
+fn authorization_jwt() {
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "authorizations": [{
+                "kind": "authorizationpolicy",
+                "name": "web-users",
+                "jwt": {
+                    "jwks": {"keys": [{
+                        "kty": "oct",
+                        "alg": "HS256",
+                        "k": "YW4tZXhhbXBsZS1zZWNyZXQtb2Ytc3VmZmljaWVudC1sZW5ndGg"
+                    }]},
+                    "issuer": "https://issuer.example.com",
+                    "audiences": ["web"],
+                    "claimHeaders": [{"claim": "sub", "header": "x-jwt-sub"}]
+                }
+            }]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let authz = |name: &str| Authorization {
+        networks: vec![],
+        authentication: Authentication::Unauthenticated,
+        meta: Arc::new(Meta::Resource {
+            group: "policy.linkerd.io".into(),
+            kind: "authorizationpolicy".into(),
+            name: name.into(),
+        }),
+        action: Default::default(),
+        audit: false,
+    };
+    let mut policy = mk_policy("web", "books");
+    if let Protocol::Http1(routes) = &mut policy.protocol {
+        let mut rs = routes.to_vec();
+        rs[0].rules[0].policy.authorizations = Arc::new([authz("web-users"), authz("other")]);
+        *routes = rs.into();
+    }
+
+    let policy = overrides.apply(policy);
+    let authzs = &route_policy(&policy).authorizations;
+    match &authzs[0].authentication {
+        Authentication::Jwt(jwt) => {
+            assert_eq!(jwt.issuer, "https://issuer.example.com");
+            assert_eq!(jwt.claim_headers[0].header, "x-jwt-sub");
+        }
+        authn => panic!("unexpected authentication: {authn:?}"),
+    }
+    assert_eq!(authzs[1].authentication, Authentication::Unauthenticated);
+}
+
+#[test]
+fn authorization_identity_patterns() {
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "authorizations": [{
+                "name": "payments",
+                "identityPatterns": ["spiffe://td/ns/*/sa/payments-*"]
+            }]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let authz = |name: &'static str, authentication| Authorization {
+        networks: vec![],
+        authentication,
+        meta: Meta::new_default(name),
+        action: Default::default(),
+        audit: false,
+    };
+    let tls = || Authentication::TlsAuthenticated {
+        identities: ["spiffe://td/ns/*/sa/exact".to_string()].into(),
+        suffixes: vec![],
+        patterns: vec![],
+    };
+    let mut policy = mk_policy("web", "books");
+    policy.protocol = Protocol::Opaque(Arc::new([
+        authz("payments", tls()),
+        authz("payments", Authentication::Unauthenticated),
+        authz("other", tls()),
+    ]));
+
+    let policy = overrides.apply(policy);
+    let Protocol::Opaque(authzs) = policy.protocol else {
+        panic!("unexpected protocol: {:?}", policy.protocol);
+    };
+    match &authzs[0].authentication {
+        Authentication::TlsAuthenticated {
+            identities,
+            patterns,
+            ..
+        } => {
+            // Discovered identities are never treated as patterns.
+            assert!(identities.contains("spiffe://td/ns/*/sa/exact"));
+            assert_eq!(patterns.len(), 1);
+            assert!(patterns[0].matches("spiffe://td/ns/prod/sa/payments-api"));
+        }
+        authn => panic!("unexpected authentication: {authn:?}"),
+    }
+    assert_eq!(authzs[1].authentication, Authentication::Unauthenticated);
+    assert_eq!(authzs[2].authentication, tls());
+
+    assert!(matches!(
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "identityPatterns": [""]}]}]}"#
+            .parse::<Overrides>(),
+        Err(InvalidOverrides::IdentityPattern(_))
+    ));
+}
+
+#[test]
+fn authorization_action_and_audit() {
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "authorizations": [
+                {"name": "legacy", "action": "deny"},
+                {"name": "new", "audit": true}
+            ]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let authz = |name: &'static str| Authorization {
+        networks: vec![],
+        authentication: Authentication::Unauthenticated,
+        meta: Meta::new_default(name),
+        action: Default::default(),
+        audit: false,
+    };
+    let mut policy = mk_policy("web", "books");
+    policy.protocol = Protocol::Opaque(Arc::new([authz("legacy"), authz("new"), authz("other")]));
+
+    let policy = overrides.apply(policy);
+    let Protocol::Opaque(authzs) = policy.protocol else {
+        panic!("unexpected protocol: {:?}", policy.protocol);
+    };
+    assert_eq!(
+        authzs
+            .iter()
+            .map(|a| (a.action, a.audit))
+            .collect::<Vec<_>>(),
+        vec![
+            (Action::Deny, false),
+            (Action::Allow, true),
+            (Action::Allow, false)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn concurrency_limits() {
+    use crate::ConcurrencyLimitExceeded;
+
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "concurrencyLimit": {
+                "maxInFlight": 2,
+                "queue": {"capacity": 1, "timeout": "0.5s"}
+            },
+            "routes": [{"name": "books", "concurrencyLimit": {"maxInFlight": 1}}]
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let policy = overrides.apply(mk_policy("web", "books"));
+    let server = policy
+        .concurrency_limit
+        .clone()
+        .expect("server must be limited");
+    assert_eq!(server.max_in_flight(), 2);
+    assert_eq!(
+        server.queue(),
+        Some(crate::concurrency_limit::Queue {
+            capacity: 1,
+            timeout: std::time::Duration::from_millis(500),
+        })
+    );
+
+    let route = route_policy(&policy)
+        .concurrency_limit
+        .clone()
+        .expect("route must be limited");
+    let _permit = route.acquire().await.expect("must acquire");
+
+    // The limit's state is preserved across policy updates.
+    let updated = overrides.apply(mk_policy("web", "books"));
+    assert!(Arc::ptr_eq(
+        updated.concurrency_limit.as_ref().unwrap(),
+        &server
+    ));
+    assert_eq!(
+        route_policy(&updated)
+            .concurrency_limit
+            .as_ref()
+            .unwrap()
+            .acquire()
+            .await
+            .unwrap_err(),
+        ConcurrencyLimitExceeded::InFlight(1)
+    );
+}
+
+#[test]
+fn connection_limit() {
+    use crate::{connection_limit::ConnectionKey, ConnectionLimitExceeded};
+    use std::num::NonZeroUsize;
+
+    let overrides = r#"{
+        "servers": [{
+            "name": "web",
+            "connectionLimit": {"key": "clientIdentity", "maxConcurrent": 1}
+        }]
+    }"#
+    .parse::<Overrides>()
+    .expect("overrides must parse");
+
+    let limit = overrides
+        .apply(mk_policy("web", "books"))
+        .connection_limit
+        .expect("server must be limited");
+    assert_eq!(limit.key(), ConnectionLimitKey::ClientIdentity);
+    let client = ConnectionKey::Ip([192, 0, 2, 1].into());
+    let _permit = limit.acquire(client.clone()).expect("must acquire");
+
+    // The limit's state is preserved across policy updates.
+    let updated = overrides
+        .apply(mk_policy("web", "books"))
+        .connection_limit
+        .unwrap();
+    assert!(Arc::ptr_eq(&updated, &limit));
+    assert_eq!(
+        updated.acquire(client).unwrap_err(),
+        ConnectionLimitExceeded::Concurrency(NonZeroUsize::new(1).unwrap())
+    );
+
+    assert!(overrides
+        .apply(mk_policy("api", "books"))
+        .connection_limit
+        .is_none());
+}
+
+#[test]
+fn proxy_protocol() {
+    let overrides = r#"{"servers": [{"name": "web", "proxyProtocol": true}]}"#
+        .parse::<Overrides>()
+        .expect("overrides must parse");
+
+    assert!(overrides.apply(mk_policy("web", "books")).proxy_protocol);
+    assert!(!overrides.apply(mk_policy("api", "books")).proxy_protocol);
+}
+
+#[test]
+fn dscp() {
+    let overrides = r#"{"servers": [{"name": "web", "dscp": 46}]}"#
+        .parse::<Overrides>()
+        .expect("overrides must parse");
+
+    assert_eq!(overrides.apply(mk_policy("web", "books")).dscp, Some(46));
+    assert_eq!(overrides.apply(mk_policy("api", "books")).dscp, None);
+}
+
+#[test]
+fn invalid() {
+    for doc in [
+        "",
+        r#"{"servers": [{"kind": "server"}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "localRateLimit": {"total": 0}}]}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "localRateLimit": {"header": {"name": "bad header", "requestsPerSecond": 1}}}]}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "localRateLimit": {"overrides": [{"requestsPerSecond": 1, "identities": [""]}]}}]}]}"#,
+        r#"{"servers": [{"name": "web", "localRateLimit": {"rejection": {"httpStatus": 200, "grpcCode": 14}}}]}"#,
+        r#"{"servers": [{"name": "web", "localRateLimit": {"rejection": {"httpStatus": 429, "grpcCode": 0}}}]}"#,
+        r#"{"servers": [{"name": "web", "localRateLimit": {"rejection": {"httpStatus": 429, "grpcCode": 17}}}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "globalRateLimit": {"domain": "", "descriptors": [{"entries": [{"path": {"key": "p"}}]}]}}]}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "globalRateLimit": {"domain": "d", "descriptors": []}}]}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "globalRateLimit": {"domain": "d", "descriptors": [{"entries": []}]}}]}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "globalRateLimit": {"domain": "d", "descriptors": [{"entries": [{"requestHeader": {"key": "k", "header": "bad header"}}]}]}}]}]}"#,
+        r#"{"servers": [{"name": "web", "routes": [{"name": "r", "extAuthz": {"includeHeaders": ["bad header"]}}]}]}"#,
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "jwt": {"issuer": "i"}}]}]}"#,
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "jwt": {"jwks": {"keys": []}, "jwksFile": "/jwks.json", "issuer": "i"}}]}]}"#,
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "jwt": {"jwks": {"keys": []}, "issuer": ""}}]}]}"#,
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "jwt": {"jwks": {"keys": []}, "issuer": "i", "claimHeaders": [{"claim": "sub", "header": "authorization"}]}}]}]}"#,
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "jwt": {"jwksFile": "/does/not/exist.json", "issuer": "i"}}]}]}"#,
+        r#"{"servers": [{"name": "web", "authorizations": [{"name": "a", "action": "audit"}]}]}"#,
+        r#"{"servers": [{"name": "web", "concurrencyLimit": {"maxInFlight": 0}}]}"#,
+        r#"{"servers": [{"name": "web", "concurrencyLimit": {"maxInFlight": 1, "queue": {"capacity": 1, "timeout": "1"}}}]}"#,
+        r#"{"servers": [{"name": "web", "concurrencyLimit": {"maxInFlight": 1, "queue": {"capacity": 1, "timeout": "0s"}}}]}"#,
+        r#"{"servers": [{"name": "web", "connectionLimit": {"key": "clientIp"}}]}"#,
+        r#"{"servers": [{"name": "web", "connectionLimit": {"key": "clientIp", "maxConcurrent": 0}}]}"#,
+        r#"{"servers": [{"name": "web", "connectionLimit": {"key": "serverIp", "maxConcurrent": 1}}]}"#,
+        r#"{"servers": [{"name": "web", "proxyProtocol": "v2"}]}"#,
+        r#"{"servers": [{"name": "web", "dscp": 64}]}"#,
+        r#"{"servers": [{"name": "web", "dscp": 256}]}"#,
+    ] {
+        assert!(doc.parse::<Overrides>().is_err(), "{doc:?} must not parse");
+    }
+}
@@ -1,19 +1,34 @@
 use futures::prelude::*;
 use linkerd_duplex::Duplex;
 use linkerd_error::{Error, Result};
+use linkerd_io::{self as io, AsyncRead, AsyncWrite};
 use linkerd_stack::layer;
 use std::{
+    net::SocketAddr,
     pin::Pin,
     task::{Context, Poll},
 };
-use tokio::io::{AsyncRead, AsyncWrite};
 use tower::Service;
 
+/// Forwards a connection's bytes to a connection obtained from `C`.
+///
+/// When neither connection buffers or transforms data (e.g. with TLS), bytes
+/// are spliced between their sockets on Linux rather than copied.
 #[derive(Clone, Debug)]
 pub struct Forward<C> {
     connect: C,
 }
 
+/// Like [`Forward`], but obtains the connection from `C` with the peer address
+/// of the forwarded connection, so that the connector may identify the original
+/// client to the destination.
+#[derive(Clone, Debug)]
+pub struct ForwardFromPeer<C> {
+    connect: C,
+}
+
+// === impl Forward ===
+
 impl<C> Forward<C> {
     fn new(connect: C) -> Self {
         Self { connect }
@@ -26,11 +41,11 @@ impl<C> Forward<C> {
 
 impl<C, I> Service<I> for Forward<C>
 where
-    I: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    I: AsyncRead + AsyncWrite + io::Splice + Send + Unpin + 'static,
     C: tower::Service<()> + Send + 'static,
     C::Error: Into<Error>,
     C::Future: Send + 'static,
-    C::Response: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    C::Response: AsyncRead + AsyncWrite + io::Splice + Send + Unpin + 'static,
 {
     type Response = ();
     type Error = Error;
@@ -46,7 +61,50 @@ where
             self.connect
                 .call(())
                 .err_into::<Error>()
-                .and_then(|dst_io| Duplex::new(src_io, dst_io).err_into::<Error>()),
+                .and_then(|dst_io| Duplex::with_splice(src_io, dst_io).err_into::<Error>()),
+        )
+    }
+}
+
+// === impl ForwardFromPeer ===
+
+impl<C> ForwardFromPeer<C> {
+    fn new(connect: C) -> Self {
+        Self { connect }
+    }
+
+    pub fn layer() -> impl layer::Layer<C, Service = Self> + Copy {
+        layer::mk(Self::new)
+    }
+}
+
+impl<C, I> Service<I> for ForwardFromPeer<C>
+where
+    I: AsyncRead + AsyncWrite + io::PeerAddr + io::Splice + Send + Unpin + 'static,
+    C: tower::Service<SocketAddr> + Send + 'static,
+    C::Error: Into<Error>,
+    C::Future: Send + 'static,
+    C::Response: AsyncRead + AsyncWrite + io::Splice + Send + Unpin + 'static,
+{
+    type Response = ();
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), self::Error>> {
+        self.connect.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, src_io: I) -> Self::Future {
+        let peer = match src_io.peer_addr() {
+            Ok(peer) => peer,
+            Err(error) => return Box::pin(future::err(error.into())),
+        };
+        Box::pin(
+            self.connect
+                .call(peer)
+                .err_into::<Error>()
+                .and_then(|dst_io| Duplex::with_splice(src_io, dst_io).err_into::<Error>()),
         )
     }
 }
@@ -1,13 +1,16 @@
-use crate::{ClientAddr, Keepalive, Local, Remote, ServerAddr, UserTimeout};
+use crate::{ClientAddr, Keepalive, Local, Marking, Remote, ServerAddr, UserTimeout};
 use linkerd_io as io;
 use linkerd_stack::{Param, Service};
 use std::{
     future::Future,
+    net::{IpAddr, SocketAddr},
+    path::Path,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
-use tokio::net::TcpStream;
-use tracing::debug;
+use tokio::net::{TcpSocket, TcpStream};
+use tracing::{debug, warn};
 
 #[derive(Copy, Clone, Debug)]
 pub struct ConnectTcp {
@@ -15,6 +18,48 @@ pub struct ConnectTcp {
     user_timeout: UserTimeout,
 }
 
+/// A non-local address from which a connection should originate, so that the
+/// server observes the client's address rather than the proxy's.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransparentSource(pub IpAddr);
+
+/// Connects from a target's transparent source address, if it has one, and
+/// otherwise connects like [`ConnectTcp`].
+///
+/// Binding to a non-local address requires `IP_TRANSPARENT`, which is only
+/// supported on Linux.
+#[derive(Copy, Clone, Debug)]
+pub struct ConnectTransparent {
+    tcp: ConnectTcp,
+}
+
+/// Connects with a target's packet marking, if it has one, and otherwise
+/// connects like [`ConnectTcp`].
+#[derive(Copy, Clone, Debug)]
+pub struct ConnectMarked {
+    tcp: ConnectTcp,
+}
+
+/// The path of a Unix domain socket to which a connection should be
+/// established instead of its TCP address.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnixAddr(pub Arc<Path>);
+
+/// Connects to a target's Unix domain socket, if it has one, and otherwise
+/// delegates to the inner TCP connector.
+#[derive(Copy, Clone, Debug)]
+pub struct ConnectUnix<C> {
+    inner: C,
+}
+
+#[cfg(unix)]
+type UnixStream = tokio::net::UnixStream;
+
+#[cfg(not(unix))]
+type UnixStream = io::DuplexStream;
+
+// === impl ConnectTcp ===
+
 impl ConnectTcp {
     pub fn new(keepalive: Keepalive, user_timeout: UserTimeout) -> Self {
         Self {
@@ -40,16 +85,344 @@ impl<T: Param<Remote<ServerAddr>>> Service<T> for ConnectTcp {
         debug!(server.addr = %addr, "Connecting");
         Box::pin(async move {
             let io = TcpStream::connect(&addr).await?;
-            super::set_nodelay_or_warn(&io);
-            let io = super::set_keepalive_or_warn(io, keepalive)?;
-            let io = super::set_user_timeout_or_warn(io, user_timeout)?;
-            let local_addr = io.local_addr()?;
-            debug!(
-                local.addr = %local_addr,
-                ?keepalive,
-                "Connected",
-            );
-            Ok((io::ScopedIo::client(io), Local(ClientAddr(local_addr))))
+            connected(io, keepalive, user_timeout)
+        })
+    }
+}
+
+fn connected(
+    io: TcpStream,
+    keepalive: Option<std::time::Duration>,
+    user_timeout: Option<std::time::Duration>,
+) -> io::Result<(io::ScopedIo<TcpStream>, Local<ClientAddr>)> {
+    super::set_nodelay_or_warn(&io);
+    let io = super::set_keepalive_or_warn(io, keepalive)?;
+    let io = super::set_user_timeout_or_warn(io, user_timeout)?;
+    let local_addr = io.local_addr()?;
+    debug!(
+        local.addr = %local_addr,
+        ?keepalive,
+        "Connected",
+    );
+    Ok((io::ScopedIo::client(io), Local(ClientAddr(local_addr))))
+}
+
+// === impl ConnectTransparent ===
+
+impl ConnectTransparent {
+    pub fn new(keepalive: Keepalive, user_timeout: UserTimeout) -> Self {
+        Self {
+            tcp: ConnectTcp::new(keepalive, user_timeout),
+        }
+    }
+}
+
+impl<T> Service<T> for ConnectTransparent
+where
+    T: Param<Remote<ServerAddr>> + Param<Option<TransparentSource>>,
+{
+    type Response = (io::ScopedIo<TcpStream>, Local<ClientAddr>);
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send + Sync + 'static>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, t: T) -> Self::Future {
+        let Remote(ServerAddr(addr)) = t.param();
+        let source = match t.param() {
+            Some(TransparentSource(ip)) if ip.is_ipv4() == addr.is_ipv4() => ip,
+            Some(TransparentSource(ip)) => {
+                // A connection cannot originate from an address of another
+                // family, so the client's address is not preserved.
+                warn!(
+                    client.ip = %ip,
+                    server.addr = %addr,
+                    "Client and server address families differ; connecting from a local address",
+                );
+                return self.tcp.call(t);
+            }
+            None => return self.tcp.call(t),
+        };
+
+        let Keepalive(keepalive) = self.tcp.keepalive;
+        let UserTimeout(user_timeout) = self.tcp.user_timeout;
+        debug!(server.addr = %addr, client.ip = %source, "Connecting transparently");
+        Box::pin(async move {
+            let socket = crate::transparent::socket(addr)?;
+            // The ephemeral port is chosen by the kernel, as the client's own
+            // port may already be in use by its connection to the proxy.
+            socket.bind(SocketAddr::new(source, 0))?;
+            let io = socket.connect(addr).await?;
+            connected(io, keepalive, user_timeout)
+        })
+    }
+}
+
+// === impl ConnectMarked ===
+
+impl ConnectMarked {
+    pub fn new(keepalive: Keepalive, user_timeout: UserTimeout) -> Self {
+        Self {
+            tcp: ConnectTcp::new(keepalive, user_timeout),
+        }
+    }
+}
+
+impl<T> Service<T> for ConnectMarked
+where
+    T: Param<Remote<ServerAddr>> + Param<Option<Marking>>,
+{
+    type Response = (io::ScopedIo<TcpStream>, Local<ClientAddr>);
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send + Sync + 'static>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, t: T) -> Self::Future {
+        let Some(marking) = t.param() else {
+            return self.tcp.call(t);
+        };
+
+        let Keepalive(keepalive) = self.tcp.keepalive;
+        let UserTimeout(user_timeout) = self.tcp.user_timeout;
+        let Remote(ServerAddr(addr)) = t.param();
+        debug!(server.addr = %addr, ?marking, "Connecting");
+        Box::pin(async move {
+            let socket = match addr {
+                SocketAddr::V4(_) => TcpSocket::new_v4()?,
+                SocketAddr::V6(_) => TcpSocket::new_v6()?,
+            };
+            marking.apply(&socket, addr)?;
+            let io = socket.connect(addr).await?;
+            connected(io, keepalive, user_timeout)
+        })
+    }
+}
+
+// === impl UnixAddr ===
+
+impl<P: AsRef<Path>> From<P> for UnixAddr {
+    fn from(path: P) -> Self {
+        Self(path.as_ref().into())
+    }
+}
+
+impl std::fmt::Display for UnixAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.display().fmt(f)
+    }
+}
+
+// === impl ConnectUnix ===
+
+impl<C> ConnectUnix<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, C, I, M> Service<T> for ConnectUnix<C>
+where
+    T: Param<Option<UnixAddr>>,
+    C: Service<T, Response = (I, M), Error = io::Error>,
+    C::Future: Send + 'static,
+    I: Send + 'static,
+    M: Send + 'static,
+{
+    type Response = (io::EitherIo<I, io::ScopedIo<UnixStream>>, Option<M>);
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, t: T) -> Self::Future {
+        let Some(UnixAddr(path)) = t.param() else {
+            let connect = self.inner.call(t);
+            return Box::pin(async move {
+                let (io, meta) = connect.await?;
+                Ok((io::EitherIo::Left(io), Some(meta)))
+            });
+        };
+
+        debug!(server.path = %path.display(), "Connecting");
+        Box::pin(async move {
+            let io = connect_unix(&path).await?;
+            debug!(server.path = %path.display(), "Connected");
+            Ok((io::EitherIo::Right(io::ScopedIo::client(io)), None))
         })
     }
 }
+
+#[cfg(unix)]
+async fn connect_unix(path: &Path) -> io::Result<UnixStream> {
+    UnixStream::connect(path).await
+}
+
+#[cfg(not(unix))]
+async fn connect_unix(path: &Path) -> io::Result<UnixStream> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "cannot connect to {}: Unix domain sockets are not supported on this platform",
+            path.display()
+        ),
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use io::{AsyncReadExt, AsyncWriteExt};
+
+    #[derive(Clone, Debug)]
+    struct Target(Option<UnixAddr>);
+
+    impl Param<Option<UnixAddr>> for Target {
+        fn param(&self) -> Option<UnixAddr> {
+            self.0.clone()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Transparent(SocketAddr, Option<TransparentSource>);
+
+    impl Param<Remote<ServerAddr>> for Transparent {
+        fn param(&self) -> Remote<ServerAddr> {
+            Remote(ServerAddr(self.0))
+        }
+    }
+
+    impl Param<Option<TransparentSource>> for Transparent {
+        fn param(&self) -> Option<TransparentSource> {
+            self.1
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Marked(SocketAddr, Option<Marking>);
+
+    impl Param<Remote<ServerAddr>> for Marked {
+        fn param(&self) -> Remote<ServerAddr> {
+            Remote(ServerAddr(self.0))
+        }
+    }
+
+    impl Param<Option<Marking>> for Marked {
+        fn param(&self) -> Option<Marking> {
+            self.1
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Unreachable;
+
+    impl Service<Target> for Unreachable {
+        type Response = (io::DuplexStream, ());
+        type Error = io::Error;
+        type Future = futures::future::Ready<io::Result<Self::Response>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: Target) -> Self::Future {
+            futures::future::ready(Err(io::ErrorKind::ConnectionRefused.into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn connects_to_unix_socket() {
+        let dir = std::env::temp_dir().join(format!("linkerd-connect-unix-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.sock");
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut io, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 5];
+            io.read_exact(&mut buf).await.unwrap();
+            io.write_all(&buf).await.unwrap();
+        });
+
+        let mut connect = ConnectUnix::new(Unreachable);
+        let (mut io, meta) = connect
+            .call(Target(Some(UnixAddr::from(&path))))
+            .await
+            .expect("must connect to the socket");
+        assert!(meta.is_none());
+        io.write_all(b"hello").await.unwrap();
+        let mut buf = [0u8; 5];
+        io.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+
+        server.await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn delegates_without_path() {
+        let mut connect = ConnectUnix::new(Unreachable);
+        let err = connect
+            .call(Target(None))
+            .await
+            .expect_err("must use the inner connector");
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    #[ignore = "requires CAP_NET_ADMIN to set IP_TRANSPARENT"]
+    async fn connects_from_transparent_source() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let source = TransparentSource([127, 0, 0, 2].into());
+
+        let mut connect = ConnectTransparent::new(Keepalive(None), UserTimeout(None));
+        let (_io, Local(ClientAddr(local))) = connect
+            .call(Transparent(addr, Some(source)))
+            .await
+            .expect("must connect");
+        assert_eq!(local.ip(), source.0);
+
+        let (_io, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer.ip(), source.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn connects_with_dscp() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let marking = Marking {
+            dscp: Some(46),
+            mark: None,
+        };
+
+        let mut connect = ConnectMarked::new(Keepalive(None), UserTimeout(None));
+        let (io, _) = connect
+            .call(Marked(addr, Some(marking)))
+            .await
+            .expect("must connect");
+        let tos = socket2::SockRef::from(io.get_ref()).tos().unwrap();
+        assert_eq!(tos, 46 << 2);
+
+        let err = connect
+            .call(Marked(
+                addr,
+                Some(Marking {
+                    dscp: Some(64),
+                    mark: None,
+                }),
+            ))
+            .await
+            .expect_err("DSCP values are 6 bits");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
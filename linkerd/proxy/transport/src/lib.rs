@@ -1,6 +1,7 @@
 //! Utilities for use TCP servers & clients.
 //!
-//! Uses unsafe code to interact with socket options for SO_ORIGINAL_DST.
+//! Uses unsafe code to interact with socket options for SO_ORIGINAL_DST and
+//! IP_TRANSPARENT.
 
 #![deny(
     rust_2018_idioms,
@@ -12,15 +13,20 @@
 pub mod addrs;
 mod connect;
 pub mod listen;
+mod marking;
 pub mod orig_dst;
+mod transparent;
 
 pub use self::{
     addrs::{
         AddrPair, ClientAddr, DualListenAddr, ListenAddr, Local, OrigDstAddr, Remote, ServerAddr,
     },
-    connect::ConnectTcp,
-    listen::{Bind, BindTcp},
-    orig_dst::BindWithOrigDst,
+    connect::{
+        ConnectMarked, ConnectTcp, ConnectTransparent, ConnectUnix, TransparentSource, UnixAddr,
+    },
+    listen::{AcceptMetrics, Bind, BindTcp},
+    marking::{Marking, SetDscp},
+    orig_dst::{BindWithOrigDst, OrigDstMode},
 };
 use linkerd_io as io;
 use socket2::TcpKeepalive;
@@ -45,6 +51,24 @@ impl From<UserTimeout> for Option<Duration> {
     }
 }
 
+/// The number of sockets bound for each listener with `SO_REUSEPORT`. The
+/// kernel balances new connections across them.
+///
+/// Each shard produces its own stream of incoming connections (see
+/// [`listen::Bind::bind_shards`]) so that it is served by its own serve loop,
+/// and shards accept connections concurrently.
+///
+/// A single socket is bound when fewer than two shards are configured.
+/// Sharding is only supported on Linux.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ListenShards(pub usize);
+
+impl Default for ListenShards {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
 // Misc.
 
 fn set_nodelay_or_warn(socket: &TcpStream) {
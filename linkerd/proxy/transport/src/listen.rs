@@ -1,14 +1,16 @@
 mod dual_bind;
+mod metrics;
 
-use crate::{addrs::*, Keepalive, UserTimeout};
+pub use self::metrics::AcceptMetrics;
+use crate::{addrs::*, orig_dst::OrigDstMode, Keepalive, ListenShards, UserTimeout};
 use dual_bind::DualBind;
 use futures::prelude::*;
 use linkerd_error::Result;
 use linkerd_io as io;
 use linkerd_stack::Param;
-use std::{fmt, net::SocketAddr, pin::Pin};
+use std::{fmt, net::SocketAddr, pin::Pin, time::Duration};
 use thiserror::Error;
-use tokio::net::TcpStream;
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
 use tokio_stream::wrappers::TcpListenerStream;
 
 /// Binds a listener, producing a stream of incoming connections.
@@ -20,6 +22,7 @@ pub trait Bind<T> {
         + io::AsyncWrite
         + io::Peek
         + io::PeerAddr
+        + io::Splice
         + fmt::Debug
         + Unpin
         + Send
@@ -30,10 +33,32 @@ pub trait Bind<T> {
     type Incoming: Stream<Item = Result<(Self::Addrs, Self::Io)>> + Send + Sync + 'static;
 
     fn bind(self, params: &T) -> Result<(Self::BoundAddrs, Self::Incoming)>;
+
+    /// Binds a listener, producing a stream of incoming connections for each
+    /// of its shards (see [`ListenShards`]) so that each shard may be served
+    /// by its own serve loop.
+    ///
+    /// Listeners that are not sharded produce a single stream.
+    fn bind_shards(self, params: &T) -> Result<(Self::BoundAddrs, Vec<Self::Incoming>)>
+    where
+        Self: Sized,
+    {
+        let (addrs, incoming) = self.bind(params)?;
+        Ok((addrs, vec![incoming]))
+    }
 }
 
-#[derive(Copy, Clone, Debug, Default)]
-pub struct BindTcp(());
+/// The number of pending connections that each listener queues before they
+/// are accepted. The kernel further limits this to `net.core.somaxconn`.
+const LISTEN_BACKLOG: u32 = 1024;
+
+#[derive(Clone, Debug, Default)]
+pub struct BindTcp {
+    /// Binds listeners with `IP_TRANSPARENT` so that they may accept
+    /// connections delivered by TPROXY rules.
+    transparent: bool,
+    metrics: AcceptMetrics,
+}
 
 #[derive(Clone, Debug)]
 pub struct Addrs {
@@ -57,6 +82,14 @@ struct UserTimeoutError(#[source] io::Error);
 #[error("failed to obtain peer address: {0}")]
 struct PeerAddrError(#[source] io::Error);
 
+/// Configures the sockets accepted by a listener.
+#[derive(Copy, Clone, Debug)]
+struct Accept {
+    server: Local<ServerAddr>,
+    keepalive: Option<Duration>,
+    user_timeout: Option<Duration>,
+}
+
 // === impl BindTcp ===
 
 impl BindTcp {
@@ -67,11 +100,72 @@ impl BindTcp {
     pub fn dual_with_orig_dst() -> DualBind<super::BindWithOrigDst<Self>> {
         DualBind::from(super::BindWithOrigDst::default())
     }
+
+    /// Binds listeners that determine each connection's original destination
+    /// address according to `mode`, recording accepted connections in
+    /// `metrics`.
+    ///
+    /// TPROXY listeners are bound with `IP_TRANSPARENT`, which requires the
+    /// `CAP_NET_ADMIN` capability.
+    pub fn with_orig_dst_mode(
+        mode: OrigDstMode,
+        metrics: AcceptMetrics,
+    ) -> super::BindWithOrigDst<Self> {
+        let transparent = mode == OrigDstMode::Tproxy;
+        super::BindWithOrigDst::new(
+            Self {
+                transparent,
+                metrics,
+            },
+            mode,
+        )
+    }
+
+    pub fn dual_with_orig_dst_mode(
+        mode: OrigDstMode,
+        metrics: AcceptMetrics,
+    ) -> DualBind<super::BindWithOrigDst<Self>> {
+        DualBind::from(Self::with_orig_dst_mode(mode, metrics))
+    }
+
+    fn listen(&self, addr: SocketAddr) -> io::Result<TcpListener> {
+        let socket = self.socket(addr)?;
+        socket.bind(addr)?;
+        socket.listen(LISTEN_BACKLOG)
+    }
+
+    /// Binds `shards` listeners to the same address with `SO_REUSEPORT`.
+    fn listen_shards(&self, mut addr: SocketAddr, shards: usize) -> io::Result<Vec<TcpListener>> {
+        let mut listeners = Vec::with_capacity(shards);
+        for _ in 0..shards {
+            let socket = self.socket(addr)?;
+            set_reuseport(&socket)?;
+            socket.bind(addr)?;
+            let listen = socket.listen(LISTEN_BACKLOG)?;
+            // If the listener was bound to an ephemeral port, the remaining
+            // shards must share the port that was chosen.
+            addr = listen.local_addr()?;
+            listeners.push(listen);
+        }
+        Ok(listeners)
+    }
+
+    fn socket(&self, addr: SocketAddr) -> io::Result<TcpSocket> {
+        let socket = if self.transparent {
+            crate::transparent::socket(addr)?
+        } else if addr.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+        socket.set_reuseaddr(true)?;
+        Ok(socket)
+    }
 }
 
 impl<T> Bind<T> for BindTcp
 where
-    T: Param<ListenAddr> + Param<Keepalive> + Param<UserTimeout>,
+    T: Param<ListenAddr> + Param<Keepalive> + Param<UserTimeout> + Param<ListenShards>,
 {
     type Addrs = Addrs;
     type BoundAddrs = Local<ServerAddr>;
@@ -79,41 +173,84 @@ where
     type Io = TcpStream;
 
     fn bind(self, params: &T) -> Result<(Self::BoundAddrs, Self::Incoming)> {
-        let listen = {
-            let ListenAddr(addr) = params.param();
-            let l = std::net::TcpListener::bind(addr)?;
-            // Ensure that O_NONBLOCK is set on the socket before using it with Tokio.
-            l.set_nonblocking(true)?;
-            tokio::net::TcpListener::from_std(l).expect("listener must be valid")
-        };
-        let server = Local(ServerAddr(listen.local_addr()?));
+        let (server, shards) = self.bind_shards(params)?;
+        Ok((server, Box::pin(stream::select_all(shards))))
+    }
+
+    fn bind_shards(self, params: &T) -> Result<(Self::BoundAddrs, Vec<Self::Incoming>)> {
+        let ListenAddr(addr) = params.param();
+        let ListenShards(shards) = params.param();
         let Keepalive(keepalive) = params.param();
         let UserTimeout(user_timeout) = params.param();
-        let accept = TcpListenerStream::new(listen).map(move |res| {
-            let tcp = res.map_err(AcceptError)?;
-            super::set_nodelay_or_warn(&tcp);
-            let tcp = super::set_keepalive_or_warn(tcp, keepalive).map_err(KeepaliveError)?;
-            let tcp =
-                super::set_user_timeout_or_warn(tcp, user_timeout).map_err(UserTimeoutError)?;
-
-            fn ipv4_mapped(orig: SocketAddr) -> SocketAddr {
-                if let SocketAddr::V6(v6) = orig {
-                    if let Some(ip) = v6.ip().to_ipv4_mapped() {
-                        return (ip, orig.port()).into();
-                    }
+
+        let listeners = if shards > 1 {
+            self.listen_shards(addr, shards)?
+        } else {
+            vec![self.listen(addr)?]
+        };
+        let server = Local(ServerAddr(listeners[0].local_addr()?));
+        let accept = Accept {
+            server,
+            keepalive,
+            user_timeout,
+        };
+        let incoming = listeners
+            .into_iter()
+            .enumerate()
+            .map(|(shard, listen)| {
+                let metrics = self.metrics.shard(server, shard);
+                let incoming = TcpListenerStream::new(listen).map(move |res| {
+                    let res = accept.accept(res);
+                    metrics.record(&res);
+                    res
+                });
+                Box::pin(incoming) as Self::Incoming
+            })
+            .collect();
+
+        Ok((server, incoming))
+    }
+}
+
+// === impl Accept ===
+
+impl Accept {
+    fn accept(&self, res: io::Result<TcpStream>) -> Result<(Addrs, TcpStream)> {
+        let tcp = res.map_err(AcceptError)?;
+        super::set_nodelay_or_warn(&tcp);
+        let tcp = super::set_keepalive_or_warn(tcp, self.keepalive).map_err(KeepaliveError)?;
+        let tcp =
+            super::set_user_timeout_or_warn(tcp, self.user_timeout).map_err(UserTimeoutError)?;
+
+        fn ipv4_mapped(orig: SocketAddr) -> SocketAddr {
+            if let SocketAddr::V6(v6) = orig {
+                if let Some(ip) = v6.ip().to_ipv4_mapped() {
+                    return (ip, orig.port()).into();
                 }
-                orig
             }
+            orig
+        }
 
-            let client_addr = tcp.peer_addr().map_err(PeerAddrError)?;
-            let client = Remote(ClientAddr(ipv4_mapped(client_addr)));
-            Ok((Addrs { server, client }, tcp))
-        });
-
-        Ok((server, Box::pin(accept)))
+        let client_addr = tcp.peer_addr().map_err(PeerAddrError)?;
+        let client = Remote(ClientAddr(ipv4_mapped(client_addr)));
+        let server = self.server;
+        Ok((Addrs { server, client }, tcp))
     }
 }
 
+#[cfg(target_os = "linux")]
+fn set_reuseport(socket: &TcpSocket) -> io::Result<()> {
+    socket.set_reuseport(true)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_reuseport(_: &TcpSocket) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "listener sharding not supported on this operating system",
+    ))
+}
+
 // === impl Addrs ===
 
 impl Param<Remote<ClientAddr>> for Addrs {
@@ -138,3 +275,127 @@ impl Param<AddrPair> for Addrs {
         AddrPair(client, server)
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use linkerd_metrics::prom;
+
+    #[derive(Clone, Debug)]
+    struct Params(ListenShards);
+
+    impl Param<ListenAddr> for Params {
+        fn param(&self) -> ListenAddr {
+            ListenAddr(([127, 0, 0, 1], 0).into())
+        }
+    }
+
+    impl Param<Keepalive> for Params {
+        fn param(&self) -> Keepalive {
+            Keepalive(None)
+        }
+    }
+
+    impl Param<UserTimeout> for Params {
+        fn param(&self) -> UserTimeout {
+            UserTimeout(None)
+        }
+    }
+
+    impl Param<ListenShards> for Params {
+        fn param(&self) -> ListenShards {
+            self.0
+        }
+    }
+
+    /// Sums the accepts recorded by each shard of the listener.
+    fn accepts(registry: &prom::Registry) -> (usize, u64) {
+        let mut text = String::new();
+        prom::encoding::text::encode(&mut text, registry).unwrap();
+        let counts = text
+            .lines()
+            .filter(|l| l.starts_with("accepts_total{"))
+            .map(|l| l.rsplit(' ').next().unwrap().parse::<u64>().unwrap())
+            .collect::<Vec<_>>();
+        (counts.len(), counts.iter().sum())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn accepts_on_shards() {
+        let mut registry = prom::Registry::default();
+        let bind = BindTcp {
+            transparent: false,
+            metrics: AcceptMetrics::register(&mut registry),
+        };
+        let (Local(ServerAddr(addr)), mut incoming) =
+            bind.bind(&Params(ListenShards(4))).expect("must bind");
+
+        for _ in 0..16 {
+            let client = TcpStream::connect(addr).await.unwrap();
+            let (addrs, _tcp) = incoming.next().await.unwrap().unwrap();
+            assert_eq!(addrs.server, Local(ServerAddr(addr)));
+            assert_eq!(
+                addrs.client,
+                Remote(ClientAddr(client.local_addr().unwrap()))
+            );
+        }
+
+        assert_eq!(accepts(&registry), (4, 16));
+    }
+
+    /// Each shard is served independently: a shard whose serve loop is busy
+    /// does not prevent the other shards from accepting connections.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn shards_accept_concurrently() {
+        let bind = BindTcp::default();
+        let (Local(ServerAddr(addr)), shards) = bind
+            .bind_shards(&Params(ListenShards(2)))
+            .expect("must bind");
+        assert_eq!(shards.len(), 2);
+
+        // Each shard's serve loop accepts a connection and then waits for the
+        // other shard to accept a connection as well.
+        let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(2));
+        let serves = shards
+            .into_iter()
+            .map(|mut incoming| {
+                let barrier = barrier.clone();
+                tokio::spawn(async move {
+                    let (_addrs, tcp) = incoming.next().await.unwrap().unwrap();
+                    barrier.wait().await;
+                    // The listener is held so that clients are not refused.
+                    (incoming, tcp)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // The kernel balances connections across shards by their addresses,
+        // so enough connections are opened that each shard receives some.
+        let mut clients = Vec::new();
+        for _ in 0..32 {
+            clients.push(TcpStream::connect(addr).await.unwrap());
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), future::try_join_all(serves))
+            .await
+            .expect("shards must accept concurrently")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn accepts_unsharded() {
+        let mut registry = prom::Registry::default();
+        let bind = BindTcp {
+            transparent: false,
+            metrics: AcceptMetrics::register(&mut registry),
+        };
+        let (Local(ServerAddr(addr)), mut incoming) = bind
+            .bind(&Params(ListenShards::default()))
+            .expect("must bind");
+
+        let _client = TcpStream::connect(addr).await.unwrap();
+        incoming.next().await.unwrap().unwrap();
+
+        assert_eq!(accepts(&registry), (1, 1));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{addrs::DualListenAddr, listen::Bind, Keepalive, ListenAddr, UserTimeout};
+use crate::{
+    addrs::DualListenAddr, listen::Bind, Keepalive, ListenAddr, ListenShards, UserTimeout,
+};
 use futures::Stream;
 use linkerd_error::Result;
 use linkerd_stack::Param;
@@ -26,7 +28,7 @@ impl<B> From<B> for DualBind<B> {
 
 impl<T, B> Bind<T> for DualBind<B>
 where
-    T: Param<DualListenAddr> + Param<Keepalive> + Param<UserTimeout> + Clone,
+    T: Param<DualListenAddr> + Param<Keepalive> + Param<UserTimeout> + Param<ListenShards> + Clone,
     B: Bind<Listen<T>, Io = TcpStream> + Clone + 'static,
 {
     type Addrs = B::Addrs;
@@ -52,6 +54,33 @@ where
             None => Ok(((addr1, None), Box::pin(incoming1))),
         }
     }
+    fn bind_shards(self, target: &T) -> Result<(Self::BoundAddrs, Vec<Self::Incoming>)> {
+        let DualListenAddr(addr1, addr2) = target.param();
+        let (addr1, shards1) = self.inner.clone().bind_shards(&Listen {
+            addr: addr1,
+            parent: target.clone(),
+        })?;
+        let mut shards = shards1
+            .into_iter()
+            .map(|incoming| Box::pin(incoming) as Self::Incoming)
+            .collect::<Vec<_>>();
+        let addr2 = match addr2 {
+            Some(addr2) => {
+                let (addr2, shards2) = self.inner.bind_shards(&Listen {
+                    addr: addr2,
+                    parent: target.clone(),
+                })?;
+                shards.extend(
+                    shards2
+                        .into_iter()
+                        .map(|incoming| Box::pin(incoming) as Self::Incoming),
+                );
+                Some(addr2)
+            }
+            None => None,
+        };
+        Ok(((addr1, addr2), shards))
+    }
 }
 
 // === impl Listen ===
@@ -68,6 +97,12 @@ impl<T: Param<UserTimeout>> Param<UserTimeout> for Listen<T> {
     }
 }
 
+impl<T: Param<ListenShards>> Param<ListenShards> for Listen<T> {
+    fn param(&self) -> ListenShards {
+        self.parent.param()
+    }
+}
+
 impl<T> Param<ListenAddr> for Listen<T> {
     fn param(&self) -> ListenAddr {
         ListenAddr(self.addr)
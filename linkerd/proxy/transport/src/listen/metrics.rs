@@ -0,0 +1,72 @@
+use crate::addrs::{Local, ServerAddr};
+use linkerd_error::Result;
+use linkerd_metrics::prom;
+
+/// Counts the connections accepted by each shard of a listener.
+///
+/// Listeners that are not sharded record their connections as shard 0.
+#[derive(Clone, Debug, Default)]
+pub struct AcceptMetrics {
+    accepts: prom::Family<ShardLabels, prom::Counter>,
+    errors: prom::Family<ShardLabels, prom::Counter>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ShardMetrics {
+    accepts: prom::Counter,
+    errors: prom::Counter,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, prom::encoding::EncodeLabelSet)]
+struct ShardLabels {
+    listen_addr: String,
+    shard: usize,
+}
+
+// === impl AcceptMetrics ===
+
+impl AcceptMetrics {
+    pub fn register(registry: &mut prom::Registry) -> Self {
+        let accepts = prom::Family::default();
+        registry.register(
+            "accepts",
+            "The total number of connections accepted by each listener shard",
+            accepts.clone(),
+        );
+
+        let errors = prom::Family::default();
+        registry.register(
+            "accept_errors",
+            "The total number of connections that each listener shard failed to accept",
+            errors.clone(),
+        );
+
+        Self { accepts, errors }
+    }
+
+    pub(super) fn shard(
+        &self,
+        Local(ServerAddr(addr)): Local<ServerAddr>,
+        shard: usize,
+    ) -> ShardMetrics {
+        let labels = ShardLabels {
+            listen_addr: addr.to_string(),
+            shard,
+        };
+        ShardMetrics {
+            accepts: self.accepts.get_or_create(&labels).clone(),
+            errors: self.errors.get_or_create(&labels).clone(),
+        }
+    }
+}
+
+// === impl ShardMetrics ===
+
+impl ShardMetrics {
+    pub(super) fn record<T>(&self, res: &Result<T>) {
+        match res {
+            Ok(_) => self.accepts.inc(),
+            Err(_) => self.errors.inc(),
+        };
+    }
+}
@@ -0,0 +1,105 @@
+//! Packet marking with DSCP values and `SO_MARK`.
+//!
+//! A Differentiated Services Code Point (DSCP) is written to the upper six bits
+//! of the IPv4 TOS field or the IPv6 traffic class so that the network may
+//! prioritize a connection's packets. A firewall mark (`SO_MARK`) is only
+//! visible to the local host, where it may select routing rules. Setting a
+//! firewall mark requires the `CAP_NET_ADMIN` capability.
+//!
+//! Marking is only supported on Linux.
+
+use linkerd_io as io;
+use socket2::SockRef;
+use std::net::SocketAddr;
+use tokio::net::TcpSocket;
+
+/// Marks to apply to the packets of a connection.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Marking {
+    /// A 6-bit Differentiated Services Code Point.
+    pub dscp: Option<u8>,
+
+    /// A firewall mark, set with `SO_MARK`.
+    pub mark: Option<u32>,
+}
+
+/// A stream whose outgoing packets may be marked with a DSCP value.
+pub trait SetDscp {
+    fn set_dscp(&self, dscp: u8) -> io::Result<()>;
+}
+
+// === impl Marking ===
+
+impl Marking {
+    /// Applies marks to a socket, before it is connected, so that they apply to
+    /// every packet of the connection.
+    pub(crate) fn apply(&self, socket: &TcpSocket, addr: SocketAddr) -> io::Result<()> {
+        let socket = SockRef::from(socket);
+        if let Some(dscp) = self.dscp {
+            set_dscp(&socket, addr, dscp)?;
+        }
+        if let Some(mark) = self.mark {
+            set_mark(&socket, mark)?;
+        }
+        Ok(())
+    }
+}
+
+// === impl SetDscp ===
+
+impl SetDscp for tokio::net::TcpStream {
+    fn set_dscp(&self, dscp: u8) -> io::Result<()> {
+        set_dscp(&SockRef::from(self), self.local_addr()?, dscp)
+    }
+}
+
+impl<I: SetDscp> SetDscp for io::ScopedIo<I> {
+    fn set_dscp(&self, dscp: u8) -> io::Result<()> {
+        self.get_ref().set_dscp(dscp)
+    }
+}
+
+impl SetDscp for tokio::io::DuplexStream {
+    fn set_dscp(&self, _: u8) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_dscp(socket: &SockRef<'_>, addr: SocketAddr, dscp: u8) -> io::Result<()> {
+    const MAX_DSCP: u8 = 0x3f;
+    if dscp > MAX_DSCP {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid DSCP value {dscp}; must be at most {MAX_DSCP}"),
+        ));
+    }
+    let tos = u32::from(dscp) << 2;
+    match addr {
+        SocketAddr::V4(_) => socket.set_tos(tos),
+        SocketAddr::V6(_) => socket.set_tclass_v6(tos),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_mark(socket: &SockRef<'_>, mark: u32) -> io::Result<()> {
+    socket.set_mark(mark)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_dscp(_: &SockRef<'_>, _: SocketAddr, _: u8) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_mark(_: &SockRef<'_>, _: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "packet marking not supported on this operating system",
+    )
+}
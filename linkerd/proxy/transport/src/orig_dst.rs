@@ -12,6 +12,23 @@ use tokio::net::TcpStream;
 #[derive(Copy, Clone, Debug, Default)]
 pub struct BindWithOrigDst<B = listen::BindTcp> {
     inner: B,
+    mode: OrigDstMode,
+}
+
+/// Determines how the original destination address of an accepted connection
+/// is obtained.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OrigDstMode {
+    /// Connections are redirected to the listener (i.e. by an iptables
+    /// `REDIRECT` rule), which records the original destination address in the
+    /// `SO_ORIGINAL_DST` socket option.
+    #[default]
+    Redirect,
+
+    /// Connections are delivered to the listener by a TPROXY rule, which
+    /// preserves the original destination as the connection's local address.
+    /// The listener must be bound with `IP_TRANSPARENT`.
+    Tproxy,
 }
 
 #[derive(Clone, Debug)]
@@ -61,9 +78,15 @@ where
 
 // === impl WithOrigDst ===
 
+impl<B> BindWithOrigDst<B> {
+    pub fn new(inner: B, mode: OrigDstMode) -> Self {
+        Self { inner, mode }
+    }
+}
+
 impl<B> From<B> for BindWithOrigDst<B> {
     fn from(inner: B) -> Self {
-        Self { inner }
+        Self::new(inner, OrigDstMode::default())
     }
 }
 
@@ -80,23 +103,47 @@ where
 
     fn bind(self, t: &T) -> Result<(Self::BoundAddrs, Self::Incoming)> {
         let (addr, incoming) = self.inner.bind(t)?;
-
-        let incoming = incoming.map(|res| {
-            let (inner, tcp) = res?;
-            let orig_dst = match inner.param() {
-                // IPv4-mapped IPv6 addresses are unwrapped by BindTcp::bind() and received here as
-                // SocketAddr::V4. We must call getsockopt with IPv4 constants (via
-                // orig_dst_addr_v4) even if it originally was an IPv6
-                Remote(ClientAddr(SocketAddr::V4(_))) => orig_dst_addr_v4(&tcp)?,
-                Remote(ClientAddr(SocketAddr::V6(_))) => orig_dst_addr_v6(&tcp)?,
-            };
-            let orig_dst = OrigDstAddr(orig_dst);
-            let addrs = Addrs { inner, orig_dst };
-            Ok((addrs, tcp))
-        });
-
+        let mode = self.mode;
+        let incoming = incoming.map(move |res| with_orig_dst(mode, res));
         Ok((addr, Box::pin(incoming)))
     }
+
+    fn bind_shards(self, t: &T) -> Result<(Self::BoundAddrs, Vec<Self::Incoming>)> {
+        let (addr, shards) = self.inner.bind_shards(t)?;
+        let mode = self.mode;
+        let shards = shards
+            .into_iter()
+            .map(|incoming| {
+                let incoming = incoming.map(move |res| with_orig_dst(mode, res));
+                Box::pin(incoming) as Self::Incoming
+            })
+            .collect();
+        Ok((addr, shards))
+    }
+}
+
+/// Determines the original destination address of an accepted connection.
+fn with_orig_dst<A>(mode: OrigDstMode, res: Result<(A, TcpStream)>) -> Result<(Addrs<A>, TcpStream)>
+where
+    A: Param<Remote<ClientAddr>>,
+{
+    let (inner, tcp) = res?;
+    let orig_dst = match (mode, inner.param()) {
+        // TPROXY does not rewrite the connection's destination, so its
+        // local address is the original destination.
+        (OrigDstMode::Tproxy, _) => {
+            let addr = tcp.local_addr()?;
+            SocketAddr::new(addr.ip().to_canonical(), addr.port())
+        }
+        // IPv4-mapped IPv6 addresses are unwrapped by BindTcp::bind() and received here as
+        // SocketAddr::V4. We must call getsockopt with IPv4 constants (via
+        // orig_dst_addr_v4) even if it originally was an IPv6
+        (OrigDstMode::Redirect, Remote(ClientAddr(SocketAddr::V4(_)))) => orig_dst_addr_v4(&tcp)?,
+        (OrigDstMode::Redirect, Remote(ClientAddr(SocketAddr::V6(_)))) => orig_dst_addr_v6(&tcp)?,
+    };
+    let orig_dst = OrigDstAddr(orig_dst);
+    let addrs = Addrs { inner, orig_dst };
+    Ok((addrs, tcp))
 }
 
 #[cfg(target_os = "linux")]
@@ -235,3 +282,49 @@ mod linux {
         <u32>::from_be(i)
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::{Keepalive, ListenAddr, ListenShards, UserTimeout};
+
+    #[derive(Clone, Debug)]
+    struct Params(SocketAddr);
+
+    impl Param<ListenAddr> for Params {
+        fn param(&self) -> ListenAddr {
+            ListenAddr(self.0)
+        }
+    }
+
+    impl Param<Keepalive> for Params {
+        fn param(&self) -> Keepalive {
+            Keepalive(None)
+        }
+    }
+
+    impl Param<UserTimeout> for Params {
+        fn param(&self) -> UserTimeout {
+            UserTimeout(None)
+        }
+    }
+
+    impl Param<ListenShards> for Params {
+        fn param(&self) -> ListenShards {
+            ListenShards::default()
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires CAP_NET_ADMIN to set IP_TRANSPARENT"]
+    async fn tproxy_uses_local_addr() {
+        let bind = listen::BindTcp::with_orig_dst_mode(OrigDstMode::Tproxy, Default::default());
+        let (Local(ServerAddr(addr)), mut incoming) = bind
+            .bind(&Params(([127, 0, 0, 1], 0).into()))
+            .expect("must bind");
+
+        let _client = TcpStream::connect(addr).await.unwrap();
+        let (addrs, _) = incoming.next().await.unwrap().unwrap();
+        assert_eq!(addrs.orig_dst, OrigDstAddr(addr));
+    }
+}
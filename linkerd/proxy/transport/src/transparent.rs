@@ -0,0 +1,77 @@
+//! Transparent proxying with `IP_TRANSPARENT`.
+//!
+//! Transparent sockets may bind to non-local addresses. This allows listeners
+//! to accept connections that are delivered by TPROXY rules (whose local
+//! address is the connection's original destination) and allows connections to
+//! originate from a client's address rather than the proxy's.
+//!
+//! Setting `IP_TRANSPARENT` requires the `CAP_NET_ADMIN` capability. Routing
+//! rules must also deliver packets for these non-local addresses to the proxy.
+
+use linkerd_io as io;
+use std::net::SocketAddr;
+use tokio::net::TcpSocket;
+
+/// Creates a transparent socket for the given address's family.
+pub(crate) fn socket(addr: SocketAddr) -> io::Result<TcpSocket> {
+    let socket = match addr {
+        SocketAddr::V4(_) => TcpSocket::new_v4()?,
+        SocketAddr::V6(_) => TcpSocket::new_v6()?,
+    };
+    set_transparent(&socket, addr)?;
+    Ok(socket)
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+fn set_transparent(socket: &TcpSocket, addr: SocketAddr) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    match addr {
+        SocketAddr::V4(_) => socket2::SockRef::from(socket).set_ip_transparent(true),
+        // socket2 does not support IPV6_TRANSPARENT.
+        SocketAddr::V6(_) => {
+            let fd = socket.as_raw_fd();
+            // Safety: `fd` is a valid socket descriptor, as it is borrowed
+            // from `socket` for the duration of the call.
+            unsafe { linux::set_bool_opt(fd, libc::SOL_IPV6, libc::IPV6_TRANSPARENT) }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_transparent(_: &TcpSocket, _: SocketAddr) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "IP_TRANSPARENT not supported on this operating system",
+    ))
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod linux {
+    use std::os::unix::io::RawFd;
+    use std::{io, mem};
+
+    /// Enables a boolean socket option.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be a valid socket descriptor. The option value is an
+    /// initialized `c_int` whose size is passed along with it, so the kernel
+    /// never reads past it.
+    pub unsafe fn set_bool_opt(fd: RawFd, level: i32, optname: i32) -> io::Result<()> {
+        let enabled: libc::c_int = 1;
+        let ret = libc::setsockopt(
+            fd,
+            level,
+            optname,
+            &enabled as *const _ as *const libc::c_void,
+            mem::size_of::<libc::c_int>() as libc::socklen_t,
+        );
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
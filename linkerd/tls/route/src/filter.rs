@@ -0,0 +1,82 @@
+//! Filters that may be applied to TLS sessions without terminating TLS.
+
+use crate::{sni::MatchSni, SessionInfo};
+use linkerd_tls::NegotiatedProtocol;
+use std::time;
+
+/// Rejects sessions whose SNI matches any of the given patterns.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct DenySni {
+    pub snis: Vec<MatchSni>,
+}
+
+/// Rejects sessions that do not offer at least one of the given ALPN
+/// protocols in the ClientHello.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct AllowAlpn {
+    pub protocols: Vec<NegotiatedProtocol>,
+}
+
+/// Limits the amount of time that a session may remain open.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SessionLifetime {
+    pub max_duration: time::Duration,
+}
+
+// === impl DenySni ===
+
+impl DenySni {
+    pub fn is_denied(&self, session: &SessionInfo) -> bool {
+        self.snis
+            .iter()
+            .any(|m| m.summarize_match(&session.sni).is_some())
+    }
+}
+
+// === impl AllowAlpn ===
+
+impl AllowAlpn {
+    pub fn is_allowed(&self, session: &SessionInfo) -> bool {
+        session.alpn.iter().any(|p| self.protocols.contains(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(sni: &str, alpn: &[&[u8]]) -> SessionInfo {
+        SessionInfo {
+            sni: sni.parse().expect("must parse"),
+            alpn: alpn
+                .iter()
+                .map(|p| NegotiatedProtocol(p.to_vec()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn deny_sni() {
+        let deny = DenySni {
+            snis: vec![
+                "*.internal.example.com".parse().unwrap(),
+                "admin.example.com".parse().unwrap(),
+            ],
+        };
+        assert!(deny.is_denied(&session("db.internal.example.com", &[])));
+        assert!(deny.is_denied(&session("admin.example.com", &[])));
+        assert!(!deny.is_denied(&session("www.example.com", &[])));
+        assert!(!deny.is_denied(&session("internal.example.com", &[])));
+    }
+
+    #[test]
+    fn allow_alpn() {
+        let allow = AllowAlpn {
+            protocols: vec![NegotiatedProtocol(b"h2".to_vec())],
+        };
+        assert!(allow.is_allowed(&session("example.com", &[b"h2", b"http/1.1"])));
+        assert!(allow.is_allowed(&session("example.com", &[b"http/1.1", b"h2"])));
+        assert!(!allow.is_allowed(&session("example.com", &[b"http/1.1"])));
+        assert!(!allow.is_allowed(&session("example.com", &[])));
+    }
+}
@@ -4,9 +4,10 @@
 #![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
 #![forbid(unsafe_code)]
 
-use linkerd_tls::ServerName;
+use linkerd_tls::{NegotiatedProtocol, ServerName};
 use tracing::trace;
 
+pub mod filter;
 pub mod sni;
 #[cfg(test)]
 mod tests;
@@ -32,12 +33,14 @@ pub struct RouteMatch {
     sni: Option<SniMatch>,
 }
 
-/// Provides metadata information about a TLS session. For now this contains
-/// only the SNI value but further down the line, we could add more metadata
-/// if want to support more advanced routing scenarios.
+/// Provides metadata information about a TLS session, as read from the
+/// client's ClientHello without terminating TLS.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct SessionInfo {
     pub sni: ServerName,
+
+    /// The protocols offered via ALPN, in the client's order of preference.
+    pub alpn: Vec<NegotiatedProtocol>,
 }
 
 pub fn find<P>(routes: &[Route<P>], session_info: SessionInfo) -> Option<(RouteMatch, &P)> {
@@ -29,6 +29,7 @@ fn sni_precedence() {
 
     let si = SessionInfo {
         sni: "foo.example.com".parse().expect("must parse"),
+        alpn: vec![],
     };
 
     let (_, policy) = find(&rts, si).expect("must match");
@@ -51,6 +52,7 @@ fn first_identical_wins() {
 
     let si = SessionInfo {
         sni: "api.github.io".parse().expect("must parse"),
+        alpn: vec![],
     };
 
     let (_, policy) = find(&rts, si).expect("must match");
@@ -66,6 +68,7 @@ fn no_match_suffix() {
 
     let si = SessionInfo {
         sni: "test.example.com".parse().expect("must parse"),
+        alpn: vec![],
     };
 
     assert!(find(&rts, si).is_none(), "should have no matches");
@@ -80,6 +83,7 @@ fn no_match_exact() {
 
     let si = SessionInfo {
         sni: "fest.example.com".parse().expect("must parse"),
+        alpn: vec![],
     };
 
     assert!(find(&rts, si).is_none(), "should have no matches");
@@ -90,6 +94,7 @@ fn no_routes_no_match() {
     let rts: Vec<Route<Policy>> = Vec::default();
     let si = SessionInfo {
         sni: "fest.example.com".parse().expect("must parse"),
+        alpn: vec![],
     };
 
     assert!(find(&rts, si).is_none(), "should have no matches");
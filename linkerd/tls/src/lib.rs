@@ -7,8 +7,8 @@ pub mod server;
 pub use self::{
     client::{Client, ClientTls, ConditionalClientTls, ConnectMeta, NoClientTls, ServerId},
     server::{
-        ClientId, ConditionalServerTls, NewDetectRequiredSni, NewDetectTls, NoServerTls,
-        NoSniFoundError, ServerTls, SniDetectionTimeoutError,
+        ClientHello, ClientId, ConditionalServerTls, NewDetectRequiredSni, NewDetectTls,
+        NoServerTls, NoSniFoundError, ServerTls, SniDetectionTimeoutError,
     },
 };
 
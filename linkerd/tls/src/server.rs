@@ -19,7 +19,10 @@ use thiserror::Error;
 use tokio::time::{self, Duration};
 use tracing::{debug, trace, warn};
 
-pub use self::required_sni::{NewDetectRequiredSni, NoSniFoundError, SniDetectionTimeoutError};
+pub use self::{
+    client_hello::ClientHello,
+    required_sni::{NewDetectRequiredSni, NoSniFoundError, SniDetectionTimeoutError},
+};
 
 /// Describes the authenticated identity of a remote client.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -196,7 +199,19 @@ where
 }
 
 /// Peek or buffer the provided stream to determine an SNI value.
-pub(crate) async fn detect_sni<I>(mut io: I) -> io::Result<(Option<ServerName>, DetectIo<I>)>
+pub(crate) async fn detect_sni<I>(io: I) -> io::Result<(Option<ServerName>, DetectIo<I>)>
+where
+    I: io::Peek + io::AsyncRead + io::AsyncWrite + Send + Sync + Unpin,
+{
+    let (hello, io) = detect_client_hello(io).await?;
+    Ok((hello.map(|ClientHello { sni, .. }| sni), io))
+}
+
+/// Peek or buffer the provided stream to read a TLS ClientHello with an SNI
+/// value.
+pub(crate) async fn detect_client_hello<I>(
+    mut io: I,
+) -> io::Result<(Option<ClientHello>, DetectIo<I>)>
 where
     I: io::Peek + io::AsyncRead + io::AsyncWrite + Send + Sync + Unpin,
 {
@@ -211,9 +226,9 @@ where
     debug!(sz, "Peeked bytes from TCP stream");
     // Peek may return 0 bytes if the socket is not peekable.
     if sz > 0 {
-        match client_hello::parse_sni(buf.as_ref()) {
-            Ok(sni) => {
-                return Ok((sni, EitherIo::Left(io)));
+        match client_hello::parse_client_hello(buf.as_ref()) {
+            Ok(hello) => {
+                return Ok((hello, EitherIo::Left(io)));
             }
 
             Err(client_hello::Incomplete) => {}
@@ -227,9 +242,9 @@ where
     debug!(buf.capacity = %buf.capacity(), "Reading bytes from TCP stream");
     while io.read_buf(&mut buf).await? != 0 {
         debug!(buf.len = %buf.len(), "Read bytes from TCP stream");
-        match client_hello::parse_sni(buf.as_ref()) {
-            Ok(sni) => {
-                return Ok((sni, EitherIo::Right(PrefixedIo::new(buf.freeze(), io))));
+        match client_hello::parse_client_hello(buf.as_ref()) {
+            Ok(hello) => {
+                return Ok((hello, EitherIo::Right(PrefixedIo::new(buf.freeze(), io))));
             }
 
             Err(client_hello::Incomplete) => {
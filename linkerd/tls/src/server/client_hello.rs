@@ -1,10 +1,28 @@
-use crate::ServerName;
+use crate::{NegotiatedProtocol, ServerName};
 use linkerd_dns_name as dns;
 use tracing::trace;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct Incomplete;
 
+/// Describes the parts of a TLS ClientHello that may be inspected without
+/// terminating the session.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ClientHello {
+    /// The value of the SNI extension.
+    pub sni: ServerName,
+
+    /// The protocols offered via ALPN, in the client's order of preference.
+    pub alpn: Vec<NegotiatedProtocol>,
+}
+
+/// The raw values of the ClientHello extensions we care about.
+#[derive(Default)]
+struct Extensions<'a> {
+    sni: Option<untrusted::Input<'a>>,
+    alpn: Vec<untrusted::Input<'a>>,
+}
+
 /// Determines whether the given `input` looks like the start of a TLS connection.
 ///
 /// The determination is made based on whether the input looks like (the start of) a valid
@@ -19,13 +37,25 @@ pub struct Incomplete;
 /// reasonable implementations do. (If they were not to, they wouldn't interoperate with picky
 /// servers.)
 pub fn parse_sni(input: &[u8]) -> Result<Option<ServerName>, Incomplete> {
+    parse_client_hello(input).map(|hello| hello.map(|ClientHello { sni, .. }| sni))
+}
+
+/// Like [`parse_sni`], but also returns the ALPN protocols offered by the
+/// client.
+///
+/// Returns `Ok(None)` if the input is not a ClientHello or if the ClientHello
+/// does not include a valid SNI extension.
+pub fn parse_client_hello(input: &[u8]) -> Result<Option<ClientHello>, Incomplete> {
     let r = untrusted::Input::from(input).read_all(untrusted::EndOfInput, |input| {
-        let r = extract_sni(input);
+        let r = extract_extensions(input);
         input.skip_to_end(); // Ignore anything after what we parsed.
         r
     });
     match r {
-        Ok(Some(sni)) => {
+        Ok(Some(Extensions {
+            sni: Some(sni),
+            alpn,
+        })) => {
             let sni = match std::str::from_utf8(sni.as_slice_less_safe())
                 .ok()
                 .and_then(|n| n.parse::<dns::Name>().ok())
@@ -33,26 +63,34 @@ pub fn parse_sni(input: &[u8]) -> Result<Option<ServerName>, Incomplete> {
                 Some(sni) => sni,
                 None => return Ok(None),
             };
-            trace!(?sni, "parse_sni: parsed correctly up to SNI");
-            Ok(Some(ServerName(sni)))
+            trace!(?sni, "parse_client_hello: parsed correctly up to SNI");
+            let alpn = alpn
+                .into_iter()
+                .map(|p| NegotiatedProtocol(p.as_slice_less_safe().to_vec()))
+                .collect::<Vec<_>>();
+            trace!(?alpn, "parse_client_hello: parsed ALPN protocols");
+            Ok(Some(ClientHello {
+                sni: ServerName(sni),
+                alpn,
+            }))
         }
-        Ok(None) => {
-            trace!("parse_sni: failed to parse up to SNI");
+        Ok(_) => {
+            trace!("parse_client_hello: failed to parse up to SNI");
             Ok(None)
         }
         Err(untrusted::EndOfInput) => {
-            trace!("parse_sni: needs more input");
+            trace!("parse_client_hello: needs more input");
             Err(Incomplete)
         }
     }
 }
 
-/// The result is `Ok(Some(hostname))` if the SNI extension was found, `Ok(None)`
-/// if we affirmatively rejected the input before we found the SNI extension, or
-/// `Err(EndOfInput)` if we don't have enough input to continue.
-fn extract_sni<'a>(
+/// The result is `Ok(Some(extensions))` if the ClientHello's extensions were
+/// read, `Ok(None)` if we affirmatively rejected the input before we found the
+/// extensions, or `Err(EndOfInput)` if we don't have enough input to continue.
+fn extract_extensions<'a>(
     input: &mut untrusted::Reader<'a>,
-) -> Result<Option<untrusted::Input<'a>>, untrusted::EndOfInput> {
+) -> Result<Option<Extensions<'a>>, untrusted::EndOfInput> {
     // TLS ciphertext record header.
 
     if input.read_byte()? != 22 {
@@ -99,38 +137,65 @@ fn extract_sni<'a>(
             }
             skip_vector_u8(input)?; // compression_methods
 
-            // Look for the SNI extension as specified in
-            // https://tools.ietf.org/html/rfc6066#section-1.1
             read_vector(input, |input| {
+                let mut extensions = Extensions::default();
                 while !input.at_end() {
-                    let extension_type = read_u16(input)?;
-                    if extension_type != 0 {
-                        // ExtensionType::server_name
-                        skip_vector(input)?;
-                        continue;
-                    }
-
-                    // Treat extension_length followed by extension_value as a
-                    // vector<u16>.
-                    let r = read_vector(input, |input| {
-                        // server_name_list
-                        read_vector(input, |input| {
-                            // Nobody sends an SNI extension with anything
-                            // other than a single `host_name` value.
-                            if input.read_byte()? != 0 {
-                                // NameType::host_name
-                                return Ok(None);
+                    match read_u16(input)? {
+                        // ExtensionType::server_name, as specified in
+                        // https://tools.ietf.org/html/rfc6066#section-1.1
+                        0 => {
+                            // Treat extension_length followed by
+                            // extension_value as a vector<u16>.
+                            let sni = read_vector(input, |input| {
+                                // server_name_list
+                                read_vector(input, |input| {
+                                    // Nobody sends an SNI extension with
+                                    // anything other than a single `host_name`
+                                    // value.
+                                    if input.read_byte()? != 0 {
+                                        // NameType::host_name
+                                        return Ok(None);
+                                    }
+                                    // Return the value of the `HostName`.
+                                    read_vector(input, |input| Ok(Some(input.read_bytes_to_end())))
+                                })
+                            })?;
+                            match sni {
+                                Some(sni) => extensions.sni = Some(sni),
+                                None => return Ok(None),
                             }
-                            // Return the value of the `HostName`.
-                            read_vector(input, |input| Ok(Some(input.read_bytes_to_end())))
-                        })
-                    });
+                        }
+
+                        // ExtensionType::application_layer_protocol_negotiation,
+                        // as specified in https://tools.ietf.org/html/rfc7301#section-3.1
+                        16 => {
+                            // Treat extension_length followed by
+                            // extension_value as a vector<u16>.
+                            let alpn = read_vector(input, |input| {
+                                // protocol_name_list
+                                let protocols = read_vector(input, |input| {
+                                    let mut protocols = Vec::new();
+                                    while !input.at_end() {
+                                        let length = input.read_byte()?;
+                                        protocols.push(input.read_bytes(usize::from(length))?);
+                                    }
+                                    Ok(Some(protocols))
+                                });
+                                // A malformed protocol list is ignored so
+                                // that it doesn't prevent SNI detection.
+                                input.skip_to_end();
+                                Ok(protocols.ok().flatten())
+                            })?;
+                            extensions.alpn = alpn.unwrap_or_default();
+                        }
 
-                    input.skip_to_end(); // Ignore stuff after SNI
-                    return r;
+                        _ => {
+                            skip_vector(input)?;
+                        }
+                    }
                 }
 
-                Ok(None) // No SNI extension.
+                Ok(Some(extensions))
             })
         })
     });
@@ -217,4 +282,28 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn alpn() {
+        let input = include_bytes!("testdata/curl-example-com-client-hello.bin");
+        assert_eq!(
+            parse_client_hello(input),
+            Ok(Some(ClientHello {
+                sni: ServerName("example.com".parse().unwrap()),
+                alpn: vec![
+                    NegotiatedProtocol(b"h2".to_vec()),
+                    NegotiatedProtocol(b"http/1.1".to_vec()),
+                ],
+            }))
+        );
+
+        let input = include_bytes!("testdata/example-com-client-hello.bin");
+        assert_eq!(
+            parse_client_hello(input),
+            Ok(Some(ClientHello {
+                sni: ServerName("example.com".parse().unwrap()),
+                alpn: vec![],
+            }))
+        );
+    }
 }
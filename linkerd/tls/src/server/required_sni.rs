@@ -1,7 +1,4 @@
-use crate::{
-    server::{detect_sni, DetectIo},
-    ServerName,
-};
+use crate::server::{detect_client_hello, ClientHello, DetectIo};
 use linkerd_error::Error;
 use linkerd_io as io;
 use linkerd_stack::{layer, NewService, Service, ServiceExt};
@@ -23,7 +20,8 @@ pub struct SniDetectionTimeoutError;
 pub struct NoSniFoundError;
 
 /// A NewService that instruments an inner stack with knowledge of the
-/// connection's TLS ServerName (i.e. from an SNI header).
+/// connection's TLS ClientHello, including its ServerName (i.e. from an SNI
+/// header) and any ALPN protocols offered by the client.
 ///
 /// This differs from the parent module's NewDetectTls in a a few ways:
 ///
@@ -86,7 +84,7 @@ impl<T, I, N, S> Service<I> for DetectRequiredSni<T, N>
 where
     T: Clone + Send + Sync + 'static,
     I: io::AsyncRead + io::Peek + io::AsyncWrite + Send + Sync + Unpin + 'static,
-    N: NewService<(ServerName, T), Service = S> + Clone + Send + 'static,
+    N: NewService<(ClientHello, T), Service = S> + Clone + Send + 'static,
     S: Service<DetectIo<I>> + Send,
     S::Error: Into<Error>,
     S::Future: Send,
@@ -105,13 +103,13 @@ where
         let new_accept = self.inner.clone();
 
         // Detect the SNI from a ClientHello (or timeout).
-        let detect = time::timeout(self.timeout, detect_sni(io));
+        let detect = time::timeout(self.timeout, detect_client_hello(io));
         Box::pin(async move {
             let (res, io) = detect.await.map_err(|_| SniDetectionTimeoutError)??;
-            let sni = res.ok_or(NoSniFoundError)?;
-            debug!(?sni, "Detected TLS");
+            let hello = res.ok_or(NoSniFoundError)?;
+            debug!(sni = ?hello.sni, alpn = ?hello.alpn, "Detected TLS");
 
-            let svc = new_accept.new_service((sni, target));
+            let svc = new_accept.new_service((hello, target));
             svc.oneshot(io).await.map_err(Into::into)
         })
     }
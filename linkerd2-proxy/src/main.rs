@@ -11,7 +11,7 @@ compile_error!(
     "at least one of the following TLS implementations must be enabled: 'meshtls-boring', 'meshtls-rustls'"
 );
 
-use linkerd_app::{trace, BindTcp, Config, BUILD_INFO};
+use linkerd_app::{trace, AcceptMetrics, BindTcp, Config, BUILD_INFO};
 use linkerd_signal as signal;
 use tokio::{sync::mpsc, time};
 use tracing::{debug, info, warn};
@@ -63,8 +63,11 @@ fn main() {
         let (shutdown_tx, mut shutdown_rx) = mpsc::unbounded_channel();
         let shutdown_grace_period = config.shutdown_grace_period;
 
-        let bind_in = BindTcp::with_orig_dst();
-        let bind_out = BindTcp::dual_with_orig_dst();
+        // Record the connections accepted by each shard of the proxy's
+        // inbound and outbound listeners.
+        let accept = AcceptMetrics::register(metrics.sub_registry_with_prefix("listen"));
+        let bind_in = BindTcp::with_orig_dst_mode(config.inbound_orig_dst, accept.clone());
+        let bind_out = BindTcp::dual_with_orig_dst_mode(config.outbound_orig_dst, accept);
         let app = match config
             .build(
                 bind_in,
@@ -89,6 +92,9 @@ fn main() {
         if let Some(addr) = app.outbound_addr_additional() {
             info!("Outbound interface on {addr}");
         }
+        if let Some(addr) = app.forward_proxy_addr() {
+            info!("Forward proxy interface on {addr}");
+        }
 
         match app.tap_addr() {
             None => info!("Tap DISABLED"),